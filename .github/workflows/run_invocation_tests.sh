@@ -4,5 +4,23 @@
 # shellcheck source=/dev/null
 source test_profile
 
-# Run all tests with invocation feature (enables JavaVM ITs)
-cargo test --features=invocation
+# Run all tests with invocation feature (enables JavaVM ITs) the async features and the serde features
+cargo test --features=invocation,async,tokio,serde,serde_json,macros
+
+# The local reference tracking tests change global settings, so they run separately
+cargo test --features=invocation,local-ref-tracking --test local_ref_tracking
+
+# The reference statistics tests read global counters, so they run separately
+cargo test --features=invocation,stats --test stats
+
+# The strict exception check tests change a global setting, so they run separately
+cargo test --features=invocation,strict-exception-checks --test strict_exception_checks
+
+# The JNI call tracing tests only need the trace-jni feature
+cargo test --features=invocation,trace-jni --test trace_jni
+
+# Native methods declared with #[jni_native] are collected across the whole test binary
+cargo test --features=invocation,auto-register --test auto_register
+cargo test --features=invocation,auto-register,tokio --test jni_native_async
+cargo test --features=invocation,auto-register,tracing --test jni_native_instrument
+cargo test --features=invocation,auto-register --test jni_native_non_null
@@ -1,3 +1,16 @@
 $env:Path += ";$(Split-Path -Path (Get-Childitem –Path $Env:JAVA_HOME -Filter jvm.dll -Recurse) -Parent)"
 
-cargo test --features=invocation
+cargo test --features=invocation,async,tokio,serde,serde_json,macros
+
+cargo test --features=invocation,local-ref-tracking --test local_ref_tracking
+
+cargo test --features=invocation,stats --test stats
+
+cargo test --features=invocation,strict-exception-checks --test strict_exception_checks
+
+cargo test --features=invocation,trace-jni --test trace_jni
+
+cargo test --features=invocation,auto-register --test auto_register
+cargo test --features=invocation,auto-register,tokio --test jni_native_async
+cargo test --features=invocation,auto-register,tracing --test jni_native_instrument
+cargo test --features=invocation,auto-register --test jni_native_non_null
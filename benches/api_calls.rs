@@ -30,6 +30,13 @@ static SIG_LOCAL_DATE_TIME_OF: &str = "(IIIIIII)Ljava/time/LocalDateTime;";
 // 32 characters
 static TEST_STRING_UNICODE: &str = "_񍷕㳧~δ򗊁᪘׷ġ˥쩽|ņ/򖕡ٶԦ萴퀉֒ٞHy󢕒%ӓ娎񢞊ăꊦȮ񳗌";
 
+// 264 characters
+static TEST_STRING_ASCII: &str = concat!(
+    "The quick brown fox jumps over the lazy dog. 0123456789 !\"#$%&'()*+,-./:;<=>?@[]^_`{|}~ ",
+    "The quick brown fox jumps over the lazy dog. 0123456789 !\"#$%&'()*+,-./:;<=>?@[]^_`{|}~ ",
+    "The quick brown fox jumps over the lazy dog. 0123456789 !\"#$%&'()*+,-./:;<=>?@[]^_`{|}~ ",
+);
+
 #[inline(never)]
 fn native_abs(x: i32) -> i32 {
     x.abs()
@@ -291,6 +298,28 @@ fn jni_new_object_by_id_jclass(c: &mut Criterion) {
     });
 }
 
+fn jni_find_class_uncached(c: &mut Criterion) {
+    let mut env = VM.attach_current_thread().unwrap();
+
+    c.bench_function("jni_find_class_uncached", |b| {
+        b.iter(|| {
+            let class = env.find_class(CLASS_LOCAL_DATE_TIME).unwrap();
+            env.delete_local_ref(class);
+        })
+    });
+}
+
+/// Checks the cost of looking up a class by name once it's in the class
+/// cache, which only needs a new local reference instead of `FindClass`.
+fn jni_find_class_cached(c: &mut Criterion) {
+    let mut env = VM.attach_current_thread().unwrap();
+    env.cache_class(CLASS_LOCAL_DATE_TIME).unwrap();
+
+    c.bench_function("jni_find_class_cached", |b| {
+        b.iter(|| Desc::<JClass>::lookup(CLASS_LOCAL_DATE_TIME, &mut env).unwrap())
+    });
+}
+
 fn jni_new_global_ref(c: &mut Criterion) {
     let mut env = VM.attach_current_thread().unwrap();
     let class = CLASS_OBJECT;
@@ -349,6 +378,42 @@ fn jni_get_string_unchecked(c: &mut Criterion) {
     });
 }
 
+fn jni_get_string_unchecked_ascii(c: &mut Criterion) {
+    let env = VM.attach_current_thread().unwrap();
+    let string = env.new_string(TEST_STRING_ASCII).unwrap();
+
+    c.bench_function("jni_get_string_unchecked_ascii", |b| {
+        b.iter(|| {
+            let s: String = unsafe { env.get_string_unchecked(&string) }.unwrap().into();
+            assert_eq!(s, TEST_STRING_ASCII);
+        })
+    });
+}
+
+fn jni_to_rust_string(c: &mut Criterion) {
+    let env = VM.attach_current_thread().unwrap();
+    let string = env.new_string(TEST_STRING_UNICODE).unwrap();
+
+    c.bench_function("jni_to_rust_string", |b| {
+        b.iter(|| {
+            let s = string.to_rust_string(&env).unwrap();
+            assert_eq!(s, TEST_STRING_UNICODE);
+        })
+    });
+}
+
+fn jni_to_rust_string_ascii(c: &mut Criterion) {
+    let env = VM.attach_current_thread().unwrap();
+    let string = env.new_string(TEST_STRING_ASCII).unwrap();
+
+    c.bench_function("jni_to_rust_string_ascii", |b| {
+        b.iter(|| {
+            let s = string.to_rust_string(&env).unwrap();
+            assert_eq!(s, TEST_STRING_ASCII);
+        })
+    });
+}
+
 /// A benchmark measuring Push/PopLocalFrame overhead.
 ///
 /// Such operations are *required* if one attaches a long-running
@@ -463,11 +528,16 @@ criterion_group!(
     jni_new_object_by_id_str,
     jni_new_object_jclass,
     jni_new_object_by_id_jclass,
+    jni_find_class_uncached,
+    jni_find_class_cached,
     jni_new_global_ref,
     jni_check_exception,
     jni_get_java_vm,
     jni_get_string,
     jni_get_string_unchecked,
+    jni_get_string_unchecked_ascii,
+    jni_to_rust_string,
+    jni_to_rust_string_ascii,
     jni_noop_with_local_frame,
     jni_with_local_frame_returning_local,
     jni_with_local_frame_returning_global_to_local,
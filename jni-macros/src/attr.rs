@@ -0,0 +1,224 @@
+use syn::{
+    spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Result, Type,
+};
+
+/// The `#[java(...)]` attributes of a struct.
+pub struct Class {
+    /// The internal (`/`-separated) name of the Java class.
+    pub name: String,
+    pub constructor: bool,
+}
+
+/// A unit variant of an enum, along with its `#[java(...)]` attributes.
+pub struct Variant<'a> {
+    pub ident: &'a Ident,
+    /// The name of the Java enum constant, which defaults to the variant name
+    /// in `SCREAMING_SNAKE_CASE`.
+    pub name: String,
+}
+
+/// A named field of a struct, along with its `#[java(...)]` attributes.
+pub struct Field<'a> {
+    pub ident: &'a Ident,
+    pub ty: &'a Type,
+    /// The name of the Java property, which defaults to the field name in
+    /// camel case.
+    pub name: String,
+    pub getter: Option<String>,
+    pub setter: Option<String>,
+    pub skip: bool,
+}
+
+impl Class {
+    pub fn from_input(input: &DeriveInput) -> Result<Self> {
+        let mut name = None;
+        let mut constructor = false;
+        for attr in java_attrs(&input.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("class") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    name = Some(value.value().replace('.', "/"));
+                    Ok(())
+                } else if meta.path.is_ident("constructor") {
+                    constructor = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `class` or `constructor`"))
+                }
+            })?;
+        }
+
+        let name = name.ok_or_else(|| {
+            Error::new(
+                input.ident.span(),
+                "missing `#[java(class = \"...\")]` attribute",
+            )
+        })?;
+        Ok(Class { name, constructor })
+    }
+
+    /// The JNI type signature of the class.
+    pub fn signature(&self) -> String {
+        format!("L{};", self.name)
+    }
+}
+
+impl<'a> Field<'a> {
+    /// Returns the fields of a struct with named fields.
+    pub fn from_input(input: &'a DeriveInput) -> Result<Vec<Self>> {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "can't be derived for a generic struct",
+            ));
+        }
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => {
+                    return Err(Error::new(
+                        input.ident.span(),
+                        "can only be derived for a struct with named fields",
+                    ))
+                }
+            },
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "can only be derived for a struct",
+                ))
+            }
+        };
+
+        fields
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().expect("named field");
+                let mut parsed = Field {
+                    ident,
+                    ty: &field.ty,
+                    name: camel_case(&ident.to_string()),
+                    getter: None,
+                    setter: None,
+                    skip: false,
+                };
+                for attr in java_attrs(&field.attrs) {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("skip") {
+                            parsed.skip = true;
+                            return Ok(());
+                        }
+                        let value = meta.value()?.parse::<LitStr>()?.value();
+                        if meta.path.is_ident("name") {
+                            parsed.name = value;
+                        } else if meta.path.is_ident("getter") {
+                            parsed.getter = Some(value);
+                        } else if meta.path.is_ident("setter") {
+                            parsed.setter = Some(value);
+                        } else {
+                            return Err(meta.error("expected `name`, `getter`, `setter` or `skip`"));
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(parsed)
+            })
+            .collect()
+    }
+
+    pub fn getter(&self) -> String {
+        self.getter
+            .clone()
+            .unwrap_or_else(|| format!("get{}", capitalize(&self.name)))
+    }
+
+    pub fn setter(&self) -> String {
+        self.setter
+            .clone()
+            .unwrap_or_else(|| format!("set{}", capitalize(&self.name)))
+    }
+}
+
+impl<'a> Variant<'a> {
+    /// Returns the variants of an enum with only unit variants.
+    pub fn from_input(input: &'a DeriveInput) -> Result<Vec<Self>> {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "can't be derived for a generic enum",
+            ));
+        }
+        let variants = match &input.data {
+            Data::Enum(data) => &data.variants,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "can only be derived for an enum",
+                ))
+            }
+        };
+
+        variants
+            .iter()
+            .map(|variant| {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new(
+                        variant.span(),
+                        "can only be derived for an enum with unit variants",
+                    ));
+                }
+                let mut parsed = Variant {
+                    ident: &variant.ident,
+                    name: screaming_snake_case(&variant.ident.to_string()),
+                };
+                for attr in java_attrs(&variant.attrs) {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            parsed.name = meta.value()?.parse::<LitStr>()?.value();
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `name`"))
+                        }
+                    })?;
+                }
+                Ok(parsed)
+            })
+            .collect()
+    }
+}
+
+fn java_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("java"))
+}
+
+/// Converts a `snake_case` field name to `camelCase`.
+pub fn camel_case(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let mut parts = name.split('_').filter(|part| !part.is_empty());
+    let mut camel = parts.next().unwrap_or_default().to_owned();
+    for part in parts {
+        camel.push_str(&capitalize(part));
+    }
+    camel
+}
+
+/// Converts a `CamelCase` variant name to `SCREAMING_SNAKE_CASE`.
+fn screaming_snake_case(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let mut screaming = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            screaming.push('_');
+        }
+        screaming.extend(c.to_uppercase());
+    }
+    screaming
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
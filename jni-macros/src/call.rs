@@ -0,0 +1,213 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, Expr, Ident, LitStr, Result, Token, Type,
+};
+
+use crate::native::type_signature;
+
+/// The input of a `jni_call!` macro:
+/// `env, obj.method(arg: Type, ...) -> Type`.
+pub struct Call {
+    env: Expr,
+    obj: Expr,
+    method: Ident,
+    args: Vec<Arg>,
+    ret: Option<Typed>,
+}
+
+/// An argument of a `jni_call!`.
+struct Arg {
+    value: Expr,
+    ty: Typed,
+}
+
+/// A Rust type, with an optional JNI type signature (`Type as "sig"`) for
+/// when it can't be inferred.
+struct Typed {
+    ty: Type,
+    sig: Option<LitStr>,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let value = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(Arg { value, ty })
+    }
+}
+
+impl Parse for Typed {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ty = input.parse()?;
+        let sig = if input.parse::<Option<Token![as]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Typed { ty, sig })
+    }
+}
+
+impl Typed {
+    /// Returns the JNI type signature of the type.
+    fn signature(&self) -> Result<String> {
+        match &self.sig {
+            Some(sig) => Ok(sig.value()),
+            None => signature(&self.ty),
+        }
+    }
+}
+
+impl Parse for Call {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let env = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        // The object is everything before the last `.method(...)`, which
+        // can't be parsed as an expression, since the arguments have types
+        let mut tokens = Vec::new();
+        while !input.is_empty() && !input.peek(Token![->]) {
+            tokens.push(input.parse::<TokenTree>()?);
+        }
+        let call = (0..tokens.len().saturating_sub(2)).rev().find(|&i| {
+            matches!(&tokens[i], TokenTree::Punct(punct) if punct.as_char() == '.')
+                && matches!(&tokens[i + 1], TokenTree::Ident(_))
+                && matches!(&tokens[i + 2], TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis)
+                && i + 3 == tokens.len()
+        });
+        let i = call.ok_or_else(|| input.error("expected `obj.method(arg: Type, ...)`"))?;
+        let obj = syn::parse2(tokens[..i].iter().cloned().collect())?;
+        let method = match &tokens[i + 1] {
+            TokenTree::Ident(ident) => ident.clone(),
+            _ => unreachable!(),
+        };
+        let args = match &tokens[i + 2] {
+            TokenTree::Group(group) => {
+                Punctuated::<Arg, Token![,]>::parse_terminated.parse2(group.stream())?
+            }
+            _ => unreachable!(),
+        };
+
+        let ret = if input.parse::<Option<Token![->]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Call {
+            env,
+            obj,
+            method,
+            args: args.into_iter().collect(),
+            ret,
+        })
+    }
+}
+
+/// Returns the JNI type signature of an argument or return type, looking
+/// through references.
+fn signature(ty: &Type) -> Result<String> {
+    match ty {
+        Type::Reference(reference) => signature(&reference.elem),
+        _ => type_signature(ty, &[]).map_err(|err| {
+            Error::new(
+                err.span(),
+                format!("{}; give its signature with `Type as \"...\"`", err),
+            )
+        }),
+    }
+}
+
+pub fn jni_call(call: Call) -> Result<TokenStream> {
+    let Call {
+        env,
+        obj,
+        method,
+        args,
+        ret,
+    } = call;
+
+    let mut sig = String::from("(");
+    let mut bindings = Vec::new();
+    let mut values = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        let arg_sig = arg.ty.signature()?;
+        if arg_sig.is_empty() || arg_sig == "V" {
+            return Err(Error::new(arg.ty.ty.span(), "an argument can't be `void`"));
+        }
+        let binding = format_ident!("__jni_arg{}", i);
+        let ty = &arg.ty.ty;
+        let value = &arg.value;
+        bindings.push(quote! { let #binding: #ty = #value; });
+        values.push(if arg_sig.starts_with('L') || arg_sig.starts_with('[') {
+            quote! {
+                ::jni::objects::JValue::Object(
+                    ::std::convert::AsRef::<::jni::objects::JObject>::as_ref(&#binding),
+                ).as_jni()
+            }
+        } else {
+            quote! { ::jni::objects::JValue::from(#binding).as_jni() }
+        });
+        sig.push_str(&arg_sig);
+    }
+    sig.push(')');
+
+    let ret_sig = match &ret {
+        Some(ty) => ty.signature()?,
+        None => String::from("V"),
+    };
+    sig.push_str(&ret_sig);
+    let ret = ret.map(|ret| ret.ty);
+    let (ret_ty, convert) = match ret_sig.as_bytes().first().copied().unwrap_or(b'V') {
+        b'L' => (quote!(Object), quote!(value.l().map(<#ret>::from))),
+        b'[' => (quote!(Array), quote!(value.l().map(<#ret>::from))),
+        primitive => {
+            let (primitive, getter) = match primitive {
+                b'Z' => ("Boolean", "z"),
+                b'B' => ("Byte", "b"),
+                b'C' => ("Char", "c"),
+                b'S' => ("Short", "s"),
+                b'I' => ("Int", "i"),
+                b'J' => ("Long", "j"),
+                b'F' => ("Float", "f"),
+                b'D' => ("Double", "d"),
+                _ => ("Void", "v"),
+            };
+            let primitive = format_ident!("{}", primitive);
+            let getter = format_ident!("{}", getter);
+            (
+                quote!(Primitive(::jni::signature::Primitive::#primitive)),
+                quote!(value.#getter()),
+            )
+        }
+    };
+    let name = method.to_string();
+
+    Ok(quote! {{
+        static METHOD: ::jni::convert::CachedMethodId = ::jni::convert::CachedMethodId::new();
+        #(#bindings)*
+        let __jni_env: &mut ::jni::JNIEnv = #env;
+        let __jni_obj: &::jni::objects::JObject = ::std::convert::AsRef::as_ref(&#obj);
+        match METHOD.lookup(__jni_env, __jni_obj, #name, #sig) {
+            ::std::result::Result::Ok(id) => {
+                // Safety: the method ID was looked up in the class of `obj`
+                // (or a superclass), and the arguments and the return type
+                // match its signature.
+                let value = unsafe {
+                    __jni_env.call_method_unchecked(
+                        __jni_obj,
+                        id,
+                        ::jni::signature::ReturnType::#ret_ty,
+                        &[#(#values),*],
+                    )
+                };
+                value.and_then(|value| #convert)
+            }
+            ::std::result::Result::Err(err) => ::std::result::Result::Err(err),
+        }
+    }})
+}
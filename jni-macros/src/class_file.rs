@@ -0,0 +1,234 @@
+//! Reads the methods of a compiled Java class, for checking native methods
+//! against it with `verify = "..."`.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Error, LitStr, Result};
+
+/// The `ACC_NATIVE` access flag of a method.
+const ACC_NATIVE: u16 = 0x0100;
+
+/// A Java class file.
+pub struct ClassFile {
+    class: String,
+    /// The absolute path of the class file.
+    path: PathBuf,
+    methods: Vec<Method>,
+}
+
+/// The parts of a class file that are read.
+pub struct ClassInfo {
+    /// The internal (`/`-separated) name of the class.
+    pub name: String,
+    /// The names of the superclass and the interfaces of the class.
+    pub supertypes: Vec<String>,
+    methods: Vec<Method>,
+}
+
+struct Method {
+    name: String,
+    descriptor: String,
+    access_flags: u16,
+}
+
+/// Returns a path relative to the directory of the crate's `Cargo.toml`.
+pub fn manifest_path(path: &str) -> PathBuf {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    Path::new(&dir).join(path)
+}
+
+impl ClassFile {
+    /// Reads the class file of `class` (an internal, `/`-separated name),
+    /// where `path` is either the class file itself or a directory of class
+    /// files, like a class path entry.
+    pub fn load(path: &LitStr, class: &str) -> Result<Self> {
+        let mut full_path = manifest_path(&path.value());
+        if full_path.extension() == Some("jar".as_ref()) {
+            return Err(Error::new(
+                path.span(),
+                "`verify` doesn't support jar files; give a directory of class files or a class file",
+            ));
+        }
+        if full_path.is_dir() {
+            full_path = full_path.join(format!("{}.class", class));
+        }
+        let bytes = fs::read(&full_path).map_err(|err| {
+            Error::new(
+                path.span(),
+                format!("can't read `{}`: {}", full_path.display(), err),
+            )
+        })?;
+        let info = parse(&bytes).ok_or_else(|| {
+            Error::new(
+                path.span(),
+                format!("`{}` isn't a valid class file", full_path.display()),
+            )
+        })?;
+        Ok(ClassFile {
+            class: class.to_owned(),
+            path: full_path,
+            methods: info.methods,
+        })
+    }
+
+    /// Checks that the class declares a native method with the given name
+    /// and type signature.
+    pub fn check(&self, name: &str, sig: &str, span: Span) -> Result<()> {
+        let method = self
+            .methods
+            .iter()
+            .find(|method| method.name == name && method.descriptor == sig);
+        match method {
+            Some(method) if method.access_flags & ACC_NATIVE != 0 => Ok(()),
+            Some(_) => Err(Error::new(
+                span,
+                format!("`{}.{}{}` isn't a native method", self.class, name, sig),
+            )),
+            None => {
+                let overloads: Vec<_> = self
+                    .methods
+                    .iter()
+                    .filter(|method| method.name == name)
+                    .map(|method| format!("`{}`", method.descriptor))
+                    .collect();
+                let mut message = format!(
+                    "`{}` has no method `{}` with the signature `{}`",
+                    self.class, name, sig
+                );
+                if !overloads.is_empty() {
+                    message.push_str(&format!(" (it has {})", overloads.join(", ")));
+                }
+                Err(Error::new(span, message))
+            }
+        }
+    }
+
+    /// Returns an item that includes the class file, so that the crate is
+    /// rebuilt when the class changes.
+    pub fn track(&self) -> TokenStream {
+        let path = self.path.to_string_lossy();
+        quote! {
+            const _: &[u8] = ::std::include_bytes!(#path);
+        }
+    }
+}
+
+/// A cursor over the big-endian contents of a class file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u2(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u4(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Skips the attributes of a field or method.
+    fn skip_attributes(&mut self) -> Option<()> {
+        for _ in 0..self.u2()? {
+            self.u2()?;
+            let len = self.u4()? as usize;
+            self.take(len)?;
+        }
+        Some(())
+    }
+}
+
+/// Parses a class file, as described in chapter 4 of the Java Virtual Machine
+/// Specification.
+pub fn parse(bytes: &[u8]) -> Option<ClassInfo> {
+    let mut reader = Reader { bytes };
+    if reader.u4()? != 0xCAFE_BABE {
+        return None;
+    }
+    // Minor and major version
+    reader.take(4)?;
+
+    // The constant pool, of which only the `CONSTANT_Utf8` entries and the
+    // names of the `CONSTANT_Class` entries are kept
+    let count = reader.u2()? as usize;
+    let mut utf8 = vec![None; count];
+    let mut classes = vec![None; count];
+    let mut index = 1;
+    while index < count {
+        let tag = reader.take(1)?[0];
+        match tag {
+            1 => {
+                let len = reader.u2()? as usize;
+                let value = reader.take(len)?;
+                utf8[index] = Some(String::from_utf8_lossy(value).into_owned());
+            }
+            7 => classes[index] = Some(reader.u2()?),
+            8 | 16 | 19 | 20 => {
+                reader.take(2)?;
+            }
+            15 => {
+                reader.take(3)?;
+            }
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                reader.take(4)?;
+            }
+            // Long and double constants take up two entries
+            5 | 6 => {
+                reader.take(8)?;
+                index += 1;
+            }
+            _ => return None,
+        }
+        index += 1;
+    }
+    let string = |index: u16| utf8.get(index as usize).cloned().flatten();
+    let class = |index: u16| string(classes.get(index as usize).copied().flatten()?);
+
+    // Access flags
+    reader.take(2)?;
+    let name = class(reader.u2()?)?;
+    // The superclass is 0 for `java.lang.Object`
+    let mut supertypes: Vec<_> = class(reader.u2()?).into_iter().collect();
+    for _ in 0..reader.u2()? {
+        supertypes.push(class(reader.u2()?)?);
+    }
+
+    for _ in 0..reader.u2()? {
+        reader.take(6)?;
+        reader.skip_attributes()?;
+    }
+
+    let mut methods = Vec::new();
+    for _ in 0..reader.u2()? {
+        let access_flags = reader.u2()?;
+        let name = string(reader.u2()?)?;
+        let descriptor = string(reader.u2()?)?;
+        reader.skip_attributes()?;
+        methods.push(Method {
+            name,
+            descriptor,
+            access_flags,
+        });
+    }
+    Some(ClassInfo {
+        name,
+        supertypes,
+        methods,
+    })
+}
@@ -0,0 +1,339 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, GenericParam, Result};
+
+use crate::attr::{Class, Field, Variant};
+
+pub fn into_java(input: &DeriveInput) -> Result<TokenStream> {
+    if let Data::Enum(_) = input.data {
+        return enum_into_java(input);
+    }
+    let class = Class::from_input(input)?;
+    let fields = Field::from_input(input)?;
+    let fields: Vec<_> = fields.iter().filter(|field| !field.skip).collect();
+
+    let ident = &input.ident;
+    let class_name = &class.name;
+    let signature = class.signature();
+    let idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let tys: Vec<_> = fields.iter().map(|field| field.ty).collect();
+
+    let (methods, body) = if class.constructor {
+        let methods = quote! {
+            ::std::vec![(
+                "<init>",
+                ::std::format!(
+                    "({})V",
+                    <[&'static str]>::concat(&[
+                        #(<#tys as ::jni::convert::IntoJava>::SIGNATURE),*
+                    ])
+                ),
+            )]
+        };
+        let body = quote! {
+            let args = ::std::vec![
+                #(::jni::convert::IntoJava::to_java(&self.#idents, env)?),*
+            ];
+            let obj = methods.new_object(env, 0, args)?;
+        };
+        (methods, body)
+    } else {
+        let setters = fields.iter().map(|field| field.setter());
+        let indices = 1..=fields.len();
+        let methods = quote! {
+            ::std::vec![
+                ("<init>", ::std::string::String::from("()V")),
+                #((
+                    #setters,
+                    ::std::format!("({})V", <#tys as ::jni::convert::IntoJava>::SIGNATURE),
+                )),*
+            ]
+        };
+        let body = quote! {
+            let obj = methods.new_object(env, 0, ::std::vec::Vec::new())?;
+            let res = (|| {
+                #(methods.set(env, &obj, #indices, &self.#idents)?;)*
+                ::std::result::Result::Ok::<(), ::jni::errors::Error>(())
+            })();
+            if let ::std::result::Result::Err(err) = res {
+                env.delete_local_ref(obj);
+                return ::std::result::Result::Err(err);
+            }
+        };
+        (methods, body)
+    };
+
+    Ok(quote! {
+        impl ::jni::convert::IntoJava for #ident {
+            const SIGNATURE: &'static str = #signature;
+
+            fn to_java<'local>(
+                &self,
+                env: &mut ::jni::JNIEnv<'local>,
+            ) -> ::jni::errors::Result<::jni::objects::JValueOwned<'local>> {
+                static METHODS: ::std::sync::OnceLock<::jni::convert::CachedMethods> =
+                    ::std::sync::OnceLock::new();
+                let methods =
+                    ::jni::convert::CachedMethods::lookup(&METHODS, env, #class_name, || #methods)?;
+                #body
+                ::std::result::Result::Ok(::jni::objects::JValueOwned::from(obj))
+            }
+        }
+    })
+}
+
+pub fn from_java(input: &DeriveInput) -> Result<TokenStream> {
+    if let Data::Enum(_) = input.data {
+        return enum_from_java(input);
+    }
+    let class = Class::from_input(input)?;
+    let fields = Field::from_input(input)?;
+
+    let ident = &input.ident;
+    let class_name = &class.name;
+    let signature = class.signature();
+
+    let getters = fields.iter().filter(|field| !field.skip).map(|field| {
+        let getter = field.getter();
+        let ty = field.ty;
+        quote! {
+            (#getter, ::std::format!("(){}", <#ty as ::jni::convert::FromJava>::SIGNATURE))
+        }
+    });
+    let mut index = 0usize;
+    let values = fields.iter().map(|field| {
+        let ident = field.ident;
+        if field.skip {
+            quote!(#ident: ::std::default::Default::default())
+        } else {
+            let value = quote!(#ident: methods.get(env, obj, #index)?);
+            index += 1;
+            value
+        }
+    });
+
+    Ok(quote! {
+        impl ::jni::convert::FromJava for #ident {
+            const SIGNATURE: &'static str = #signature;
+
+            fn from_java(
+                env: &mut ::jni::JNIEnv,
+                value: ::jni::objects::JValue<'_>,
+            ) -> ::jni::errors::Result<Self> {
+                static METHODS: ::std::sync::OnceLock<::jni::convert::CachedMethods> =
+                    ::std::sync::OnceLock::new();
+                let methods = ::jni::convert::CachedMethods::lookup(&METHODS, env, #class_name, || {
+                    ::std::vec![#(#getters),*]
+                })?;
+                let obj = value.l()?;
+                methods.check_instance(env, obj)?;
+                ::std::result::Result::Ok(Self {
+                    #(#values),*
+                })
+            }
+        }
+    })
+}
+
+/// Generates the `static` that caches the constants of a Java enum, and the
+/// expression that looks them up.
+fn enum_constants(class: &Class, variants: &[Variant]) -> TokenStream {
+    let class_name = &class.name;
+    let names = variants.iter().map(|variant| &variant.name);
+    quote! {
+        static CONSTANTS: ::std::sync::OnceLock<::jni::convert::CachedEnum> =
+            ::std::sync::OnceLock::new();
+        let constants =
+            ::jni::convert::CachedEnum::lookup(&CONSTANTS, env, #class_name, &[#(#names),*])?;
+    }
+}
+
+fn enum_into_java(input: &DeriveInput) -> Result<TokenStream> {
+    let class = Class::from_input(input)?;
+    let variants = Variant::from_input(input)?;
+
+    let ident = &input.ident;
+    let signature = class.signature();
+    let constants = enum_constants(&class, &variants);
+    let idents = variants.iter().map(|variant| variant.ident);
+    let indices = 0..variants.len();
+
+    Ok(quote! {
+        impl ::jni::convert::IntoJava for #ident {
+            const SIGNATURE: &'static str = #signature;
+
+            fn to_java<'local>(
+                &self,
+                env: &mut ::jni::JNIEnv<'local>,
+            ) -> ::jni::errors::Result<::jni::objects::JValueOwned<'local>> {
+                #constants
+                let index = match self {
+                    #(Self::#idents => #indices),*
+                };
+                ::std::result::Result::Ok(::jni::objects::JValueOwned::from(
+                    constants.constant(env, index)?,
+                ))
+            }
+        }
+    })
+}
+
+fn enum_from_java(input: &DeriveInput) -> Result<TokenStream> {
+    let class = Class::from_input(input)?;
+    let variants = Variant::from_input(input)?;
+
+    let ident = &input.ident;
+    let signature = class.signature();
+    let constants = enum_constants(&class, &variants);
+    let idents = variants.iter().map(|variant| variant.ident);
+    let indices = 0..variants.len();
+
+    Ok(quote! {
+        impl ::jni::convert::FromJava for #ident {
+            const SIGNATURE: &'static str = #signature;
+
+            fn from_java(
+                env: &mut ::jni::JNIEnv,
+                value: ::jni::objects::JValue<'_>,
+            ) -> ::jni::errors::Result<Self> {
+                #constants
+                match constants.index_of(env, value.l()?)? {
+                    #(#indices => ::std::result::Result::Ok(Self::#idents),)*
+                    _ => ::std::unreachable!("CachedEnum::index_of returned an unknown index"),
+                }
+            }
+        }
+    })
+}
+
+pub fn reference(input: &DeriveInput) -> Result<TokenStream> {
+    let class = Class::from_input(input)?;
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let class_name = &class.name;
+
+    let is_transparent = input.attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && matches!(attr.parse_args::<syn::Ident>(), Ok(repr) if repr == "transparent")
+    });
+    if !is_transparent {
+        return Err(Error::new(
+            ident.span(),
+            "`Reference` can only be derived for a `#[repr(transparent)]` struct",
+        ));
+    }
+    let lifetime = match input.generics.params.iter().collect::<Vec<_>>()[..] {
+        [GenericParam::Lifetime(param)] => &param.lifetime,
+        _ => {
+            return Err(Error::new(
+                input.generics.span(),
+                "`Reference` can only be derived for a struct with a single lifetime parameter",
+            ))
+        }
+    };
+    match &input.data {
+        Data::Struct(data) if matches!(&data.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1) =>
+            {}
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "`Reference` can only be derived for a newtype struct around a `JObject`",
+            ))
+        }
+    }
+
+    Ok(quote! {
+        impl<#lifetime> ::std::convert::AsRef<#ident<#lifetime>> for #ident<#lifetime> {
+            fn as_ref(&self) -> &#ident<#lifetime> {
+                self
+            }
+        }
+
+        impl<#lifetime> ::std::convert::AsRef<::jni::objects::JObject<#lifetime>> for #ident<#lifetime> {
+            fn as_ref(&self) -> &::jni::objects::JObject<#lifetime> {
+                &self.0
+            }
+        }
+
+        impl<#lifetime> ::std::ops::Deref for #ident<#lifetime> {
+            type Target = ::jni::objects::JObject<#lifetime>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<#lifetime> ::std::convert::From<#ident<#lifetime>> for ::jni::objects::JObject<#lifetime> {
+            fn from(other: #ident<#lifetime>) -> Self {
+                other.0
+            }
+        }
+
+        /// This conversion assumes that the `JObject` is an instance of the class.
+        impl<#lifetime> ::std::convert::From<::jni::objects::JObject<#lifetime>> for #ident<#lifetime> {
+            fn from(other: ::jni::objects::JObject<#lifetime>) -> Self {
+                Self(other)
+            }
+        }
+
+        /// This conversion assumes that the `JObject` is an instance of the class.
+        impl<#lifetime, '__obj_ref> ::std::convert::From<&'__obj_ref ::jni::objects::JObject<#lifetime>>
+            for &'__obj_ref #ident<#lifetime>
+        {
+            fn from(other: &'__obj_ref ::jni::objects::JObject<#lifetime>) -> Self {
+                // Safety: the struct is `repr(transparent)` around `JObject`.
+                unsafe {
+                    &*(other as *const ::jni::objects::JObject<#lifetime> as *const #ident<#lifetime>)
+                }
+            }
+        }
+
+        impl<#lifetime> ::std::default::Default for #ident<#lifetime> {
+            fn default() -> Self {
+                Self(::jni::objects::JObject::null())
+            }
+        }
+
+        impl<#lifetime> #ident<#lifetime> {
+            /// Creates a wrapper around the given `raw` `jobject`.
+            ///
+            /// # Safety
+            ///
+            /// The same requirements as for `JObject::from_raw` apply, and
+            /// `raw` must either be null or refer to an instance of the class.
+            #vis const unsafe fn from_raw(raw: ::jni::sys::jobject) -> Self {
+                Self(::jni::objects::JObject::from_raw(raw))
+            }
+
+            /// Unwrap to the raw jni type.
+            #vis const fn into_raw(self) -> ::jni::sys::jobject {
+                self.0.into_raw()
+            }
+        }
+
+        // Safety: the struct is `repr(transparent)` around `JObject`.
+        unsafe impl<'__any> ::jni::objects::Reference for #ident<'__any> {
+            type Kind<'__local> = #ident<'__local>;
+
+            unsafe fn kind_from_raw<'__local>(raw: ::jni::sys::jobject) -> Self::Kind<'__local> {
+                #ident::from_raw(raw)
+            }
+
+            fn lookup_class<'__local>(
+                env: &mut ::jni::JNIEnv<'__local>,
+            ) -> ::jni::errors::Result<::jni::objects::JClass<'__local>> {
+                static CLASS: ::std::sync::OnceLock<::jni::objects::GlobalRef> =
+                    ::std::sync::OnceLock::new();
+                if let ::std::option::Option::Some(class) = CLASS.get() {
+                    return ::std::result::Result::Ok(::jni::objects::JClass::from(
+                        env.new_local_ref(class)?,
+                    ));
+                }
+                let class = env.find_class(#class_name)?;
+                // Another thread may have raced to set this, which is fine
+                let _ = CLASS.set(env.new_global_ref(&class)?);
+                ::std::result::Result::Ok(class)
+            }
+        }
+    })
+}
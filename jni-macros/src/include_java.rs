@@ -0,0 +1,202 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Ident, LitInt, LitStr, Result, Token,
+};
+
+use crate::class_file::{self, manifest_path};
+
+/// Numbers the output directories of the `javac` runs of this process.
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// The Java release that classes are compiled for by default, so that they
+/// can be defined by any Java VM, including Android's.
+const DEFAULT_RELEASE: u32 = 8;
+
+/// The input of `include_java_class!`: the path of a source file, optionally
+/// followed by `release = N`.
+pub struct Input {
+    path: LitStr,
+    release: u32,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let mut release = DEFAULT_RELEASE;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let name: Ident = input.parse()?;
+            if name != "release" {
+                return Err(Error::new(name.span(), "expected `release = N`"));
+            }
+            input.parse::<Token![=]>()?;
+            release = input.parse::<LitInt>()?.base10_parse()?;
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Input { path, release })
+    }
+}
+
+/// Returns the `javac` of `JAVA_HOME`, if it's set, or else the `javac` on
+/// the `PATH`.
+fn javac() -> PathBuf {
+    env::var_os("JAVA_HOME")
+        .map(|home| Path::new(&home).join("bin").join("javac"))
+        .filter(|javac| javac.exists() || javac.with_extension("exe").exists())
+        .unwrap_or_else(|| PathBuf::from("javac"))
+}
+
+/// Returns the `javac` options that compile classes for the given Java
+/// release.
+///
+/// The `javac` of JDK 8 doesn't support `--release`, so it's given `-source`
+/// and `-target` instead.
+fn release_args(javac: &Path, release: u32) -> Vec<String> {
+    let legacy = Command::new(javac)
+        .arg("-version")
+        .output()
+        .map(|output| {
+            // JDK 8 prints the version to stderr, and later versions to stdout
+            let version = [output.stdout, output.stderr].concat();
+            String::from_utf8_lossy(&version).contains("javac 1.")
+        })
+        .unwrap_or(false);
+    let release = release.to_string();
+    if legacy {
+        vec!["-source".into(), release.clone(), "-target".into(), release]
+    } else {
+        vec!["--release".into(), release]
+    }
+}
+
+/// Collects the class files in a directory and its subdirectories.
+fn class_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            class_files(&path, files)?;
+        } else if path.extension() == Some("class".as_ref()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compiles a Java source file for the given Java release into `out_dir`,
+/// which must be empty, and returns the names and class files of its classes.
+fn compile(
+    source: &Path,
+    release: u32,
+    out_dir: &Path,
+) -> std::result::Result<Vec<(String, Vec<u8>)>, String> {
+    let javac = javac();
+    let output = Command::new(&javac)
+        .args(release_args(&javac, release))
+        .arg("-encoding")
+        .arg("UTF-8")
+        .arg("-d")
+        .arg(out_dir)
+        .arg(source)
+        .output()
+        .map_err(|err| format!("can't run `javac`: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`javac` failed to compile `{}`:\n{}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let mut files = Vec::new();
+    class_files(out_dir, &mut files).map_err(|err| err.to_string())?;
+    files
+        .into_iter()
+        .map(|file| {
+            let bytes = fs::read(&file).map_err(|err| err.to_string())?;
+            let info = class_file::parse(&bytes).ok_or_else(|| {
+                format!("`javac` wrote an invalid class file `{}`", file.display())
+            })?;
+            Ok((info.name, info.supertypes, bytes))
+        })
+        .collect::<std::result::Result<Vec<_>, String>>()
+        .map(order_classes)
+}
+
+/// Orders classes so that each one comes after its superclass and the
+/// interfaces that it implements, so that they can be defined in order.
+fn order_classes(mut classes: Vec<(String, Vec<String>, Vec<u8>)>) -> Vec<(String, Vec<u8>)> {
+    classes.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut ordered = Vec::with_capacity(classes.len());
+    while !classes.is_empty() {
+        // A cycle isn't possible, but take the first class if there is one
+        let next = classes
+            .iter()
+            .position(|(_, supertypes, _)| {
+                supertypes
+                    .iter()
+                    .all(|supertype| classes.iter().all(|(name, _, _)| name != supertype))
+            })
+            .unwrap_or(0);
+        let (name, _, bytes) = classes.remove(next);
+        ordered.push((name, bytes));
+    }
+    ordered
+}
+
+pub fn include_java_class(Input { path, release }: Input) -> Result<TokenStream> {
+    let source = manifest_path(&path.value());
+    if !source.is_file() {
+        return Err(Error::new(
+            path.span(),
+            format!("`{}` doesn't exist", source.display()),
+        ));
+    }
+
+    let out_dir = env::temp_dir().join(format!(
+        "jni-include-java-class-{}-{}",
+        process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    // A process with the same ID may have left class files behind
+    let _ = fs::remove_dir_all(&out_dir);
+    let classes = compile(&source, release, &out_dir);
+    let _ = fs::remove_dir_all(&out_dir);
+    let classes = classes.map_err(|err| Error::new(path.span(), err))?;
+
+    // The class that's named after the source file
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let name = classes
+        .iter()
+        .map(|(name, _)| name)
+        .find(|name| name.rsplit('/').next() == Some(&*stem))
+        .ok_or_else(|| {
+            Error::new(
+                path.span(),
+                format!(
+                    "`{}` doesn't declare a class named `{}`",
+                    source.display(),
+                    stem
+                ),
+            )
+        })?;
+
+    let names = classes.iter().map(|(name, _)| name);
+    let bytes = classes.iter().map(|(_, bytes)| Literal::byte_string(bytes));
+    let source = source.to_string_lossy();
+    Ok(quote! {{
+        // Rebuild when the source file changes
+        const _: &[u8] = ::std::include_bytes!(#source);
+        ::jni::objects::EmbeddedClasses {
+            name: #name,
+            classes: &[#((#names, #bytes)),*],
+        }
+    }})
+}
@@ -0,0 +1,358 @@
+//! Derive macros for the [`jni`](https://docs.rs/jni) crate.
+//!
+//! These are re-exported by `jni::convert` (and `jni::objects` for
+//! `Reference`) when the `macros` feature of `jni` is enabled, and should be
+//! used from there, alongside the traits that they implement. The
+//! `jni_class` and `jni_native` attributes are re-exported by `jni::registry`
+//! (`jni_native` only with the `auto-register` feature), and
+//! `include_java_class` by `jni::objects`.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn, ItemImpl};
+
+mod attr;
+mod call;
+mod class_file;
+mod expand;
+mod include_java;
+mod native;
+
+/// Derives `jni::convert::IntoJava` for a struct that corresponds to a Java
+/// class.
+///
+/// The Java class is given with `#[java(class = "com.example.Point")]`. By
+/// default, an object is created with the class's no-argument constructor,
+/// and then each field is converted with `IntoJava` and passed to a setter
+/// method. For a field named `first_name`, the setter is `setFirstName`.
+///
+/// With `#[java(constructor)]`, all the fields are instead passed to a
+/// constructor, in the order that they're declared in.
+///
+/// The IDs of the constructor and the setters are looked up the first time
+/// that a value is converted, and cached for later conversions.
+///
+/// # Field attributes
+///
+/// * `#[java(name = "x")]` sets the name of the Java property, which is
+///   otherwise the field name in camel case.
+/// * `#[java(setter = "setX")]` sets the name of the setter.
+/// * `#[java(getter = "getX")]` sets the name of the getter. This is only
+///   used by `FromJava`.
+/// * `#[java(skip)]` skips the field.
+///
+/// # Enums
+///
+/// For an enum with only unit variants, the class is a Java enum, and each
+/// variant is converted to the enum constant with the same name in
+/// `SCREAMING_SNAKE_CASE`, which can be set with `#[java(name = "...")]` on
+/// the variant. The constants are looked up the first time that a value is
+/// converted, and cached as global references. `FromJava` converts a constant
+/// to a variant by its `ordinal()`, and fails with
+/// `Error::UnknownEnumConstant` for a constant that has no variant.
+///
+/// # Example
+///
+/// ```ignore
+/// use jni::convert::{FromJava, IntoJava};
+///
+/// #[derive(IntoJava, FromJava)]
+/// #[java(class = "com.example.Point", constructor)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// #[derive(IntoJava, FromJava)]
+/// #[java(class = "java.util.concurrent.TimeUnit")]
+/// enum TimeUnit {
+///     Seconds,
+///     #[java(name = "MILLISECONDS")]
+///     Millis,
+/// }
+/// ```
+#[proc_macro_derive(IntoJava, attributes(java))]
+pub fn derive_into_java(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::into_java(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `jni::convert::FromJava` for a struct that corresponds to a Java
+/// class.
+///
+/// The Java class is given with `#[java(class = "com.example.Point")]`. Each
+/// field is set by calling a getter method on the Java object, and converting
+/// the value that it returns with `FromJava`. For a field named `first_name`,
+/// the getter is `getFirstName`. Fields that are skipped with
+/// `#[java(skip)]` are set to their `Default` value.
+///
+/// The IDs of the getters are looked up the first time that a value is
+/// converted, and cached for later conversions.
+///
+/// See the [`IntoJava`](derive@IntoJava) derive macro for the attributes that
+/// can be used on fields, and for enums.
+#[proc_macro_derive(FromJava, attributes(java))]
+pub fn derive_from_java(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::from_java(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `jni::objects::Reference`, and the usual conversions, for a
+/// newtype wrapper around a `JObject` that's an instance of a Java class.
+///
+/// The struct has to be `#[repr(transparent)]`, with a single lifetime
+/// parameter, and the Java class is given with
+/// `#[java(class = "com.example.Foo")]`. Along with the `Reference`
+/// implementation, this generates the same conversions as the wrappers in
+/// `jni::objects` have: `AsRef<JObject>`, `Deref<Target = JObject>`, `From`
+/// in both directions (which assume that the object is an instance of the
+/// class), `Default` (null), and `from_raw` and `into_raw` methods.
+///
+/// The class is looked up the first time that `Reference::lookup_class` is
+/// called, and cached as a global reference.
+///
+/// # Example
+///
+/// ```ignore
+/// use jni::objects::{JObject, Reference};
+///
+/// #[derive(Reference)]
+/// #[java(class = "com.example.Foo")]
+/// #[repr(transparent)]
+/// pub struct Foo<'local>(JObject<'local>);
+/// ```
+#[proc_macro_derive(Reference, attributes(java))]
+pub fn derive_reference(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::reference(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares that a function implements a native method of a Java class, so
+/// that it's registered by `jni::registry::register_collected`.
+///
+/// The class is given with `class = "com.example.Foo"` and the method's type
+/// signature with `sig = "(II)I"`. The name of the Java method defaults to
+/// the name of the function in camel case, and can be set with
+/// `name = "..."`.
+///
+/// # Null checks
+///
+/// `non_null = [a, b]` checks that the named reference arguments aren't null
+/// before the function's body runs. If one is, the exception that
+/// `jni::errors::ExceptionMap` maps `jni::errors::Error::NullPtr` to is thrown
+/// (a `NullPointerException` by default), and the method returns null, zero or
+/// `false`. The `JNIEnv` argument has to be a plain name, such as `env` or
+/// `_env`.
+///
+/// # Verification
+///
+/// `verify = "path"` checks at compile time that the Java class declares a
+/// native method with the name and signature, so that a typo is a compile
+/// error instead of a `NoSuchMethodError` when the method is registered. The
+/// path (relative to the crate's `Cargo.toml`) is either the class file, or a
+/// directory of class files laid out by package, like a class path entry. Jar
+/// files aren't supported.
+///
+/// # Async functions
+///
+/// With the `tokio` feature, the function can be an `async fn` that returns
+/// `jni::errors::Result<GlobalRef>`, for a Java method that returns a
+/// `CompletableFuture`. The `runtime = path::to::handle` option gives a
+/// function that returns the `tokio::runtime::Handle` to spawn the future on.
+///
+/// The `async fn` takes the arguments of the Java method, without the
+/// `JNIEnv` and the `this` object, and can take a `JavaVM` before them. An
+/// argument of type `GlobalRef` is a reference argument of the Java method,
+/// which is converted to a global reference before the future is spawned.
+/// Other arguments are passed as they are.
+///
+/// The method returns a new `CompletableFuture` that's completed with the
+/// output of the future (see `JNIEnv::new_completable_future_from`).
+///
+/// # Tracing
+///
+/// With the `tracing` feature, `instrument = true` runs the method in a
+/// `tracing` span named `native_method`, with the `class`, `method` and `sig`
+/// of the method as fields. An error event is recorded in the span if the
+/// method returns with a pending exception or panics, or if the future of an
+/// `async fn` fails. For an `extern "system"` function, the `JNIEnv` argument
+/// has to be a plain name, such as `env` or `_env`.
+///
+/// # Example
+///
+/// ```ignore
+/// use jni::{
+///     errors::Result,
+///     objects::{GlobalRef, JClass},
+///     registry::jni_native,
+///     sys::jint,
+///     JNIEnv, JavaVM,
+/// };
+///
+/// #[jni_native(class = "com.example.Math", sig = "(II)I")]
+/// extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+///     a + b
+/// }
+///
+/// #[jni_native(
+///     class = "com.example.Math",
+///     sig = "(Ljava/lang/String;)Ljava/util/concurrent/CompletableFuture;",
+///     runtime = runtime_handle
+/// )]
+/// async fn fetch(vm: JavaVM, url: GlobalRef) -> Result<GlobalRef> {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn jni_native(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut parsed = native::Args::default();
+    let parser = syn::meta::parser(|meta| parsed.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemFn);
+    native::jni_native(parsed, &item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turns the `extern "system"` functions of an `impl` block into the native
+/// methods of a Java class.
+///
+/// The class is given with `class = "com.example.Foo"`. This generates a
+/// `NATIVE_METHODS` constant with a `jni::registry::NativeEntry` for each
+/// `extern "system"` function in the block, and with `register`, a
+/// `register(env)` function that registers them. Other functions in the block
+/// are left alone.
+///
+/// The name of each Java method defaults to the name of the function in camel
+/// case, and its type signature is inferred from the types of the function's
+/// parameters (after the `JNIEnv` and the `JObject` or `JClass` that it's
+/// called on) and its return type. Primitive types (such as `jint` or `i32`),
+/// `JObject`, `JString`, `JClass`, `JThrowable`, `JByteBuffer` and the array
+/// types are inferred, and other types can be mapped to a JNI type signature
+/// with `type_map(MyType = "Lcom/example/MyType;")`.
+///
+/// Type maps that are shared between several impl blocks can be kept in a
+/// file instead, with `type_map_file = "src/type_map.txt"` (relative to the
+/// crate's `Cargo.toml`). Each line of the file maps a Rust type to a Java
+/// type, as in `MyType => com.example.MyType` or `Bytes => byte[]`, and `//`
+/// starts a comment. Entries in `type_map(...)` take precedence over the
+/// file.
+///
+/// With `verify = "path"`, each method is checked against the compiled Java
+/// class at compile time, as for `#[jni_native]`.
+///
+/// # Method attributes
+///
+/// * `#[jni(name = "x")]` sets the name of the Java method.
+/// * `#[jni(sig = "(I)V")]` sets the type signature of the Java method,
+///   instead of inferring it.
+///
+/// # Example
+///
+/// ```ignore
+/// use jni::{
+///     objects::{JClass, JObject, JString},
+///     registry::jni_class,
+///     sys::jint,
+///     JNIEnv,
+/// };
+///
+/// struct Counter;
+///
+/// #[jni_class(class = "com.example.Counter", register)]
+/// impl Counter {
+///     extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+///         a + b
+///     }
+///
+///     #[jni(name = "nativeName")]
+///     extern "system" fn name<'local>(
+///         env: JNIEnv<'local>,
+///         _this: JObject<'local>,
+///     ) -> JString<'local> {
+///         env.new_string("counter").unwrap()
+///     }
+/// }
+///
+/// // Registers `add(II)I` and `nativeName()Ljava/lang/String;`
+/// # fn example(env: &mut JNIEnv) -> jni::errors::Result<()> {
+/// Counter::register(env)?;
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn jni_class(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut parsed = native::ClassArgs::default();
+    let parser = syn::meta::parser(|meta| parsed.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemImpl);
+    native::jni_class(parsed, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Calls a Java method on an object, with a signature that's built at
+/// compile time from the types of the arguments and the return value.
+///
+/// ```ignore
+/// let name: JString = jni_call!(env, obj.getName(prefix: &JString, index: jint) -> JString)?;
+/// ```
+///
+/// The first argument is a `&mut JNIEnv`, and the object can be any
+/// expression of a type that implements `AsRef<JObject>`. Each argument of the
+/// method is given with its type, and the JNI signature of each type is
+/// inferred from its name (looking through references), in the same way as
+/// for `#[jni_class]`: primitives such as `jint` or `bool`, and `JObject`,
+/// `JString`, `JClass` and the array types. The signature of any other type,
+/// or of a method that takes a supertype, is given after the type, as in
+/// `-> JObject as "Ljava/lang/StringBuilder;"`. Without a return type, the
+/// method returns `void`.
+///
+/// The method ID is cached for each call site, in a
+/// `jni::convert::CachedMethodId`, and the method is called with
+/// `JNIEnv::call_method_unchecked`. The macro evaluates to a
+/// `jni::errors::Result` of the return type, which is converted from the
+/// `JObject` with `From` for a reference type.
+#[proc_macro]
+pub fn jni_call(input: TokenStream) -> TokenStream {
+    let call = parse_macro_input!(input as call::Call);
+    call::jni_call(call)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Compiles a Java source file with `javac` when the crate is built, and
+/// embeds the class files in a `jni::objects::EmbeddedClasses`.
+///
+/// ```ignore
+/// const GREETER: EmbeddedClasses = include_java_class!("tests/java/com/example/Greeter.java");
+///
+/// let loader = JClassLoader::system(env)?;
+/// let class = GREETER.define(env, &loader)?;
+/// ```
+///
+/// The path is relative to the crate's `Cargo.toml`, and the source file has
+/// to declare a class that's named after it. Every class in the file
+/// (including nested and anonymous classes) is embedded, and `define` defines
+/// them all with the given class loader and returns the class that's named
+/// after the file.
+///
+/// The `javac` of `JAVA_HOME` is used if it's set, or else the `javac` on the
+/// `PATH`. The source file can only depend on classes of the JDK.
+///
+/// The classes are compiled for Java 8 (with `javac --release 8`), so that
+/// they can be defined by any Java VM, including Android's. A later release
+/// can be given after the path, as in
+/// `include_java_class!("path/Foo.java", release = 11)`.
+#[proc_macro]
+pub fn include_java_class(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as include_java::Input);
+    include_java::include_java_class(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
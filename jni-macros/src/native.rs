@@ -0,0 +1,684 @@
+use std::fs;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    bracketed, meta::ParseNestedMeta, parse_quote, punctuated::Punctuated, spanned::Spanned, Abi,
+    Error, FnArg, Ident, ImplItem, ItemFn, ItemImpl, LitBool, LitStr, Pat, Path, Result,
+    ReturnType, Signature, Token, Type, Visibility,
+};
+
+use crate::{
+    attr::camel_case,
+    class_file::{manifest_path, ClassFile},
+};
+
+/// The arguments of a `#[jni_native(...)]` attribute.
+#[derive(Default)]
+pub struct Args {
+    /// The internal (`/`-separated) name of the Java class.
+    class: Option<String>,
+    name: Option<String>,
+    sig: Option<String>,
+    /// A function that returns the Tokio runtime handle for an `async fn`.
+    runtime: Option<Path>,
+    /// Whether to wrap the method in a `tracing` span.
+    instrument: bool,
+    /// The reference arguments that are checked for null.
+    non_null: Vec<Ident>,
+    /// A class file, or directory of class files, to check the method against.
+    verify: Option<LitStr>,
+}
+
+impl Args {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
+        if meta.path.is_ident("runtime") {
+            self.runtime = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+        if meta.path.is_ident("instrument") {
+            self.instrument = meta.value()?.parse::<LitBool>()?.value;
+            return Ok(());
+        }
+        if meta.path.is_ident("non_null") {
+            let value = meta.value()?;
+            let content;
+            bracketed!(content in value);
+            let names = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+            self.non_null.extend(names);
+            return Ok(());
+        }
+        if meta.path.is_ident("verify") {
+            self.verify = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+        let value = meta.value()?.parse::<LitStr>()?.value();
+        if meta.path.is_ident("class") {
+            self.class = Some(value.replace('.', "/"));
+        } else if meta.path.is_ident("name") {
+            self.name = Some(value);
+        } else if meta.path.is_ident("sig") {
+            self.sig = Some(value);
+        } else {
+            return Err(meta.error(
+                "expected `class`, `name`, `sig`, `runtime`, `instrument`, `non_null` or `verify`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub fn jni_native(args: Args, item: &ItemFn) -> Result<TokenStream> {
+    let ident = &item.sig.ident;
+    let class = args.class.ok_or_else(|| {
+        Error::new(
+            ident.span(),
+            "missing `class = \"...\"` in `#[jni_native(...)]`",
+        )
+    })?;
+    let sig = args.sig.ok_or_else(|| {
+        Error::new(
+            ident.span(),
+            "missing `sig = \"...\"` in `#[jni_native(...)]`",
+        )
+    })?;
+    let name = args.name.unwrap_or_else(|| camel_case(&ident.to_string()));
+    let fn_ptr = format_ident!("__jni_native_fn_ptr_{}", ident);
+    let java_type = java_declaration(&class, &name, &sig);
+    let verified = match &args.verify {
+        Some(path) => {
+            let class_file = ClassFile::load(path, &class)?;
+            class_file.check(&name, &sig, ident.span())?;
+            class_file.track()
+        }
+        None => quote! {},
+    };
+
+    let mut item = match (&item.sig.asyncness, args.runtime) {
+        (Some(_), Some(runtime)) => async_wrapper(item, &runtime, args.instrument)?,
+        (Some(asyncness), None) => {
+            return Err(Error::new(
+                asyncness.span(),
+                "an `async fn` needs a `runtime = ...` in `#[jni_native(...)]`",
+            ))
+        }
+        (None, Some(runtime)) => {
+            return Err(Error::new(
+                runtime.span(),
+                "`runtime` can only be given for an `async fn`",
+            ))
+        }
+        (None, None) => item.clone(),
+    };
+    if !args.non_null.is_empty() {
+        check_non_null(&mut item, &args.non_null)?;
+    }
+    if args.instrument {
+        instrument(&mut item, &class, &name, &sig)?;
+    }
+
+    Ok(quote! {
+        #item
+
+        #verified
+
+        ::jni::registry::inventory::submit! {
+            ::jni::registry::NativeEntry {
+                class_name: #class,
+                name: #name,
+                sig: #sig,
+                fn_ptr: {
+                    fn #fn_ptr() -> *mut ::std::ffi::c_void {
+                        #ident as *mut ::std::ffi::c_void
+                    }
+                    #fn_ptr
+                },
+                rust_fn: ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#ident)),
+                java_type: #java_type,
+                location: ::std::panic::Location::caller(),
+            }
+        }
+    })
+}
+
+/// Generates an `extern "system"` function that runs an `async fn` on a Tokio
+/// runtime, and returns a `CompletableFuture` that's completed with its
+/// output.
+///
+/// The `async fn` takes the arguments of the Java method (optionally after a
+/// `JavaVM`), where each `GlobalRef` argument is a reference argument of the
+/// Java method.
+fn async_wrapper(item: &ItemFn, runtime: &Path, instrument: bool) -> Result<ItemFn> {
+    let vis = &item.vis;
+    let ident = &item.sig.ident;
+    let mut implementation = item.clone();
+    implementation.vis = Visibility::Inherited;
+    implementation.sig.ident = format_ident!("__jni_native_async_{}", ident);
+    let implementation_ident = &implementation.sig.ident;
+
+    let mut params = Vec::new();
+    let mut conversions = Vec::new();
+    let mut call_args = Vec::new();
+    for (i, input) in item.sig.inputs.iter().enumerate() {
+        let arg = match input {
+            FnArg::Typed(arg) => arg,
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new(
+                    receiver.span(),
+                    "a native method can't take `self`",
+                ))
+            }
+        };
+        let ty_ident = match &*arg.ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        let param = match &*arg.pat {
+            Pat::Ident(pat) => pat.ident.clone(),
+            _ => format_ident!("arg{}", i),
+        };
+        if i == 0 && ty_ident.as_deref() == Some("JavaVM") {
+            conversions.push(quote! { let #param = __jni_env.get_java_vm()?; });
+        } else if ty_ident.as_deref() == Some("GlobalRef") {
+            params.push(quote! { #param: ::jni::objects::JObject<'local> });
+            conversions.push(quote! { let #param = __jni_env.new_global_ref(&#param)?; });
+        } else {
+            let ty = &arg.ty;
+            params.push(quote! { #param: #ty });
+        }
+        call_args.push(param);
+    }
+
+    let mut future = quote! { #implementation_ident(#(#call_args),*) };
+    if instrument {
+        future = quote! { ::jni::registry::instrument_future(#future) };
+    }
+
+    Ok(parse_quote! {
+        #vis extern "system" fn #ident<'local>(
+            mut __jni_env: ::jni::JNIEnv<'local>,
+            _: ::jni::objects::JObject<'local>,
+            #(#params),*
+        ) -> ::jni::sys::jobject {
+            #implementation
+
+            ::jni::registry::spawn_native_future(&mut __jni_env, &#runtime(), |__jni_env| {
+                #(#conversions)*
+                ::std::result::Result::Ok(#future)
+            })
+        }
+    })
+}
+
+/// Wraps the body of an `extern "system"` function in a `tracing` span that
+/// records whether the method threw an exception or panicked.
+fn instrument(item: &mut ItemFn, class: &str, name: &str, sig: &str) -> Result<()> {
+    let env = env_ident(item, "instrument")?;
+    let block = &item.block;
+    item.block = parse_quote!({
+        let __jni_raw_env = #env.get_raw();
+        ::jni::registry::instrument(__jni_raw_env, #class, #name, #sig, move || #block)
+    });
+    Ok(())
+}
+
+/// Inserts checks at the start of an `extern "system"` function that throw a
+/// `NullPointerException` if any of the given arguments is null.
+fn check_non_null(item: &mut ItemFn, names: &[Ident]) -> Result<()> {
+    let env = env_ident(item, "non_null")?;
+    let params: Vec<&Ident> = item
+        .sig
+        .inputs
+        .iter()
+        .skip(2)
+        .filter_map(|input| match input {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let mut checks = Vec::new();
+    for name in names {
+        if !params.contains(&name) {
+            return Err(Error::new(
+                name.span(),
+                format!("no reference argument named `{}`", name),
+            ));
+        }
+        let name_str = name.to_string();
+        checks.push(quote! {
+            if #name.is_null() {
+                // Safety: this returns null, zero or false from the native method
+                return unsafe { ::jni::registry::null_argument(#env.get_raw(), #name_str) };
+            }
+        });
+    }
+    let block = &item.block;
+    item.block = parse_quote!({
+        #(#checks)*
+        #block
+    });
+    Ok(())
+}
+
+/// Returns the name of the `JNIEnv` argument of an `extern "system"` function,
+/// which `option` needs.
+fn env_ident(item: &ItemFn, option: &str) -> Result<Ident> {
+    match item.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) => match &*arg.pat {
+            Pat::Ident(pat) => Ok(pat.ident.clone()),
+            pat => Err(Error::new(
+                pat.span(),
+                format!(
+                    "`{}` needs the `JNIEnv` argument to be a plain name",
+                    option
+                ),
+            )),
+        },
+        _ => Err(Error::new(
+            item.sig.span(),
+            format!("`{}` needs a `JNIEnv` argument", option),
+        )),
+    }
+}
+
+/// The arguments of a `#[jni_class(...)]` attribute.
+#[derive(Default)]
+pub struct ClassArgs {
+    /// The internal (`/`-separated) name of the Java class.
+    class: Option<String>,
+    register: bool,
+    /// Extra mappings from the names of Rust types to JNI type signatures.
+    type_map: Vec<(Ident, String)>,
+    /// Mappings read from `type_map_file`s, which come after `type_map`.
+    file_type_map: Vec<(Ident, String)>,
+    /// The absolute paths of the `type_map_file`s.
+    type_map_files: Vec<String>,
+    /// A class file, or directory of class files, to check the methods against.
+    verify: Option<LitStr>,
+}
+
+impl ClassArgs {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
+        if meta.path.is_ident("class") {
+            let value = meta.value()?.parse::<LitStr>()?.value();
+            self.class = Some(value.replace('.', "/"));
+        } else if meta.path.is_ident("register") {
+            self.register = true;
+        } else if meta.path.is_ident("type_map") {
+            meta.parse_nested_meta(|entry| {
+                let ident = entry.path.require_ident()?.clone();
+                let value = entry.value()?.parse::<LitStr>()?.value();
+                self.type_map.push((ident, value));
+                Ok(())
+            })?;
+        } else if meta.path.is_ident("type_map_file") {
+            let path = meta.value()?.parse::<LitStr>()?;
+            self.read_type_map_file(&path)?;
+        } else if meta.path.is_ident("verify") {
+            self.verify = Some(meta.value()?.parse()?);
+        } else {
+            return Err(
+                meta.error("expected `class`, `register`, `type_map`, `type_map_file` or `verify`")
+            );
+        }
+        Ok(())
+    }
+
+    /// Reads a file of `RustType => java.Type` lines, relative to the
+    /// directory of the crate's `Cargo.toml`.
+    fn read_type_map_file(&mut self, path: &LitStr) -> Result<()> {
+        let full_path = manifest_path(&path.value());
+        let contents = fs::read_to_string(&full_path).map_err(|err| {
+            Error::new(
+                path.span(),
+                format!("can't read `{}`: {}", full_path.display(), err),
+            )
+        })?;
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            let line = line.strip_suffix(',').unwrap_or(line).trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let entry = line.split_once("=>").and_then(|(rust_type, java_type)| {
+                let ident = syn::parse_str::<Ident>(rust_type.trim()).ok()?;
+                Some((ident, java_type_signature(java_type.trim())?))
+            });
+            let entry = entry.ok_or_else(|| {
+                Error::new(
+                    path.span(),
+                    format!(
+                        "{}:{}: expected `RustType => java.Type`",
+                        full_path.display(),
+                        number + 1
+                    ),
+                )
+            })?;
+            self.file_type_map.push(entry);
+        }
+        self.type_map_files
+            .push(full_path.to_string_lossy().into_owned());
+        Ok(())
+    }
+
+    /// Returns the JNI type signature of a parameter or return type.
+    fn signature(&self, ty: &Type) -> Result<String> {
+        type_signature(ty, &self.type_map).map_err(|err| {
+            Error::new(
+                err.span(),
+                format!(
+                    "{}; add it to `type_map(...)` or give the method a `#[jni(sig = \"...\")]`",
+                    err
+                ),
+            )
+        })
+    }
+}
+
+/// Returns the JNI type signature of a Java type name, such as `int`,
+/// `java.lang.String` or `byte[]`.
+fn java_type_signature(java_type: &str) -> Option<String> {
+    let mut base = java_type;
+    let mut signature = String::new();
+    while let Some(element) = base.strip_suffix("[]") {
+        signature.push('[');
+        base = element.trim_end();
+    }
+    let primitive = match base {
+        "boolean" => "Z",
+        "byte" => "B",
+        "char" => "C",
+        "short" => "S",
+        "int" => "I",
+        "long" => "J",
+        "float" => "F",
+        "double" => "D",
+        "void" if signature.is_empty() => "V",
+        _ => "",
+    };
+    if !primitive.is_empty() {
+        signature.push_str(primitive);
+    } else if !base.is_empty()
+        && base
+            .split(['.', '/', '$'])
+            .all(|part| syn::parse_str::<Ident>(part).is_ok())
+    {
+        signature.push('L');
+        signature.push_str(&base.replace('.', "/"));
+        signature.push(';');
+    } else {
+        return None;
+    }
+    Some(signature)
+}
+
+/// Returns the JNI type signature of a Rust type, inferred from the name of
+/// the type, or looked up in `type_map`.
+pub fn type_signature(ty: &Type, type_map: &[(Ident, String)]) -> Result<String> {
+    let ident = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().map(|s| &s.ident),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => return Ok(String::from("V")),
+        _ => None,
+    };
+    let ident =
+        ident.ok_or_else(|| Error::new(ty.span(), "can't infer the JNI type of this type"))?;
+    if let Some((_, sig)) = type_map.iter().find(|(name, _)| name == ident) {
+        return Ok(sig.clone());
+    }
+    let sig = match ident.to_string().as_str() {
+        "jboolean" | "bool" => "Z",
+        "jbyte" | "i8" => "B",
+        "jchar" | "u16" => "C",
+        "jshort" | "i16" => "S",
+        "jint" | "i32" | "jsize" => "I",
+        "jlong" | "i64" => "J",
+        "jfloat" | "f32" => "F",
+        "jdouble" | "f64" => "D",
+        "JObject" | "jobject" => "Ljava/lang/Object;",
+        "JString" | "jstring" => "Ljava/lang/String;",
+        "JClass" | "jclass" => "Ljava/lang/Class;",
+        "JThrowable" | "jthrowable" => "Ljava/lang/Throwable;",
+        "JByteBuffer" => "Ljava/nio/ByteBuffer;",
+        "JBooleanArray" | "jbooleanArray" => "[Z",
+        "JByteArray" | "jbyteArray" => "[B",
+        "JCharArray" | "jcharArray" => "[C",
+        "JShortArray" | "jshortArray" => "[S",
+        "JIntArray" | "jintArray" => "[I",
+        "JLongArray" | "jlongArray" => "[J",
+        "JFloatArray" | "jfloatArray" => "[F",
+        "JDoubleArray" | "jdoubleArray" => "[D",
+        "JObjectArray" | "jobjectArray" => "[Ljava/lang/Object;",
+        _ => {
+            return Err(Error::new(
+                ty.span(),
+                format!("can't infer the JNI type of `{}`", ident),
+            ))
+        }
+    };
+    Ok(String::from(sig))
+}
+
+/// The `#[jni(...)]` attributes of a method in a `#[jni_class]` impl block.
+#[derive(Default)]
+struct MethodArgs {
+    name: Option<String>,
+    sig: Option<String>,
+}
+
+pub fn jni_class(mut args: ClassArgs, mut item: ItemImpl) -> Result<TokenStream> {
+    args.type_map.append(&mut args.file_type_map);
+    let class = args.class.clone().ok_or_else(|| {
+        Error::new(
+            item.self_ty.span(),
+            "missing `class = \"...\"` in `#[jni_class(...)]`",
+        )
+    })?;
+    if !item.generics.params.is_empty() {
+        return Err(Error::new(
+            item.generics.span(),
+            "`#[jni_class]` can't be used on a generic impl block",
+        ));
+    }
+    let self_ty = item.self_ty.clone();
+    let class_file = match &args.verify {
+        Some(path) => Some(ClassFile::load(path, &class)?),
+        None => None,
+    };
+
+    let mut entries = Vec::new();
+    for impl_item in &mut item.items {
+        let method = match impl_item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        let mut method_args = MethodArgs::default();
+        let mut error = None;
+        method.attrs.retain(|attr| {
+            if !attr.path().is_ident("jni") {
+                return true;
+            }
+            let parsed = attr.parse_nested_meta(|meta| {
+                let value = meta.value()?.parse::<LitStr>()?.value();
+                if meta.path.is_ident("name") {
+                    method_args.name = Some(value);
+                } else if meta.path.is_ident("sig") {
+                    method_args.sig = Some(value);
+                } else {
+                    return Err(meta.error("expected `name` or `sig`"));
+                }
+                Ok(())
+            });
+            if let Err(err) = parsed {
+                error = Some(err);
+            }
+            false
+        });
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        // Only `extern "system"` functions can be native methods, so other
+        // functions in the impl block are left alone
+        let is_native = matches!(
+            &method.sig.abi,
+            Some(Abi { name: Some(name), .. }) if name.value() == "system"
+        );
+        if !is_native {
+            continue;
+        }
+
+        let ident = &method.sig.ident;
+        let sig = match method_args.sig {
+            Some(sig) => sig,
+            None => method_signature(&args, &method.sig)?,
+        };
+        let name = method_args
+            .name
+            .unwrap_or_else(|| camel_case(&ident.to_string()));
+        if let Some(class_file) = &class_file {
+            class_file.check(&name, &sig, ident.span())?;
+        }
+        let fn_ptr = format_ident!("__jni_native_fn_ptr_{}", ident);
+        let java_type = java_declaration(&class, &name, &sig);
+        entries.push(quote! {
+            ::jni::registry::NativeEntry {
+                class_name: #class,
+                name: #name,
+                sig: #sig,
+                fn_ptr: {
+                    fn #fn_ptr() -> *mut ::std::ffi::c_void {
+                        <#self_ty>::#ident as *mut ::std::ffi::c_void
+                    }
+                    #fn_ptr
+                },
+                rust_fn: ::std::concat!(
+                    ::std::module_path!(),
+                    "::",
+                    ::std::stringify!(#self_ty),
+                    "::",
+                    ::std::stringify!(#ident)
+                ),
+                java_type: #java_type,
+                location: ::std::panic::Location::caller(),
+            }
+        });
+    }
+
+    let register = if args.register {
+        quote! {
+            /// Registers the native methods of this type with the Java class.
+            pub fn register(env: &mut ::jni::JNIEnv) -> ::jni::errors::Result<()> {
+                ::jni::registry::register_entries(env, Self::NATIVE_METHODS)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Rebuild when a type map file changes
+    let type_map_files = &args.type_map_files;
+    let verified = class_file.map(|class_file| class_file.track());
+
+    Ok(quote! {
+        #item
+
+        #(const _: &[u8] = ::std::include_bytes!(#type_map_files);)*
+        #verified
+
+        impl #self_ty {
+            /// The native methods of this type, which are the `extern "system"`
+            /// functions of its `#[jni_class]` impl block.
+            pub const NATIVE_METHODS: &'static [::jni::registry::NativeEntry] = &[#(#entries),*];
+
+            #register
+        }
+    })
+}
+
+/// Infers the JNI type signature of a native method from its parameters,
+/// which start with the `JNIEnv` and the object or class that the method is
+/// called on.
+fn method_signature(args: &ClassArgs, sig: &Signature) -> Result<String> {
+    if sig.inputs.len() < 2 {
+        return Err(Error::new(
+            sig.span(),
+            "a native method takes a `JNIEnv` and a `JObject` or `JClass` as its first parameters",
+        ));
+    }
+    let mut signature = String::from("(");
+    for input in sig.inputs.iter().skip(2) {
+        match input {
+            FnArg::Typed(arg) => signature.push_str(&args.signature(&arg.ty)?),
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new(
+                    receiver.span(),
+                    "a native method can't take `self`",
+                ))
+            }
+        }
+    }
+    signature.push(')');
+    match &sig.output {
+        ReturnType::Default => signature.push('V'),
+        ReturnType::Type(_, ty) => signature.push_str(&args.signature(ty)?),
+    }
+    Ok(signature)
+}
+
+/// Returns a description of a native method as it would be declared in Java,
+/// such as `native int com.example.Calculator.add(int, int)`.
+fn java_declaration(class: &str, name: &str, sig: &str) -> String {
+    let class = class.replace('/', ".");
+    let parsed = sig.strip_prefix('(').and_then(|sig| {
+        let (mut args, ret) = sig.split_once(')')?;
+        let mut arg_types = Vec::new();
+        while !args.is_empty() {
+            let (arg_type, rest) = java_type_name(args)?;
+            arg_types.push(arg_type);
+            args = rest;
+        }
+        match java_type_name(ret)? {
+            (ret, "") => Some((ret, arg_types.join(", "))),
+            _ => None,
+        }
+    });
+    match parsed {
+        Some((ret, args)) => format!("native {} {}.{}({})", ret, class, name, args),
+        None => format!("native {}.{}{}", class, name, sig),
+    }
+}
+
+/// Returns the Java name of the first type in a JNI type signature, such as
+/// `int` or `java.lang.String[]`, along with the rest of the signature.
+fn java_type_name(sig: &str) -> Option<(String, &str)> {
+    let name = match sig.chars().next()? {
+        'Z' => "boolean",
+        'B' => "byte",
+        'C' => "char",
+        'S' => "short",
+        'I' => "int",
+        'J' => "long",
+        'F' => "float",
+        'D' => "double",
+        'V' => "void",
+        'L' => {
+            let (class, rest) = sig[1..].split_once(';')?;
+            return Some((class.replace(['/', '$'], "."), rest));
+        }
+        '[' => {
+            let (element, rest) = java_type_name(&sig[1..])?;
+            return Some((format!("{}[]", element), rest));
+        }
+        _ => return None,
+    };
+    Some((name.to_owned(), &sig[1..]))
+}
@@ -0,0 +1,38 @@
+package io.github.jni_rs;
+
+import java.util.function.BiConsumer;
+
+/**
+ * A {@link BiConsumer} that's registered with
+ * {@code CompletableFuture.whenComplete} to wake a Rust future, which is
+ * created by {@code JCompletableFuture::into_future} in jni-rs.
+ *
+ * <p>The class is compiled with {@code javac --release 8 -g:none} and bundled
+ * with jni-rs as {@code RustCompletionHandler.class}, which is defined at
+ * runtime the first time that it's needed.
+ */
+final class RustCompletionHandler implements BiConsumer<Object, Throwable> {
+    /** A pointer to the Rust future's shared state, or 0 once it's been taken. */
+    private long state;
+
+    private RustCompletionHandler(long state) {
+        this.state = state;
+    }
+
+    @Override
+    public void accept(Object value, Throwable exception) {
+        long state = take();
+        if (state != 0) {
+            complete(state, value, exception);
+        }
+    }
+
+    /** Takes ownership of the state, so that it's completed (or dropped) only once. */
+    private synchronized long take() {
+        long state = this.state;
+        this.state = 0;
+        return state;
+    }
+
+    private static native void complete(long state, Object value, Throwable exception);
+}
@@ -0,0 +1,35 @@
+package io.github.jni_rs;
+
+/**
+ * A {@link Runnable} that runs a Rust closure, which is created by
+ * {@code JRunnable::from_fn} in jni-rs.
+ *
+ * <p>The class is compiled with {@code javac --release 8 -g:none} and bundled
+ * with jni-rs as {@code RustRunnable.class}, which is defined at runtime the
+ * first time that it's needed.
+ */
+final class RustRunnable implements Runnable {
+    /** A pointer to the boxed Rust closure, or 0 once it's been taken. */
+    private long closure;
+
+    private RustRunnable(long closure) {
+        this.closure = closure;
+    }
+
+    @Override
+    public void run() {
+        long closure = take();
+        if (closure != 0) {
+            run(closure);
+        }
+    }
+
+    /** Takes ownership of the closure, so that it's run (or dropped) only once. */
+    private synchronized long take() {
+        long closure = this.closure;
+        this.closure = 0;
+        return closure;
+    }
+
+    private static native void run(long closure);
+}
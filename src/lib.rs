@@ -223,13 +223,41 @@ mod wrapper {
     mod jnienv;
     pub use self::jnienv::*;
 
+    /// Registration of the native methods of multiple classes.
+    pub mod registry;
+
     /// Java VM interface.
     mod java_vm;
     pub use self::java_vm::*;
 
+    /// Raw bindings and a minimal safe layer for the JVM Tool Interface.
+    pub mod jvmti;
+
     /// Optional thread attachment manager.
     mod executor;
     pub use self::executor::*;
+
+    #[cfg(feature = "serde")]
+    pub mod serde;
+
+    #[cfg(any(feature = "local-ref-tracking", feature = "strict-exception-checks"))]
+    pub mod diagnostics;
+
+    #[cfg(feature = "local-ref-tracking")]
+    pub mod local_ref_tracking;
+
+    #[cfg(feature = "stats")]
+    pub mod stats;
+
+    #[cfg(feature = "strict-exception-checks")]
+    pub mod strict_exception_checks;
+
+    #[cfg(feature = "android")]
+    pub mod android;
+
+    /// Conversion of Rust types to and from Java values, including structs
+    /// that correspond to Java classes.
+    pub mod convert;
 }
 
 pub use wrapper::*;
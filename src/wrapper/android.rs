@@ -0,0 +1,96 @@
+//! Android-specific utilities.
+//!
+//! With the `android` feature, [`MainThread`] can run Rust closures on the
+//! main (UI) thread of an Android application, which is the only thread that
+//! can touch views and some other platform APIs.
+//!
+//! The closures are run by [`JRunnable`]s, so the application has to include
+//! the `RustRunnable` helper class (see [`JRunnable::from_fn`]).
+
+use crate::{
+    errors::Result,
+    objects::{JObject, JRunnable, JValue},
+    JNIEnv,
+};
+
+/// The main thread of an Android application, which runs the main
+/// `android.os.Looper`.
+pub struct MainThread;
+
+impl MainThread {
+    /// Posts a Rust closure to be run on the main thread, with an attached
+    /// [`JNIEnv`], via `new Handler(Looper.getMainLooper()).post(Runnable)`.
+    ///
+    /// This returns without waiting for the closure to run, even if it's
+    /// called on the main thread. See [`JRunnable::from_fn`] for how the
+    /// closure is run, and how errors and panics are reported.
+    ///
+    /// Returns `Ok(false)` (and drops the closure without running it) if the
+    /// main looper is exiting.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{android::MainThread, errors::Result, objects::GlobalRef, JNIEnv};
+    /// #
+    /// # fn example(env: &mut JNIEnv, view: GlobalRef) -> Result<()> {
+    /// MainThread::post(env, move |env| {
+    ///     env.call_method(&view, "invalidate", "()V", &[])?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn post<F>(env: &mut JNIEnv, f: F) -> Result<bool>
+    where
+        F: FnOnce(&mut JNIEnv) -> Result<()> + Send + 'static,
+    {
+        env.with_local_frame(4, |env| {
+            let looper = Self::looper(env)?;
+            let handler = env.new_object(
+                "android/os/Handler",
+                "(Landroid/os/Looper;)V",
+                &[JValue::from(&looper)],
+            )?;
+            let runnable = JRunnable::from_fn(env, f)?;
+            let posted = env
+                .call_method(
+                    &handler,
+                    "post",
+                    "(Ljava/lang/Runnable;)Z",
+                    &[JValue::from(&runnable)],
+                )
+                .and_then(|posted| posted.z());
+            if !matches!(posted, Ok(true)) {
+                runnable.cancel(env)?;
+            }
+            posted
+        })
+    }
+
+    /// Checks whether the current thread is the main thread.
+    pub fn is_current(env: &mut JNIEnv) -> Result<bool> {
+        env.with_local_frame(2, |env| {
+            let main = Self::looper(env)?;
+            let current = env
+                .call_static_method(
+                    "android/os/Looper",
+                    "myLooper",
+                    "()Landroid/os/Looper;",
+                    &[],
+                )?
+                .l()?;
+            Ok(env.is_same_object(&main, &current))
+        })
+    }
+
+    /// Returns the main looper (`Looper.getMainLooper()`).
+    fn looper<'local>(env: &mut JNIEnv<'local>) -> Result<JObject<'local>> {
+        env.call_static_method(
+            "android/os/Looper",
+            "getMainLooper",
+            "()Landroid/os/Looper;",
+            &[],
+        )?
+        .l()
+    }
+}
@@ -0,0 +1,748 @@
+use std::{
+    str::FromStr,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use crate::{
+    errors::{Error, JniError, Result},
+    objects::{
+        GlobalRef, JClass, JFieldID, JMethodID, JObject, JString, JValue, JValueOwned, Reference,
+    },
+    signature::{JavaType, Primitive, ReturnType, TypeSignature},
+    sys::jvalue,
+    JNIEnv,
+};
+
+#[cfg(feature = "macros")]
+pub use jni_macros::{jni_call, FromJava, IntoJava};
+
+/// A Rust type that can be converted into a Java value.
+///
+/// This is implemented for the Java primitive types (`bool`, `i8`, `i16`,
+/// `i32`, `i64`, `f32` and `f64`), for [`String`], and for [`Option`]s of
+/// types that are converted into Java objects (where `None` is converted into
+/// `null`).
+///
+/// With the `macros` feature, it can be derived for a struct that
+/// corresponds to a Java class, with `#[derive(IntoJava)]`. See the
+/// documentation of the `IntoJava` derive macro for details.
+pub trait IntoJava {
+    /// The JNI type signature of the Java value, such as `"I"` or
+    /// `"Ljava/lang/String;"`.
+    ///
+    /// This is used to build the signatures of the Java methods that a value
+    /// is passed to, so [`IntoJava::to_java`] must return a value of this
+    /// type.
+    const SIGNATURE: &'static str;
+
+    /// Converts this value into a Java value.
+    ///
+    /// If the Java value is an object, the returned local reference belongs to
+    /// the caller.
+    fn to_java<'local>(&self, env: &mut JNIEnv<'local>) -> Result<JValueOwned<'local>>;
+}
+
+/// A Rust type that can be converted from a Java value.
+///
+/// This is implemented for the same types as [`IntoJava`], and it can also be
+/// derived with the `macros` feature. See the documentation of the
+/// `FromJava` derive macro for details.
+pub trait FromJava: Sized {
+    /// The JNI type signature of the Java value, such as `"I"` or
+    /// `"Ljava/lang/String;"`.
+    ///
+    /// This is used to build the signatures of the Java methods that a value
+    /// is returned from.
+    const SIGNATURE: &'static str;
+
+    /// Converts a Java value into this type.
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongJValueType`] if `value` isn't of the type given by
+    /// [`FromJava::SIGNATURE`].
+    fn from_java(env: &mut JNIEnv, value: JValue) -> Result<Self>;
+}
+
+macro_rules! convert_primitive {
+    ($type:ty, $sig:literal, $getter:ident) => {
+        impl IntoJava for $type {
+            const SIGNATURE: &'static str = $sig;
+
+            fn to_java<'local>(&self, _env: &mut JNIEnv<'local>) -> Result<JValueOwned<'local>> {
+                Ok(JValueOwned::from(*self))
+            }
+        }
+
+        impl FromJava for $type {
+            const SIGNATURE: &'static str = $sig;
+
+            fn from_java(_env: &mut JNIEnv, value: JValue) -> Result<Self> {
+                value.$getter()
+            }
+        }
+    };
+}
+
+convert_primitive!(bool, "Z", z);
+convert_primitive!(i8, "B", b);
+convert_primitive!(i16, "S", s);
+convert_primitive!(i32, "I", i);
+convert_primitive!(i64, "J", j);
+convert_primitive!(f32, "F", f);
+convert_primitive!(f64, "D", d);
+
+impl IntoJava for String {
+    const SIGNATURE: &'static str = "Ljava/lang/String;";
+
+    fn to_java<'local>(&self, env: &mut JNIEnv<'local>) -> Result<JValueOwned<'local>> {
+        Ok(JObject::from(env.new_string(self)?).into())
+    }
+}
+
+impl FromJava for String {
+    const SIGNATURE: &'static str = "Ljava/lang/String;";
+
+    fn from_java(env: &mut JNIEnv, value: JValue) -> Result<Self> {
+        let obj = null_check!(value.l()?, "String::from_java value argument")?;
+        let class = JString::lookup_class(env)?;
+        let is_string = env.is_instance_of(obj, &class);
+        env.delete_local_ref(class);
+        if !is_string? {
+            return Err(Error::WrongJValueType("java.lang.String", "object"));
+        }
+        <&JString>::from(obj).to_rust_string(env)
+    }
+}
+
+/// Checks that `sig` is the signature of an object (or array) type.
+///
+/// This is evaluated at compile time, so that `Option<T>` can't be converted
+/// for a primitive `T`.
+const fn object_signature(sig: &'static str) -> &'static str {
+    let first = sig.as_bytes()[0];
+    assert!(
+        first == b'L' || first == b'[',
+        "Option<T> can only be converted if T is converted to a Java object"
+    );
+    sig
+}
+
+impl<T: IntoJava> IntoJava for Option<T> {
+    const SIGNATURE: &'static str = object_signature(T::SIGNATURE);
+
+    fn to_java<'local>(&self, env: &mut JNIEnv<'local>) -> Result<JValueOwned<'local>> {
+        let _ = Self::SIGNATURE;
+        match self {
+            Some(value) => value.to_java(env),
+            None => Ok(JObject::null().into()),
+        }
+    }
+}
+
+impl<T: FromJava> FromJava for Option<T> {
+    const SIGNATURE: &'static str = object_signature(T::SIGNATURE);
+
+    fn from_java(env: &mut JNIEnv, value: JValue) -> Result<Self> {
+        let _ = Self::SIGNATURE;
+        if value.l()?.is_null() {
+            return Ok(None);
+        }
+        T::from_java(env, value).map(Some)
+    }
+}
+
+/// Returns the value in `cache`, or looks up the class with the given name,
+/// creates the value from it with `init` and stores it in `cache` if this is
+/// the first call.
+///
+/// This is for values that are looked up once and then shared by all
+/// threads, such as method IDs. If multiple threads race to make the first
+/// call, each of them creates a value, and the one that's stored first is
+/// kept.
+pub(crate) fn lookup_cached<'cache, T>(
+    cache: &'cache OnceLock<T>,
+    env: &mut JNIEnv,
+    class: &str,
+    init: impl FnOnce(&mut JNIEnv, &JClass) -> Result<T>,
+) -> Result<&'cache T> {
+    if let Some(cached) = cache.get() {
+        return Ok(cached);
+    }
+
+    let class = env.find_class(class)?;
+    let class = env.auto_local(class);
+    let value = init(env, &class)?;
+    Ok(cache.get_or_init(|| value))
+}
+
+/// A method of a [`CachedMethods`].
+#[derive(Debug)]
+struct CachedMethod {
+    id: JMethodID,
+    sig: TypeSignature,
+}
+
+/// A Java class, along with the IDs of some of its methods, which are looked
+/// up once and then shared by all threads.
+///
+/// This is used by the code that's generated by the [`IntoJava`] and
+/// [`FromJava`] derive macros, but it can also be used directly to call a
+/// fixed set of methods of a class without looking up their IDs each time.
+/// The argument and return types of each call are checked against the
+/// method's signature, in the same way as [`JNIEnv::call_method`], so these
+/// calls are safe.
+///
+/// ```rust,no_run
+/// # use jni::{convert::CachedMethods, errors::Result, objects::JObject, JNIEnv};
+/// # use std::sync::OnceLock;
+/// #
+/// # fn example<'local>(env: &mut JNIEnv<'local>) -> Result<JObject<'local>> {
+/// static METHODS: OnceLock<CachedMethods> = OnceLock::new();
+///
+/// let methods = CachedMethods::lookup(&METHODS, env, "java/lang/Thread", || {
+///     vec![("<init>", "()V".to_owned()), ("setName", "(Ljava/lang/String;)V".to_owned())]
+/// })?;
+/// let thread = methods.new_object(env, 0, vec![])?;
+/// let name = env.new_string("worker")?;
+/// methods.call_method(env, &thread, 1, vec![JObject::from(name).into()])?;
+/// # Ok(thread)
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachedMethods {
+    class: GlobalRef,
+    methods: Vec<CachedMethod>,
+}
+
+impl CachedMethods {
+    /// Returns the `CachedMethods` in `cache`, or looks up the class and its
+    /// methods and stores them in `cache` if this is the first call.
+    ///
+    /// `class` is the name of the class, as it's passed to
+    /// [`JNIEnv::find_class`]. `methods` returns the name and the signature of
+    /// each method, and constructors can be included with the name
+    /// `"<init>"`. Methods are referred to by their index in this list.
+    pub fn lookup<'cache>(
+        cache: &'cache OnceLock<CachedMethods>,
+        env: &mut JNIEnv,
+        class: &str,
+        methods: impl FnOnce() -> Vec<(&'static str, String)>,
+    ) -> Result<&'cache CachedMethods> {
+        lookup_cached(cache, env, class, |env, class| {
+            let methods = methods()
+                .into_iter()
+                .map(|(name, sig)| {
+                    Ok(CachedMethod {
+                        id: env.get_method_id(class, name, &sig)?,
+                        sig: TypeSignature::from_str(&sig)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok(CachedMethods {
+                class: env.new_global_ref(class)?,
+                methods,
+            })
+        })
+    }
+
+    /// Returns a reference to the class.
+    pub fn class(&self) -> &GlobalRef {
+        &self.class
+    }
+
+    /// Checks that `obj` is a (non-`null`) instance of the class.
+    ///
+    /// # Errors
+    /// Returns [`Error::NullPtr`] if `obj` is `null`, and [`Error::JniCall`]
+    /// with [`JniError::InvalidArguments`] if it's an instance of some other
+    /// class.
+    pub fn check_instance(&self, env: &mut JNIEnv, obj: &JObject) -> Result<()> {
+        let obj = null_check!(obj, "check_instance obj argument")?;
+        if env.is_instance_of(obj, &self.class)? {
+            Ok(())
+        } else {
+            Err(Error::JniCall(JniError::InvalidArguments))
+        }
+    }
+
+    /// Calls the constructor at `index` to create a new object. The local
+    /// references in `args` are deleted afterwards.
+    pub fn new_object<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        index: usize,
+        args: Vec<JValueOwned<'local>>,
+    ) -> Result<JObject<'local>> {
+        let method = self.method(index, &args)?;
+        if method.sig.ret != ReturnType::Primitive(Primitive::Void) {
+            delete_args(env, args);
+            return Err(Error::InvalidCtorReturn);
+        }
+        let jni_args: Vec<jvalue> = args.iter().map(|arg| arg.as_jni()).collect();
+        // Safety: the method ID is for a constructor of `self.class`, and the argument types were
+        // checked against its signature.
+        let obj = unsafe { env.new_object_unchecked(&self.class, method.id, &jni_args) };
+        delete_args(env, args);
+        obj
+    }
+
+    /// Calls the method at `index` on `obj`. The local references in `args`
+    /// are deleted afterwards.
+    ///
+    /// # Errors
+    /// See [`CachedMethods::check_instance`] for the errors that are returned
+    /// if `obj` isn't an instance of the class.
+    pub fn call_method<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        obj: &JObject,
+        index: usize,
+        args: Vec<JValueOwned<'local>>,
+    ) -> Result<JValueOwned<'local>> {
+        let method = match self
+            .method(index, &args)
+            .and_then(|method| self.check_instance(env, obj).map(|()| method))
+        {
+            Ok(method) => method,
+            Err(err) => {
+                delete_args(env, args);
+                return Err(err);
+            }
+        };
+        let jni_args: Vec<jvalue> = args.iter().map(|arg| arg.as_jni()).collect();
+        // Safety: the method ID is for a method of `self.class`, `obj` is an instance of that
+        // class, and the argument types were checked against the method's signature.
+        let ret =
+            unsafe { env.call_method_unchecked(obj, method.id, method.sig.ret.clone(), &jni_args) };
+        delete_args(env, args);
+        ret
+    }
+
+    /// Calls the getter method at `index` on `obj`, and converts the value that
+    /// it returns with [`FromJava`].
+    pub fn get<T: FromJava>(&self, env: &mut JNIEnv, obj: &JObject, index: usize) -> Result<T> {
+        let value = self.call_method(env, obj, index, Vec::new())?;
+        let res = T::from_java(env, value.borrow());
+        if let JValueOwned::Object(obj) = value {
+            env.delete_local_ref(obj);
+        }
+        res
+    }
+
+    /// Converts `value` with [`IntoJava`], and calls the setter method at
+    /// `index` on `obj` with it.
+    pub fn set<T: IntoJava + ?Sized>(
+        &self,
+        env: &mut JNIEnv,
+        obj: &JObject,
+        index: usize,
+        value: &T,
+    ) -> Result<()> {
+        let value = value.to_java(env)?;
+        let ret = self.call_method(env, obj, index, vec![value])?;
+        if let JValueOwned::Object(obj) = ret {
+            env.delete_local_ref(obj);
+        }
+        Ok(())
+    }
+
+    /// Returns the method at `index`, after checking that `args` matches its
+    /// signature.
+    fn method(&self, index: usize, args: &[JValueOwned]) -> Result<&CachedMethod> {
+        let method = self
+            .methods
+            .get(index)
+            .ok_or(Error::JniCall(JniError::InvalidArguments))?;
+        let args_match = method.sig.args.len() == args.len()
+            && method
+                .sig
+                .args
+                .iter()
+                .zip(args)
+                .all(|(exp, act)| match exp {
+                    JavaType::Primitive(p) => act.primitive_type() == Some(*p),
+                    JavaType::Object(_) | JavaType::Array(_) => act.primitive_type().is_none(),
+                    JavaType::Method(_) => false,
+                });
+        if !args_match {
+            return Err(Error::InvalidArgList(method.sig.clone()));
+        }
+        Ok(method)
+    }
+}
+
+/// A Java enum class, along with the constants that correspond to the
+/// variants of a Rust enum, which are cached like a [`CachedMethods`].
+///
+/// This is used by the code that's generated by the [`IntoJava`] and
+/// [`FromJava`] derive macros for an enum. A Java constant is converted to a
+/// Rust variant by its `ordinal()`, without comparing names, and a Rust
+/// variant is converted to a new local reference to the Java constant.
+///
+/// ```rust,no_run
+/// # use jni::{convert::CachedEnum, errors::Result, objects::JObject, JNIEnv};
+/// # use std::sync::OnceLock;
+/// #
+/// # fn example(env: &mut JNIEnv, unit: &JObject) -> Result<bool> {
+/// static CONSTANTS: OnceLock<CachedEnum> = OnceLock::new();
+///
+/// let constants = CachedEnum::lookup(
+///     &CONSTANTS,
+///     env,
+///     "java/util/concurrent/TimeUnit",
+///     &["SECONDS", "MINUTES"],
+/// )?;
+/// let is_minutes = constants.index_of(env, unit)? == 1;
+/// # Ok(is_minutes)
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachedEnum {
+    class: GlobalRef,
+    /// The constants, in the order that they were looked up in.
+    constants: Vec<GlobalRef>,
+    /// The index of the constant with each ordinal, if it was looked up.
+    by_ordinal: Vec<Option<usize>>,
+    ordinal: JMethodID,
+}
+
+impl CachedEnum {
+    /// Returns the `CachedEnum` in `cache`, or looks up the class and its
+    /// constants and stores them in `cache` if this is the first call.
+    ///
+    /// `class` is the name of the enum class, as it's passed to
+    /// [`JNIEnv::find_class`], and `constants` are the names of its constants
+    /// (its `static final` fields). Constants are referred to by their index
+    /// in this list.
+    pub fn lookup<'cache>(
+        cache: &'cache OnceLock<CachedEnum>,
+        env: &mut JNIEnv,
+        class: &str,
+        constants: &[&str],
+    ) -> Result<&'cache CachedEnum> {
+        let sig = format!("L{};", class);
+        lookup_cached(cache, env, class, |env, class| {
+            let ordinal = env.get_method_id("java/lang/Enum", "ordinal", "()I")?;
+            let mut by_ordinal = Vec::new();
+            let constants = constants
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let constant = env.get_static_field(class, *name, &sig)?.l()?;
+                    let constant = env.auto_local(constant);
+                    // Safety: `ordinal` is a method of `java.lang.Enum`, which the constant is an
+                    // instance of, and it takes no arguments.
+                    let ordinal = unsafe {
+                        env.call_method_unchecked(
+                            &constant,
+                            ordinal,
+                            ReturnType::Primitive(Primitive::Int),
+                            &[],
+                        )
+                    }?
+                    .i()? as usize;
+                    if by_ordinal.len() <= ordinal {
+                        by_ordinal.resize(ordinal + 1, None);
+                    }
+                    by_ordinal[ordinal] = Some(index);
+                    env.new_global_ref(&constant)
+                })
+                .collect::<Result<_>>()?;
+            Ok(CachedEnum {
+                class: env.new_global_ref(class)?,
+                constants,
+                by_ordinal,
+                ordinal,
+            })
+        })
+    }
+
+    /// Returns a reference to the enum class.
+    pub fn class(&self) -> &GlobalRef {
+        &self.class
+    }
+
+    /// Returns a new local reference to the constant at `index`.
+    pub fn constant<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        index: usize,
+    ) -> Result<JObject<'local>> {
+        let constant = self
+            .constants
+            .get(index)
+            .ok_or(Error::JniCall(JniError::InvalidArguments))?;
+        env.new_local_ref(constant)
+    }
+
+    /// Returns the index of the constant that `obj` is, using its `ordinal()`.
+    ///
+    /// # Errors
+    /// Returns [`Error::NullPtr`] if `obj` is `null`, [`Error::JniCall`] with
+    /// [`JniError::InvalidArguments`] if it isn't an instance of the class,
+    /// and [`Error::UnknownEnumConstant`] if it's a constant that wasn't
+    /// looked up.
+    pub fn index_of(&self, env: &mut JNIEnv, obj: &JObject) -> Result<usize> {
+        let obj = null_check!(obj, "index_of obj argument")?;
+        if !env.is_instance_of(obj, &self.class)? {
+            return Err(Error::JniCall(JniError::InvalidArguments));
+        }
+        // Safety: `obj` is an instance of an enum class, and `ordinal` is a method of
+        // `java.lang.Enum` that takes no arguments.
+        let ordinal = unsafe {
+            env.call_method_unchecked(
+                obj,
+                self.ordinal,
+                ReturnType::Primitive(Primitive::Int),
+                &[],
+            )
+        }?
+        .i()?;
+        match self.by_ordinal.get(ordinal as usize) {
+            Some(Some(index)) => Ok(*index),
+            _ => {
+                let name = env
+                    .call_method(obj, "name", "()Ljava/lang/String;", &[])?
+                    .l()?;
+                let name = env.auto_local(JString::from(name));
+                Err(Error::UnknownEnumConstant(name.to_rust_string(env)?))
+            }
+        }
+    }
+}
+
+/// The ID of a method that's called on objects of varying classes, such as
+/// an interface method, along with the class that it was looked up in.
+///
+/// This is used by the code that's generated by the `jni_call!` macro (with
+/// the `macros` feature), where each call site has its own `CachedMethodId`.
+/// The ID is looked up again whenever the object isn't an instance of the
+/// class that it was last looked up in.
+#[derive(Debug)]
+pub struct CachedMethodId {
+    cached: RwLock<Option<(GlobalRef, JMethodID)>>,
+}
+
+impl CachedMethodId {
+    /// Creates an empty cache, which can be stored in a `static`.
+    pub const fn new() -> Self {
+        Self {
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Returns the ID of the method with the given name and signature, for
+    /// calling it on `obj`.
+    ///
+    /// # Errors
+    /// Returns [`Error::NullPtr`] if `obj` is `null`, or an error if the class
+    /// of `obj` doesn't have the method.
+    pub fn lookup(
+        &self,
+        env: &mut JNIEnv,
+        obj: &JObject,
+        name: &str,
+        sig: &str,
+    ) -> Result<JMethodID> {
+        let obj = null_check!(obj, "CachedMethodId obj argument")?;
+        let cached = self
+            .cached
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some((class, id)) = cached {
+            if env.is_instance_of(obj, &class)? {
+                return Ok(id);
+            }
+        }
+
+        let class = env.get_object_class(obj)?;
+        let class = env.auto_local(class);
+        let id = env.get_method_id(&class, name, sig)?;
+        *self.cached.write().unwrap_or_else(PoisonError::into_inner) =
+            Some((env.new_global_ref(&class)?, id));
+        Ok(id)
+    }
+}
+
+impl Default for CachedMethodId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An instance field of a Java class, along with its ID, which is cached like
+/// a [`CachedMethods`].
+///
+/// This is used by the code that's generated by the [`jni_field!`] macro.
+/// The type of each value that's read or written is checked against the
+/// field's signature, and each object is checked to be an instance of the
+/// class, so these accesses are safe.
+#[derive(Debug)]
+pub struct CachedField {
+    class: GlobalRef,
+    id: JFieldID,
+    sig: &'static str,
+    ty: ReturnType,
+}
+
+impl CachedField {
+    /// Returns the `CachedField` in `cache`, or looks up the class and the
+    /// field and stores them in `cache` if this is the first call.
+    ///
+    /// `class` is the name of the class, as it's passed to
+    /// [`JNIEnv::find_class`], and `sig` is the type signature of the field,
+    /// such as `"I"`.
+    pub fn lookup<'cache>(
+        cache: &'cache OnceLock<CachedField>,
+        env: &mut JNIEnv,
+        class: &str,
+        name: &str,
+        sig: &'static str,
+    ) -> Result<&'cache CachedField> {
+        lookup_cached(cache, env, class, |env, class| {
+            Ok(CachedField {
+                ty: ReturnType::from_str(sig)?,
+                id: env.get_field_id(class, name, sig)?,
+                class: env.new_global_ref(class)?,
+                sig,
+            })
+        })
+    }
+
+    /// Returns a reference to the class.
+    pub fn class(&self) -> &GlobalRef {
+        &self.class
+    }
+
+    /// Reads the field of `obj`, and converts its value with [`FromJava`].
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongJValueType`] if [`FromJava::SIGNATURE`] isn't the
+    /// field's signature. See [`CachedMethods::check_instance`] for the errors
+    /// that are returned if `obj` isn't an instance of the class.
+    pub fn get<T: FromJava>(&self, env: &mut JNIEnv, obj: &JObject) -> Result<T> {
+        self.check(env, obj, T::SIGNATURE)?;
+        // Safety: the field ID is for a field of `self.class`, `obj` is an instance of that
+        // class, and `self.ty` was parsed from the field's signature.
+        let value = unsafe { env.get_field_unchecked(obj, self.id, self.ty.clone())? };
+        let res = T::from_java(env, value.borrow());
+        if let JValueOwned::Object(obj) = value {
+            env.delete_local_ref(obj);
+        }
+        res
+    }
+
+    /// Converts `value` with [`IntoJava`], and writes it to the field of
+    /// `obj`.
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongJValueType`] if [`IntoJava::SIGNATURE`] isn't the
+    /// field's signature. See [`CachedMethods::check_instance`] for the errors
+    /// that are returned if `obj` isn't an instance of the class.
+    pub fn set<T: IntoJava + ?Sized>(
+        &self,
+        env: &mut JNIEnv,
+        obj: &JObject,
+        value: &T,
+    ) -> Result<()> {
+        self.check(env, obj, T::SIGNATURE)?;
+        let value = value.to_java(env)?;
+        // Safety: the field ID is for a field of `self.class`, `obj` is an instance of that
+        // class, and the value's type matches the field's signature.
+        let res = unsafe { env.set_field_unchecked(obj, self.id, value.borrow()) };
+        if let JValueOwned::Object(obj) = value {
+            env.delete_local_ref(obj);
+        }
+        res
+    }
+
+    /// Checks that `sig` is the field's signature, and that `obj` is a
+    /// (non-`null`) instance of the class.
+    fn check(&self, env: &mut JNIEnv, obj: &JObject, sig: &'static str) -> Result<()> {
+        if sig != self.sig {
+            return Err(Error::WrongJValueType(self.sig, sig));
+        }
+        let obj = null_check!(obj, "CachedField obj argument")?;
+        if env.is_instance_of(obj, &self.class)? {
+            Ok(())
+        } else {
+            Err(Error::JniCall(JniError::InvalidArguments))
+        }
+    }
+}
+
+/// Declares a module with `get` and `set` functions for an instance field of
+/// a Java class, which look up the field's ID once and cache it.
+///
+/// The type of the field is a Rust type that implements [`IntoJava`] and
+/// [`FromJava`], such as [`jint`](crate::sys::jint) or [`String`], and its
+/// JNI signature is [`FromJava::SIGNATURE`]. The ID is looked up with that
+/// signature (which fails if the Java field has a different type), and the
+/// accesses are checked by [`CachedField`]. The name of the Java field is the
+/// name of the module, and the type is resolved in the module that the macro
+/// is used in.
+///
+/// ```rust,no_run
+/// # use jni::{errors::Result, objects::JObject, JNIEnv};
+/// jni::jni_field!(java_type = "java.awt.Point", pub field x: jni::sys::jint);
+///
+/// # fn example(env: &mut JNIEnv, point: &JObject) -> Result<()> {
+/// let value = x::get(env, point)?;
+/// x::set(env, point, &(value + 1))?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! jni_field {
+    (java_type = $class:literal, $vis:vis field $name:ident : $ty:ty $(,)?) => {
+        #[allow(non_snake_case)]
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            static FIELD: ::std::sync::OnceLock<$crate::convert::CachedField> =
+                ::std::sync::OnceLock::new();
+
+            fn field(
+                env: &mut $crate::JNIEnv,
+            ) -> $crate::errors::Result<&'static $crate::convert::CachedField> {
+                if let Some(field) = FIELD.get() {
+                    return Ok(field);
+                }
+                $crate::convert::CachedField::lookup(
+                    &FIELD,
+                    env,
+                    &$class.replace('.', "/"),
+                    ::std::stringify!($name),
+                    <$ty as $crate::convert::FromJava>::SIGNATURE,
+                )
+            }
+
+            /// Reads the field of `obj`.
+            pub fn get(
+                env: &mut $crate::JNIEnv,
+                obj: &$crate::objects::JObject,
+            ) -> $crate::errors::Result<$ty> {
+                field(env)?.get(env, obj)
+            }
+
+            /// Writes `value` to the field of `obj`.
+            pub fn set(
+                env: &mut $crate::JNIEnv,
+                obj: &$crate::objects::JObject,
+                value: &$ty,
+            ) -> $crate::errors::Result<()> {
+                field(env)?.set(env, obj, value)
+            }
+        }
+    };
+}
+
+fn delete_args(env: &JNIEnv, args: Vec<JValueOwned>) {
+    for arg in args {
+        if let JValueOwned::Object(obj) = arg {
+            env.delete_local_ref(obj);
+        }
+    }
+}
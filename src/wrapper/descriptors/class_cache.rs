@@ -0,0 +1,63 @@
+use std::{
+    collections::BTreeMap,
+    ffi::CString,
+    sync::{PoisonError, RwLock},
+};
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass},
+    strings::JNIString,
+    JNIEnv,
+};
+
+/// Classes in this package are always loaded by the bootstrap class loader, so
+/// looking them up by name gives the same class regardless of which class
+/// loader `FindClass` would have used.
+const WELL_KNOWN_PREFIX: &[u8] = b"java/lang/";
+
+/// Global references to classes that are looked up by name without calling
+/// `FindClass`, keyed by the modified UTF-8 class name.
+static CLASSES: RwLock<BTreeMap<CString, GlobalRef>> = RwLock::new(BTreeMap::new());
+
+/// Looks up a class by name, checking the class cache before calling
+/// `FindClass`.
+///
+/// Well-known classes (in the `java.lang` package) are added to the cache the
+/// first time that they are found.
+pub(crate) fn find_class<'local>(
+    env: &mut JNIEnv<'local>,
+    name: JNIString,
+) -> Result<JClass<'local>> {
+    let cached = CLASSES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name.as_cstr())
+        .cloned();
+    if let Some(class) = cached {
+        return Ok(JClass::from(env.new_local_ref(&class)?));
+    }
+
+    if !name.as_cstr().to_bytes().starts_with(WELL_KNOWN_PREFIX) {
+        return env.find_class(name);
+    }
+
+    let key = name.as_cstr().to_owned();
+    let class = env.find_class(name)?;
+    insert(key, env.new_global_ref(&class)?);
+    Ok(class)
+}
+
+/// Adds a class to the cache, replacing any existing class with the same name.
+pub(crate) fn insert(name: CString, class: GlobalRef) {
+    CLASSES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name, class);
+}
+
+/// Removes all classes from the cache, deleting their global references.
+pub(crate) fn clear() {
+    let classes = std::mem::take(&mut *CLASSES.write().unwrap_or_else(PoisonError::into_inner));
+    drop(classes);
+}
@@ -1,5 +1,5 @@
 use crate::{
-    descriptors::Desc,
+    descriptors::{class_cache, Desc},
     errors::*,
     objects::{AutoLocal, GlobalRef, JClass, JObject},
     strings::JNIString,
@@ -13,7 +13,8 @@ where
     type Output = AutoLocal<'local, JClass<'local>>;
 
     fn lookup(self, env: &mut JNIEnv<'local>) -> Result<Self::Output> {
-        Ok(AutoLocal::new(env.find_class(self)?, env))
+        let class = class_cache::find_class(env, self.into())?;
+        Ok(AutoLocal::new(class, env))
     }
 }
 
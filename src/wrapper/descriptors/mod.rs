@@ -1,6 +1,8 @@
 mod desc;
 pub use self::desc::*;
 
+pub(crate) mod class_cache;
+
 mod class_desc;
 
 mod method_desc;
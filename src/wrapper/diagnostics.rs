@@ -0,0 +1,44 @@
+//! Settings that are shared by the diagnostic features,
+//! `local-ref-tracking` and `strict-exception-checks`.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// What to do when a diagnostic feature finds a problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Log an error, with the `log` crate, and carry on.
+    Log,
+    /// Panic.
+    Panic,
+}
+
+const ACTION_DEFAULT: u8 = 0;
+const ACTION_LOG: u8 = 1;
+const ACTION_PANIC: u8 = 2;
+
+/// The [`Action`] of a diagnostic feature, which defaults to
+/// [`Action::Panic`] in debug builds and [`Action::Log`] otherwise.
+pub(crate) struct ActionSetting(AtomicU8);
+
+impl ActionSetting {
+    pub(crate) const fn new() -> Self {
+        Self(AtomicU8::new(ACTION_DEFAULT))
+    }
+
+    pub(crate) fn set(&self, action: Action) {
+        let action = match action {
+            Action::Log => ACTION_LOG,
+            Action::Panic => ACTION_PANIC,
+        };
+        self.0.store(action, Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> Action {
+        match self.0.load(Ordering::Relaxed) {
+            ACTION_LOG => Action::Log,
+            ACTION_PANIC => Action::Panic,
+            _ if cfg!(debug_assertions) => Action::Panic,
+            _ => Action::Log,
+        }
+    }
+}
@@ -1,11 +1,19 @@
 #![allow(missing_docs)]
 
-use std::char::{CharTryFromError, DecodeUtf16Error};
+use std::{
+    any::{Any, TypeId},
+    char::{CharTryFromError, DecodeUtf16Error},
+    collections::BTreeMap,
+    fmt::Display,
+    sync::{PoisonError, RwLock},
+};
 
 use thiserror::Error;
 
+use crate::objects::GlobalRef;
 use crate::sys;
 use crate::wrapper::signature::TypeSignature;
+use crate::JNIEnv;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -69,6 +77,38 @@ pub enum Error {
 
     #[error("This Java virtual machine is too old; at least Java 1.4 is required")]
     UnsupportedVersion,
+
+    /// A time or duration couldn't be converted between Java and Rust because
+    /// it's out of range for the target type.
+    #[error("Time out of range when converting {0}")]
+    TimeOutOfRange(&'static str),
+
+    /// `RegisterNatives` rejected one or more native methods. See
+    /// [`JNIEnv::register_native_methods_checked`](crate::JNIEnv::register_native_methods_checked).
+    #[error("Failed to register native methods: {0}")]
+    RegisterNativesFailed(String),
+
+    /// A Java exception was thrown on another thread, where it couldn't be
+    /// left pending, so it was caught. See
+    /// [`Executor::execute_blocking`](crate::Executor::execute_blocking).
+    #[error("Java exception was thrown and caught")]
+    CaughtJavaException(GlobalRef),
+
+    /// A Java enum constant has no corresponding Rust enum variant. See
+    /// [`CachedEnum`](crate::convert::CachedEnum).
+    #[error("No Rust variant for the Java enum constant {0}")]
+    UnknownEnumConstant(String),
+
+    /// The `JavaVM` has been destroyed by
+    /// [`JavaVM::destroy`](crate::JavaVM::destroy), so threads can't be
+    /// attached to it anymore.
+    #[error("The Java VM has been destroyed")]
+    JavaVMDestroyed,
+
+    /// A JVMTI function failed with the given `jvmtiError` code. See
+    /// [`jvmti`](crate::jvmti).
+    #[error("JVMTI call failed with error code {0}")]
+    JvmtiCall(sys::jint),
 }
 
 #[derive(Debug, Error)]
@@ -118,6 +158,104 @@ pub trait ToException {
     fn to_exception(&self) -> Exception;
 }
 
+/// The class of the exception that's thrown by [`ExceptionMap::throw`] for an
+/// error type that doesn't have a mapping.
+const UNMAPPED_EXCEPTION_CLASS: &str = "java/lang/RuntimeException";
+
+type ExceptionMapping = Box<dyn Fn(&dyn Any) -> Exception + Send + Sync>;
+
+static EXCEPTION_MAPPINGS: RwLock<BTreeMap<TypeId, ExceptionMapping>> =
+    RwLock::new(BTreeMap::new());
+
+/// A global registry of conversions from Rust error types to Java exceptions.
+///
+/// An application registers a conversion for each of its error types once,
+/// for example from `JNI_OnLoad`, and then native methods in any module can
+/// use [`ExceptionMap::throw`] to throw the corresponding Java exception for
+/// an error, instead of each of them choosing an exception class.
+///
+/// # Example
+/// ```rust,no_run
+/// # use jni::{errors::{Exception, ExceptionMap}, JNIEnv};
+/// #
+/// #[derive(Debug)]
+/// enum MyError {
+///     NotFound(String),
+///     Invalid(String),
+/// }
+///
+/// impl std::fmt::Display for MyError {
+///     // ...
+/// #   fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+/// }
+///
+/// ExceptionMap::register(|err: &MyError| match err {
+///     MyError::NotFound(name) => Exception {
+///         class: "java/util/NoSuchElementException".into(),
+///         msg: name.clone(),
+///     },
+///     MyError::Invalid(reason) => Exception {
+///         class: "java/lang/IllegalArgumentException".into(),
+///         msg: reason.clone(),
+///     },
+/// });
+///
+/// # fn example(env: &mut JNIEnv) -> jni::errors::Result<()> {
+/// ExceptionMap::throw(env, &MyError::NotFound("config".into()))?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ExceptionMap(());
+
+impl ExceptionMap {
+    /// Registers the conversion from errors of type `E` to Java exceptions.
+    ///
+    /// This replaces any conversion that was previously registered for `E`.
+    pub fn register<E: 'static>(convert: fn(&E) -> Exception) {
+        let mapping: ExceptionMapping = Box::new(move |err: &dyn Any| {
+            convert(
+                err.downcast_ref::<E>()
+                    .expect("exception mappings are keyed by the error's TypeId"),
+            )
+        });
+        EXCEPTION_MAPPINGS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(TypeId::of::<E>(), mapping);
+    }
+
+    /// Removes the conversion that's registered for errors of type `E`, if
+    /// any.
+    pub fn unregister<E: 'static>() {
+        EXCEPTION_MAPPINGS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&TypeId::of::<E>());
+    }
+
+    /// Returns the Java exception that `err` is mapped to, or `None` if no
+    /// conversion has been registered for errors of type `E`.
+    pub fn lookup<E: 'static>(err: &E) -> Option<Exception> {
+        let mappings = EXCEPTION_MAPPINGS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        mappings.get(&TypeId::of::<E>()).map(|convert| convert(err))
+    }
+
+    /// Throws the Java exception that `err` is mapped to.
+    ///
+    /// If no conversion has been registered for errors of type `E`, a
+    /// `java.lang.RuntimeException` is thrown, with the error's [`Display`]
+    /// output as its message.
+    pub fn throw<E: Display + 'static>(env: &mut JNIEnv, err: &E) -> Result<()> {
+        let exception = Self::lookup(err).unwrap_or_else(|| Exception {
+            class: UNMAPPED_EXCEPTION_CLASS.into(),
+            msg: err.to_string(),
+        });
+        env.throw_new(exception.class, exception.msg)
+    }
+}
+
 /// An error that occurred while starting the JVM using the JNI Invocation API.
 ///
 /// This only exists if the "invocation" feature is enabled.
@@ -146,7 +284,7 @@ pub enum StartJvmError {
     #[error("Couldn't load the Java VM shared library ({0}): {1}")]
     LoadError(String, #[source] libloading::Error),
 
-    /// The JNI function `JNI_CreateJavaVM` returned an error.
+    /// The JNI function `JNI_CreateJavaVM` (or `JNI_GetCreatedJavaVMs`) returned an error.
     #[error("{0}")]
     Create(
         #[from]
@@ -167,3 +305,57 @@ pub struct CharToJavaError {
     /// The character that could not be converted.
     pub char: char,
 }
+
+/// The step of a [`ClassRegistration`] that a [`ClassRegistrationFailure`]
+/// happened in.
+///
+/// [`ClassRegistration`]: crate::registry::ClassRegistration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegistrationStage {
+    /// Loading the class through the class loader.
+    Load,
+    /// Registering the class's native methods.
+    RegisterNatives,
+    /// Running the class's `init` hook.
+    Init,
+}
+
+impl std::fmt::Display for RegistrationStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RegistrationStage::Load => "loading the class",
+            RegistrationStage::RegisterNatives => "registering native methods",
+            RegistrationStage::Init => "running the init hook",
+        })
+    }
+}
+
+/// A single class that failed to be registered by [`register_all`].
+///
+/// [`register_all`]: crate::registry::register_all
+#[derive(Debug, Error)]
+#[error("{class_name}: {stage} failed: {}", exception.as_deref().map_or_else(|| source.to_string(), str::to_owned))]
+pub struct ClassRegistrationFailure {
+    /// The name of the class, as given in the registration.
+    pub class_name: String,
+    /// The step that failed.
+    pub stage: RegistrationStage,
+    /// The cause of the failure.
+    #[source]
+    pub source: Error,
+    /// If `source` is [`Error::JavaException`], the `toString()` of the
+    /// exception that was thrown. The exception itself is cleared so that the
+    /// remaining classes can still be registered.
+    pub exception: Option<String>,
+}
+
+/// Raised by [`register_all`] when one or more classes failed to be registered.
+///
+/// [`register_all`]: crate::registry::register_all
+#[derive(Debug, Error)]
+#[error("failed to register {} class(es): {}", failures.len(), failures.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct RegistrationError {
+    /// Every failure, in the order that the classes were registered.
+    pub failures: Vec<ClassRegistrationFailure>,
+}
@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::{
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use log::error;
 
 use crate::{errors::*, JNIEnv, JavaVM};
 
@@ -17,6 +23,15 @@ pub const DEFAULT_LOCAL_FRAME_CAPACITY: i32 = 32;
 /// Threads using the Executor are attached on the first invocation as daemons,
 /// hence they do not block JVM exit. Finished threads detach automatically.
 ///
+/// ## Thread pool
+///
+/// An executor can also own a small pool of permanently attached threads (see
+/// [`thread_pool`](Executor::thread_pool)). [`execute`](Executor::execute) and
+/// [`execute_blocking`](Executor::execute_blocking) then run closures on those
+/// threads, so that threads which are only occasionally used for JNI work
+/// don't need to be attached at all. The pool's threads exit once the
+/// executor and all of its clones have been dropped.
+///
 /// ## Example
 ///
 /// ```rust
@@ -53,12 +68,108 @@ pub const DEFAULT_LOCAL_FRAME_CAPACITY: i32 = 32;
 #[derive(Clone)]
 pub struct Executor {
     vm: Arc<JavaVM>,
+    local_frame_capacity: i32,
+    pool: Option<Arc<ThreadPool>>,
+}
+
+type Job = Box<dyn FnOnce(&mut JNIEnv) + Send>;
+
+/// The threads of an executor's pool, which receive jobs from a shared
+/// channel. Dropping the sender makes the threads exit.
+struct ThreadPool {
+    sender: Mutex<mpsc::Sender<Job>>,
+}
+
+impl ThreadPool {
+    fn new(vm: &Arc<JavaVM>, threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..threads {
+            let vm = vm.clone();
+            let receiver = receiver.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("jni-executor-{}", i))
+                .spawn(move || {
+                    // Safety: the pool's threads only call into the JVM to run jobs, and
+                    // attaching them fails once the JavaVM has been destroyed
+                    let mut env = match unsafe { vm.attach_current_thread_as_daemon() } {
+                        Ok(env) => env,
+                        Err(err) => {
+                            error!("jni executor thread failed to attach: {:#?}", err);
+                            return;
+                        }
+                    };
+                    loop {
+                        // The lock is released as soon as a job is received
+                        let job = match receiver.lock().unwrap().recv() {
+                            Ok(job) => job,
+                            Err(_) => return,
+                        };
+                        job(&mut env);
+                    }
+                });
+            if let Err(err) = spawned {
+                error!("failed to spawn jni executor thread: {}", err);
+            }
+        }
+        ThreadPool {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Sends a job to the pool, which fails if none of its threads are running.
+    fn send(&self, job: Job) -> Result<()> {
+        self.sender
+            .lock()
+            .unwrap()
+            .send(job)
+            .map_err(|_| Error::JniCall(JniError::ThreadDetached))
+    }
 }
 
 impl Executor {
     /// Creates new Executor with specified JVM.
+    ///
+    /// The executor allocates local frames with
+    /// [the default capacity](constant.DEFAULT_LOCAL_FRAME_CAPACITY.html),
+    /// unless that is changed with
+    /// [`local_frame_capacity`](Executor::local_frame_capacity).
     pub fn new(vm: Arc<JavaVM>) -> Self {
-        Self { vm }
+        Self {
+            vm,
+            local_frame_capacity: DEFAULT_LOCAL_FRAME_CAPACITY,
+            pool: None,
+        }
+    }
+
+    /// Starts a pool of `threads` threads, which are permanently attached to
+    /// the JVM, for [`execute`](Executor::execute) and
+    /// [`execute_blocking`](Executor::execute_blocking) to run closures on.
+    ///
+    /// The threads are attached as daemons, so they don't block JVM exit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn thread_pool(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "a thread pool needs at least one thread");
+        self.pool = Some(Arc::new(ThreadPool::new(&self.vm, threads)));
+        self
+    }
+
+    /// Sets the capacity of the local frames that [`with_attached`](Executor::with_attached)
+    /// allocates.
+    ///
+    /// Closures that create a predictable number of local references can set this once, instead
+    /// of calling [`JNIEnv::ensure_local_capacity`] in each closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` isn't positive.
+    pub fn local_frame_capacity(mut self, capacity: i32) -> Self {
+        assert!(capacity > 0, "capacity should be a positive integer");
+        self.local_frame_capacity = capacity;
+        self
     }
 
     /// Executes a provided closure, making sure that the current thread
@@ -74,6 +185,7 @@ impl Executor {
         assert!(capacity > 0, "capacity should be a positive integer");
 
         let mut jni_env = self.vm.attach_current_thread_permanently()?;
+        jni_env.delete_deferred_refs();
         jni_env.with_local_frame(capacity, |jni_env| f(jni_env))
     }
 
@@ -81,13 +193,108 @@ impl Executor {
     /// is attached to the JVM. Additionally ensures that local object references are freed after
     /// call.
     ///
-    /// Allocates a local frame with
-    /// [the default capacity](constant.DEFAULT_LOCAL_FRAME_CAPACITY.html).
+    /// Allocates a local frame with the capacity that's set by
+    /// [`local_frame_capacity`](Executor::local_frame_capacity), which is
+    /// [`DEFAULT_LOCAL_FRAME_CAPACITY`] by default.
     pub fn with_attached<F, T, E>(&self, f: F) -> std::result::Result<T, E>
     where
         F: FnOnce(&mut JNIEnv) -> std::result::Result<T, E>,
         E: From<Error>,
     {
-        self.with_attached_capacity(DEFAULT_LOCAL_FRAME_CAPACITY, f)
+        self.with_attached_capacity(self.local_frame_capacity, f)
+    }
+
+    /// Runs a closure with an attached [`JNIEnv`] in the background, on one
+    /// of the threads of the [pool](Executor::thread_pool), in a local frame
+    /// that's allocated like [`with_attached`](Executor::with_attached) does.
+    ///
+    /// If the closure returns an error, or panics, it's logged. Any exception
+    /// that's pending once the closure returns is also logged, and cleared. If
+    /// the executor doesn't have a thread pool, the closure is run on the
+    /// current thread (which is attached permanently) before this returns.
+    ///
+    /// Returns `Err(Error::JniCall(JniError::ThreadDetached))` if none of the
+    /// pool's threads are running, which happens if they failed to attach.
+    pub fn execute<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut JNIEnv) -> Result<()> + Send + 'static,
+    {
+        let capacity = self.local_frame_capacity;
+        let job = move |env: &mut JNIEnv| {
+            let res = catch_unwind(AssertUnwindSafe(|| env.with_local_frame(capacity, f)));
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => error!("error in jni executor job: {:#?}", err),
+                Err(_) => error!("jni executor job panicked"),
+            }
+            if let Some(description) = env.take_exception_description() {
+                error!("exception in jni executor job: {}", description);
+            }
+        };
+        match &self.pool {
+            Some(pool) => pool.send(Box::new(job)),
+            None => {
+                let mut env = self.vm.attach_current_thread_permanently()?;
+                job(&mut env);
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs a closure with an attached [`JNIEnv`] on one of the threads of the
+    /// [pool](Executor::thread_pool), and waits for it to return.
+    ///
+    /// This is like [`with_attached`](Executor::with_attached), except that
+    /// the current thread doesn't need to be attached. If the closure panics,
+    /// the panic is resumed on the current thread. If the executor doesn't
+    /// have a thread pool, this is the same as `with_attached`.
+    ///
+    /// An exception can't be left pending on the pool's thread, so if there's
+    /// a pending exception once the closure returns, it's cleared and
+    /// `Err(Error::CaughtJavaException)` is returned, with a reference to the
+    /// exception, instead of the closure's result.
+    ///
+    /// Returns `Err(Error::JniCall(JniError::ThreadDetached))` if none of the
+    /// pool's threads are running, which happens if they failed to attach.
+    pub fn execute_blocking<F, T, E>(&self, f: F) -> std::result::Result<T, E>
+    where
+        F: FnOnce(&mut JNIEnv) -> std::result::Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: From<Error> + Send + 'static,
+    {
+        let pool = match &self.pool {
+            Some(pool) => pool,
+            None => return self.with_attached(f),
+        };
+
+        let capacity = self.local_frame_capacity;
+        let (sender, receiver) = mpsc::sync_channel(1);
+        pool.send(Box::new(move |env: &mut JNIEnv| {
+            let res = catch_unwind(AssertUnwindSafe(|| env.with_local_frame(capacity, f)));
+            let exception = take_exception(env);
+            // The receiver only goes away if the calling thread panicked
+            let _ = sender.send((res, exception));
+        }))?;
+
+        match receiver.recv() {
+            Ok((Err(payload), _)) => resume_unwind(payload),
+            Ok((Ok(_), Some(exception))) => Err(exception.into()),
+            Ok((Ok(res), None)) => res,
+            // The job was dropped without running, since the pool's threads
+            // exited
+            Err(_) => Err(Error::JniCall(JniError::ThreadDetached).into()),
+        }
     }
 }
+
+/// Clears any pending exception, and returns it as an
+/// [`Error::CaughtJavaException`].
+fn take_exception(env: &mut JNIEnv) -> Option<Error> {
+    let exception = env.exception_occurred()?;
+    env.exception_clear();
+    let exception = env.auto_local(exception);
+    Some(match env.new_global_ref(&exception) {
+        Ok(exception) => Error::CaughtJavaException(exception),
+        Err(err) => err,
+    })
+}
@@ -0,0 +1,87 @@
+//! A queue of global and weak global references that were dropped on threads
+//! that aren't attached to the JVM, and so couldn't be deleted straight away.
+//!
+//! The queue is a lock-free stack, which is only ever pushed to or emptied
+//! all at once, so it isn't affected by the ABA problem. Since a process can
+//! only have a single JVM, the queue is a `static` instead of being stored in
+//! each [`JavaVM`](crate::JavaVM).
+
+use std::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{sys, JNIEnv};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RefKind {
+    Global,
+    Weak,
+}
+
+struct Node {
+    raw: sys::jobject,
+    kind: RefKind,
+    next: *mut Node,
+}
+
+static QUEUE: AtomicPtr<Node> = AtomicPtr::new(ptr::null_mut());
+
+/// Deletes a global or weak global reference.
+///
+/// # Safety
+///
+/// `raw` must be a valid reference of the given kind, which isn't used again.
+pub(crate) unsafe fn delete_ref(env: &JNIEnv, raw: sys::jobject, kind: RefKind) {
+    // Safety: These methods are safe to call in case of pending exceptions
+    // (see chapter 2 of the spec), and jni-rs requires JNI_VERSION > 1.2
+    match kind {
+        RefKind::Global => {
+            jni_call_unchecked!(env, v1_1, DeleteGlobalRef, raw);
+            #[cfg(feature = "stats")]
+            crate::stats::global_ref_deleted(raw);
+        }
+        RefKind::Weak => {
+            jni_call_unchecked!(env, v1_2, DeleteWeakGlobalRef, raw);
+            #[cfg(feature = "stats")]
+            crate::stats::weak_ref_deleted(raw);
+        }
+    }
+}
+
+/// Queues a reference to be deleted by the next call to [`flush`].
+///
+/// # Safety
+///
+/// `raw` must be a valid reference of the given kind, which isn't used again.
+pub(crate) unsafe fn defer(raw: sys::jobject, kind: RefKind) {
+    let node = Box::into_raw(Box::new(Node {
+        raw,
+        kind,
+        next: ptr::null_mut(),
+    }));
+    let mut head = QUEUE.load(Ordering::Relaxed);
+    loop {
+        (*node).next = head;
+        match QUEUE.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Deletes all of the references in the queue.
+pub(crate) fn flush(env: &JNIEnv) {
+    if QUEUE.load(Ordering::Relaxed).is_null() {
+        return;
+    }
+    let mut node = QUEUE.swap(ptr::null_mut(), Ordering::Acquire);
+    while !node.is_null() {
+        // Safety: the nodes were created with `Box::into_raw` by `defer`, and
+        // swapping the head out of the queue gives us exclusive ownership of
+        // them
+        let boxed = unsafe { Box::from_raw(node) };
+        unsafe { delete_ref(env, boxed.raw, boxed.kind) };
+        node = boxed.next;
+    }
+}
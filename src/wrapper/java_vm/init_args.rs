@@ -1,9 +1,9 @@
-use std::{borrow::Cow, ffi::CStr, io, os::raw::c_void, ptr};
+use std::{borrow::Cow, env, ffi::CStr, io, os::raw::c_void, path::Path, ptr, sync::PoisonError};
 
 use thiserror::Error;
 
 use crate::{
-    sys::{JavaVMInitArgs, JavaVMOption},
+    sys::{jint, JavaVMInitArgs, JavaVMOption},
     JNIVersion,
 };
 
@@ -11,6 +11,8 @@ use cfg_if::cfg_if;
 
 mod char_encoding_generic;
 
+mod hooks;
+
 #[cfg(windows)]
 mod char_encoding_windows;
 
@@ -73,6 +75,11 @@ pub enum JvmError {
         #[source]
         error: io::Error,
     },
+
+    /// [`InitArgsBuilder::classpath`] was used, but a path contains the platform's path separator
+    /// or isn't valid Unicode.
+    #[error("invalid class path: {0}")]
+    InvalidClassPath(String),
 }
 
 impl JvmError {
@@ -83,6 +90,7 @@ impl JvmError {
             Self::OptStringTooLong { opt_string, .. } => Some(opt_string),
             Self::OptStringNotRepresentable { opt_string, .. } => Some(opt_string),
             Self::OptStringTranscodeFailure { opt_string, .. } => Some(opt_string),
+            Self::InvalidClassPath(_) => None,
         }
         .map(String::as_str)
     }
@@ -94,6 +102,7 @@ impl JvmError {
             Self::OptStringTooLong { opt_string, .. } => Some(opt_string),
             Self::OptStringNotRepresentable { opt_string, .. } => Some(opt_string),
             Self::OptStringTranscodeFailure { opt_string, .. } => Some(opt_string),
+            Self::InvalidClassPath(_) => None,
         }
     }
 }
@@ -108,6 +117,18 @@ const SPECIAL_OPTIONS_C: &[&CStr] = unsafe {
     ]
 };
 
+/// The kinds of events that [`InitArgsBuilder::verbose`] makes the JVM log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VerboseFlag {
+    /// Class loading (`-verbose:class`).
+    Class,
+    /// Garbage collection (`-verbose:gc`).
+    Gc,
+    /// JNI calls, such as the registration of native methods (`-verbose:jni`).
+    Jni,
+}
+
 /// Builder for JavaVM InitArgs.
 ///
 /// *This API requires "invocation" feature to be enabled,
@@ -117,6 +138,10 @@ pub struct InitArgsBuilder<'a> {
     opts: Result<Vec<Cow<'a, CStr>>, JvmError>,
     ignore_unrecognized: bool,
     version: JNIVersion,
+    #[cfg(unix)]
+    vfprintf_hook: Option<fn(&str)>,
+    exit_hook: Option<fn(jint)>,
+    abort_hook: Option<fn()>,
 }
 
 impl<'a> Default for InitArgsBuilder<'a> {
@@ -125,6 +150,10 @@ impl<'a> Default for InitArgsBuilder<'a> {
             opts: Ok(vec![]),
             ignore_unrecognized: false,
             version: JNIVersion::V1_8,
+            #[cfg(unix)]
+            vfprintf_hook: None,
+            exit_hook: None,
+            abort_hook: None,
         }
     }
 }
@@ -139,8 +168,9 @@ impl<'a> InitArgsBuilder<'a> {
     ///
     /// See [the JNI specification][jni-options] for details on which options are accepted.
     ///
-    /// The `vfprintf`, `abort`, and `exit` options are unsupported at this time. Setting one of
-    /// these options has no effect.
+    /// The `vfprintf`, `abort`, and `exit` options can't be set with this method, and setting one
+    /// of them has no effect. Use [`InitArgsBuilder::vfprintf_hook`],
+    /// [`InitArgsBuilder::abort_hook`] and [`InitArgsBuilder::exit_hook`] instead.
     ///
     /// The option must not contain any U+0000 code points except one at the end. A U+0000 code
     /// point at the end is not required, but on platforms where UTF-8 is the default character
@@ -177,8 +207,9 @@ impl<'a> InitArgsBuilder<'a> {
     ///
     /// See [the JNI specification][jni-options] for details on which options are accepted.
     ///
-    /// The `vfprintf`, `abort`, and `exit` options are unsupported at this time. Setting one of
-    /// these options has no effect.
+    /// The `vfprintf`, `abort`, and `exit` options can't be set with this method, and setting one
+    /// of them has no effect. Use [`InitArgsBuilder::vfprintf_hook`],
+    /// [`InitArgsBuilder::abort_hook`] and [`InitArgsBuilder::exit_hook`] instead.
     ///
     /// The option must not contain any U+0000 code points except one at the end. A U+0000 code
     /// point at the end is not required, but on platforms where UTF-8 is the default character
@@ -251,8 +282,9 @@ impl<'a> InitArgsBuilder<'a> {
     ///
     /// See [the JNI specification][jni-options] for details on which options are accepted.
     ///
-    /// The `vfprintf`, `abort`, and `exit` options are unsupported at this time. Setting one of
-    /// these options has no effect.
+    /// The `vfprintf`, `abort`, and `exit` options can't be set with this method, and setting one
+    /// of them has no effect. Use [`InitArgsBuilder::vfprintf_hook`],
+    /// [`InitArgsBuilder::abort_hook`] and [`InitArgsBuilder::exit_hook`] instead.
     ///
     /// This method does not fail, and will neither return nor defer an error.
     ///
@@ -277,6 +309,106 @@ impl<'a> InitArgsBuilder<'a> {
         self
     }
 
+    /// Sets the initial and maximum sizes of the heap, in bytes (the `-Xms` and `-Xmx` options).
+    ///
+    /// The JVM requires both sizes to be multiples of 1024.
+    pub fn heap_size(self, initial: u64, max: u64) -> Self {
+        self.option(format!("-Xms{}", initial))
+            .option(format!("-Xmx{}", max))
+    }
+
+    /// Sets a system property (the `-Dkey=value` option), which Java code can read with
+    /// `System.getProperty(key)`.
+    pub fn system_property(self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.option(format!("-D{}={}", key.as_ref(), value.as_ref()))
+    }
+
+    /// Sets the class path to the given directories and jar files (the `java.class.path` system
+    /// property), joined with the platform's path separator (`;` on Windows and `:` elsewhere).
+    ///
+    /// # Errors
+    ///
+    /// If a path contains the path separator or isn't valid Unicode, then
+    /// [`JvmError::InvalidClassPath`] is deferred and returned from [`InitArgsBuilder::build`].
+    /// Otherwise, this can fail in the same ways as [`InitArgsBuilder::option`].
+    pub fn classpath(mut self, paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Self {
+        let paths: Vec<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_owned())
+            .collect();
+        let classpath = env::join_paths(paths)
+            .map_err(|error| JvmError::InvalidClassPath(error.to_string()))
+            .and_then(|classpath| {
+                classpath.into_string().map_err(|classpath| {
+                    JvmError::InvalidClassPath(format!("{:?} isn't valid Unicode", classpath))
+                })
+            });
+        match classpath {
+            Ok(classpath) => self.system_property("java.class.path", classpath),
+            Err(error) => {
+                if self.opts.is_ok() {
+                    self.opts = Err(error);
+                }
+                self
+            }
+        }
+    }
+
+    /// Enables assertions in all classes except the system classes (the `-ea` option).
+    pub fn enable_assertions(self) -> Self {
+        self.option("-ea")
+    }
+
+    /// Makes the JVM log the given kind of events to the standard output (the `-verbose:...`
+    /// options).
+    pub fn verbose(self, flag: VerboseFlag) -> Self {
+        self.option(match flag {
+            VerboseFlag::Class => "-verbose:class",
+            VerboseFlag::Gc => "-verbose:gc",
+            VerboseFlag::Jni => "-verbose:jni",
+        })
+    }
+
+    /// Sets a function that's called with the JVM's diagnostic messages (the `vfprintf` hook),
+    /// instead of the JVM printing them to the standard error, so that they can be passed to a
+    /// logger, for example.
+    ///
+    /// Messages are truncated to 4095 bytes. A message is often only part of a line, so a hook
+    /// that logs them may need to buffer them until a newline.
+    ///
+    /// This is only available on Unix platforms.
+    ///
+    /// The hook is set when [`InitArgsBuilder::build`] is called, and there can only be one
+    /// `vfprintf` hook in a process.
+    #[cfg(unix)]
+    pub fn vfprintf_hook(mut self, hook: fn(&str)) -> Self {
+        self.vfprintf_hook = Some(hook);
+        self
+    }
+
+    /// Sets a function that's called when the JVM exits (the `exit` hook), such as when Java code
+    /// calls `System.exit`, with the exit status.
+    ///
+    /// The hook is called after the JVM's shutdown hooks have run, and the process exits when it
+    /// returns, so this is the last chance to flush logs or to record the status.
+    ///
+    /// The hook is set when [`InitArgsBuilder::build`] is called, and there can only be one `exit`
+    /// hook in a process. A panic in the hook aborts the process.
+    pub fn exit_hook(mut self, hook: fn(jint)) -> Self {
+        self.exit_hook = Some(hook);
+        self
+    }
+
+    /// Sets a function that's called when the JVM aborts (the `abort` hook), such as after a fatal
+    /// error. The process aborts when the hook returns.
+    ///
+    /// The hook is set when [`InitArgsBuilder::build`] is called, and there can only be one
+    /// `abort` hook in a process. A panic in the hook aborts the process.
+    pub fn abort_hook(mut self, hook: fn()) -> Self {
+        self.abort_hook = Some(hook);
+        self
+    }
+
     /// Set JNI version for the init args
     ///
     /// Default: V8
@@ -308,13 +440,39 @@ impl<'a> InitArgsBuilder<'a> {
     pub fn build(self) -> Result<InitArgs<'a>, JvmError> {
         let opt_strings = self.opts?;
 
-        let opts: Vec<JavaVMOption> = opt_strings
-            .iter()
-            .map(|opt_string| JavaVMOption {
-                optionString: opt_string.as_ptr() as _,
-                extraInfo: ptr::null_mut(),
+        // The hooks come first, so that they're set before the JVM reports any problems with the
+        // other options
+        let mut opts = Vec::with_capacity(opt_strings.len() + SPECIAL_OPTIONS_C.len());
+        let mut hook = |name: &'static CStr, trampoline: *mut c_void| {
+            opts.push(JavaVMOption {
+                optionString: name.as_ptr() as _,
+                extraInfo: trampoline,
             })
-            .collect();
+        };
+        #[cfg(unix)]
+        if let Some(vfprintf_hook) = self.vfprintf_hook {
+            *hooks::VFPRINTF_HOOK
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = Some(vfprintf_hook);
+            hook(SPECIAL_OPTIONS_C[0], hooks::vfprintf as *mut c_void);
+        }
+        if let Some(abort_hook) = self.abort_hook {
+            *hooks::ABORT_HOOK
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = Some(abort_hook);
+            hook(SPECIAL_OPTIONS_C[1], hooks::abort as *mut c_void);
+        }
+        if let Some(exit_hook) = self.exit_hook {
+            *hooks::EXIT_HOOK
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = Some(exit_hook);
+            hook(SPECIAL_OPTIONS_C[2], hooks::exit as *mut c_void);
+        }
+
+        opts.extend(opt_strings.iter().map(|opt_string| JavaVMOption {
+            optionString: opt_string.as_ptr() as _,
+            extraInfo: ptr::null_mut(),
+        }));
 
         Ok(InitArgs {
             inner: JavaVMInitArgs {
@@ -358,3 +516,52 @@ impl<'a> InitArgs<'a> {
         &self.inner as *const _ as _
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{InitArgsBuilder, JvmError, VerboseFlag};
+
+    fn options<'a>(builder: &'a InitArgsBuilder) -> Vec<Cow<'a, str>> {
+        builder
+            .options()
+            .unwrap()
+            .iter()
+            .map(|option| option.to_string_lossy())
+            .collect()
+    }
+
+    #[test]
+    fn typed_options() {
+        let builder = InitArgsBuilder::new()
+            .heap_size(64 * 1024 * 1024, 256 * 1024 * 1024)
+            .system_property("app.name", "demo")
+            .classpath(["classes", "lib/dep.jar"])
+            .enable_assertions()
+            .verbose(VerboseFlag::Gc);
+
+        let separator = if cfg!(windows) { ';' } else { ':' };
+        assert_eq!(
+            options(&builder),
+            vec![
+                "-Xms67108864".to_owned(),
+                "-Xmx268435456".to_owned(),
+                "-Dapp.name=demo".to_owned(),
+                format!("-Djava.class.path=classes{}lib/dep.jar", separator),
+                "-ea".to_owned(),
+                "-verbose:gc".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn classpath_with_separator() {
+        let path = if cfg!(windows) { "a;b" } else { "a:b" };
+        let builder = InitArgsBuilder::new().classpath([path]).option("-ea");
+        assert!(matches!(
+            builder.options(),
+            Err(JvmError::InvalidClassPath(_))
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+//! Trampolines for the `vfprintf`, `exit` and `abort` hooks of the invocation API, which call
+//! the Rust functions that were given to [`InitArgsBuilder`](super::InitArgsBuilder).
+//!
+//! There's only one JVM per process, so the hooks are kept in statics.
+
+use std::sync::{PoisonError, RwLock};
+#[cfg(unix)]
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_void},
+};
+
+use crate::sys::jint;
+
+#[cfg(unix)]
+pub(super) static VFPRINTF_HOOK: RwLock<Option<fn(&str)>> = RwLock::new(None);
+pub(super) static EXIT_HOOK: RwLock<Option<fn(jint)>> = RwLock::new(None);
+pub(super) static ABORT_HOOK: RwLock<Option<fn()>> = RwLock::new(None);
+
+/// The maximum length of a message that's passed to the `vfprintf` hook, after which it's
+/// truncated.
+///
+/// A `va_list` can only be consumed once, so the message can't be measured before it's
+/// formatted.
+#[cfg(unix)]
+const VFPRINTF_BUFFER_LEN: usize = 4096;
+
+/// A C `va_list`, which is passed as (or decays to) a pointer on the supported platforms.
+#[cfg(unix)]
+type VaList = *mut c_void;
+
+#[cfg(unix)]
+extern "C" {
+    fn vsnprintf(s: *mut c_char, n: usize, format: *const c_char, args: VaList) -> i32;
+}
+
+#[cfg(unix)]
+pub(super) extern "system" fn vfprintf(
+    _stream: *mut c_void,
+    format: *const c_char,
+    args: VaList,
+) -> jint {
+    let hook = *VFPRINTF_HOOK.read().unwrap_or_else(PoisonError::into_inner);
+    let mut buffer = [0 as c_char; VFPRINTF_BUFFER_LEN];
+    // Safety: the JVM passes a valid format string with matching arguments, and `vsnprintf`
+    // always nul-terminates the buffer.
+    let len = unsafe { vsnprintf(buffer.as_mut_ptr(), buffer.len(), format, args) };
+    if let Some(hook) = hook {
+        let message = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        hook(&message.to_string_lossy());
+    }
+    len
+}
+
+pub(super) extern "system" fn exit(code: jint) {
+    let hook = *EXIT_HOOK.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(hook) = hook {
+        hook(code);
+    }
+}
+
+pub(super) extern "system" fn abort() {
+    let hook = *ABORT_HOOK.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(hook) = hook {
+        hook();
+    }
+}
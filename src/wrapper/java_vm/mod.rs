@@ -5,3 +5,5 @@ pub use self::init_args::*;
 
 mod vm;
 pub use self::vm::*;
+
+pub(crate) mod deferred_refs;
@@ -1,19 +1,30 @@
 use std::{
     cell::RefCell,
     ops::{Deref, DerefMut},
+    os::raw::c_void,
     ptr,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        PoisonError, RwLock,
+    },
     thread::{current, Thread},
 };
 
 use log::{debug, error};
 
-use crate::{errors::*, sys, JNIEnv, JNIVersion};
+use super::deferred_refs;
+use crate::{
+    descriptors::class_cache,
+    errors::*,
+    jvmti::JvmtiEnv,
+    objects::{clear_bundled_classes, GlobalRef},
+    strings::JNIString,
+    sys, JNIEnv, JNIVersion,
+};
 
 #[cfg(feature = "invocation")]
 use {
     crate::InitArgs,
-    std::os::raw::c_void,
     std::{ffi::OsStr, path::PathBuf},
 };
 
@@ -157,16 +168,22 @@ impl JavaVM {
     /// library (`jvm.dll`, `libjvm.so`, or `libjvm.dylib`, depending on the platform).
     #[cfg(feature = "invocation")]
     pub fn new(args: InitArgs) -> StartJvmResult<Self> {
-        Self::with_libjvm(args, || {
-            Ok([
-                java_locator::locate_jvm_dyn_library()
-                    .map_err(StartJvmError::NotFound)?
-                    .as_str(),
-                java_locator::get_jvm_dyn_lib_file_name(),
-            ]
-            .iter()
-            .collect::<PathBuf>())
-        })
+        Self::with_libjvm(args, Self::locate_libjvm)
+    }
+
+    /// Locates the JVM shared library with [java-locator].
+    ///
+    /// [java-locator]: https://crates.io/crates/java-locator
+    #[cfg(feature = "invocation")]
+    fn locate_libjvm() -> StartJvmResult<PathBuf> {
+        Ok([
+            java_locator::locate_jvm_dyn_library()
+                .map_err(StartJvmError::NotFound)?
+                .as_str(),
+            java_locator::get_jvm_dyn_lib_file_name(),
+        ]
+        .iter()
+        .collect::<PathBuf>())
     }
 
     /// Launch a new JavaVM using the provided init args, loading it from the given shared library file if it's not already loaded.
@@ -231,6 +248,75 @@ impl JavaVM {
         Ok(vm)
     }
 
+    /// Returns the JavaVM that has already been created in this process, if
+    /// there is one.
+    ///
+    /// This is for adopting a JVM that was created by a host application
+    /// (such as an application server, or a program that embeds a JVM and
+    /// loads plugins), without knowing its `JavaVM` pointer. It uses
+    /// `JNI_GetCreatedJavaVMs`, and locates the JVM shared library with
+    /// [java-locator], like [`JavaVM::new`]. Use
+    /// [`existing_with_libjvm`][Self::existing_with_libjvm] to give an
+    /// explicit location, which should be the library that the host loaded.
+    ///
+    /// If there is no [`JavaVM::singleton`] yet, the returned JavaVM also
+    /// becomes the singleton.
+    ///
+    /// *This API requires the "invocation" feature to be enabled.*
+    ///
+    /// [java-locator]: https://crates.io/crates/java-locator
+    #[cfg(feature = "invocation")]
+    pub fn existing() -> StartJvmResult<Option<Self>> {
+        Self::existing_with_libjvm(Self::locate_libjvm)
+    }
+
+    /// Returns the JavaVM that has already been created in this process, if
+    /// there is one, getting it from the given shared library file.
+    ///
+    /// See [`JavaVM::existing`]. As for [`JavaVM::with_libjvm`], the
+    /// `libjvm_path` closure returns the path to the JVM shared library.
+    ///
+    /// *This API requires the "invocation" feature to be enabled.*
+    #[cfg(feature = "invocation")]
+    pub fn existing_with_libjvm<P: AsRef<OsStr>>(
+        libjvm_path: impl FnOnce() -> StartJvmResult<P>,
+    ) -> StartJvmResult<Option<Self>> {
+        let libjvm_path = libjvm_path()?;
+        let libjvm_path_string = libjvm_path.as_ref().to_string_lossy().into_owned();
+
+        let libjvm = match unsafe { libloading::Library::new(libjvm_path.as_ref()) } {
+            Ok(ok) => ok,
+            Err(error) => return Err(StartJvmError::LoadError(libjvm_path_string, error)),
+        };
+
+        let vm = unsafe {
+            let get_created_fn: libloading::Symbol<
+                unsafe extern "system" fn(
+                    vm_buf: *mut *mut sys::JavaVM,
+                    buf_len: sys::jsize,
+                    n_vms: *mut sys::jsize,
+                ) -> sys::jint,
+            > = libjvm
+                .get(b"JNI_GetCreatedJavaVMs\0")
+                .map_err(|error| StartJvmError::LoadError(libjvm_path_string, error))?;
+
+            // There can only be one JavaVM per process
+            let mut ptr: *mut sys::JavaVM = ::std::ptr::null_mut();
+            let mut count: sys::jsize = 0;
+            jni_error_code_to_result(get_created_fn(&mut ptr, 1, &mut count))?;
+            if count == 0 || ptr.is_null() {
+                return Ok(None);
+            }
+            Self::from_raw(ptr)?
+        };
+
+        let mut singleton = SINGLETON.write().unwrap_or_else(PoisonError::into_inner);
+        if singleton.is_none() {
+            *singleton = Some(vm.clone());
+        }
+        Ok(Some(vm))
+    }
+
     /// Create a JavaVM from a raw pointer.
     ///
     /// # Safety
@@ -253,17 +339,36 @@ impl JavaVM {
     ///
     /// The thread will detach itself automatically when it exits.
     ///
+    /// The Java thread is named after the Rust thread, if it has a name (see
+    /// [`attach_current_thread_permanently_with_name`][with-name]).
+    ///
     /// Attached threads [block JVM exit][block]. If it is not desirable — consider using
     /// [`attach_current_thread_as_daemon`][attach-as-daemon].
     ///
     /// [block]: https://docs.oracle.com/en/java/javase/12/docs/specs/jni/invocation.html#unloading-the-vm
     /// [attach-as-daemon]: struct.JavaVM.html#method.attach_current_thread_as_daemon
+    /// [with-name]: JavaVM::attach_current_thread_permanently_with_name
     pub fn attach_current_thread_permanently(&self) -> Result<JNIEnv> {
+        self.attach_current_thread_permanently_impl(None)
+    }
+
+    /// Like [`attach_current_thread_permanently`][attach], but names the Java
+    /// thread `name` instead of after the Rust thread.
+    ///
+    /// The name is only used if the thread isn't attached already.
+    ///
+    /// [attach]: JavaVM::attach_current_thread_permanently
+    pub fn attach_current_thread_permanently_with_name(&self, name: &str) -> Result<JNIEnv<'_>> {
+        self.attach_current_thread_permanently_impl(Some(name))
+    }
+
+    fn attach_current_thread_permanently_impl(&self, name: Option<&str>) -> Result<JNIEnv<'_>> {
+        check_not_destroyed()?;
         // Safety: NOT SAFE CURRENTLY: https://github.com/jni-rs/jni-rs/discussions/436#discussioncomment-5421738
         unsafe {
             match self.get_env(JNIVersion::V1_4) {
                 Ok(env) => Ok(env),
-                Err(_) => self.attach_current_thread_impl(ThreadType::Normal),
+                Err(_) => self.attach_current_thread_impl(ThreadType::Normal, name),
             }
         }
     }
@@ -273,6 +378,10 @@ impl JavaVM {
     /// when dropped. Calling this in a thread that is already attached is a no-op, and
     /// will neither change its daemon status nor prematurely detach it.
     ///
+    /// The Java thread is named after the Rust thread, if it has a name, so
+    /// that thread dumps and profilers show names like `tokio-runtime-worker`
+    /// (see [`attach_current_thread_with_name`][with-name]).
+    ///
     /// Attached threads [block JVM exit][block].
     ///
     /// Attaching and detaching a thread is an expensive operation. If you use it frequently
@@ -281,13 +390,29 @@ impl JavaVM {
     ///
     /// [block]: https://docs.oracle.com/en/java/javase/12/docs/specs/jni/invocation.html#unloading-the-vm
     /// [attach-as-daemon]: struct.JavaVM.html#method.attach_current_thread_as_daemon
+    /// [with-name]: JavaVM::attach_current_thread_with_name
     pub fn attach_current_thread(&self) -> Result<AttachGuard> {
+        self.attach_current_thread_guarded(None)
+    }
+
+    /// Like [`attach_current_thread`][attach], but names the Java thread
+    /// `name` instead of after the Rust thread.
+    ///
+    /// The name is only used if the thread isn't attached already.
+    ///
+    /// [attach]: JavaVM::attach_current_thread
+    pub fn attach_current_thread_with_name(&self, name: &str) -> Result<AttachGuard<'_>> {
+        self.attach_current_thread_guarded(Some(name))
+    }
+
+    fn attach_current_thread_guarded(&self, name: Option<&str>) -> Result<AttachGuard<'_>> {
+        check_not_destroyed()?;
         // Safety: NOT SAFE CURRENTLY: https://github.com/jni-rs/jni-rs/discussions/436#discussioncomment-5421738
         unsafe {
             match self.get_env(JNIVersion::V1_4) {
                 Ok(env) => Ok(AttachGuard::new_nested(env)),
                 Err(_) => {
-                    let env = self.attach_current_thread_impl(ThreadType::Normal)?;
+                    let env = self.attach_current_thread_impl(ThreadType::Normal, name)?;
                     Ok(AttachGuard::new(env))
                 }
             }
@@ -338,6 +463,11 @@ impl JavaVM {
     ///
     /// The thread will detach itself automatically when it exits.
     ///
+    /// The Java thread is named after the Rust thread, if it has a name (see
+    /// [`attach_current_thread_as_daemon_with_name`][with-name]).
+    ///
+    /// [with-name]: JavaVM::attach_current_thread_as_daemon_with_name
+    ///
     /// # Safety
     ///
     /// The use of daemon threads is only relevant in applications that might later try to
@@ -349,9 +479,34 @@ impl JavaVM {
     /// likely be removed from this crate, in favor of relegating the
     /// functionality to the `jni-sys` crate instead.
     pub unsafe fn attach_current_thread_as_daemon(&self) -> Result<JNIEnv> {
+        self.attach_current_thread_as_daemon_impl(None)
+    }
+
+    /// Like [`attach_current_thread_as_daemon`][attach], but names the Java
+    /// thread `name` instead of after the Rust thread.
+    ///
+    /// The name is only used if the thread isn't attached already.
+    ///
+    /// # Safety
+    ///
+    /// See [`attach_current_thread_as_daemon`][attach].
+    ///
+    /// [attach]: JavaVM::attach_current_thread_as_daemon
+    pub unsafe fn attach_current_thread_as_daemon_with_name(
+        &self,
+        name: &str,
+    ) -> Result<JNIEnv<'_>> {
+        self.attach_current_thread_as_daemon_impl(Some(name))
+    }
+
+    unsafe fn attach_current_thread_as_daemon_impl(
+        &self,
+        name: Option<&str>,
+    ) -> Result<JNIEnv<'_>> {
+        check_not_destroyed()?;
         match self.get_env(JNIVersion::V1_4) {
             Ok(env) => Ok(env),
-            Err(_) => self.attach_current_thread_impl(ThreadType::Daemon),
+            Err(_) => self.attach_current_thread_impl(ThreadType::Daemon, name),
         }
     }
 
@@ -362,6 +517,66 @@ impl JavaVM {
         ATTACHED_THREADS.load(Ordering::SeqCst)
     }
 
+    /// Sets a class loader that [`JNIEnv::find_class`] (and every API that
+    /// looks up a class by name) falls back to when `FindClass` can't find a
+    /// class.
+    ///
+    /// `FindClass` uses the class loader of the native method that's being
+    /// called, or the system class loader on threads that were attached from
+    /// native code, which can't find an application's classes on Android. So
+    /// looking up an application class from a native thread fails unless a
+    /// class loader that can find it is set here, typically by capturing the
+    /// class loader of one of the application's classes in `JNI_OnLoad`:
+    ///
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, JavaVM};
+    /// #
+    /// # fn example(vm: &JavaVM, env: &mut JNIEnv) -> Result<()> {
+    /// let class = env.find_class("com/example/MainActivity")?;
+    /// let loader = env
+    ///     .call_method(&class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+    ///     .l()?;
+    /// vm.set_default_class_loader(env.new_global_ref(loader)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The class loader is only used if `FindClass` throws a
+    /// `NoClassDefFoundError`. It's loaded with `ClassLoader.loadClass()`,
+    /// and if that fails too, the original `NoClassDefFoundError` is thrown.
+    ///
+    /// `loader` must refer to a `java.lang.ClassLoader`. This replaces any
+    /// class loader that was previously set.
+    pub fn set_default_class_loader(&self, loader: GlobalRef) {
+        *DEFAULT_CLASS_LOADER
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(loader);
+    }
+
+    /// Returns the class loader that's set by [`JavaVM::set_default_class_loader`],
+    /// if any.
+    pub fn default_class_loader(&self) -> Option<GlobalRef> {
+        DEFAULT_CLASS_LOADER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the `JavaVM` that was passed to the `JNI_OnLoad` function that's
+    /// generated by [`jni_on_load!`](crate::jni_on_load), if the library has
+    /// been loaded (and hasn't been unloaded), or else the first JavaVM that
+    /// was adopted with [`JavaVM::existing`].
+    pub fn singleton() -> Option<JavaVM> {
+        SINGLETON
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn set_singleton(vm: Option<JavaVM>) {
+        *SINGLETON.write().unwrap_or_else(PoisonError::into_inner) = vm;
+    }
+
     /// Get the `JNIEnv` associated with the current thread, or
     /// `ErrorKind::Detached`
     /// if the current thread is not attached to the java VM.
@@ -393,6 +608,7 @@ impl JavaVM {
     /// pre-existing [`JObject`] that might belong to a lower stack frame.
     ///
     pub unsafe fn get_env(&self, version: JNIVersion) -> Result<JNIEnv> {
+        check_not_destroyed()?;
         let mut ptr = ptr::null_mut();
         if version < JNIVersion::V1_4 {
             return Err(Error::UnsupportedVersion);
@@ -404,20 +620,171 @@ impl JavaVM {
         }
     }
 
-    /// Creates `InternalAttachGuard` and attaches current thread.
-    unsafe fn attach_current_thread_impl(&self, thread_type: ThreadType) -> Result<JNIEnv> {
+    /// Returns a new [JVMTI](crate::jvmti) environment of the VM.
+    ///
+    /// Fails with [`JniError::WrongVersion`] if the VM doesn't support
+    /// JVMTI 1.2.
+    pub fn get_jvmti_env(&self) -> Result<JvmtiEnv> {
+        // HotSpot only creates JVMTI environments for attached threads
+        let _guard = self.attach_current_thread()?;
+        let mut ptr = ptr::null_mut();
+        unsafe {
+            let res = java_vm_call_unchecked!(
+                self,
+                v1_2,
+                GetEnv,
+                &mut ptr,
+                crate::jvmti::sys::JVMTI_VERSION_1_2
+            );
+            jni_error_code_to_result(res)?;
+            JvmtiEnv::from_raw(ptr as *mut crate::jvmti::sys::jvmtiEnv)
+        }
+    }
+
+    /// Spawns a future on a Tokio runtime, on a worker thread that's
+    /// attached to the Java VM whenever the future is polled.
+    ///
+    /// `f` is given a clone of this `JavaVM`, and returns the future to
+    /// spawn. Each time the future is polled, the worker thread that polls it
+    /// is attached permanently as a daemon thread (if it isn't already), so
+    /// the future can call
+    /// [`attach_current_thread`](JavaVM::attach_current_thread) without the
+    /// cost of attaching and detaching the thread each time, and the worker
+    /// threads don't keep [`JavaVM::destroy`] waiting.
+    ///
+    /// A [`JNIEnv`] can't be held across an `.await`, since the future may be
+    /// resumed on a different worker thread, so get one each time it's needed
+    /// and create [`GlobalRef`](crate::objects::GlobalRef)s for any objects
+    /// that are kept across an `.await`.
+    ///
+    /// If a worker thread fails to attach, it's logged and the future is
+    /// polled anyway, so that the error is returned when the future tries to
+    /// use the Java VM.
+    ///
+    /// This is only available if the "tokio" feature is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JavaVM};
+    /// #
+    /// # async fn fetch_name() -> String { String::new() }
+    /// # fn example(vm: &JavaVM, runtime: &tokio::runtime::Handle) {
+    /// vm.spawn_attached(runtime, |vm| async move {
+    ///     let name = fetch_name().await;
+    ///     let mut env = vm.attach_current_thread()?;
+    ///     let key = env.new_string("app.user.name")?;
+    ///     let name = env.new_string(name)?;
+    ///     env.call_static_method(
+    ///         "java/lang/System",
+    ///         "setProperty",
+    ///         "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+    ///         &[(&key).into(), (&name).into()],
+    ///     )?;
+    ///     Ok::<_, jni::errors::Error>(())
+    /// });
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn spawn_attached<F, Fut>(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        f: F,
+    ) -> tokio::task::JoinHandle<Fut::Output>
+    where
+        F: FnOnce(JavaVM) -> Fut,
+        Fut: std::future::Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let vm = self.clone();
+        let mut future = Box::pin(f(self.clone()));
+        runtime.spawn(std::future::poll_fn(move |cx| {
+            // Safety: the worker thread isn't detached once the JavaVM is
+            // destroyed, and attaching it fails after that
+            match unsafe { vm.attach_current_thread_as_daemon() } {
+                Ok(env) => env.delete_deferred_refs(),
+                Err(err) => error!("Failed to attach Tokio worker thread: {:#?}", err),
+            }
+            std::future::Future::poll(future.as_mut(), cx)
+        }))
+    }
+
+    /// Runs a closure with an attached [`JNIEnv`] on the blocking thread pool
+    /// of a Tokio runtime, and returns a handle to its result.
+    ///
+    /// The blocking thread is attached for the duration of the closure (if it
+    /// isn't already attached), like [`attach_current_thread`](JavaVM::attach_current_thread),
+    /// and the closure is run in a local frame with
+    /// [`DEFAULT_LOCAL_FRAME_CAPACITY`](crate::DEFAULT_LOCAL_FRAME_CAPACITY),
+    /// so that its local references are freed when it returns.
+    ///
+    /// This is intended for calls into Java that may block, or take a long
+    /// time, which shouldn't run on the runtime's worker threads.
+    ///
+    /// This is only available if the "tokio" feature is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JavaVM};
+    /// #
+    /// # async fn example(vm: &JavaVM, runtime: &tokio::runtime::Handle) -> Result<()> {
+    /// let length = vm
+    ///     .spawn_blocking_attached(runtime, |env| {
+    ///         let string = env.new_string("Hello")?;
+    ///         env.get_string_length(&string)
+    ///     })
+    ///     .await
+    ///     .expect("closure panicked")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn spawn_blocking_attached<F, T, E>(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        f: F,
+    ) -> tokio::task::JoinHandle<std::result::Result<T, E>>
+    where
+        F: FnOnce(&mut JNIEnv) -> std::result::Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: From<Error> + Send + 'static,
+    {
+        let vm = self.clone();
+        runtime.spawn_blocking(move || {
+            let mut env = vm.attach_current_thread()?;
+            env.delete_deferred_refs();
+            env.with_local_frame(crate::DEFAULT_LOCAL_FRAME_CAPACITY, f)
+        })
+    }
+
+    /// Creates `InternalAttachGuard` and attaches current thread, with the
+    /// given name or else the name of the Rust thread.
+    unsafe fn attach_current_thread_impl(
+        &self,
+        thread_type: ThreadType,
+        name: Option<&str>,
+    ) -> Result<JNIEnv<'_>> {
         let guard = InternalAttachGuard::new(self.clone());
+        let name = name.or_else(|| guard.thread.name()).map(JNIString::from);
+        let mut args = sys::JavaVMAttachArgs {
+            version: JNIVersion::V1_4.into(),
+            name: name
+                .as_ref()
+                .map_or(ptr::null_mut(), |name| name.as_ptr() as *mut _),
+            group: ptr::null_mut(),
+        };
         let env_ptr = unsafe {
             if thread_type == ThreadType::Daemon {
-                guard.attach_current_thread_as_daemon()?
+                guard.attach_current_thread_as_daemon(&mut args)?
             } else {
-                guard.attach_current_thread()?
+                guard.attach_current_thread(&mut args)?
             }
         };
 
         InternalAttachGuard::fill_tls(guard);
 
-        unsafe { JNIEnv::from_raw(env_ptr as *mut sys::JNIEnv) }
+        let env = unsafe { JNIEnv::from_raw(env_ptr as *mut sys::JNIEnv) }?;
+        deferred_refs::flush(&env);
+        Ok(env)
     }
 
     /// Unloads the JavaVM and frees all it's associated resources
@@ -498,11 +865,35 @@ impl JavaVM {
     /// So although the `JavaVM` won't necessarily be solely owned by this
     /// thread when `destroy()` is first called it will conceptually own the
     /// `JavaVM` before `destroy()` returns.
+    ///
+    /// ## Attaching after `destroy()`
+    ///
+    /// Before the `JavaVM` is destroyed, the [`JavaVM::singleton`], the
+    /// [default class loader](JavaVM::set_default_class_loader) and the
+    /// [class cache](JNIEnv::cache_class) are cleared.
+    /// Once it has been destroyed, attaching a thread (or [`JavaVM::get_env`])
+    /// fails with [`Error::JavaVMDestroyed`] instead of calling into the
+    /// destroyed `JavaVM`. A JVM can't be created again in the same process,
+    /// so this is permanent.
     pub unsafe fn destroy(&self) -> Result<()> {
+        // Dropping the class loader and the cached classes deletes global
+        // references, so it has to happen while the JavaVM is still alive
+        drop(
+            DEFAULT_CLASS_LOADER
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take(),
+        );
+        class_cache::clear();
+        clear_bundled_classes();
+        Self::set_singleton(None);
+
         unsafe {
             let res = java_vm_call_unchecked!(self, v1_1, DestroyJavaVM);
-            jni_error_code_to_result(res)
+            jni_error_code_to_result(res)?;
         }
+        DESTROYED.store(true, Ordering::SeqCst);
+        Ok(())
     }
 }
 
@@ -512,9 +903,41 @@ thread_local! {
 
 static ATTACHED_THREADS: AtomicUsize = AtomicUsize::new(0);
 
+/// The class loader that's set by [`JavaVM::set_default_class_loader`]. Since
+/// a process can only have a single JVM, this isn't stored in each `JavaVM`.
+static DEFAULT_CLASS_LOADER: RwLock<Option<GlobalRef>> = RwLock::new(None);
+
+/// The `JavaVM` that's stored by the `JNI_OnLoad` of [`jni_on_load!`](crate::jni_on_load).
+static SINGLETON: RwLock<Option<JavaVM>> = RwLock::new(None);
+
+/// Whether the JavaVM has been destroyed by [`JavaVM::destroy`].
+static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+/// Fails with [`Error::JavaVMDestroyed`] if the JavaVM has been destroyed.
+fn check_not_destroyed() -> Result<()> {
+    if DESTROYED.load(Ordering::SeqCst) {
+        Err(Error::JavaVMDestroyed)
+    } else {
+        Ok(())
+    }
+}
+
 /// A RAII implementation of scoped guard which detaches the current thread
 /// when dropped. The attached `JNIEnv` can be accessed through this guard
 /// via its `Deref` implementation.
+///
+/// # Virtual threads
+///
+/// Attaching always attaches the current native thread, which Java sees as a
+/// platform thread, never a virtual thread.
+///
+/// When a native method is called from a virtual thread, that thread is
+/// already attached (through the carrier thread that it's mounted on), so
+/// attaching is a no-op and the returned guard won't detach. The virtual
+/// thread stays on the same carrier until the native method returns, but may
+/// be moved to a different carrier thread afterwards, so an `AttachGuard` (or
+/// `JNIEnv`) must not be kept in a thread-local and reused by a later native
+/// call. [`JNIEnv::is_virtual_thread`] can be used to detect this case.
 pub struct AttachGuard<'local> {
     env: JNIEnv<'local>,
     should_detach: bool,
@@ -601,14 +1024,17 @@ impl InternalAttachGuard {
         });
     }
 
-    unsafe fn attach_current_thread(&self) -> Result<*mut sys::JNIEnv> {
+    unsafe fn attach_current_thread(
+        &self,
+        args: &mut sys::JavaVMAttachArgs,
+    ) -> Result<*mut sys::JNIEnv> {
         let mut env_ptr = ptr::null_mut();
         let res = java_vm_call_unchecked!(
             self.java_vm,
             v1_1,
             AttachCurrentThread,
             &mut env_ptr,
-            ptr::null_mut()
+            args as *mut sys::JavaVMAttachArgs as *mut c_void
         );
         jni_error_code_to_result(res)?;
 
@@ -627,14 +1053,17 @@ impl InternalAttachGuard {
     // TODO: remove this API: https://github.com/jni-rs/jni-rs/issues/469
     // This API is also awkward because we don't currently have a way
     // to know that the implementation supports JNI >= 1.4
-    unsafe fn attach_current_thread_as_daemon(&self) -> Result<*mut sys::JNIEnv> {
+    unsafe fn attach_current_thread_as_daemon(
+        &self,
+        args: &mut sys::JavaVMAttachArgs,
+    ) -> Result<*mut sys::JNIEnv> {
         let mut env_ptr = ptr::null_mut();
         let res = java_vm_call_unchecked!(
             self.java_vm,
             v1_4,
             AttachCurrentThreadAsDaemon,
             &mut env_ptr,
-            ptr::null_mut()
+            args as *mut sys::JavaVMAttachArgs as *mut c_void
         );
         jni_error_code_to_result(res)?;
 
@@ -651,6 +1080,11 @@ impl InternalAttachGuard {
     }
 
     fn detach(&mut self) -> Result<()> {
+        // A daemon thread that's still attached when the JavaVM is destroyed
+        // can't be detached from it anymore
+        if DESTROYED.load(Ordering::SeqCst) {
+            return Err(Error::JavaVMDestroyed);
+        }
         unsafe {
             java_vm_call_unchecked!(self.java_vm, v1_1, DetachCurrentThread);
         }
@@ -1,8 +1,10 @@
 use std::{
-    convert::TryInto,
+    backtrace::{Backtrace, BacktraceStatus},
+    convert::{TryFrom, TryInto},
+    fmt,
     marker::PhantomData,
     os::raw::{c_char, c_void},
-    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe, Location},
     ptr, str,
     str::FromStr,
     sync::{Mutex, MutexGuard},
@@ -11,19 +13,21 @@ use std::{
 use jni_sys::jobject;
 
 use crate::{
-    descriptors::Desc,
+    descriptors::{class_cache, Desc},
     errors::*,
     objects::{
-        AutoElements, AutoElementsCritical, AutoLocal, GlobalRef, JByteBuffer, JClass, JFieldID,
-        JList, JMap, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JString, JThrowable,
-        JValue, JValueOwned, ReleaseMode, TypeArray, WeakRef,
+        AutoElements, AutoElementsCritical, AutoLocal, GlobalRef, JByteBuffer, JClass,
+        JClassLoader, JFieldID, JIterator, JList, JMap, JMethodID, JModule, JObject,
+        JStaticFieldID, JStaticMethodID, JString, JThrowable, JValue, JValueOwned, Reference,
+        ReferenceType, ReleaseMode, ThrowableType, TypeArray, WeakRef,
     },
     signature::{JavaType, Primitive, TypeSignature},
-    strings::{JNIStr, JNIString, JavaStr},
+    strings::{JNIStr, JNIString, JavaStr, JavaStr16},
     sys::{
         self, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort, jsize, jvalue,
         JNINativeMethod,
     },
+    wrapper::java_vm::deferred_refs,
     JNIVersion, JavaVM,
 };
 use crate::{
@@ -35,6 +39,9 @@ use crate::{
 };
 use crate::{objects::AsJArrayRaw, signature::ReturnType};
 
+#[cfg(feature = "tokio")]
+use {crate::objects::JCompletableFuture, std::future::Future};
+
 /// FFI-compatible JNIEnv struct. You can safely use this as the JNIEnv argument
 /// to exported methods that will be called by java. This is where most of the
 /// magic happens. All methods on this object are wrappers around JNI functions,
@@ -193,6 +200,16 @@ use crate::{objects::AsJArrayRaw, signature::ReturnType};
 ///
 /// Calling unchecked methods with invalid arguments and/or invalid class and
 /// method descriptors may lead to segmentation fault.
+///
+/// # Tracing
+///
+/// With the `trace-jni` feature, each JNI function is called in a `tracing`
+/// span at the `TRACE` level, with the target `jni`, that's named after the
+/// function (such as `FindClass`). The methods that look up classes, methods
+/// and fields by name, such as `find_class`, `get_method_id` and
+/// `call_method`, also run in a span that's named after the method, with the
+/// names and type signatures that they're given. A subscriber records how
+/// long each span takes, so this shows which JNI calls take the most time.
 #[repr(transparent)]
 #[derive(Debug)]
 pub struct JNIEnv<'local> {
@@ -321,7 +338,7 @@ impl<'local> JNIEnv<'local> {
                 buf.as_ptr() as *const jbyte,
                 buf.len() as jsize
             )
-            .map(|class| JClass::from_raw(class))
+            .map(|class| JClass::from_raw(track_local!(class)))
         }
     }
 
@@ -351,7 +368,7 @@ impl<'local> JNIEnv<'local> {
                 buf.as_ptr(),
                 buf.len() as _
             )
-            .map(|class| JClass::from_raw(class))
+            .map(|class| JClass::from_raw(track_local!(class)))
         }
     }
 
@@ -371,15 +388,97 @@ impl<'local> JNIEnv<'local> {
         S: Into<JNIString>,
     {
         let name = name.into();
+        trace_env_method!("find_class", class = name.to_str());
         // Safety:
         // FindClass is 1.1 API that must be valid
         // name is non-null
-        unsafe {
+        let class = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, FindClass, name.as_ptr())
-                .map(|class| JClass::from_raw(class))
+                .map(|class| JClass::from_raw(track_local!(class)))
+        };
+        match class {
+            Err(Error::JavaException) => self.find_class_with_default_loader(&name),
+            class => class,
+        }
+    }
+
+    /// Retries a class lookup that threw a `NoClassDefFoundError` with the
+    /// [default class loader](JavaVM::set_default_class_loader), if one is
+    /// set. If that fails, the original exception is thrown again.
+    fn find_class_with_default_loader(&mut self, name: &JNIString) -> Result<JClass<'local>> {
+        let exception = match self.exception_occurred() {
+            Some(exception) => self.auto_local(exception),
+            None => return Err(Error::JavaException),
+        };
+        // No other JNI functions can be called while the exception is pending
+        self.exception_clear();
+
+        let loader = self
+            .get_java_vm()
+            .ok()
+            .and_then(|vm| vm.default_class_loader());
+        let retry = match loader {
+            Some(loader) => {
+                match self.is_instance_of(&exception, "java/lang/NoClassDefFoundError") {
+                    Ok(true) => {
+                        let loader: &JClassLoader = loader.as_obj().into();
+                        loader.load_class(self, &name.to_str())
+                    }
+                    Ok(false) => Err(Error::JavaException),
+                    Err(err) => Err(err),
+                }
+            }
+            None => Err(Error::JavaException),
+        };
+        match retry {
+            Ok(class) => Ok(class),
+            Err(_) => {
+                self.exception_clear();
+                self.throw(&exception)?;
+                Err(Error::JavaException)
+            }
         }
     }
 
+    /// Adds a class to the class cache, so that APIs which take a class name
+    /// (such as [`Self::call_static_method`]) no longer need to call
+    /// `FindClass` to look it up.
+    ///
+    /// Classes in the `java.lang` package are always cached automatically the
+    /// first time that they're looked up by name. This can be used to cache
+    /// other frequently used classes at startup (such as in `JNI_OnLoad`).
+    ///
+    /// The class is looked up with [`Self::find_class`], and a global
+    /// reference to it is kept for the lifetime of the process. Any later
+    /// lookup of the same `name` will return the cached class, regardless of
+    /// which class loader `FindClass` would otherwise have used, so this
+    /// should only be used for classes that are not loaded by more than one
+    /// class loader.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// env.cache_class("com/example/Callbacks")?;
+    ///
+    /// // This no longer calls `FindClass`.
+    /// env.call_static_method("com/example/Callbacks", "onEvent", "()V", &[])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cache_class<S>(&mut self, name: S) -> Result<()>
+    where
+        S: Into<JNIString>,
+    {
+        let name = name.into();
+        let key = name.as_cstr().to_owned();
+        let class = self.find_class(name)?;
+        let class = self.auto_local(class);
+        class_cache::insert(key, self.new_global_ref(&class)?);
+        Ok(())
+    }
+
     /// Returns the superclass for a particular class. Returns None for `java.lang.Object` or
     /// an interface. As with [Self::find_class], takes a descriptor
     ///
@@ -392,17 +491,45 @@ impl<'local> JNIEnv<'local> {
     {
         let class = class.lookup(self)?;
         let superclass = unsafe {
-            JClass::from_raw(jni_call_unchecked!(
+            JClass::from_raw(track_local!(jni_call_unchecked!(
                 self,
                 v1_1,
                 GetSuperclass,
                 class.as_ref().as_raw()
-            ))
+            )))
         };
 
         Ok((!superclass.is_null()).then_some(superclass))
     }
 
+    /// Returns the module that a class belongs to (`Class.getModule()`). As
+    /// with [Self::find_class], takes a descriptor.
+    ///
+    /// Classes that are loaded from the class path belong to the unnamed
+    /// module of their class loader, which [`JModule::is_named`] can be used
+    /// to check for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedVersion`] if the JVM doesn't support JNI 9,
+    /// which added `GetModule`.
+    pub fn get_module<'other_local, T>(&mut self, class: T) -> Result<JModule<'local>>
+    where
+        T: Desc<'local, JClass<'other_local>>,
+    {
+        if self.version() < JNIVersion::V9 {
+            return Err(Error::UnsupportedVersion);
+        }
+        let class = class.lookup(self)?;
+        let class = null_check!(class.as_ref(), "get_module class")?;
+        // Safety: we've checked that GetModule is available, and that the
+        // class isn't null
+        unsafe {
+            let module = jni_call_check_ex_and_null_ret!(self, v9, GetModule, class.as_raw())?;
+            Ok(JModule::from_raw(track_local!(module)))
+        }
+    }
+
     /// Tests whether class1 is assignable from class2.
     pub fn is_assignable_from<'other_local_1, 'other_local_2, T, U>(
         &mut self,
@@ -486,6 +613,61 @@ impl<'local> JNIEnv<'local> {
         }
     }
 
+    /// Returns the kind of reference that `obj` is: local, global or weak
+    /// global.
+    ///
+    /// A null reference is reported as [`ReferenceType::Invalid`], without
+    /// calling into the JVM.
+    ///
+    /// This is mostly useful for debugging. Note that it can't reliably detect
+    /// a reference that has already been deleted: the JVM may reuse the slot
+    /// for a new reference, and some JVMs will abort if `-Xcheck:jni` is
+    /// enabled.
+    ///
+    /// Returns [`Error::UnsupportedVersion`] if the JVM doesn't support JNI
+    /// 1.6, which added `GetObjectRefType`.
+    pub fn get_object_ref_type<'other_local, O>(&self, obj: O) -> Result<ReferenceType>
+    where
+        O: AsRef<JObject<'other_local>>,
+    {
+        let obj = obj.as_ref();
+        if obj.is_null() {
+            return Ok(ReferenceType::Invalid);
+        }
+        if self.version() < JNIVersion::V1_6 {
+            return Err(Error::UnsupportedVersion);
+        }
+        // Safety: we've checked that GetObjectRefType is available, and that
+        // the reference isn't null
+        let ty = unsafe { jni_call_unchecked!(self, v1_6, GetObjectRefType, obj.as_raw()) };
+        Ok(ReferenceType::from(ty))
+    }
+
+    /// Returns whether `thread` (a `java.lang.Thread`) is a virtual thread.
+    ///
+    /// Virtual threads are scheduled by the JVM on a pool of platform
+    /// "carrier" threads, and may move to a different carrier thread whenever
+    /// they aren't running native code. Native thread-local state, such as a
+    /// Rust `thread_local!`, belongs to the carrier thread, so it must not be
+    /// cached across native calls that are made by a virtual thread. See
+    /// [`JThread::current`](crate::objects::JThread::current) to get the current thread.
+    ///
+    /// This uses the `IsVirtualThread` function that was added in JNI 19 (and
+    /// finalized in Java 21). Older JVMs don't support virtual threads, so
+    /// this always returns `false` for them.
+    pub fn is_virtual_thread<'other_local, O>(&self, thread: O) -> Result<bool>
+    where
+        O: AsRef<JObject<'other_local>>,
+    {
+        let thread = null_check!(thread.as_ref(), "is_virtual_thread thread")?;
+        if self.version() < JNIVersion::V19 {
+            return Ok(false);
+        }
+        // Safety: we've checked that IsVirtualThread is available, and that
+        // the thread isn't null
+        Ok(unsafe { jni_call_unchecked!(self, v19, IsVirtualThread, thread.as_raw()) })
+    }
+
     /// Raise an exception from an existing object. This will continue being
     /// thrown in java unless `exception_clear` is called.
     ///
@@ -573,6 +755,61 @@ impl<'local> JNIEnv<'local> {
         }
     }
 
+    /// Create and throw a new exception from a class descriptor and an error
+    /// message, with a Rust backtrace of the caller appended to the message.
+    ///
+    /// The backtrace is captured with [`Backtrace::capture`], so it's only
+    /// included if backtraces have been enabled with the `RUST_BACKTRACE` or
+    /// `RUST_LIB_BACKTRACE` environment variables. Otherwise this is the same
+    /// as [`JNIEnv::throw_new`].
+    ///
+    /// This is useful when converting a Rust error into a Java exception,
+    /// since the Java stack trace of the exception ends at the native method
+    /// and doesn't show where in the Rust code the error came from.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// env.throw_new_with_backtrace("java/lang/RuntimeException", "something bad happened")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn throw_new_with_backtrace<'other_local, T>(&mut self, class: T, msg: &str) -> Result<()>
+    where
+        T: Desc<'local, JClass<'other_local>>,
+    {
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            self.throw_new(class, format!("{msg}\n\nRust backtrace:\n{backtrace}"))
+        } else {
+            self.throw_new(class, msg)
+        }
+    }
+
+    /// Create and throw a new exception of the type `E` with an error message.
+    ///
+    /// This is the same as [`JNIEnv::throw_new`], except that the exception
+    /// class is given by a [`ThrowableType`] instead of a class descriptor.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JIllegalArgumentException};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// env.throw_typed::<JIllegalArgumentException, _>("index must not be negative")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn throw_typed<E, S>(&mut self, msg: S) -> Result<()>
+    where
+        E: ThrowableType,
+        S: Into<JNIString>,
+    {
+        self.throw_new(E::CLASS_NAME, msg)
+    }
+
     /// Returns true if an exception is currently in the process of being thrown.
     ///
     /// This doesn't need to create any local references
@@ -592,7 +829,7 @@ impl<'local> JNIEnv<'local> {
         if throwable.is_null() {
             None
         } else {
-            Some(unsafe { JThrowable::from_raw(throwable) })
+            Some(unsafe { JThrowable::from_raw(track_local!(throwable)) })
         }
     }
 
@@ -610,6 +847,63 @@ impl<'local> JNIEnv<'local> {
         unsafe { jni_call_unchecked!(self, v1_1, ExceptionClear) };
     }
 
+    /// Catches the pending exception if it's an instance of the exception type
+    /// `E` (or one of its subclasses).
+    ///
+    /// If there's a pending exception of type `E`, it's cleared and returned
+    /// as a [`GlobalRef`], so that it can be kept (or rethrown later) without
+    /// holding on to a local reference. If there's no pending exception, or it isn't of type `E`, this returns
+    /// `None` and any pending exception is left pending, so that it will
+    /// continue being thrown when control is returned to Java.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JInterruptedException};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// loop {
+    ///     match env.call_static_method("java/lang/Thread", "sleep", "(J)V", &[100.into()]) {
+    ///         Err(jni::errors::Error::JavaException) => {
+    ///             if env.exception_catch_as::<JInterruptedException>()?.is_some() {
+    ///                 // Interrupted, so try again
+    ///                 continue;
+    ///             }
+    ///             // Anything else is left pending, to be rethrown
+    ///             return Err(jni::errors::Error::JavaException);
+    ///         }
+    ///         result => return result.map(|_| ()),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn exception_catch_as<E>(&mut self) -> Result<Option<GlobalRef>>
+    where
+        E: ThrowableType,
+    {
+        let exception = match self.exception_occurred() {
+            Some(exception) => self.auto_local(exception),
+            None => return Ok(None),
+        };
+        // `IsInstanceOf` can't be called while an exception is pending
+        self.exception_clear();
+
+        let matches = match exception.is_instance::<E>(self) {
+            Ok(matches) => matches,
+            Err(err) => {
+                // Keep the original exception pending, rather than the one from the check
+                self.exception_clear();
+                self.throw(&exception)?;
+                return Err(err);
+            }
+        };
+        if matches {
+            Ok(Some(self.new_global_ref(&exception)?))
+        } else {
+            self.throw(&exception)?;
+            Ok(None)
+        }
+    }
+
     /// Abort the JVM with an error message.
     ///
     /// This method is guaranteed not to panic, call any JNI function other
@@ -702,7 +996,7 @@ impl<'local> JNIEnv<'local> {
             data as *mut c_void,
             len as jlong
         )?;
-        Ok(JByteBuffer::from_raw(obj))
+        Ok(JByteBuffer::from_raw(track_local!(obj)))
     }
 
     /// Returns the starting address of the memory of the direct
@@ -744,6 +1038,126 @@ impl<'local> JNIEnv<'local> {
         }
     }
 
+    /// Executes the given function with a read-only, direct java.nio.ByteBuffer
+    /// that borrows `data` without copying it.
+    ///
+    /// The function runs in a new local reference frame and is passed a
+    /// read-only view (see `ByteBuffer.asReadOnlyBuffer()`) over `data`.
+    ///
+    /// Once the function returns (or panics) the buffer is invalidated before
+    /// `data` is released: its address, capacity, limit and position are all
+    /// reset to zero so any later attempt by Java code to read from a buffer
+    /// that was retained (e.g. stored in a field) will throw an
+    /// `IndexOutOfBoundsException` or `BufferUnderflowException` instead of
+    /// reading memory that is no longer borrowed.
+    ///
+    /// Any exception that is pending when the function returns will still be
+    /// pending after the buffer has been invalidated.
+    ///
+    /// This relies on the `address`, `capacity`, `limit`, `position` and
+    /// `mark` fields of `java.nio.Buffer` (as found in OpenJDK and Android) and
+    /// returns an error, without calling `f`, if they can't be found.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// let data = b"hello";
+    /// let first = unsafe {
+    ///     env.with_borrowed_byte_buffer(data, |env, buf| {
+    ///         env.call_method(buf, "get", "(I)B", &[0.into()])?.b()
+    ///     })?
+    /// };
+    /// assert_eq!(first, b'h' as i8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Only the buffer that is passed to `f` is invalidated. Buffers that are
+    /// derived from it (with `duplicate()`, `slice()`, `asReadOnlyBuffer()` or
+    /// views such as `asIntBuffer()`) copy its address when they are created
+    /// and are not invalidated, so they would read `data` after it has been
+    /// released.
+    ///
+    /// The caller must ensure that no buffer derived from the one that is
+    /// passed to `f` is used after `f` returns, by this thread or any other.
+    pub unsafe fn with_borrowed_byte_buffer<F, T, E>(
+        &mut self,
+        data: &[u8],
+        f: F,
+    ) -> std::result::Result<T, E>
+    where
+        F: for<'new_local> FnOnce(
+            &mut JNIEnv<'new_local>,
+            &JByteBuffer<'new_local>,
+        ) -> std::result::Result<T, E>,
+        E: From<Error>,
+    {
+        self.with_local_frame(4, |env| {
+            // Look up the fields up-front so that invalidating the buffers
+            // can't fail once they have been exposed to Java.
+            let buffer_class = env.find_class("java/nio/Buffer")?;
+            let address = env.get_field_id(&buffer_class, "address", "J")?;
+            let int_fields = [
+                (env.get_field_id(&buffer_class, "capacity", "I")?, 0),
+                (env.get_field_id(&buffer_class, "limit", "I")?, 0),
+                (env.get_field_id(&buffer_class, "position", "I")?, 0),
+                (env.get_field_id(&buffer_class, "mark", "I")?, -1),
+            ];
+
+            let invalidate = |env: &JNIEnv, bufs: &[&JByteBuffer]| {
+                // Set<type>Field may not be called with a pending exception, so
+                // any exception is set aside and then re-thrown afterwards.
+                let pending = jni_call_unchecked!(env, v1_1, ExceptionOccurred);
+                if !pending.is_null() {
+                    jni_call_unchecked!(env, v1_1, ExceptionClear);
+                }
+
+                // Safety: the field IDs were looked up from `java.nio.Buffer`
+                // with matching types and Set<type>Field can't throw
+                for buf in bufs {
+                    let buf = buf.as_raw();
+                    for (field, value) in int_fields {
+                        jni_call_unchecked!(env, v1_1, SetIntField, buf, field.into_raw(), value);
+                    }
+                    jni_call_unchecked!(env, v1_1, SetLongField, buf, address.into_raw(), 0);
+                }
+
+                if !pending.is_null() {
+                    jni_call_unchecked!(env, v1_1, Throw, pending);
+                    jni_call_unchecked!(env, v1_1, DeleteLocalRef, pending);
+                }
+            };
+
+            // Safety: Java is only given a read-only view of `data` and both
+            // buffers are invalidated before `data` is released. The caller
+            // guarantees that buffers derived from the view aren't used after
+            // that.
+            let buf = env.new_direct_byte_buffer(data.as_ptr() as *mut u8, data.len())?;
+            let view = env
+                .call_method(&buf, "asReadOnlyBuffer", "()Ljava/nio/ByteBuffer;", &[])
+                .and_then(|view| view.l());
+            let view = match view {
+                Ok(view) => JByteBuffer::from(view),
+                Err(err) => {
+                    invalidate(env, &[&buf]);
+                    return Err(err.into());
+                }
+            };
+
+            let ret = catch_unwind(AssertUnwindSafe(|| f(env, &view)));
+            invalidate(env, &[&view, &buf]);
+
+            match ret {
+                Ok(ret) => ret,
+                Err(payload) => resume_unwind(payload),
+            }
+        })
+    }
+
     /// Creates a new global reference to the Java object `obj`.
     ///
     /// Global references take more time to create or delete than ordinary
@@ -754,6 +1168,7 @@ impl<'local> JNIEnv<'local> {
         O: AsRef<JObject<'other_local>>,
     {
         let jvm = self.get_java_vm()?;
+        deferred_refs::flush(self);
         unsafe {
             let new_ref = jni_call_unchecked!(self, v1_1, NewGlobalRef, obj.as_ref().as_raw());
             let global = GlobalRef::from_raw(jvm, new_ref);
@@ -761,6 +1176,18 @@ impl<'local> JNIEnv<'local> {
         }
     }
 
+    /// Deletes the global and weak global references that were dropped on
+    /// threads that weren't attached to the JVM, and so couldn't be deleted at
+    /// the time.
+    ///
+    /// This also happens automatically whenever a thread is attached, or a
+    /// [`GlobalRef`] or [`WeakRef`] is created or dropped on an attached
+    /// thread, so this only needs to be called if none of those happen
+    /// regularly. See [`GlobalRef`] for more information.
+    pub fn delete_deferred_refs(&self) {
+        deferred_refs::flush(self);
+    }
+
     /// Creates a new weak global reference.
     ///
     /// Weak global references are a special kind of Java object reference that
@@ -777,6 +1204,7 @@ impl<'local> JNIEnv<'local> {
         // is fallible, we need to call it before doing anything else, so that we don't leak
         // memory if it fails.
         let vm = self.get_java_vm()?;
+        deferred_refs::flush(self);
 
         let obj = obj.as_ref().as_raw();
 
@@ -914,7 +1342,12 @@ impl<'local> JNIEnv<'local> {
 
         // Safety: we check the JNI version is > 1.2 in `from_raw`
         let local = unsafe {
-            JObject::from_raw(jni_call_unchecked!(self, v1_2, NewLocalRef, obj.as_raw()))
+            JObject::from_raw(track_local!(jni_call_unchecked!(
+                self,
+                v1_2,
+                NewLocalRef,
+                obj.as_raw()
+            )))
         };
 
         // Since we know we didn't pass a `null` `obj` reference to `NewLocalRef` then
@@ -968,6 +1401,9 @@ impl<'local> JNIEnv<'local> {
         let obj = obj.into();
         let raw = obj.into_raw();
 
+        #[cfg(feature = "local-ref-tracking")]
+        crate::local_ref_tracking::deleted(raw);
+
         // Safety: `raw` may be `null`
         unsafe {
             jni_call_unchecked!(self, v1_1, DeleteLocalRef, raw);
@@ -990,7 +1426,10 @@ impl<'local> JNIEnv<'local> {
         // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
         // We check for JNI > 1.2 in `from_raw`
         let res = unsafe { jni_call_unchecked!(self, v1_2, PushLocalFrame, capacity) };
-        jni_error_code_to_result(res)
+        jni_error_code_to_result(res)?;
+        #[cfg(feature = "local-ref-tracking")]
+        crate::local_ref_tracking::push_frame(capacity);
+        Ok(())
     }
 
     /// Pops off the current local reference frame, frees all the local
@@ -1012,12 +1451,10 @@ impl<'local> JNIEnv<'local> {
         // Safety:
         // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
         // We check for JNI > 1.2 in `from_raw`
-        Ok(JObject::from_raw(jni_call_unchecked!(
-            self,
-            v1_2,
-            PopLocalFrame,
-            result.as_raw()
-        )))
+        let obj = jni_call_unchecked!(self, v1_2, PopLocalFrame, result.as_raw());
+        #[cfg(feature = "local-ref-tracking")]
+        crate::local_ref_tracking::pop_frame();
+        Ok(JObject::from_raw(track_local!(obj)))
     }
 
     /// Executes the given function in a new local reference frame, in which at least a given number
@@ -1095,11 +1532,37 @@ impl<'local> JNIEnv<'local> {
 
     /// Allocates a new object from a class descriptor without running a
     /// constructor.
+    ///
+    /// This wraps the JNI `AllocObject` function. The fields of the returned
+    /// object all have their default values (`0`, `false` or `null`), even if
+    /// the class declares initial values for them or they are `final`, since
+    /// those are assigned by the constructor. No constructor of any superclass
+    /// runs either, so the object may not uphold the invariants of its class
+    /// until its fields are set, for example with [`JNIEnv::set_field`].
+    ///
+    /// This is mainly useful for deserialization frameworks and test fixtures,
+    /// which need to create instances of classes whose constructors have side
+    /// effects, and then set their fields directly. The class is initialized
+    /// (its static initializer runs) if it hasn't been already.
+    ///
+    /// # Errors
+    ///
+    /// * If the class is an interface or an abstract class, this returns
+    ///   [`Error::JavaException`] with a pending `InstantiationException`.
+    /// * If the class is an array class, which `AllocObject` must not be
+    ///   called with, this returns [`Error::JniCall`] with
+    ///   [`JniError::InvalidArguments`] without calling `AllocObject`. Use
+    ///   [`JNIEnv::new_object_array`] or one of the methods for creating
+    ///   primitive arrays instead.
     pub fn alloc_object<'other_local, T>(&mut self, class: T) -> Result<JObject<'local>>
     where
         T: Desc<'local, JClass<'other_local>>,
     {
         let class = class.lookup(self)?;
+        let is_array = self.call_method(class.as_ref(), "isArray", "()Z", &[])?;
+        if is_array.z()? {
+            return Err(JniCall(JniError::InvalidArguments));
+        }
         let obj = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, AllocObject, class.as_ref().as_raw())?
         };
@@ -1107,7 +1570,7 @@ impl<'local> JNIEnv<'local> {
         // Ensure that `class` isn't dropped before the JNI call returns.
         drop(class);
 
-        Ok(unsafe { JObject::from_raw(obj) })
+        Ok(unsafe { JObject::from_raw(track_local!(obj)) })
     }
 
     /// Common functionality for finding methods.
@@ -1173,6 +1636,9 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString>,
     {
+        let name = name.into();
+        let sig = sig.into();
+        trace_env_method!("get_method_id", name = name.to_str(), sig = sig.to_str());
         self.get_method_id_base(class, name, sig, |env, class, name, sig| unsafe {
             jni_call_check_ex_and_null_ret!(
                 env,
@@ -1210,6 +1676,13 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString>,
     {
+        let name = name.into();
+        let sig = sig.into();
+        trace_env_method!(
+            "get_static_method_id",
+            name = name.to_str(),
+            sig = sig.to_str()
+        );
         self.get_method_id_base(class, name, sig, |env, class, name, sig| unsafe {
             jni_call_check_ex_and_null_ret!(
                 env,
@@ -1245,9 +1718,14 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString>,
     {
-        let class = class.lookup(self)?;
         let ffi_name = name.into();
         let ffi_sig = sig.into();
+        trace_env_method!(
+            "get_field_id",
+            name = ffi_name.to_str(),
+            sig = ffi_sig.to_str()
+        );
+        let class = class.lookup(self)?;
 
         let res = unsafe {
             jni_call_check_ex_and_null_ret!(
@@ -1296,9 +1774,14 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString>,
     {
-        let class = class.lookup(self)?;
         let ffi_name = name.into();
         let ffi_sig = sig.into();
+        trace_env_method!(
+            "get_static_field_id",
+            name = ffi_name.to_str(),
+            sig = ffi_sig.to_str()
+        );
+        let class = class.lookup(self)?;
 
         let res = unsafe {
             jni_call_check_ex_and_null_ret!(
@@ -1336,12 +1819,12 @@ impl<'local> JNIEnv<'local> {
         let obj = obj.as_ref();
         let obj = null_check!(obj, "get_object_class")?;
         unsafe {
-            Ok(JClass::from_raw(jni_call_unchecked!(
+            Ok(JClass::from_raw(track_local!(jni_call_unchecked!(
                 self,
                 v1_1,
                 GetObjectClass,
                 obj.as_raw()
-            )))
+            ))))
         }
     }
 
@@ -1390,7 +1873,7 @@ impl<'local> JNIEnv<'local> {
         let ret = match ret {
             Object | Array => {
                 let obj = invoke!(CallStaticObjectMethodA -> jobject);
-                let obj = unsafe { JObject::from_raw(obj) };
+                let obj = unsafe { JObject::from_raw(track_local!(obj)) };
                 JValueOwned::from(obj)
             }
             Primitive(Boolean) => invoke!(CallStaticBooleanMethodA -> bool).into(),
@@ -1463,7 +1946,7 @@ impl<'local> JNIEnv<'local> {
         let ret = match ret_ty {
             Object | Array => {
                 let obj = invoke!(CallObjectMethodA -> jobject);
-                let obj = unsafe { JObject::from_raw(obj) };
+                let obj = unsafe { JObject::from_raw(track_local!(obj)) };
                 JValueOwned::from(obj)
             }
             Primitive(Boolean) => invoke!(CallBooleanMethodA -> bool).into(),
@@ -1531,7 +2014,7 @@ impl<'local> JNIEnv<'local> {
         let ret = match ret_ty {
             Object | Array => {
                 let obj = invoke!(CallNonvirtualObjectMethodA -> jobject);
-                let obj = unsafe { JObject::from_raw(obj) };
+                let obj = unsafe { JObject::from_raw(track_local!(obj)) };
                 JValueOwned::from(obj)
             }
             Primitive(Boolean) => invoke!(CallNonvirtualBooleanMethodA -> bool).into(),
@@ -1584,6 +2067,8 @@ impl<'local> JNIEnv<'local> {
         S: Into<JNIString>,
         T: Into<JNIString> + AsRef<str>,
     {
+        let name = name.into();
+        trace_env_method!("call_method", name = name.to_str(), sig = sig.as_ref());
         let obj = obj.as_ref();
         let obj = null_check!(obj, "call_method obj argument")?;
 
@@ -1644,6 +2129,12 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString> + AsRef<str>,
     {
+        let name = name.into();
+        trace_env_method!(
+            "call_static_method",
+            name = name.to_str(),
+            sig = sig.as_ref()
+        );
         let parsed = TypeSignature::from_str(&sig)?;
         if parsed.args.len() != args.len() {
             return Err(Error::InvalidArgList(parsed));
@@ -1703,6 +2194,12 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString> + AsRef<str>,
     {
+        let name = name.into();
+        trace_env_method!(
+            "call_nonvirtual_method",
+            name = name.to_str(),
+            sig = sig.as_ref()
+        );
         let obj = obj.as_ref();
         let obj = null_check!(obj, "call_method obj argument")?;
 
@@ -1753,6 +2250,7 @@ impl<'local> JNIEnv<'local> {
         T: Desc<'local, JClass<'other_local>>,
         U: Into<JNIString> + AsRef<str>,
     {
+        trace_env_method!("new_object", sig = ctor_sig.as_ref());
         // parse the signature
         let parsed = TypeSignature::from_str(&ctor_sig)?;
 
@@ -1826,7 +2324,7 @@ impl<'local> JNIEnv<'local> {
                 ctor_id.into_raw(),
                 jni_args
             )
-            .map(|obj| JObject::from_raw(obj))
+            .map(|obj| JObject::from_raw(track_local!(obj)))
         }?;
 
         // Ensure that `class` isn't dropped before the JNI call returns.
@@ -1849,6 +2347,20 @@ impl<'local> JNIEnv<'local> {
         JList::from_env(self, obj)
     }
 
+    /// Cast a JObject to a JIterator. This won't throw exceptions or return errors
+    /// in the event that the object isn't actually an iterator, but the methods on
+    /// the resulting iterator object will.
+    pub fn get_iterator<'other_local_1, 'obj_ref>(
+        &mut self,
+        obj: &'obj_ref JObject<'other_local_1>,
+    ) -> Result<JIterator<'local, 'other_local_1, 'obj_ref>>
+    where
+        'other_local_1: 'obj_ref,
+    {
+        let obj = null_check!(obj, "get_iterator obj argument")?;
+        JIterator::from_env(self, obj)
+    }
+
     /// Cast a JObject to a JMap. This won't throw exceptions or return errors
     /// in the event that the object isn't actually a map, but the methods on
     /// the resulting map object will.
@@ -1936,10 +2448,187 @@ impl<'local> JNIEnv<'local> {
         let ffi_str = from.into();
         unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewStringUTF, ffi_str.as_ptr())
-                .map(|s| JString::from_raw(s))
+                .map(|s| JString::from_raw(track_local!(s)))
+        }
+    }
+
+    /// Create a new java string object from UTF-16 code units, with the JNI
+    /// function `NewString`.
+    ///
+    /// Unlike [`JNIEnv::new_string`], this doesn't need to re-encode the
+    /// string, so it's the better choice for text that's already UTF-16, such
+    /// as a [`JavaString16`][crate::strings::JavaString16] or a Windows wide string. The code units aren't
+    /// required to be valid UTF-16.
+    ///
+    /// # Errors
+    /// Returns [`Error::JniCall`] with [`JniError::InvalidArguments`] if there
+    /// are more than [`jsize::MAX`] code units.
+    pub fn new_string_utf16(&self, chars: impl AsRef<[u16]>) -> Result<JString<'local>> {
+        let chars = chars.as_ref();
+        let len = jsize::try_from(chars.len()).map_err(|_| JniCall(JniError::InvalidArguments))?;
+        unsafe {
+            jni_call_check_ex_and_null_ret!(self, v1_1, NewString, chars.as_ptr(), len)
+                .map(|s| JString::from_raw(track_local!(s)))
+        }
+    }
+
+    /// Gets the UTF-16 code units of a Java string, with the JNI function
+    /// `GetStringChars`, without checking that the given object is a
+    /// `java.lang.String`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that the Object passed in is an instance of
+    /// `java.lang.String`, passing in anything else will lead to undefined
+    /// behaviour (The JNI implementation is likely to crash or abort the
+    /// process).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `obj` is `null`
+    pub unsafe fn get_string_utf16_unchecked<'other_local: 'obj_ref, 'obj_ref>(
+        &self,
+        obj: &'obj_ref JString<'other_local>,
+    ) -> Result<JavaStr16<'local, 'other_local, 'obj_ref>> {
+        JavaStr16::from_env_totally_unchecked(self, obj)
+    }
+
+    /// Gets the UTF-16 code units of a Java string.
+    ///
+    /// The returned [`JavaStr16`] derefs to a `[u16]` slice, so unlike
+    /// [`JNIEnv::get_string`] this doesn't re-encode the string at all.
+    ///
+    /// This entails checking that the given object is a `java.lang.String`,
+    /// then calling the JNI function `GetStringChars`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `obj` is `null` or is not an instance of `java.lang.String`.
+    pub fn get_string_utf16<'other_local: 'obj_ref, 'obj_ref>(
+        &mut self,
+        obj: &'obj_ref JString<'other_local>,
+    ) -> Result<JavaStr16<'local, 'other_local, 'obj_ref>> {
+        let obj = null_check!(obj, "get_string_utf16 obj argument")?;
+        if !self.is_instance_of(obj, "java/lang/String")? {
+            return Err(JniCall(JniError::InvalidArguments));
+        }
+
+        // SAFETY: We check that the passed in Object is actually a java.lang.String
+        unsafe { self.get_string_utf16_unchecked(obj) }
+    }
+
+    /// Returns the length of a Java string, in UTF-16 code units
+    /// (`String.length()`).
+    pub fn get_string_length<'other_local>(&self, string: &JString<'other_local>) -> Result<jsize> {
+        let string = null_check!(string, "get_string_length string argument")?;
+        let len: jsize =
+            unsafe { jni_call_unchecked!(self, v1_1, GetStringLength, string.as_raw()) };
+        Ok(len)
+    }
+
+    /// Returns the length of a Java string's [modified UTF-8] encoding, in
+    /// bytes and excluding any null terminator.
+    ///
+    /// [modified UTF-8]: https://en.wikipedia.org/wiki/UTF-8#Modified_UTF-8
+    pub fn get_string_utf_length<'other_local>(
+        &self,
+        string: &JString<'other_local>,
+    ) -> Result<jsize> {
+        let string = null_check!(string, "get_string_utf_length string argument")?;
+        let len: jsize =
+            unsafe { jni_call_unchecked!(self, v1_1, GetStringUTFLength, string.as_raw()) };
+        Ok(len)
+    }
+
+    /// Copy the UTF-16 code units of a Java string from the `start` index to
+    /// the `buf` slice. The number of copied code units is equal to the `buf`
+    /// length.
+    ///
+    /// Unlike [`JNIEnv::get_string`], this doesn't need to copy or convert the
+    /// whole string, so it can be used to read a very large string a piece at
+    /// a time into a fixed size buffer.
+    ///
+    /// # Errors
+    /// If `start` is negative _or_ `start + buf.len()` is greater than the
+    /// [string length] then no code units are copied, a
+    /// `StringIndexOutOfBoundsException` is thrown, and `Err` is returned.
+    ///
+    /// [string length]: JNIEnv::get_string_length
+    pub fn get_string_region<'other_local>(
+        &self,
+        string: &JString<'other_local>,
+        start: jsize,
+        buf: &mut [jchar],
+    ) -> Result<()> {
+        let string = null_check!(string, "get_string_region string argument")?;
+        unsafe {
+            jni_call_check_ex!(
+                self,
+                v1_2,
+                GetStringRegion,
+                string.as_raw(),
+                start,
+                buf.len() as jsize,
+                buf.as_mut_ptr()
+            )
         }
     }
 
+    /// Copy `len` UTF-16 code units of a Java string, from the `start` index,
+    /// into the `buf` slice in [modified UTF-8] encoding. Returns the number of
+    /// bytes that were written.
+    ///
+    /// Each code unit takes up to three bytes in modified UTF-8, and JVMs may
+    /// also write a null terminator, so `buf` must be at least `3 * len + 1`
+    /// bytes long. The result can be converted into a
+    /// Rust string with [`JNIStr`], as long as the region doesn't split a
+    /// surrogate pair.
+    ///
+    /// [modified UTF-8]: https://en.wikipedia.org/wiki/UTF-8#Modified_UTF-8
+    ///
+    /// # Errors
+    /// Returns [`Error::JniCall`] with [`JniError::InvalidArguments`] if `len`
+    /// is negative or `buf` is too short.
+    ///
+    /// If `start` is negative _or_ `start + len` is greater than the
+    /// [string length] then nothing is copied, a
+    /// `StringIndexOutOfBoundsException` is thrown, and `Err` is returned.
+    ///
+    /// [string length]: JNIEnv::get_string_length
+    pub fn get_string_utf_region<'other_local>(
+        &self,
+        string: &JString<'other_local>,
+        start: jsize,
+        len: jsize,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        let string = null_check!(string, "get_string_utf_region string argument")?;
+        // Leave room for the null terminator that JVMs write, even though the JNI spec doesn't
+        // mention it
+        let max_bytes = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(3))
+            .and_then(|bytes| bytes.checked_add(1))
+            .ok_or(JniCall(JniError::InvalidArguments))?;
+        let buf = buf
+            .get_mut(..max_bytes)
+            .ok_or(JniCall(JniError::InvalidArguments))?;
+        // Modified UTF-8 never contains a zero byte, so the end of the output is the first zero
+        buf.fill(0);
+        unsafe {
+            jni_call_check_ex!(
+                self,
+                v1_2,
+                GetStringUTFRegion,
+                string.as_raw(),
+                start,
+                len,
+                buf.as_mut_ptr() as *mut c_char
+            )
+        }?;
+        Ok(buf.iter().position(|&b| b == 0).unwrap_or(buf.len()))
+    }
+
     /// Get the length of a [`JPrimitiveArray`] or [`JObjectArray`].
     pub fn get_array_length<'other_local, 'array>(
         &self,
@@ -1979,7 +2668,7 @@ impl<'local> JNIEnv<'local> {
                 class.as_ref().as_raw(),
                 initial_element.as_ref().as_raw()
             )
-            .map(|array| JObjectArray::from_raw(array))?
+            .map(|array| JObjectArray::from_raw(track_local!(array)))?
         };
 
         // Ensure that `class` isn't dropped before the JNI call returns.
@@ -1988,23 +2677,46 @@ impl<'local> JNIEnv<'local> {
         Ok(array)
     }
 
+    /// Creates a new `String[]` array that contains a copy of each of the
+    /// given strings.
+    ///
+    /// Only one local reference is returned, for the array, since the local
+    /// reference for each element is deleted once it's been stored.
+    ///
+    /// # Errors
+    /// Returns [`Error::JniCall`] with [`JniError::InvalidArguments`] if there
+    /// are more than [`jsize::MAX`] strings.
+    pub fn new_string_array(
+        &mut self,
+        strings: &[impl AsRef<str>],
+    ) -> Result<JObjectArray<'local, JString<'static>>> {
+        let length =
+            jsize::try_from(strings.len()).map_err(|_| JniCall(JniError::InvalidArguments))?;
+        let array = JObjectArray::<JString>::new(self, length, JString::default())?;
+        for (index, string) in (0..length).zip(strings) {
+            let string = self.auto_local(self.new_string(string.as_ref())?);
+            array.set_element(self, index, &*string)?;
+        }
+        Ok(array)
+    }
+
     /// Returns a local reference to an element of the [`JObjectArray`] `array`.
-    pub fn get_object_array_element<'other_local>(
+    pub fn get_object_array_element<'other_local, T: Reference>(
         &mut self,
-        array: impl AsRef<JObjectArray<'other_local>>,
+        array: impl AsRef<JObjectArray<'other_local, T>>,
         index: jsize,
     ) -> Result<JObject<'local>> {
         let array = null_check!(array.as_ref(), "get_object_array_element array argument")?;
         unsafe {
             jni_call_check_ex!(self, v1_1, GetObjectArrayElement, array.as_raw(), index)
-                .map(|obj| JObject::from_raw(obj))
+                .map(|obj| JObject::from_raw(track_local!(obj)))
         }
     }
 
     /// Sets an element of the [`JObjectArray`] `array`.
-    pub fn set_object_array_element<'other_local_1, 'other_local_2>(
+    pub fn set_object_array_element<'other_local_1, 'other_local_2, T: Reference>(
         &self,
-        array: impl AsRef<JObjectArray<'other_local_1>>,
+        array: impl AsRef<JObjectArray<'other_local_1, T>>,
         index: jsize,
         value: impl AsRef<JObject<'other_local_2>>,
     ) -> Result<()> {
@@ -2067,7 +2779,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_boolean_array(&self, length: jsize) -> Result<JBooleanArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewBooleanArray, length)
-                .map(|array| JBooleanArray::from_raw(array))?
+                .map(|array| JBooleanArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2076,7 +2788,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_byte_array(&self, length: jsize) -> Result<JByteArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewByteArray, length)
-                .map(|array| JByteArray::from_raw(array))?
+                .map(|array| JByteArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2085,7 +2797,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_char_array(&self, length: jsize) -> Result<JCharArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewCharArray, length)
-                .map(|array| JCharArray::from_raw(array))?
+                .map(|array| JCharArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2094,7 +2806,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_short_array(&self, length: jsize) -> Result<JShortArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewShortArray, length)
-                .map(|array| JShortArray::from_raw(array))?
+                .map(|array| JShortArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2103,7 +2815,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_int_array(&self, length: jsize) -> Result<JIntArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewIntArray, length)
-                .map(|array| JIntArray::from_raw(array))?
+                .map(|array| JIntArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2112,7 +2824,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_long_array(&self, length: jsize) -> Result<JLongArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewLongArray, length)
-                .map(|array| JLongArray::from_raw(array))?
+                .map(|array| JLongArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2121,7 +2833,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_float_array(&self, length: jsize) -> Result<JFloatArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewFloatArray, length)
-                .map(|array| JFloatArray::from_raw(array))?
+                .map(|array| JFloatArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2130,7 +2842,7 @@ impl<'local> JNIEnv<'local> {
     pub fn new_double_array(&self, length: jsize) -> Result<JDoubleArray<'local>> {
         let array = unsafe {
             jni_call_check_ex_and_null_ret!(self, v1_1, NewDoubleArray, length)
-                .map(|array| JDoubleArray::from_raw(array))?
+                .map(|array| JDoubleArray::from_raw(track_local!(array)))?
         };
         Ok(array)
     }
@@ -2584,7 +3296,7 @@ impl<'local> JNIEnv<'local> {
             Object | Array => {
                 let obj = unsafe {
                     jni_call_check_ex!(self, v1_1, GetObjectField, obj, field)
-                        .map(|obj| JObject::from_raw(obj))?
+                        .map(|obj| JObject::from_raw(track_local!(obj)))?
                 };
                 Ok(obj.into())
             }
@@ -2660,6 +3372,8 @@ impl<'local> JNIEnv<'local> {
         S: Into<JNIString>,
         T: Into<JNIString> + AsRef<str>,
     {
+        let name = name.into();
+        trace_env_method!("get_field", name = name.to_str(), sig = ty.as_ref());
         let obj = obj.as_ref();
         let class = self.get_object_class(obj)?;
         let class = self.auto_local(class);
@@ -2687,6 +3401,8 @@ impl<'local> JNIEnv<'local> {
         S: Into<JNIString>,
         T: Into<JNIString> + AsRef<str>,
     {
+        let name = name.into();
+        trace_env_method!("set_field", name = name.to_str(), sig = ty.as_ref());
         let obj = obj.as_ref();
         let field_ty = JavaType::from_str(ty.as_ref())?;
         let val_primitive = val.primitive_type();
@@ -2751,7 +3467,7 @@ impl<'local> JNIEnv<'local> {
             Method(_) => Err(Error::WrongJValueType("Method", "see java field")),
             Object(_) | Array(_) => {
                 let obj = field!(GetStaticObjectField);
-                let obj = unsafe { JObject::from_raw(obj) };
+                let obj = unsafe { JObject::from_raw(track_local!(obj)) };
                 Ok(JValueOwned::from(obj))
             }
             Primitive(Boolean) => Ok(field!(GetStaticBooleanField).into()),
@@ -2783,6 +3499,12 @@ impl<'local> JNIEnv<'local> {
         U: Into<JNIString>,
         V: Into<JNIString> + AsRef<str>,
     {
+        let field = field.into();
+        trace_env_method!(
+            "get_static_field",
+            name = field.to_str(),
+            sig = sig.as_ref()
+        );
         let ty = JavaType::from_str(sig.as_ref())?;
 
         // go ahead and look up the class sincewe'll need that for the next
@@ -2869,7 +3591,7 @@ impl<'local> JNIEnv<'local> {
         let class = env.get_object_class(obj)?;
         let class = self.auto_local(class);
         let field_id: JFieldID = Desc::<JFieldID>::lookup((&class, &field, "J"), &mut env)?;
-        let guard = self.lock_obj(obj)?;
+        let guard = self.lock_object(obj)?;
         Ok((guard, field_id))
     }
 
@@ -3051,21 +3773,66 @@ impl<'local> JNIEnv<'local> {
         Ok(mbox.into_inner().unwrap())
     }
 
+    /// Enters the monitor associated with a Java object, equivalent to the
+    /// start of a `synchronized (obj) {}` block in Java.
+    ///
+    /// The returned [`MonitorGuard`] exits the monitor when it is dropped. The
+    /// guard holds its own local reference to `obj` so the monitor is always
+    /// exited for the same object, even if the caller deletes its reference
+    /// first.
+    ///
+    /// Monitors are re-entrant, so it's OK to lock the same object more than
+    /// once on the same thread (each guard exits the monitor once).
+    ///
+    /// Since the monitor is owned by the current thread, the guard is not
+    /// `Send`.
+    ///
+    /// Returns `Err(Error::NullPtr)` if `obj` is `null`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JObject};
+    /// #
+    /// # fn example(env: &mut JNIEnv, obj: &JObject) -> Result<()> {
+    /// {
+    ///     let _guard = env.lock_object(obj)?;
+    ///     // Java threads that `synchronized (obj)` will block here
+    /// }
+    /// // The monitor has been exited
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lock_object<'other_local, O>(&self, obj: O) -> Result<MonitorGuard<'local>>
+    where
+        O: AsRef<JObject<'other_local>>,
+    {
+        let obj = null_check!(obj.as_ref(), "lock_object obj argument")?;
+        // Safety: NewLocalRef and MonitorEnter have no documented exceptions
+        // and we checked that `obj` is not null.
+        unsafe {
+            let inner = jni_call_only_check_null_ret!(self, v1_2, NewLocalRef, obj.as_raw())?;
+            let res = jni_call_unchecked!(self, v1_1, MonitorEnter, inner);
+            if let Err(err) = jni_error_code_to_result(res) {
+                jni_call_unchecked!(self, v1_1, DeleteLocalRef, inner);
+                return Err(err);
+            }
+
+            Ok(MonitorGuard {
+                obj: inner,
+                env: self.internal,
+                life: Default::default(),
+            })
+        }
+    }
+
     /// Lock a Java object. The MonitorGuard that this returns is responsible
     /// for ensuring that it gets unlocked.
+    #[deprecated = "Use `lock_object` instead."]
     pub fn lock_obj<'other_local, O>(&self, obj: O) -> Result<MonitorGuard<'local>>
     where
         O: AsRef<JObject<'other_local>>,
     {
-        let inner = obj.as_ref().as_raw();
-        let res = unsafe { jni_call_unchecked!(self, v1_1, MonitorEnter, inner) };
-        jni_error_code_to_result(res)?;
-
-        Ok(MonitorGuard {
-            obj: inner,
-            env: self.internal,
-            life: Default::default(),
-        })
+        self.lock_object(obj)
     }
 
     /// Returns the Java VM interface.
@@ -3076,8 +3843,87 @@ impl<'local> JNIEnv<'local> {
         unsafe { JavaVM::from_raw(raw) }
     }
 
+    /// Creates a new `CompletableFuture` that is completed by running the given
+    /// Rust `future` on a Tokio runtime.
+    ///
+    /// When `future` resolves, the worker thread that it ran on is attached to
+    /// the Java VM as a daemon (if it isn't already), where it stays attached
+    /// until it exits, and the `CompletableFuture` is completed:
+    ///
+    /// * `Ok(value)` completes it with the object that `value` refers to.
+    /// * `Err(err)` completes it exceptionally with a
+    ///   `java.lang.RuntimeException` whose message is `err`'s description.
+    ///
+    /// If `future` panics, the `CompletableFuture` is completed exceptionally
+    /// with a `java.lang.RuntimeException("Rust future panicked")`.
+    ///
+    /// If the task is dropped before `future` resolves, for example because
+    /// the runtime is shut down, the `CompletableFuture` is never completed.
+    ///
+    /// This makes it possible to implement a native method that returns a
+    /// `CompletableFuture` using `async` Rust code.
+    ///
+    /// This is only available if the "tokio" feature is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JCompletableFuture};
+    /// #
+    /// # fn example<'local>(
+    /// #     env: &mut JNIEnv<'local>,
+    /// #     runtime: &tokio::runtime::Handle,
+    /// # ) -> Result<JCompletableFuture<'local>> {
+    /// let vm = env.get_java_vm()?;
+    /// env.new_completable_future_from(runtime, async move {
+    ///     let mut env = vm.attach_current_thread()?;
+    ///     let message = env.new_string("Hello from Rust")?;
+    ///     env.new_global_ref(message)
+    /// })
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn new_completable_future_from<F>(
+        &mut self,
+        runtime: &tokio::runtime::Handle,
+        future: F,
+    ) -> Result<JCompletableFuture<'local>>
+    where
+        F: std::future::Future<Output = Result<GlobalRef>> + Send + 'static,
+    {
+        let completable = JCompletableFuture::new(self)?;
+        let target = self.new_global_ref(&completable)?;
+        let vm = self.get_java_vm()?;
+
+        runtime.spawn(async move {
+            // A panic must not end the task without completing the Java future
+            let mut future = Box::pin(future);
+            let result = std::future::poll_fn(|cx| {
+                match catch_unwind(AssertUnwindSafe(|| Future::poll(future.as_mut(), cx))) {
+                    Ok(poll) => poll.map(Ok),
+                    Err(panic) => std::task::Poll::Ready(Err(panic)),
+                }
+            })
+            .await;
+            if let Err(err) = complete_from_result(&vm, &target, result) {
+                log::error!("Failed to complete CompletableFuture: {:#?}", err);
+            }
+        });
+
+        Ok(completable)
+    }
+
     /// Ensures that at least a given number of local references can be created
     /// in the current thread.
+    ///
+    /// The JVM only guarantees capacity for 16 local references in each
+    /// native method call (or in each [`Self::with_local_frame`] frame, for
+    /// the capacity that it's given). Code that creates a predictable number
+    /// of local references, such as a loop over a known number of elements,
+    /// can reserve capacity for all of them once, up front.
+    ///
+    /// Returns `Err(Error::JniCall(JniError::InvalidArguments))` if the
+    /// capacity doesn't fit in a `jint`. If the capacity can't be reserved,
+    /// an `OutOfMemoryError` is thrown and an error is returned.
     pub fn ensure_local_capacity(&self, capacity: usize) -> Result<()> {
         let capacity: jint = capacity
             .try_into()
@@ -3088,6 +3934,8 @@ impl<'local> JNIEnv<'local> {
         // - EnsureLocalCapacity has no documented exceptions that it throws
         let res = unsafe { jni_call_unchecked!(self, v1_2, EnsureLocalCapacity, capacity) };
         jni_error_code_to_result(res)?;
+        #[cfg(feature = "local-ref-tracking")]
+        crate::local_ref_tracking::ensure_capacity(capacity as usize);
         Ok(())
     }
 
@@ -3128,6 +3976,132 @@ impl<'local> JNIEnv<'local> {
         jni_error_code_to_result(res)
     }
 
+    /// Like [`Self::register_native_methods`], but with a more detailed error
+    /// if `RegisterNatives` rejects any of the `methods`.
+    ///
+    /// If registration fails, the pending exception (typically a
+    /// `NoSuchMethodError`) is cleared and each method is registered on its
+    /// own to find out which ones were rejected. The returned
+    /// [`Error::RegisterNativesFailed`] then lists the rejected methods,
+    /// including the extra information from each [`NativeMethodEx`], along
+    /// with the exception that each one caused.
+    ///
+    /// So that the class isn't left with only some of `methods` bound, all of
+    /// its native methods are then unregistered (`UnregisterNatives`),
+    /// including any that were registered before this call.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, NativeMethod, NativeMethodEx, objects::JClass};
+    /// # use jni::sys::jint;
+    /// #
+    /// extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+    ///     a + b
+    /// }
+    ///
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// let methods = [NativeMethodEx::new(NativeMethod {
+    ///     name: "add".into(),
+    ///     sig: "(II)I".into(),
+    ///     fn_ptr: add as *mut _,
+    /// })
+    /// .rust_fn("add")
+    /// .java_type("static native int add(int, int)")];
+    ///
+    /// env.register_native_methods_checked("com/example/Calculator", &methods)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_native_methods_checked<'other_local, T>(
+        &mut self,
+        class: T,
+        methods: &[NativeMethodEx],
+    ) -> Result<()>
+    where
+        T: Desc<'local, JClass<'other_local>>,
+    {
+        let class = class.lookup(self)?;
+        let class = class.as_ref();
+        let to_jni = |nm: &NativeMethod| JNINativeMethod {
+            name: nm.name.as_ptr() as *mut c_char,
+            signature: nm.sig.as_ptr() as *mut c_char,
+            fnPtr: nm.fn_ptr,
+        };
+
+        let jni_native_methods: Vec<JNINativeMethod> =
+            methods.iter().map(|nm| to_jni(&nm.method)).collect();
+        let res = unsafe {
+            jni_call_unchecked!(
+                self,
+                v1_1,
+                RegisterNatives,
+                class.as_raw(),
+                jni_native_methods.as_ptr(),
+                jni_native_methods.len() as jint
+            )
+        };
+        if res == sys::JNI_OK && !self.exception_check() {
+            return Ok(());
+        }
+        let overall = self.take_exception_description();
+
+        let mut rejected = Vec::new();
+        for method in methods {
+            let jni_native_method = to_jni(&method.method);
+            let res = unsafe {
+                jni_call_unchecked!(
+                    self,
+                    v1_1,
+                    RegisterNatives,
+                    class.as_raw(),
+                    &jni_native_method,
+                    1
+                )
+            };
+            if res != sys::JNI_OK || self.exception_check() {
+                let exception = self
+                    .take_exception_description()
+                    .unwrap_or_else(|| format!("error code {}", res));
+                rejected.push(format!("{}: {}", method, exception));
+            }
+        }
+
+        if rejected.is_empty() {
+            rejected.push(overall.unwrap_or_else(|| format!("error code {}", res)));
+        }
+
+        // Unbind the methods that were accepted on their own
+        let res = unsafe { jni_call_unchecked!(self, v1_1, UnregisterNatives, class.as_raw()) };
+        if res != sys::JNI_OK || self.exception_check() {
+            let exception = self
+                .take_exception_description()
+                .unwrap_or_else(|| format!("error code {}", res));
+            rejected.push(format!("failed to unregister natives: {}", exception));
+        }
+        Err(Error::RegisterNativesFailed(rejected.join("; ")))
+    }
+
+    /// Clears any pending exception and returns its `toString()`.
+    pub(crate) fn take_exception_description(&mut self) -> Option<String> {
+        let throwable = self.exception_occurred()?;
+        self.exception_clear();
+        let description = (|| -> Result<String> {
+            let description = self
+                .call_method(&throwable, "toString", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let description = JString::from(description);
+            let string = String::from(self.get_string(&description)?);
+            self.delete_local_ref(description);
+            Ok(string)
+        })()
+        .unwrap_or_else(|_| {
+            self.exception_clear();
+            "<unknown exception>".to_owned()
+        });
+        self.delete_local_ref(throwable);
+        Some(description)
+    }
+
     /// Unbind all native methods of class.
     pub fn unregister_native_methods<'other_local, T>(&mut self, class: T) -> Result<()>
     where
@@ -3309,6 +4283,50 @@ impl<'local> JNIEnv<'local> {
     }
 }
 
+/// Completes `target` (a global reference to a `CompletableFuture`) with the
+/// output of a Rust future, or `Err` if it panicked. See
+/// [`JNIEnv::new_completable_future_from`].
+#[cfg(feature = "tokio")]
+fn complete_from_result(
+    vm: &JavaVM,
+    target: &GlobalRef,
+    result: std::thread::Result<Result<GlobalRef>>,
+) -> Result<()> {
+    // Safety: the worker thread isn't detached once the JavaVM is destroyed,
+    // and attaching it fails after that
+    let mut env = unsafe { vm.attach_current_thread_as_daemon()? };
+    let target: &JCompletableFuture = target.as_obj().into();
+    // The thread stays attached, so don't leak local references
+    let completed = env.with_local_frame(2, |env| {
+        let message = match result {
+            Ok(Ok(value)) => {
+                target.complete(env, value.as_obj())?;
+                return Ok(());
+            }
+            Ok(Err(err)) => err.to_string(),
+            Err(_) => "Rust future panicked".to_string(),
+        };
+        let message = env.new_string(message)?;
+        let exception: JThrowable = env
+            .new_object(
+                "java/lang/RuntimeException",
+                "(Ljava/lang/String;)V",
+                &[JValue::from(&message)],
+            )?
+            .into();
+        target.complete_exceptionally(env, &exception)?;
+        Ok(())
+    });
+    // Don't leave an exception pending on the worker thread
+    if let Some(description) = env.take_exception_description() {
+        log::error!(
+            "Exception while completing CompletableFuture: {}",
+            description
+        );
+    }
+    completed
+}
+
 /// Native method descriptor.
 pub struct NativeMethod {
     /// Name of method.
@@ -3323,9 +4341,72 @@ pub struct NativeMethod {
     pub fn_ptr: *mut c_void,
 }
 
-/// Guard for a lock on a java object. This gets returned from the `lock_obj`
-/// method.
+/// A [`NativeMethod`] with extra information about where it came from, which
+/// is included in the error from [`JNIEnv::register_native_methods_checked`]
+/// if the method can't be registered.
+pub struct NativeMethodEx {
+    /// The native method to register.
+    pub method: NativeMethod,
+    /// The path of the Rust function that implements the method.
+    pub rust_fn: Option<&'static str>,
+    /// A description of the method's Java type, such as
+    /// `"static native int add(int, int)"`.
+    pub java_type: Option<&'static str>,
+    /// Where the method was declared in the Rust source code.
+    pub location: Option<&'static Location<'static>>,
+}
+
+impl NativeMethodEx {
+    /// Wraps a [`NativeMethod`], recording the caller's source location.
+    #[track_caller]
+    pub fn new(method: NativeMethod) -> Self {
+        Self {
+            method,
+            rust_fn: None,
+            java_type: None,
+            location: Some(Location::caller()),
+        }
+    }
+
+    /// Sets the path of the Rust function that implements the method.
+    pub fn rust_fn(mut self, rust_fn: &'static str) -> Self {
+        self.rust_fn = Some(rust_fn);
+        self
+    }
+
+    /// Sets the description of the method's Java type.
+    pub fn java_type(mut self, java_type: &'static str) -> Self {
+        self.java_type = Some(java_type);
+        self
+    }
+}
+
+impl fmt::Display for NativeMethodEx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.method.name.to_str(),
+            self.method.sig.to_str()
+        )?;
+        if let Some(java_type) = self.java_type {
+            write!(f, " (Java: {})", java_type)?;
+        }
+        if let Some(rust_fn) = self.rust_fn {
+            write!(f, " implemented by {}", rust_fn)?;
+        }
+        if let Some(location) = self.location {
+            write!(f, " declared at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Guard for a lock on a java object. This gets returned from the
+/// [`JNIEnv::lock_object`] method and exits the object's monitor when dropped.
+#[must_use = "the monitor is exited as soon as the guard is dropped"]
 pub struct MonitorGuard<'local> {
+    /// A local reference to the locked object, owned by the guard
     obj: sys::jobject,
     env: *mut sys::JNIEnv,
     life: PhantomData<&'local ()>,
@@ -3347,16 +4428,14 @@ impl<'local> Drop for MonitorGuard<'local> {
         // This also means we can assume the `IllegalMonitorStateException`
         // exception can't be thrown due to the current thread not owning
         // the monitor.
-        let res = unsafe {
-            jni_call_unchecked!(
-                &JNIEnv::from_raw_unchecked(self.env),
-                v1_1,
-                MonitorExit,
-                self.obj
-            )
-        };
+        //
+        // `self.obj` is a local reference that's owned by the guard, so it
+        // can't have been deleted before now.
+        let env = unsafe { JNIEnv::from_raw_unchecked(self.env) };
+        let res = unsafe { jni_call_unchecked!(&env, v1_1, MonitorExit, self.obj) };
         if let Err(err) = jni_error_code_to_result(res) {
             log::error!("error releasing java monitor: {err}");
         }
+        unsafe { jni_call_unchecked!(&env, v1_1, DeleteLocalRef, self.obj) };
     }
 }
@@ -0,0 +1,439 @@
+//! A minimal safe layer over the [JVM Tool Interface][jvmti] (JVMTI), for
+//! profiling and observability tools.
+//!
+//! A [`JvmtiEnv`] is obtained with [`JavaVM::get_jvmti_env`]. Most of its
+//! functions need capabilities, which have to be added with
+//! [`JvmtiEnv::add_capabilities`] first. A VM that's already running only
+//! offers some capabilities (see [`JvmtiEnv::potential_capabilities`]); others
+//! can only be added by an agent that's loaded when the VM starts.
+//!
+//! ```rust,no_run
+//! # use jni::{errors::Result, JNIEnv, JavaVM};
+//! use jni::jvmti::{Capabilities, Capability};
+//!
+//! # fn example(vm: &JavaVM, env: &mut JNIEnv) -> Result<()> {
+//! let jvmti = vm.get_jvmti_env()?;
+//! jvmti.add_capabilities(&Capabilities::new().with(Capability::TagObjects))?;
+//!
+//! let object = env.new_object("java/lang/Object", "()V", &[])?;
+//! jvmti.set_tag(&object, 42)?;
+//! assert_eq!(jvmti.get_tag(&object)?, 42);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The functions that aren't wrapped can be called through [`JvmtiEnv::get_raw`]
+//! and the raw bindings in [`sys`].
+//!
+//! [jvmti]: https://docs.oracle.com/en/java/javase/21/docs/specs/jvmti.html
+
+use std::{
+    os::raw::{c_char, c_void},
+    ptr,
+    sync::{PoisonError, RwLock},
+};
+
+use crate::{
+    errors::{Error, Result},
+    objects::{JClass, JObject},
+    strings::JNIStr,
+    sys::{jint, jlong},
+    JNIEnv,
+};
+
+#[cfg(doc)]
+use crate::JavaVM;
+
+pub mod sys;
+
+/// Calls a function of the JVMTI function table, and converts its error code
+/// into a `Result`.
+macro_rules! jvmti_call {
+    ( $env:expr, $name:ident $(, $args:expr )* ) => {{
+        let env: *mut sys::jvmtiEnv = $env.get_raw();
+        match (**env).$name {
+            Some(function) => jvmti_error_to_result(function(env $(, $args)*)),
+            None => Err(Error::JvmtiCall(sys::JVMTI_ERROR_NOT_AVAILABLE)),
+        }
+    }};
+}
+
+fn jvmti_error_to_result(error: sys::jvmtiError) -> Result<()> {
+    match error {
+        sys::JVMTI_ERROR_NONE => Ok(()),
+        error => Err(Error::JvmtiCall(error)),
+    }
+}
+
+/// A JVMTI capability, which has to be added to a [`JvmtiEnv`] before the
+/// functions and events that need it can be used.
+///
+/// The discriminant of each capability is its bit in [`sys::jvmtiCapabilities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Capability {
+    /// `can_tag_objects`, for [`JvmtiEnv::set_tag`], [`JvmtiEnv::get_tag`]
+    /// and [`JvmtiEnv::iterate_through_heap`].
+    TagObjects = 0,
+    /// `can_get_source_file_name`.
+    GetSourceFileName = 11,
+    /// `can_get_line_numbers`.
+    GetLineNumbers = 12,
+    /// `can_generate_all_class_hook_events`.
+    GenerateAllClassHookEvents = 26,
+    /// `can_generate_vm_object_alloc_events`.
+    GenerateVmObjectAllocEvents = 29,
+    /// `can_generate_garbage_collection_events`.
+    GenerateGarbageCollectionEvents = 31,
+    /// `can_generate_object_free_events`.
+    GenerateObjectFreeEvents = 32,
+    /// `can_retransform_classes`.
+    RetransformClasses = 37,
+}
+
+/// A set of [`Capability`]s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities(pub sys::jvmtiCapabilities);
+
+impl Capabilities {
+    /// Returns an empty set of capabilities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the set with `capability` added.
+    pub fn with(mut self, capability: Capability) -> Self {
+        let bit = capability as usize;
+        self.0.bits[bit / 32] |= 1 << (bit % 32);
+        self
+    }
+
+    /// Returns whether the set contains `capability`.
+    pub fn contains(&self, capability: Capability) -> bool {
+        let bit = capability as usize;
+        self.0.bits[bit / 32] & (1 << (bit % 32)) != 0
+    }
+}
+
+/// The objects that [`JvmtiEnv::iterate_through_heap`] reports, depending on
+/// their tags and the tags of their classes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapFilter(pub jint);
+
+impl HeapFilter {
+    /// Reports all objects.
+    pub const NONE: HeapFilter = HeapFilter(0);
+    /// Skips tagged objects.
+    pub const TAGGED: HeapFilter = HeapFilter(sys::JVMTI_HEAP_FILTER_TAGGED);
+    /// Skips untagged objects.
+    pub const UNTAGGED: HeapFilter = HeapFilter(sys::JVMTI_HEAP_FILTER_UNTAGGED);
+    /// Skips objects whose classes are tagged.
+    pub const CLASS_TAGGED: HeapFilter = HeapFilter(sys::JVMTI_HEAP_FILTER_CLASS_TAGGED);
+    /// Skips objects whose classes are untagged.
+    pub const CLASS_UNTAGGED: HeapFilter = HeapFilter(sys::JVMTI_HEAP_FILTER_CLASS_UNTAGGED);
+}
+
+impl std::ops::BitOr for HeapFilter {
+    type Output = HeapFilter;
+
+    fn bitor(self, rhs: HeapFilter) -> HeapFilter {
+        HeapFilter(self.0 | rhs.0)
+    }
+}
+
+/// An object on the heap, as reported by [`JvmtiEnv::iterate_through_heap`].
+#[derive(Debug)]
+pub struct HeapObject<'a> {
+    /// The tag of the object's class, or zero if it's untagged.
+    pub class_tag: jlong,
+    /// The size of the object, in bytes.
+    pub size: jlong,
+    /// The tag of the object, or zero if it's untagged, which can be changed
+    /// to tag or untag the object.
+    pub tag: &'a mut jlong,
+    /// The length of the object if it's an array, or else `-1`.
+    pub length: jint,
+}
+
+/// A hook that's called for a class event, with the class.
+pub type ClassHook = fn(&mut JNIEnv, &JClass);
+
+/// There's only one JVM per process, so the class hooks are kept in statics,
+/// like the invocation hooks.
+static CLASS_LOAD_HOOK: RwLock<Option<ClassHook>> = RwLock::new(None);
+static CLASS_PREPARE_HOOK: RwLock<Option<ClassHook>> = RwLock::new(None);
+
+unsafe extern "system" fn class_load(
+    _jvmti_env: *mut sys::jvmtiEnv,
+    jni_env: *mut crate::sys::JNIEnv,
+    _thread: crate::sys::jobject,
+    klass: crate::sys::jclass,
+) {
+    call_class_hook(&CLASS_LOAD_HOOK, jni_env, klass);
+}
+
+unsafe extern "system" fn class_prepare(
+    _jvmti_env: *mut sys::jvmtiEnv,
+    jni_env: *mut crate::sys::JNIEnv,
+    _thread: crate::sys::jobject,
+    klass: crate::sys::jclass,
+) {
+    call_class_hook(&CLASS_PREPARE_HOOK, jni_env, klass);
+}
+
+unsafe fn call_class_hook(
+    hook: &RwLock<Option<ClassHook>>,
+    jni_env: *mut crate::sys::JNIEnv,
+    klass: crate::sys::jclass,
+) {
+    let hook = *hook.read().unwrap_or_else(PoisonError::into_inner);
+    if let (Some(hook), Ok(mut env)) = (hook, JNIEnv::from_raw(jni_env)) {
+        hook(&mut env, &JClass::from_raw(klass));
+    }
+}
+
+/// A JVMTI environment, which is obtained with [`JavaVM::get_jvmti_env`].
+///
+/// Unlike a [`JNIEnv`], a JVMTI environment isn't tied to a thread, and it
+/// stays valid until it's [disposed](JvmtiEnv::dispose) or the VM is
+/// destroyed. Its functions can be called from any thread that's attached to
+/// the VM, and fail with `JVMTI_ERROR_UNATTACHED_THREAD` on other threads.
+#[derive(Debug)]
+pub struct JvmtiEnv {
+    raw: *mut sys::jvmtiEnv,
+}
+
+unsafe impl Send for JvmtiEnv {}
+unsafe impl Sync for JvmtiEnv {}
+
+impl JvmtiEnv {
+    /// Wraps a raw JVMTI environment pointer.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid JVMTI environment that isn't disposed while the
+    /// `JvmtiEnv` is used.
+    pub unsafe fn from_raw(raw: *mut sys::jvmtiEnv) -> Result<Self> {
+        let raw = null_check!(raw, "from_raw ptr argument")?;
+        Ok(JvmtiEnv { raw })
+    }
+
+    /// Returns the raw JVMTI environment pointer.
+    pub fn get_raw(&self) -> *mut sys::jvmtiEnv {
+        self.raw
+    }
+
+    /// Returns the JVMTI version of the environment.
+    pub fn version(&self) -> Result<jint> {
+        let mut version = 0;
+        unsafe { jvmti_call!(self, GetVersionNumber, &mut version)? };
+        Ok(version)
+    }
+
+    /// Returns the capabilities that can be added to the environment at this
+    /// point.
+    pub fn potential_capabilities(&self) -> Result<Capabilities> {
+        let mut capabilities = Capabilities::new();
+        unsafe { jvmti_call!(self, GetPotentialCapabilities, &mut capabilities.0)? };
+        Ok(capabilities)
+    }
+
+    /// Returns the capabilities that the environment has.
+    pub fn capabilities(&self) -> Result<Capabilities> {
+        let mut capabilities = Capabilities::new();
+        unsafe { jvmti_call!(self, GetCapabilities, &mut capabilities.0)? };
+        Ok(capabilities)
+    }
+
+    /// Adds capabilities to the environment.
+    ///
+    /// Fails with `JVMTI_ERROR_NOT_AVAILABLE` if one of the capabilities isn't
+    /// [potentially available](JvmtiEnv::potential_capabilities).
+    pub fn add_capabilities(&self, capabilities: &Capabilities) -> Result<()> {
+        unsafe { jvmti_call!(self, AddCapabilities, &capabilities.0) }
+    }
+
+    /// Gives up capabilities of the environment.
+    pub fn relinquish_capabilities(&self, capabilities: &Capabilities) -> Result<()> {
+        unsafe { jvmti_call!(self, RelinquishCapabilities, &capabilities.0) }
+    }
+
+    /// Tags an object, or untags it if `tag` is zero.
+    ///
+    /// Needs [`Capability::TagObjects`]. Tags are local to the environment.
+    pub fn set_tag(&self, object: &JObject, tag: jlong) -> Result<()> {
+        unsafe { jvmti_call!(self, SetTag, object.as_raw(), tag) }
+    }
+
+    /// Returns the tag of an object, or zero if it's untagged.
+    ///
+    /// Needs [`Capability::TagObjects`].
+    pub fn get_tag(&self, object: &JObject) -> Result<jlong> {
+        let mut tag = 0;
+        unsafe { jvmti_call!(self, GetTag, object.as_raw(), &mut tag)? };
+        Ok(tag)
+    }
+
+    /// Calls `callback` for each object on the heap that isn't skipped by
+    /// `filter`, and that's an instance of `class` if it's given.
+    ///
+    /// The iteration stops when `callback` returns `false`. The callback is
+    /// called while the VM is stopped, so it must not call JNI functions.
+    ///
+    /// Needs [`Capability::TagObjects`].
+    pub fn iterate_through_heap<F>(
+        &self,
+        filter: HeapFilter,
+        class: Option<&JClass>,
+        mut callback: F,
+    ) -> Result<()>
+    where
+        F: FnMut(HeapObject) -> bool,
+    {
+        unsafe extern "system" fn trampoline<F: FnMut(HeapObject) -> bool>(
+            class_tag: jlong,
+            size: jlong,
+            tag_ptr: *mut jlong,
+            length: jint,
+            user_data: *mut c_void,
+        ) -> jint {
+            let callback = &mut *(user_data as *mut F);
+            let object = HeapObject {
+                class_tag,
+                size,
+                tag: &mut *tag_ptr,
+                length,
+            };
+            if callback(object) {
+                0
+            } else {
+                sys::JVMTI_VISIT_ABORT
+            }
+        }
+
+        let callbacks = sys::jvmtiHeapCallbacks {
+            heap_iteration_callback: Some(trampoline::<F>),
+            heap_reference_callback: ptr::null_mut(),
+            primitive_field_callback: ptr::null_mut(),
+            array_primitive_value_callback: ptr::null_mut(),
+            string_primitive_value_callback: ptr::null_mut(),
+            reserved: [ptr::null_mut(); 11],
+        };
+        let class = class.map_or(ptr::null_mut(), |class| class.as_raw());
+        unsafe {
+            jvmti_call!(
+                self,
+                IterateThroughHeap,
+                filter.0,
+                class,
+                &callbacks,
+                &mut callback as *mut F as *const c_void
+            )
+        }
+    }
+
+    /// Returns all the classes that are loaded in the VM, as local references.
+    pub fn get_loaded_classes<'local>(
+        &self,
+        _env: &mut JNIEnv<'local>,
+    ) -> Result<Vec<JClass<'local>>> {
+        let mut count = 0;
+        let mut classes = ptr::null_mut();
+        unsafe {
+            jvmti_call!(self, GetLoadedClasses, &mut count, &mut classes)?;
+            let loaded = std::slice::from_raw_parts(classes, count as usize)
+                .iter()
+                .map(|&class| JClass::from_raw(class))
+                .collect();
+            self.deallocate(classes as *mut _)?;
+            Ok(loaded)
+        }
+    }
+
+    /// Returns the type signature of a class, such as `Ljava/lang/String;`.
+    pub fn get_class_signature(&self, class: &JClass) -> Result<String> {
+        let mut signature: *mut c_char = ptr::null_mut();
+        unsafe {
+            jvmti_call!(
+                self,
+                GetClassSignature,
+                class.as_raw(),
+                &mut signature,
+                ptr::null_mut()
+            )?;
+            let string = JNIStr::from_ptr(signature).to_str().into_owned();
+            self.deallocate(signature as *mut _)?;
+            Ok(string)
+        }
+    }
+
+    /// Sets the hook that's called when a class is loaded, or removes it if
+    /// `hook` is `None`.
+    ///
+    /// The class isn't prepared yet when the hook is called, so its methods
+    /// and fields can't be looked up; see [`JvmtiEnv::set_class_prepare_hook`].
+    ///
+    /// The hooks are shared by all the JVMTI environments of the process. A
+    /// hook must not panic.
+    pub fn set_class_load_hook(&self, hook: Option<ClassHook>) -> Result<()> {
+        self.set_class_hook(&CLASS_LOAD_HOOK, sys::JVMTI_EVENT_CLASS_LOAD, hook)
+    }
+
+    /// Sets the hook that's called when a class is prepared, after it's
+    /// loaded and before any of its code runs, or removes it if `hook` is
+    /// `None`.
+    ///
+    /// The hooks are shared by all the JVMTI environments of the process. A
+    /// hook must not panic.
+    pub fn set_class_prepare_hook(&self, hook: Option<ClassHook>) -> Result<()> {
+        self.set_class_hook(&CLASS_PREPARE_HOOK, sys::JVMTI_EVENT_CLASS_PREPARE, hook)
+    }
+
+    fn set_class_hook(
+        &self,
+        slot: &RwLock<Option<ClassHook>>,
+        event: jint,
+        hook: Option<ClassHook>,
+    ) -> Result<()> {
+        *slot.write().unwrap_or_else(PoisonError::into_inner) = hook;
+        let callbacks = sys::jvmtiEventCallbacks {
+            VMInit: ptr::null_mut(),
+            VMDeath: ptr::null_mut(),
+            ThreadStart: ptr::null_mut(),
+            ThreadEnd: ptr::null_mut(),
+            ClassFileLoadHook: ptr::null_mut(),
+            ClassLoad: Some(class_load),
+            ClassPrepare: Some(class_prepare),
+            VMStart: ptr::null_mut(),
+        };
+        let mode = if hook.is_some() {
+            sys::JVMTI_ENABLE
+        } else {
+            sys::JVMTI_DISABLE
+        };
+        unsafe {
+            jvmti_call!(
+                self,
+                SetEventCallbacks,
+                &callbacks,
+                std::mem::size_of::<sys::jvmtiEventCallbacks>() as jint
+            )?;
+            jvmti_call!(self, SetEventNotificationMode, mode, event, ptr::null_mut())
+        }
+    }
+
+    /// Frees memory that was allocated by a JVMTI function.
+    ///
+    /// # Safety
+    ///
+    /// `mem` must have been returned by a function of this environment, and
+    /// not freed already.
+    pub unsafe fn deallocate(&self, mem: *mut u8) -> Result<()> {
+        jvmti_call!(self, Deallocate, mem)
+    }
+
+    /// Disposes the environment, which relinquishes its capabilities.
+    pub fn dispose(self) -> Result<()> {
+        unsafe { jvmti_call!(self, DisposeEnvironment) }
+    }
+}
@@ -0,0 +1,328 @@
+//! Raw bindings to the parts of `jvmti.h` that are used by the safe layer.
+//!
+//! The function table is complete, but only the functions that are used are
+//! typed; the others are left as `*mut c_void`.
+
+#![allow(non_camel_case_types, non_snake_case, missing_docs)]
+
+use std::os::raw::{c_char, c_uchar, c_void};
+
+use crate::sys::{jclass, jint, jlong, jobject, JNIEnv};
+
+pub type jvmtiError = jint;
+pub type jvmtiEnv = *const jvmtiInterface_1;
+
+pub const JVMTI_VERSION_1_2: jint = 0x3001_0200;
+
+pub const JVMTI_ERROR_NONE: jvmtiError = 0;
+pub const JVMTI_ERROR_NOT_AVAILABLE: jvmtiError = 98;
+pub const JVMTI_ERROR_MUST_POSSESS_CAPABILITY: jvmtiError = 99;
+pub const JVMTI_ERROR_WRONG_PHASE: jvmtiError = 112;
+pub const JVMTI_ERROR_UNATTACHED_THREAD: jvmtiError = 115;
+
+pub const JVMTI_ENABLE: jint = 1;
+pub const JVMTI_DISABLE: jint = 0;
+
+pub const JVMTI_HEAP_FILTER_TAGGED: jint = 0x4;
+pub const JVMTI_HEAP_FILTER_UNTAGGED: jint = 0x8;
+pub const JVMTI_HEAP_FILTER_CLASS_TAGGED: jint = 0x10;
+pub const JVMTI_HEAP_FILTER_CLASS_UNTAGGED: jint = 0x20;
+
+pub const JVMTI_VISIT_OBJECTS: jint = 0x100;
+pub const JVMTI_VISIT_ABORT: jint = 0x8000;
+
+pub const JVMTI_EVENT_VM_INIT: jint = 50;
+pub const JVMTI_EVENT_VM_DEATH: jint = 51;
+pub const JVMTI_EVENT_THREAD_START: jint = 52;
+pub const JVMTI_EVENT_THREAD_END: jint = 53;
+pub const JVMTI_EVENT_CLASS_FILE_LOAD_HOOK: jint = 54;
+pub const JVMTI_EVENT_CLASS_LOAD: jint = 55;
+pub const JVMTI_EVENT_CLASS_PREPARE: jint = 56;
+pub const JVMTI_EVENT_VM_START: jint = 57;
+
+/// The 128 one-bit fields of `jvmtiCapabilities`, of which `can_tag_objects`
+/// is the least significant bit of the first word.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct jvmtiCapabilities {
+    pub bits: [u32; 4],
+}
+
+pub type jvmtiHeapIterationCallback = Option<
+    unsafe extern "system" fn(
+        class_tag: jlong,
+        size: jlong,
+        tag_ptr: *mut jlong,
+        length: jint,
+        user_data: *mut c_void,
+    ) -> jint,
+>;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct jvmtiHeapCallbacks {
+    pub heap_iteration_callback: jvmtiHeapIterationCallback,
+    pub heap_reference_callback: *mut c_void,
+    pub primitive_field_callback: *mut c_void,
+    pub array_primitive_value_callback: *mut c_void,
+    pub string_primitive_value_callback: *mut c_void,
+    pub reserved: [*mut c_void; 11],
+}
+
+pub type jvmtiEventClassLoad = Option<
+    unsafe extern "system" fn(
+        jvmti_env: *mut jvmtiEnv,
+        jni_env: *mut JNIEnv,
+        thread: jobject,
+        klass: jclass,
+    ),
+>;
+pub type jvmtiEventClassPrepare = jvmtiEventClassLoad;
+
+/// The first eight entries of `jvmtiEventCallbacks`, for the events 50 to 57.
+///
+/// `SetEventCallbacks` takes the size of the structure, so a prefix of it can
+/// be passed, and the callbacks of the later events are cleared.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct jvmtiEventCallbacks {
+    pub VMInit: *mut c_void,
+    pub VMDeath: *mut c_void,
+    pub ThreadStart: *mut c_void,
+    pub ThreadEnd: *mut c_void,
+    pub ClassFileLoadHook: *mut c_void,
+    pub ClassLoad: jvmtiEventClassLoad,
+    pub ClassPrepare: jvmtiEventClassPrepare,
+    pub VMStart: *mut c_void,
+}
+
+#[repr(C)]
+pub struct jvmtiInterface_1 {
+    pub reserved1: *mut c_void,
+    pub SetEventNotificationMode: Option<
+        unsafe extern "C" fn(
+            env: *mut jvmtiEnv,
+            mode: jint,
+            event_type: jint,
+            event_thread: jobject,
+            ...
+        ) -> jvmtiError,
+    >,
+    pub GetAllModules: *mut c_void,
+    pub GetAllThreads: *mut c_void,
+    pub SuspendThread: *mut c_void,
+    pub ResumeThread: *mut c_void,
+    pub StopThread: *mut c_void,
+    pub InterruptThread: *mut c_void,
+    pub GetThreadInfo: *mut c_void,
+    pub GetOwnedMonitorInfo: *mut c_void,
+    pub GetCurrentContendedMonitor: *mut c_void,
+    pub RunAgentThread: *mut c_void,
+    pub GetTopThreadGroups: *mut c_void,
+    pub GetThreadGroupInfo: *mut c_void,
+    pub GetThreadGroupChildren: *mut c_void,
+    pub GetFrameCount: *mut c_void,
+    pub GetThreadState: *mut c_void,
+    pub GetCurrentThread: *mut c_void,
+    pub GetFrameLocation: *mut c_void,
+    pub NotifyFramePop: *mut c_void,
+    pub GetLocalObject: *mut c_void,
+    pub GetLocalInt: *mut c_void,
+    pub GetLocalLong: *mut c_void,
+    pub GetLocalFloat: *mut c_void,
+    pub GetLocalDouble: *mut c_void,
+    pub SetLocalObject: *mut c_void,
+    pub SetLocalInt: *mut c_void,
+    pub SetLocalLong: *mut c_void,
+    pub SetLocalFloat: *mut c_void,
+    pub SetLocalDouble: *mut c_void,
+    pub CreateRawMonitor: *mut c_void,
+    pub DestroyRawMonitor: *mut c_void,
+    pub RawMonitorEnter: *mut c_void,
+    pub RawMonitorExit: *mut c_void,
+    pub RawMonitorWait: *mut c_void,
+    pub RawMonitorNotify: *mut c_void,
+    pub RawMonitorNotifyAll: *mut c_void,
+    pub SetBreakpoint: *mut c_void,
+    pub ClearBreakpoint: *mut c_void,
+    pub GetNamedModule: *mut c_void,
+    pub SetFieldAccessWatch: *mut c_void,
+    pub ClearFieldAccessWatch: *mut c_void,
+    pub SetFieldModificationWatch: *mut c_void,
+    pub ClearFieldModificationWatch: *mut c_void,
+    pub IsModifiableClass: *mut c_void,
+    pub Allocate: *mut c_void,
+    pub Deallocate:
+        Option<unsafe extern "system" fn(env: *mut jvmtiEnv, mem: *mut c_uchar) -> jvmtiError>,
+    pub GetClassSignature: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            klass: jclass,
+            signature_ptr: *mut *mut c_char,
+            generic_ptr: *mut *mut c_char,
+        ) -> jvmtiError,
+    >,
+    pub GetClassStatus: *mut c_void,
+    pub GetSourceFileName: *mut c_void,
+    pub GetClassModifiers: *mut c_void,
+    pub GetClassMethods: *mut c_void,
+    pub GetClassFields: *mut c_void,
+    pub GetImplementedInterfaces: *mut c_void,
+    pub IsInterface: *mut c_void,
+    pub IsArrayClass: *mut c_void,
+    pub GetClassLoader: *mut c_void,
+    pub GetObjectHashCode: *mut c_void,
+    pub GetObjectMonitorUsage: *mut c_void,
+    pub GetFieldName: *mut c_void,
+    pub GetFieldDeclaringClass: *mut c_void,
+    pub GetFieldModifiers: *mut c_void,
+    pub IsFieldSynthetic: *mut c_void,
+    pub GetMethodName: *mut c_void,
+    pub GetMethodDeclaringClass: *mut c_void,
+    pub GetMethodModifiers: *mut c_void,
+    pub reserved67: *mut c_void,
+    pub GetMaxLocals: *mut c_void,
+    pub GetArgumentsSize: *mut c_void,
+    pub GetLineNumberTable: *mut c_void,
+    pub GetMethodLocation: *mut c_void,
+    pub GetLocalVariableTable: *mut c_void,
+    pub SetNativeMethodPrefix: *mut c_void,
+    pub SetNativeMethodPrefixes: *mut c_void,
+    pub GetBytecodes: *mut c_void,
+    pub IsMethodNative: *mut c_void,
+    pub IsMethodSynthetic: *mut c_void,
+    pub GetLoadedClasses: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            class_count_ptr: *mut jint,
+            classes_ptr: *mut *mut jclass,
+        ) -> jvmtiError,
+    >,
+    pub GetClassLoaderClasses: *mut c_void,
+    pub PopFrame: *mut c_void,
+    pub ForceEarlyReturnObject: *mut c_void,
+    pub ForceEarlyReturnInt: *mut c_void,
+    pub ForceEarlyReturnLong: *mut c_void,
+    pub ForceEarlyReturnFloat: *mut c_void,
+    pub ForceEarlyReturnDouble: *mut c_void,
+    pub ForceEarlyReturnVoid: *mut c_void,
+    pub RedefineClasses: *mut c_void,
+    pub GetVersionNumber:
+        Option<unsafe extern "system" fn(env: *mut jvmtiEnv, version_ptr: *mut jint) -> jvmtiError>,
+    pub GetCapabilities: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            capabilities_ptr: *mut jvmtiCapabilities,
+        ) -> jvmtiError,
+    >,
+    pub GetSourceDebugExtension: *mut c_void,
+    pub IsMethodObsolete: *mut c_void,
+    pub SuspendThreadList: *mut c_void,
+    pub ResumeThreadList: *mut c_void,
+    pub AddModuleReads: *mut c_void,
+    pub AddModuleExports: *mut c_void,
+    pub AddModuleOpens: *mut c_void,
+    pub AddModuleUses: *mut c_void,
+    pub AddModuleProvides: *mut c_void,
+    pub IsModifiableModule: *mut c_void,
+    pub GetAllStackTraces: *mut c_void,
+    pub GetThreadListStackTraces: *mut c_void,
+    pub GetThreadLocalStorage: *mut c_void,
+    pub SetThreadLocalStorage: *mut c_void,
+    pub GetStackTrace: *mut c_void,
+    pub reserved105: *mut c_void,
+    pub GetTag: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            object: jobject,
+            tag_ptr: *mut jlong,
+        ) -> jvmtiError,
+    >,
+    pub SetTag: Option<
+        unsafe extern "system" fn(env: *mut jvmtiEnv, object: jobject, tag: jlong) -> jvmtiError,
+    >,
+    pub ForceGarbageCollection: *mut c_void,
+    pub IterateOverObjectsReachableFromObject: *mut c_void,
+    pub IterateOverReachableObjects: *mut c_void,
+    pub IterateOverHeap: *mut c_void,
+    pub IterateOverInstancesOfClass: *mut c_void,
+    pub reserved113: *mut c_void,
+    pub GetObjectsWithTags: *mut c_void,
+    pub FollowReferences: *mut c_void,
+    pub IterateThroughHeap: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            heap_filter: jint,
+            klass: jclass,
+            callbacks: *const jvmtiHeapCallbacks,
+            user_data: *const c_void,
+        ) -> jvmtiError,
+    >,
+    pub reserved117: *mut c_void,
+    pub reserved118: *mut c_void,
+    pub reserved119: *mut c_void,
+    pub SetJNIFunctionTable: *mut c_void,
+    pub GetJNIFunctionTable: *mut c_void,
+    pub SetEventCallbacks: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            callbacks: *const jvmtiEventCallbacks,
+            size_of_callbacks: jint,
+        ) -> jvmtiError,
+    >,
+    pub GenerateEvents: *mut c_void,
+    pub GetExtensionFunctions: *mut c_void,
+    pub GetExtensionEvents: *mut c_void,
+    pub SetExtensionEventCallback: *mut c_void,
+    pub DisposeEnvironment: Option<unsafe extern "system" fn(env: *mut jvmtiEnv) -> jvmtiError>,
+    pub GetErrorName: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            error: jvmtiError,
+            name_ptr: *mut *mut c_char,
+        ) -> jvmtiError,
+    >,
+    pub GetJLocationFormat: *mut c_void,
+    pub GetSystemProperties: *mut c_void,
+    pub GetSystemProperty: *mut c_void,
+    pub SetSystemProperty: *mut c_void,
+    pub GetPhase: *mut c_void,
+    pub GetCurrentThreadCpuTimerInfo: *mut c_void,
+    pub GetCurrentThreadCpuTime: *mut c_void,
+    pub GetThreadCpuTimerInfo: *mut c_void,
+    pub GetThreadCpuTime: *mut c_void,
+    pub GetTimerInfo: *mut c_void,
+    pub GetTime: *mut c_void,
+    pub GetPotentialCapabilities: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            capabilities_ptr: *mut jvmtiCapabilities,
+        ) -> jvmtiError,
+    >,
+    pub reserved141: *mut c_void,
+    pub AddCapabilities: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            capabilities_ptr: *const jvmtiCapabilities,
+        ) -> jvmtiError,
+    >,
+    pub RelinquishCapabilities: Option<
+        unsafe extern "system" fn(
+            env: *mut jvmtiEnv,
+            capabilities_ptr: *const jvmtiCapabilities,
+        ) -> jvmtiError,
+    >,
+    pub GetAvailableProcessors: *mut c_void,
+    pub GetClassVersionNumbers: *mut c_void,
+    pub GetConstantPool: *mut c_void,
+    pub GetEnvironmentLocalStorage: *mut c_void,
+    pub SetEnvironmentLocalStorage: *mut c_void,
+    pub AddToBootstrapClassLoaderSearch: *mut c_void,
+    pub SetVerboseFlag: *mut c_void,
+    pub AddToSystemClassLoaderSearch: *mut c_void,
+    pub RetransformClasses: *mut c_void,
+    pub GetOwnedMonitorStackDepthInfo: *mut c_void,
+    pub GetObjectSize: *mut c_void,
+    pub GetLocalInstance: *mut c_void,
+    pub SetHeapSamplingInterval: *mut c_void,
+}
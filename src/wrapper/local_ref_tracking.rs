@@ -0,0 +1,166 @@
+//! Diagnostics for local references that accumulate within a local frame.
+//!
+//! With the `local-ref-tracking` feature, each thread keeps a count of the
+//! local references that are created and deleted through [`JNIEnv`] within
+//! each local frame (see [`JNIEnv::with_local_frame`] and
+//! [`JNIEnv::push_local_frame`]), along with a backtrace of where each
+//! reference was created.
+//!
+//! When the number of live references in a frame exceeds the larger of the
+//! [threshold](set_threshold) and the capacity that the frame was created
+//! with (or reserved with [`JNIEnv::ensure_local_capacity`]), the references
+//! that are still live are reported, once per frame. By default, this panics
+//! in debug builds and logs an error otherwise (see [`set_action`]).
+//!
+//! This is intended to find code that leaks a local reference per loop
+//! iteration, for example by passing a class name to a method in a loop
+//! (which looks up a new local reference to the class each time). The
+//! backtraces are only captured if they're enabled with `RUST_BACKTRACE` or
+//! `RUST_LIB_BACKTRACE`.
+//!
+//! Local references that are created outside of a local frame, such as
+//! directly in the frame of a native method, aren't tracked, since they're
+//! freed when the native method returns, which can't be observed from Rust.
+//! Local references that are created through the raw JNI interface aren't
+//! tracked either. Wrap the code that is being diagnosed in
+//! [`JNIEnv::with_local_frame`] to track it.
+//!
+//! This adds overhead to every call that creates a local reference, and
+//! shouldn't be enabled in production.
+
+use std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use log::error;
+
+use crate::{
+    diagnostics::{Action, ActionSetting},
+    sys::jobject,
+};
+
+#[cfg(doc)]
+use crate::JNIEnv;
+
+/// The default [threshold](set_threshold), which is the number of local
+/// references that the JVM guarantees can be created in each frame.
+pub const DEFAULT_THRESHOLD: usize = 16;
+
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+static ACTION: ActionSetting = ActionSetting::new();
+
+struct Frame {
+    capacity: usize,
+    refs: HashMap<usize, Backtrace>,
+    reported: bool,
+}
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets the number of live local references that a frame may have (unless
+/// it was created with a larger capacity) before they're reported.
+///
+/// This applies to all threads, and defaults to [`DEFAULT_THRESHOLD`].
+pub fn set_threshold(threshold: usize) {
+    THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+/// Returns the threshold that's set by [`set_threshold`].
+pub fn threshold() -> usize {
+    THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets what to do when a frame has too many live local references.
+///
+/// This applies to all threads. By default, this is [`Action::Panic`] in
+/// debug builds and [`Action::Log`] otherwise.
+pub fn set_action(action: Action) {
+    ACTION.set(action);
+}
+
+/// Returns the action that's set by [`set_action`].
+pub fn action() -> Action {
+    ACTION.get()
+}
+
+/// Returns the number of tracked local references that are live in the
+/// current thread's innermost local frame, or `None` if the current thread
+/// isn't in a local frame.
+pub fn live_local_refs() -> Option<usize> {
+    FRAMES.with(|frames| frames.borrow().last().map(|frame| frame.refs.len()))
+}
+
+pub(crate) fn push_frame(capacity: i32) {
+    FRAMES.with(|frames| {
+        frames.borrow_mut().push(Frame {
+            capacity: capacity.max(0) as usize,
+            refs: HashMap::new(),
+            reported: false,
+        })
+    });
+}
+
+pub(crate) fn pop_frame() {
+    FRAMES.with(|frames| frames.borrow_mut().pop());
+}
+
+pub(crate) fn ensure_capacity(capacity: usize) {
+    FRAMES.with(|frames| {
+        if let Some(frame) = frames.borrow_mut().last_mut() {
+            frame.capacity = frame.capacity.max(capacity);
+        }
+    });
+}
+
+pub(crate) fn created(raw: jobject) {
+    if raw.is_null() {
+        return;
+    }
+    let report = FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        let frame = frames.last_mut()?;
+        frame.refs.insert(raw as usize, Backtrace::capture());
+
+        let limit = threshold().max(frame.capacity);
+        if frame.reported || frame.refs.len() <= limit {
+            return None;
+        }
+        frame.reported = true;
+
+        let mut report = format!(
+            "a local reference frame has {} live local references, which is more than the limit of {}",
+            frame.refs.len(),
+            limit
+        );
+        for (i, backtrace) in frame.refs.values().enumerate() {
+            report.push_str(&format!("\n\nreference {i} was created at:\n{backtrace}"));
+        }
+        Some(report)
+    });
+
+    if let Some(report) = report {
+        match action() {
+            Action::Log => error!("{report}"),
+            Action::Panic => panic!("{}", report),
+        }
+    }
+}
+
+pub(crate) fn deleted(raw: jobject) {
+    if raw.is_null() {
+        return;
+    }
+    FRAMES.with(|frames| {
+        for frame in frames.borrow_mut().iter_mut().rev() {
+            if frame.refs.remove(&(raw as usize)).is_some() {
+                return;
+            }
+        }
+    });
+}
@@ -1,11 +1,16 @@
 /// Directly calls a JNIEnv FFI function, nothing else
 ///
+/// With the `trace-jni` feature, the call is made in a `tracing` span that's
+/// named after the JNI function.
+///
 /// # Safety
 ///
 /// When calling any function added after JNI 1.1 you must know that it's valid
 /// for the current JNI version.
 macro_rules! jni_call_unchecked {
     ( $jnienv:expr, $version:tt, $name:tt $(, $args:expr )*) => {{
+        #[cfg(feature = "trace-jni")]
+        let _span = tracing::trace_span!(target: "jni", stringify!($name)).entered();
         // Safety: we know that the JNIEnv pointer can't be null, since that's
         // checked in `from_raw()`
         let env: *mut jni_sys::JNIEnv = $jnienv.get_raw();
@@ -21,8 +26,15 @@ macro_rules! jni_call_unchecked {
 /// be thrown if the native code returns to the JVM.
 ///
 /// Returns `Err` if there is a pending exception after the call.
+///
+/// With the `strict-exception-checks` feature, a call that's made while an
+/// exception is already pending is reported first.
 macro_rules! jni_call_check_ex {
     ( $jnienv:expr, $version:tt, $name:tt $(, $args:expr )* ) => ({
+        #[cfg(feature = "strict-exception-checks")]
+        if $jnienv.exception_check() {
+            $crate::strict_exception_checks::pending_exception(stringify!($name));
+        }
         let ret = jni_call_unchecked!($jnienv, $version, $name $(, $args)*);
         if $jnienv.exception_check() {
             Err(crate::errors::Error::JavaException)
@@ -80,6 +92,28 @@ macro_rules! null_check {
     };
 }
 
+/// Records a new local reference with the `local-ref-tracking` feature, and
+/// evaluates to the reference.
+macro_rules! track_local {
+    ( $raw:expr ) => {{
+        let raw = $raw;
+        #[cfg(feature = "local-ref-tracking")]
+        $crate::local_ref_tracking::created(raw as $crate::sys::jobject);
+        raw
+    }};
+}
+
+/// Enters a `tracing` span for a `JNIEnv` method with the `trace-jni`
+/// feature, until the end of the enclosing block.
+///
+/// The fields are only evaluated if the span is enabled.
+macro_rules! trace_env_method {
+    ( $method:literal $(, $field:ident = $value:expr )* ) => {
+        #[cfg(feature = "trace-jni")]
+        let _span = tracing::trace_span!(target: "jni", $method $(, $field = %$value)*).entered();
+    };
+}
+
 /// Directly calls a JavaVM function, nothing else
 macro_rules! java_vm_call_unchecked {
     ( $jvm:expr, $version:tt, $name:tt $(, $args:expr )*) => {{
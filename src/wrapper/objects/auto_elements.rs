@@ -11,7 +11,7 @@ use super::JPrimitiveArray;
 use super::JByteArray;
 
 mod type_array_sealed {
-    use crate::sys::{jarray, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort};
+    use crate::sys::{jarray, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort, jsize};
     use crate::{errors::*, JNIEnv};
     use std::ptr::NonNull;
 
@@ -25,7 +25,7 @@ mod type_array_sealed {
     /// The `get` method must return a valid pointer to the beginning of the JNI array.
     ///
     /// The `release` method must not invalidate the `ptr` if the `mode` is [`sys::JNI_COMMIT`].
-    pub unsafe trait TypeArraySealed: Copy {
+    pub unsafe trait TypeArraySealed: Copy + Default {
         /// getter
         ///
         /// # Safety
@@ -51,11 +51,34 @@ mod type_array_sealed {
             ptr: NonNull<Self>,
             mode: i32,
         ) -> Result<()>;
+
+        /// Creates a new array of this element type, with `length` zeroed elements
+        fn new_array(env: &JNIEnv, length: jsize) -> Result<jarray>;
+
+        /// Copies `buf.len()` elements of `array`, from the `start` index, into `buf`
+        ///
+        /// # Safety
+        ///
+        /// `array` must be a valid, non-null pointer to an array of this element type
+        unsafe fn get_region(
+            env: &JNIEnv,
+            array: jarray,
+            start: jsize,
+            buf: &mut [Self],
+        ) -> Result<()>;
+
+        /// Copies the elements of `buf` into `array`, from the `start` index
+        ///
+        /// # Safety
+        ///
+        /// `array` must be a valid, non-null pointer to an array of this element type
+        unsafe fn set_region(env: &JNIEnv, array: jarray, start: jsize, buf: &[Self])
+            -> Result<()>;
     }
 
     // TypeArray builder
     macro_rules! type_array {
-        ( $jni_type:ty, $jni_get:tt, $jni_release:tt ) => {
+        ( $jni_type:ty, $jni_new:tt, $jni_get:tt, $jni_release:tt, $jni_get_region:tt, $jni_set_region:tt ) => {
             /// $jni_type array access/release impl
             unsafe impl TypeArraySealed for $jni_type {
                 /// Get Java $jni_type array
@@ -81,22 +104,118 @@ mod type_array_sealed {
                     jni_call_unchecked!(env, v1_1, $jni_release, array, ptr.as_ptr(), mode as i32);
                     Ok(())
                 }
+
+                /// Create a Java $jni_type array
+                fn new_array(env: &JNIEnv, length: jsize) -> Result<jarray> {
+                    unsafe {
+                        jni_call_check_ex_and_null_ret!(env, v1_1, $jni_new, length)
+                            .map(|array| track_local!(array))
+                    }
+                }
+
+                /// Copy a region of a Java $jni_type array
+                unsafe fn get_region(
+                    env: &JNIEnv,
+                    array: jarray,
+                    start: jsize,
+                    buf: &mut [Self],
+                ) -> Result<()> {
+                    jni_call_check_ex!(
+                        env,
+                        v1_1,
+                        $jni_get_region,
+                        array,
+                        start,
+                        buf.len() as jsize,
+                        buf.as_mut_ptr()
+                    )
+                }
+
+                /// Overwrite a region of a Java $jni_type array
+                unsafe fn set_region(
+                    env: &JNIEnv,
+                    array: jarray,
+                    start: jsize,
+                    buf: &[Self],
+                ) -> Result<()> {
+                    jni_call_check_ex!(
+                        env,
+                        v1_1,
+                        $jni_set_region,
+                        array,
+                        start,
+                        buf.len() as jsize,
+                        buf.as_ptr()
+                    )
+                }
             }
         };
     }
 
-    type_array!(jint, GetIntArrayElements, ReleaseIntArrayElements);
-    type_array!(jlong, GetLongArrayElements, ReleaseLongArrayElements);
-    type_array!(jbyte, GetByteArrayElements, ReleaseByteArrayElements);
+    type_array!(
+        jint,
+        NewIntArray,
+        GetIntArrayElements,
+        ReleaseIntArrayElements,
+        GetIntArrayRegion,
+        SetIntArrayRegion
+    );
+    type_array!(
+        jlong,
+        NewLongArray,
+        GetLongArrayElements,
+        ReleaseLongArrayElements,
+        GetLongArrayRegion,
+        SetLongArrayRegion
+    );
+    type_array!(
+        jbyte,
+        NewByteArray,
+        GetByteArrayElements,
+        ReleaseByteArrayElements,
+        GetByteArrayRegion,
+        SetByteArrayRegion
+    );
     type_array!(
         jboolean,
+        NewBooleanArray,
         GetBooleanArrayElements,
-        ReleaseBooleanArrayElements
+        ReleaseBooleanArrayElements,
+        GetBooleanArrayRegion,
+        SetBooleanArrayRegion
+    );
+    type_array!(
+        jchar,
+        NewCharArray,
+        GetCharArrayElements,
+        ReleaseCharArrayElements,
+        GetCharArrayRegion,
+        SetCharArrayRegion
+    );
+    type_array!(
+        jshort,
+        NewShortArray,
+        GetShortArrayElements,
+        ReleaseShortArrayElements,
+        GetShortArrayRegion,
+        SetShortArrayRegion
+    );
+    type_array!(
+        jfloat,
+        NewFloatArray,
+        GetFloatArrayElements,
+        ReleaseFloatArrayElements,
+        GetFloatArrayRegion,
+        SetFloatArrayRegion
+    );
+    type_array!(
+        jdouble,
+        NewDoubleArray,
+        GetDoubleArrayElements,
+        ReleaseDoubleArrayElements,
+        GetDoubleArrayRegion,
+        SetDoubleArrayRegion
     );
-    type_array!(jchar, GetCharArrayElements, ReleaseCharArrayElements);
-    type_array!(jshort, GetShortArrayElements, ReleaseShortArrayElements);
-    type_array!(jfloat, GetFloatArrayElements, ReleaseFloatArrayElements);
-    type_array!(jdouble, GetDoubleArrayElements, ReleaseDoubleArrayElements);
 }
 
 /// A sealed trait to define type array access/release for primitive JNI types
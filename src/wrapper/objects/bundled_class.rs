@@ -0,0 +1,89 @@
+use std::{
+    ffi::CString,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{
+    descriptors::class_cache,
+    errors::{Error, Result},
+    objects::{GlobalRef, JClass, JClassLoader},
+    JNIEnv,
+};
+
+/// A small helper class that's bundled with jni-rs (from the `src/java`
+/// directory), and which has native methods that are implemented in Rust.
+pub(crate) struct BundledClass {
+    /// The binary name of the class, such as `"io/github/jni_rs/RustRunnable"`.
+    name: &'static str,
+    /// The bytecode of the class, which is compiled from its Java source.
+    bytecode: &'static [u8],
+    /// Registers the native methods of the class.
+    register_natives: fn(&mut JNIEnv, &JClass) -> Result<()>,
+    /// The class, once it has been defined and its native methods have been
+    /// registered.
+    class: Mutex<Option<GlobalRef>>,
+}
+
+impl BundledClass {
+    pub(crate) const fn new(
+        name: &'static str,
+        bytecode: &'static [u8],
+        register_natives: fn(&mut JNIEnv, &JClass) -> Result<()>,
+    ) -> Self {
+        Self {
+            name,
+            bytecode,
+            register_natives,
+            class: Mutex::new(None),
+        }
+    }
+
+    /// Looks up the class, defining it and registering its native methods the
+    /// first time.
+    pub(crate) fn lookup<'local>(&self, env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        let mut cached = self.class.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(class) = cached.as_ref() {
+            return Ok(JClass::from(env.new_local_ref(class)?));
+        }
+
+        // The application may include the class itself, which is necessary on
+        // Android
+        let class = match env.find_class(self.name) {
+            Ok(class) => class,
+            Err(Error::JavaException) => {
+                env.exception_clear();
+                let loader = JClassLoader::system(env)?;
+                let class = env.define_class(self.name, &loader, self.bytecode);
+                env.delete_local_ref(loader);
+                class?
+            }
+            Err(err) => return Err(err),
+        };
+        (self.register_natives)(env, &class)?;
+        let global = env.new_global_ref(&class)?;
+        class_cache::insert(
+            CString::new(self.name).expect("class name has no nul bytes"),
+            global.clone(),
+        );
+        *cached = Some(global);
+        Ok(class)
+    }
+
+    /// Deletes the global reference to the class, so that it's looked up
+    /// again if it's needed after this.
+    pub(crate) fn clear(&self) {
+        let class = self
+            .class
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        drop(class);
+    }
+}
+
+/// Deletes the global references to all of the bundled classes.
+pub(crate) fn clear_bundled_classes() {
+    super::jrunnable::RUST_RUNNABLE.clear();
+    #[cfg(feature = "async")]
+    super::jcompletable_future::RUST_COMPLETION_HANDLER.clear();
+}
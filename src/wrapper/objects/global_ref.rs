@@ -1,8 +1,14 @@
 use std::{mem, ops::Deref, sync::Arc};
 
-use log::{debug, warn};
+use log::debug;
 
-use crate::{errors::Result, objects::JObject, sys, JNIEnv, JNIVersion, JavaVM};
+use crate::{
+    errors::Result,
+    objects::{debug_assert_ref_type, JObject, ReferenceType},
+    sys,
+    wrapper::java_vm::deferred_refs::{self, delete_ref, RefKind},
+    JNIEnv, JNIVersion, JavaVM,
+};
 
 #[cfg(doc)]
 use crate::objects::WeakRef;
@@ -53,7 +59,7 @@ use crate::objects::WeakRef;
 ///
 /// To create a global reference, use the [`JNIEnv::new_global_ref`] method.
 /// To delete it, simply drop the `GlobalRef` (but be sure to do so on an
-/// attached thread if possible; see the warning below).
+/// attached thread if possible; see below).
 ///
 /// Note that, because global references take more time to create or delete
 /// than local references do, they should only be used when their benefits
@@ -82,18 +88,20 @@ use crate::objects::WeakRef;
 /// ```
 ///
 ///
-/// # Warning: Drop On an Attached Thread If Possible
+/// # Dropping On an Unattached Thread
 ///
 /// When a `GlobalRef` is dropped, a JNI call is made to delete the global
-/// reference. If this frequently happens on a thread that is not already
-/// attached to the JVM, the thread will be temporarily attached using
-/// [`JavaVM::attach_current_thread`], causing a severe performance penalty.
+/// reference. This isn't possible on a thread that is not attached to the
+/// JVM, so instead, the reference is added to a queue, and deleted the next
+/// time that any thread attaches to the JVM, creates or deletes a global or
+/// weak reference, or calls [`JNIEnv::delete_deferred_refs`].
 ///
-/// To avoid this performance penalty, ensure that `GlobalRef`s are only
-/// dropped on a thread that is already attached (or never dropped at all).
+/// This means that the Java object may be kept alive for longer than
+/// expected, so it's still best to drop `GlobalRef`s on an attached thread
+/// where possible.
 ///
 /// In the event that a global reference is dropped on an unattached thread, a
-/// message is [logged][log] at [`log::Level::Warn`].
+/// message is [logged][log] at [`log::Level::Debug`].
 
 #[derive(Clone, Debug)]
 pub struct GlobalRef {
@@ -133,6 +141,9 @@ impl GlobalRef {
     ///
     /// Expects a valid raw global reference that should be created with `NewGlobalRef` JNI function.
     pub(crate) unsafe fn from_raw(vm: JavaVM, raw_global_ref: sys::jobject) -> Self {
+        debug_assert_ref_type(&vm, raw_global_ref, ReferenceType::Global);
+        #[cfg(feature = "stats")]
+        crate::stats::global_ref_created(&vm, raw_global_ref);
         GlobalRef {
             inner: Arc::new(GlobalRefGuard::from_raw(vm, raw_global_ref)),
         }
@@ -148,6 +159,18 @@ impl GlobalRef {
     pub fn as_obj(&self) -> &JObject<'static> {
         self.as_ref()
     }
+
+    /// Returns the kind of reference that the JVM reports for this
+    /// `GlobalRef`.
+    ///
+    /// This is always [`ReferenceType::Global`], unless the reference is null
+    /// (which gives [`ReferenceType::Invalid`]), and can be used to check that
+    /// a reference was created correctly.
+    ///
+    /// See [`JNIEnv::get_object_ref_type`].
+    pub fn ref_type(&self, env: &JNIEnv) -> Result<ReferenceType> {
+        env.get_object_ref_type(self)
+    }
 }
 
 impl GlobalRefGuard {
@@ -164,29 +187,21 @@ impl GlobalRefGuard {
 impl Drop for GlobalRefGuard {
     fn drop(&mut self) {
         let raw: sys::jobject = mem::take(&mut self.obj).into_raw();
-
-        let drop_impl = |env: &JNIEnv| -> Result<()> {
-            // Safety: This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
-            unsafe {
-                jni_call_unchecked!(env, v1_1, DeleteGlobalRef, raw);
-            }
-            Ok(())
-        };
+        if raw.is_null() {
+            return;
+        }
 
         // Safety: we can assume we couldn't have created the global reference in the first place without
         // having already required the JavaVM to support JNI >= 1.4
-        let res = match unsafe { self.vm.get_env(JNIVersion::V1_4) } {
-            Ok(env) => drop_impl(&env),
+        match unsafe { self.vm.get_env(JNIVersion::V1_4) } {
+            Ok(env) => unsafe {
+                delete_ref(&env, raw, RefKind::Global);
+                deferred_refs::flush(&env);
+            },
             Err(_) => {
-                warn!("A JNI global reference was dropped on a thread that is not attached. This will cause a performance problem if it happens frequently. For more information, see the documentation for `jni::objects::GlobalRef`.");
-                self.vm
-                    .attach_current_thread()
-                    .and_then(|env| drop_impl(&env))
+                debug!("A JNI global reference was dropped on a thread that is not attached, so its deletion has been deferred. For more information, see the documentation for `jni::objects::GlobalRef`.");
+                unsafe { deferred_refs::defer(raw, RefKind::Global) };
             }
-        };
-
-        if let Err(err) = res {
-            debug!("error dropping global ref: {:#?}", err);
         }
     }
 }
@@ -0,0 +1,161 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JBigInteger, JClass, JObject, JValue, Reference},
+    sys::{jint, jobject},
+    JNIEnv,
+};
+
+use super::jbig_integer::java_to_string;
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.math.BigDecimal` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JBigDecimal<'local>(JObject<'local>);
+
+impl<'local> AsRef<JBigDecimal<'local>> for JBigDecimal<'local> {
+    fn as_ref(&self) -> &JBigDecimal<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JBigDecimal<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JBigDecimal<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JBigDecimal<'local>> for JObject<'local> {
+    fn from(other: JBigDecimal) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a big decimal.
+impl<'local> From<JObject<'local>> for JBigDecimal<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a big decimal.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JBigDecimal<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JBigDecimal` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JBigDecimal<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JBigDecimal<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JBigDecimal<'local> {
+    /// Creates a [`JBigDecimal`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.math.BigDecimal`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a new `BigDecimal` from an unscaled value and a scale, such
+    /// that the value is `unscaled × 10^-scale` (`new BigDecimal(BigInteger, int)`).
+    ///
+    /// `unscaled` is the two's-complement, big-endian byte representation of
+    /// the unscaled value, as for [`JBigInteger::from_bytes`]. This matches
+    /// how, for example, `rust_decimal::Decimal` represents a value as a
+    /// mantissa and scale.
+    pub fn from_unscaled_bytes(
+        env: &mut JNIEnv<'local>,
+        unscaled: &[u8],
+        scale: jint,
+    ) -> Result<JBigDecimal<'local>> {
+        let unscaled = JBigInteger::from_bytes(env, unscaled)?;
+        let value = env.new_object(
+            "java/math/BigDecimal",
+            "(Ljava/math/BigInteger;I)V",
+            &[JValue::from(&unscaled), JValue::from(scale)],
+        );
+        env.delete_local_ref(unscaled);
+        Ok(JBigDecimal::from(value?))
+    }
+
+    /// Parses a `BigDecimal` from its string representation, such as
+    /// `"-12.34"` or `"1.5E+3"` (`new BigDecimal(String)`).
+    ///
+    /// An invalid string results in a `NumberFormatException`.
+    pub fn from_str(env: &mut JNIEnv<'local>, value: &str) -> Result<JBigDecimal<'local>> {
+        let value = env.new_string(value)?;
+        let big = env.new_object(
+            "java/math/BigDecimal",
+            "(Ljava/lang/String;)V",
+            &[JValue::from(&value)],
+        );
+        env.delete_local_ref(value);
+        Ok(JBigDecimal::from(big?))
+    }
+
+    /// Returns the two's-complement, big-endian byte representation of the
+    /// unscaled value of this `BigDecimal` (`BigDecimal.unscaledValue()`).
+    ///
+    /// See [`JBigDecimal::scale`] for the scale.
+    pub fn unscaled_bytes(&self, env: &mut JNIEnv) -> Result<Vec<u8>> {
+        let unscaled = env
+            .call_method(self, "unscaledValue", "()Ljava/math/BigInteger;", &[])?
+            .l()?;
+        let unscaled = JBigInteger::from(unscaled);
+        let bytes = unscaled.to_bytes(env);
+        env.delete_local_ref(unscaled);
+        bytes
+    }
+
+    /// Returns the scale of this `BigDecimal` (`BigDecimal.scale()`).
+    pub fn scale(&self, env: &mut JNIEnv) -> Result<jint> {
+        env.call_method(self, "scale", "()I", &[])?.i()
+    }
+
+    /// Returns the string representation of this `BigDecimal`, using
+    /// scientific notation if an exponent is needed (`BigDecimal.toString()`).
+    ///
+    /// The result can be parsed back into an equal `BigDecimal` with
+    /// [`JBigDecimal::from_str`].
+    pub fn to_string(&self, env: &mut JNIEnv) -> Result<String> {
+        java_to_string(env, self)
+    }
+}
+
+// Safety: `JBigDecimal` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JBigDecimal<'any> {
+    type Kind<'local> = JBigDecimal<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JBigDecimal::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/math/BigDecimal".into())
+    }
+}
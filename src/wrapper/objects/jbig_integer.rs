@@ -0,0 +1,152 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JByteArray, JClass, JObject, JString, JValue, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.math.BigInteger` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JBigInteger<'local>(JObject<'local>);
+
+impl<'local> AsRef<JBigInteger<'local>> for JBigInteger<'local> {
+    fn as_ref(&self) -> &JBigInteger<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JBigInteger<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JBigInteger<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JBigInteger<'local>> for JObject<'local> {
+    fn from(other: JBigInteger) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a big integer.
+impl<'local> From<JObject<'local>> for JBigInteger<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a big integer.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JBigInteger<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JBigInteger` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JBigInteger<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JBigInteger<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JBigInteger<'local> {
+    /// Creates a [`JBigInteger`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.math.BigInteger`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a new `BigInteger` from its two's-complement, big-endian byte
+    /// representation (`new BigInteger(byte[])`).
+    ///
+    /// This is the same representation as is returned by
+    /// [`JBigInteger::to_bytes`] (and, for example, by
+    /// `num_bigint::BigInt::to_signed_bytes_be`).
+    ///
+    /// An empty slice is not a valid representation and results in a
+    /// `NumberFormatException`.
+    pub fn from_bytes(env: &mut JNIEnv<'local>, bytes: &[u8]) -> Result<JBigInteger<'local>> {
+        let bytes = env.byte_array_from_slice(bytes)?;
+        let value = env.new_object("java/math/BigInteger", "([B)V", &[JValue::from(&bytes)]);
+        env.delete_local_ref(bytes);
+        Ok(JBigInteger::from(value?))
+    }
+
+    /// Parses a `BigInteger` from its decimal string representation, such as
+    /// `"-1234"` (`new BigInteger(String)`).
+    ///
+    /// An invalid string results in a `NumberFormatException`.
+    pub fn from_str(env: &mut JNIEnv<'local>, value: &str) -> Result<JBigInteger<'local>> {
+        let value = env.new_string(value)?;
+        let big = env.new_object(
+            "java/math/BigInteger",
+            "(Ljava/lang/String;)V",
+            &[JValue::from(&value)],
+        );
+        env.delete_local_ref(value);
+        Ok(JBigInteger::from(big?))
+    }
+
+    /// Returns the two's-complement, big-endian byte representation of this
+    /// `BigInteger` (`BigInteger.toByteArray()`).
+    pub fn to_bytes(&self, env: &mut JNIEnv) -> Result<Vec<u8>> {
+        let bytes = env.call_method(self, "toByteArray", "()[B", &[])?.l()?;
+        let bytes = JByteArray::from(bytes);
+        let vec = env.convert_byte_array(&bytes);
+        env.delete_local_ref(bytes);
+        vec
+    }
+
+    /// Returns the decimal string representation of this `BigInteger`
+    /// (`BigInteger.toString()`).
+    pub fn to_string(&self, env: &mut JNIEnv) -> Result<String> {
+        java_to_string(env, self)
+    }
+}
+
+/// Calls `toString()` on `obj` and converts the result into a Rust string.
+pub(super) fn java_to_string(env: &mut JNIEnv, obj: &JObject) -> Result<String> {
+    let string = env
+        .call_method(obj, "toString", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let string = JString::from(string);
+    let rust_string = env.get_string(&string).map(String::from);
+    env.delete_local_ref(string);
+    rust_string
+}
+
+// Safety: `JBigInteger` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JBigInteger<'any> {
+    type Kind<'local> = JBigInteger<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JBigInteger::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/math/BigInteger".into())
+    }
+}
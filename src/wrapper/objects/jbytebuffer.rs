@@ -1,4 +1,10 @@
-use crate::{objects::JObject, sys::jobject};
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, Reference},
+    sys::jobject,
+    JNIEnv,
+};
 
 /// Lifetime'd representation of a `jobject` that is an instance of the
 /// ByteBuffer Java class. Just a `JObject` wrapped in a new class.
@@ -66,3 +72,16 @@ impl<'local> JByteBuffer<'local> {
         self.0.into_raw() as jobject
     }
 }
+
+// Safety: `JByteBuffer` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JByteBuffer<'any> {
+    type Kind<'local> = JByteBuffer<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JByteBuffer::from_raw(raw as _)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/nio/ByteBuffer".into())
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    objects::JObject,
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JObject, Reference},
     sys::{jclass, jobject},
+    JNIEnv,
 };
 
 /// Lifetime'd representation of a `jclass`. Just a `JObject` wrapped in a new
@@ -81,3 +84,16 @@ impl<'local> JClass<'local> {
         self.0.into_raw() as jclass
     }
 }
+
+// Safety: `JClass` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JClass<'any> {
+    type Kind<'local> = JClass<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JClass::from_raw(raw as _)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Class".into())
+    }
+}
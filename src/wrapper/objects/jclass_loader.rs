@@ -0,0 +1,243 @@
+use std::io::Read;
+
+use crate::{
+    descriptors::class_cache,
+    errors::{Error, Result},
+    objects::{JClass, JInputStream, JObject, JValue, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+#[cfg(feature = "macros")]
+pub use jni_macros::include_java_class;
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.lang.ClassLoader` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JClassLoader<'local>(JObject<'local>);
+
+impl<'local> AsRef<JClassLoader<'local>> for JClassLoader<'local> {
+    fn as_ref(&self) -> &JClassLoader<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JClassLoader<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JClassLoader<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JClassLoader<'local>> for JObject<'local> {
+    fn from(other: JClassLoader) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a class loader.
+impl<'local> From<JObject<'local>> for JClassLoader<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a class loader.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JClassLoader<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JClassLoader` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JClassLoader<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JClassLoader<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JClassLoader<'local> {
+    /// Creates a [`JClassLoader`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.lang.ClassLoader`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Returns the system class loader (`ClassLoader.getSystemClassLoader()`).
+    ///
+    /// This is the class loader for the application's class path, which is
+    /// typically the loader that was used to load the application's own
+    /// classes (except on Android, where the application's classes are loaded
+    /// by a separate `PathClassLoader`).
+    pub fn system(env: &mut JNIEnv<'local>) -> Result<JClassLoader<'local>> {
+        let loader = env
+            .call_static_method(
+                "java/lang/ClassLoader",
+                "getSystemClassLoader",
+                "()Ljava/lang/ClassLoader;",
+                &[],
+            )?
+            .l()?;
+        Ok(JClassLoader::from(loader))
+    }
+
+    /// Loads the class with the given `name` via `ClassLoader.loadClass()`.
+    ///
+    /// The `name` may be given in the same `/`-separated form that's used by
+    /// [`JNIEnv::find_class`], such as `"com/example/Foo"`, or as a binary
+    /// name, such as `"com.example.Foo"`.
+    ///
+    /// Unlike [`JNIEnv::find_class`], which uses the class loader associated
+    /// with the calling native method (or the system class loader for threads
+    /// that were attached from native code), this always resolves the class
+    /// through this class loader. The class is not initialized.
+    pub fn load_class<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+        name: &str,
+    ) -> Result<JClass<'other_local>> {
+        let name = env.new_string(name.replace('/', "."))?;
+        let class = env.call_method(
+            self,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::from(&name)],
+        );
+        env.delete_local_ref(name);
+        Ok(JClass::from(class?.l()?))
+    }
+
+    /// Reads the entire contents of the resource with the given `name` via
+    /// `ClassLoader.getResourceAsStream()`.
+    ///
+    /// Resource names are `/`-separated paths, such as `"com/example/config.json"`,
+    /// and are resolved against the class loader's class path (including the
+    /// contents of any jars).
+    ///
+    /// Returns `Ok(None)` if the resource couldn't be found.
+    ///
+    /// The stream is read with a [`JInputStream::reader`] and is closed
+    /// before returning. If reading from it throws an exception, a
+    /// `java.io.IOException` that describes that exception is left pending and
+    /// [`Error::JavaException`] is returned.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JClassLoader};
+    /// #
+    /// # fn example(env: &mut JNIEnv) -> Result<()> {
+    /// let loader = JClassLoader::system(env)?;
+    /// if let Some(config) = loader.read_resource(env, "com/example/config.json")? {
+    ///     println!("config is {} bytes", config.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_resource(&self, env: &mut JNIEnv, name: &str) -> Result<Option<Vec<u8>>> {
+        env.with_local_frame(4, |env| {
+            let name = env.new_string(name)?;
+            let stream = JInputStream::from(
+                env.call_method(
+                    self,
+                    "getResourceAsStream",
+                    "(Ljava/lang/String;)Ljava/io/InputStream;",
+                    &[JValue::from(&name)],
+                )?
+                .l()?,
+            );
+            if stream.is_null() {
+                return Ok(None);
+            }
+
+            let mut bytes = Vec::new();
+            // The reader clears any exception that's thrown by the stream, so the stream can
+            // always be closed afterwards
+            let read = stream.reader(env)?.read_to_end(&mut bytes);
+            let closed = stream.close(env);
+            if let Err(err) = read {
+                env.throw_new("java/io/IOException", err.to_string())?;
+                return Err(Error::JavaException);
+            }
+            closed?;
+
+            Ok(Some(bytes))
+        })
+    }
+}
+
+// Safety: `JClassLoader` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JClassLoader<'any> {
+    type Kind<'local> = JClassLoader<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JClassLoader::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/ClassLoader".into())
+    }
+}
+
+/// The class files of the classes in a Java source file, as embedded by
+/// [`include_java_class!`].
+///
+/// The macro is only available with the `macros` feature.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedClasses {
+    /// The internal (`/`-separated) name of the class that's named after the
+    /// source file.
+    pub name: &'static str,
+    /// The internal names and the class files of the classes, where each
+    /// class comes after its superclass and interfaces if they're from the
+    /// same source file.
+    pub classes: &'static [(&'static str, &'static [u8])],
+}
+
+impl EmbeddedClasses {
+    /// Defines all of the classes with the given class loader (see
+    /// [`JNIEnv::define_class`]), and returns the class that's named after
+    /// the source file.
+    ///
+    /// This fails if any of the classes has already been defined by the
+    /// class loader.
+    pub fn define<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        loader: &JClassLoader,
+    ) -> Result<JClass<'local>> {
+        let mut defined = None;
+        for (name, bytecode) in self.classes {
+            let class = env.define_class(*name, loader, bytecode)?;
+            if *name == self.name {
+                defined = Some(class);
+            } else {
+                env.delete_local_ref(class);
+            }
+        }
+        match defined {
+            Some(class) => Ok(class),
+            None => loader.load_class(env, self.name),
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::marker::PhantomData;
+
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, JThrowable, JValue, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+#[cfg(feature = "async")]
+use super::bundled_class::BundledClass;
+#[cfg(feature = "async")]
+use crate::{objects::GlobalRef, sys::jlong, NativeMethod};
+
+/// The bundled class that wakes a [`CompletableFutureOutput`] when a
+/// `CompletableFuture` completes, compiled from `RustCompletionHandler.java`.
+#[cfg(feature = "async")]
+pub(super) static RUST_COMPLETION_HANDLER: BundledClass = BundledClass::new(
+    "io/github/jni_rs/RustCompletionHandler",
+    include_bytes!("../../java/io/github/jni_rs/RustCompletionHandler.class"),
+    |env, class| {
+        env.register_native_methods(
+            class,
+            &[NativeMethod {
+                name: "complete".into(),
+                sig: "(JLjava/lang/Object;Ljava/lang/Throwable;)V".into(),
+                fn_ptr: complete_output as *mut _,
+            }],
+        )
+    },
+);
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.util.concurrent.CompletableFuture` Java class. Just a `JObject`
+/// wrapped in a new class.
+///
+/// The value type `T` defaults to [`JObject`], and a future with some other
+/// [`Reference`] type can be created with [`JCompletableFuture::typed`]. Since
+/// Java generics are erased, no runtime check is made that the value is
+/// actually of type `T`.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JCompletableFuture<'local, T: Reference = JObject<'static>> {
+    internal: JObject<'local>,
+    _phantom_value: PhantomData<T>,
+}
+
+impl<'local, T: Reference> AsRef<JCompletableFuture<'local, T>> for JCompletableFuture<'local, T> {
+    fn as_ref(&self) -> &JCompletableFuture<'local, T> {
+        self
+    }
+}
+
+impl<'local, T: Reference> AsRef<JObject<'local>> for JCompletableFuture<'local, T> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local, T: Reference> ::std::ops::Deref for JCompletableFuture<'local, T> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.internal
+    }
+}
+
+impl<'local, T: Reference> From<JCompletableFuture<'local, T>> for JObject<'local> {
+    fn from(other: JCompletableFuture<'local, T>) -> JObject<'local> {
+        other.internal
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a completable future.
+impl<'local, T: Reference> From<JObject<'local>> for JCompletableFuture<'local, T> {
+    fn from(other: JObject<'local>) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a completable future.
+impl<'local, 'obj_ref, T: Reference> From<&'obj_ref JObject<'local>>
+    for &'obj_ref JCompletableFuture<'local, T>
+{
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JCompletableFuture` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JCompletableFuture<'local, T>) }
+    }
+}
+
+impl<'local, T: Reference> std::default::Default for JCompletableFuture<'local, T> {
+    fn default() -> Self {
+        Self {
+            internal: JObject::null(),
+            _phantom_value: PhantomData,
+        }
+    }
+}
+
+impl<'local, T: Reference> JCompletableFuture<'local, T> {
+    /// Creates a [`JCompletableFuture`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a
+    ///   `java.util.concurrent.CompletableFuture`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self {
+            internal: JObject::from_raw(raw),
+            _phantom_value: PhantomData,
+        }
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.internal.into_raw()
+    }
+
+    /// Converts this future into a future with a value of type `U`.
+    ///
+    /// This is an unchecked cast (in the same way as converting a [`JObject`]
+    /// into a [`JString`](crate::objects::JString)), since Java generics are
+    /// erased and the value can't be checked ahead of time.
+    pub fn typed<U: Reference>(self) -> JCompletableFuture<'local, U> {
+        JCompletableFuture {
+            internal: self.internal,
+            _phantom_value: PhantomData,
+        }
+    }
+
+    /// Creates a new, incomplete `CompletableFuture`.
+    pub fn new(env: &mut JNIEnv<'local>) -> Result<JCompletableFuture<'local, T>> {
+        let future = env.new_object("java/util/concurrent/CompletableFuture", "()V", &[])?;
+        Ok(JCompletableFuture::from(future))
+    }
+
+    /// Completes the future with the given `value` (`CompletableFuture.complete()`).
+    ///
+    /// Returns `true` if this call caused the future to be completed, or
+    /// `false` if it was already completed.
+    pub fn complete(&self, env: &mut JNIEnv, value: &T::Kind<'_>) -> Result<bool> {
+        env.call_method(
+            self,
+            "complete",
+            "(Ljava/lang/Object;)Z",
+            &[JValue::from(value.as_ref())],
+        )?
+        .z()
+    }
+
+    /// Completes the future with the given exception
+    /// (`CompletableFuture.completeExceptionally()`).
+    ///
+    /// Returns `true` if this call caused the future to be completed, or
+    /// `false` if it was already completed.
+    pub fn complete_exceptionally(&self, env: &mut JNIEnv, exception: &JThrowable) -> Result<bool> {
+        env.call_method(
+            self,
+            "completeExceptionally",
+            "(Ljava/lang/Throwable;)Z",
+            &[JValue::from(exception)],
+        )?
+        .z()
+    }
+
+    /// Returns `true` if the future has been completed in any way, normally,
+    /// exceptionally or by being cancelled (`CompletableFuture.isDone()`).
+    pub fn is_done(&self, env: &mut JNIEnv) -> Result<bool> {
+        env.call_method(self, "isDone", "()Z", &[])?.z()
+    }
+
+    /// Returns a Rust [`Future`] that resolves once this `CompletableFuture`
+    /// has been completed.
+    ///
+    /// The output is `Ok(Ok(value))` if the future completed normally, or
+    /// `Ok(Err(exception))` if it completed exceptionally or was cancelled. For
+    /// an exceptional completion, the exception is the one that was given to
+    /// [`Self::complete_exceptionally`] (not the `CompletionException` that
+    /// wraps it), and for a cancellation it's a `CancellationException`.
+    ///
+    /// The Rust future is woken by an instance of a small helper class,
+    /// `io.github.jni_rs.RustCompletionHandler`, that's registered with
+    /// `CompletableFuture.whenComplete()`, so no thread is blocked while
+    /// waiting for the completion. The helper class is defined in the same
+    /// way as the one that's used by
+    /// [`JRunnable::from_fn`](crate::objects::JRunnable::from_fn), and has
+    /// the same requirements on Android.
+    ///
+    /// The state that's shared with the helper is released when the
+    /// `CompletableFuture` completes, so it's leaked if the
+    /// `CompletableFuture` is never completed.
+    ///
+    /// This is only available if the "async" feature is enabled.
+    #[cfg(feature = "async")]
+    pub fn into_future(self, env: &mut JNIEnv) -> Result<CompletableFutureOutput> {
+        let shared = Arc::new(Mutex::new(OutputState::default()));
+        let class = RUST_COMPLETION_HANDLER.lookup(env)?;
+        let state = Arc::into_raw(shared.clone());
+        let handler = env.new_object(&class, "(J)V", &[JValue::Long(state as jlong)]);
+        env.delete_local_ref(class);
+        let handler = match handler {
+            Ok(handler) => env.auto_local(handler),
+            Err(err) => {
+                // Safety: the state wasn't given to a `RustCompletionHandler`
+                drop(unsafe { Arc::from_raw(state) });
+                return Err(err);
+            }
+        };
+
+        // If the future has already completed, the handler is called before this returns
+        let dependent = env
+            .call_method(
+                &self,
+                "whenComplete",
+                "(Ljava/util/function/BiConsumer;)Ljava/util/concurrent/CompletableFuture;",
+                &[JValue::from(&handler)],
+            )
+            .and_then(|dependent| dependent.l());
+        let dependent = match dependent {
+            Ok(dependent) => dependent,
+            Err(err) => {
+                release_handler_state(env, &handler);
+                return Err(err);
+            }
+        };
+        env.delete_local_ref(dependent);
+        env.delete_local_ref(self);
+
+        Ok(CompletableFutureOutput { shared })
+    }
+}
+
+/// Takes the state from a `RustCompletionHandler` that won't be called, and
+/// releases it.
+///
+/// Any pending exception is set aside while the state is taken, and is still
+/// pending afterwards.
+#[cfg(feature = "async")]
+fn release_handler_state(env: &mut JNIEnv, handler: &JObject) {
+    let exception = env.exception_occurred();
+    env.exception_clear();
+
+    match env
+        .call_method(handler, "take", "()J", &[])
+        .and_then(|state| state.j())
+    {
+        // Safety: `take` gives up the handler's ownership of the state, so it
+        // can't be released by `complete_output` as well
+        Ok(state) if state != 0 => {
+            drop(unsafe { Arc::from_raw(state as *const Mutex<OutputState>) })
+        }
+        // The handler has already been called
+        Ok(_) => {}
+        Err(err) => {
+            env.exception_clear();
+            log::error!("Failed to release RustCompletionHandler state: {:#?}", err);
+        }
+    }
+
+    if let Some(exception) = exception {
+        let _ = env.throw(&exception);
+        env.delete_local_ref(exception);
+    }
+}
+
+/// The implementation of `RustCompletionHandler.complete(long, Object, Throwable)`.
+#[cfg(feature = "async")]
+extern "system" fn complete_output(
+    mut env: JNIEnv,
+    _class: JClass,
+    state: jlong,
+    value: JObject,
+    exception: JThrowable,
+) {
+    // Safety: `RustCompletionHandler.take` gives up the handler's ownership of
+    // the state, and it's only passed here once
+    let shared = unsafe { Arc::from_raw(state as *const Mutex<OutputState>) };
+    let result = completion_result(&mut env, value, exception);
+    // The handler is called by `CompletableFuture`, which has no use for an exception
+    env.exception_clear();
+
+    let mut state = shared.lock().unwrap_or_else(PoisonError::into_inner);
+    state.result = Some(result);
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
+    }
+}
+
+/// Converts the arguments of `BiConsumer.accept()`, from
+/// `CompletableFuture.whenComplete()`, into the output of a
+/// [`CompletableFutureOutput`].
+#[cfg(feature = "async")]
+fn completion_result(
+    env: &mut JNIEnv,
+    value: JObject,
+    exception: JThrowable,
+) -> Result<std::result::Result<GlobalRef, GlobalRef>> {
+    if exception.is_null() {
+        return Ok(Ok(env.new_global_ref(value)?));
+    }
+
+    // The exception of a dependent stage is wrapped in a `CompletionException`
+    if env.is_instance_of(&exception, "java/util/concurrent/CompletionException")? {
+        let cause = env
+            .call_method(&exception, "getCause", "()Ljava/lang/Throwable;", &[])?
+            .l()?;
+        if !cause.is_null() {
+            return Ok(Err(env.new_global_ref(cause)?));
+        }
+    }
+    Ok(Err(env.new_global_ref(exception)?))
+}
+
+/// The state that's shared between a [`CompletableFutureOutput`] and the
+/// `RustCompletionHandler` that's waiting for the `CompletableFuture` to
+/// complete.
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct OutputState {
+    result: Option<Result<std::result::Result<GlobalRef, GlobalRef>>>,
+    waker: Option<Waker>,
+}
+
+/// A Rust [`Future`] that resolves once a Java `CompletableFuture` has been
+/// completed. This gets returned from [`JCompletableFuture::into_future`].
+///
+/// This is only available if the "async" feature is enabled.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CompletableFutureOutput {
+    shared: Arc<Mutex<OutputState>>,
+}
+
+#[cfg(feature = "async")]
+impl Future for CompletableFutureOutput {
+    type Output = Result<std::result::Result<GlobalRef, GlobalRef>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// Safety: `JCompletableFuture` is `repr(transparent)` around `JObject`.
+unsafe impl<'any, T: Reference + 'static> Reference for JCompletableFuture<'any, T> {
+    type Kind<'local> = JCompletableFuture<'local, T>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JCompletableFuture::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/util/concurrent/CompletableFuture".into())
+    }
+}
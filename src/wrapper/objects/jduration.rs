@@ -0,0 +1,185 @@
+use std::{convert::TryFrom, sync::OnceLock, time::Duration};
+
+use crate::{
+    convert::lookup_cached,
+    descriptors::class_cache,
+    errors::{Error, Result},
+    objects::{GlobalRef, JClass, JMethodID, JObject, JStaticMethodID, Reference},
+    signature::{Primitive, ReturnType},
+    sys::{jlong, jobject, jvalue},
+    JNIEnv,
+};
+
+/// The class and method IDs used by [`JDuration`].
+struct DurationApi {
+    class: GlobalRef,
+    of_seconds: JStaticMethodID,
+    get_seconds: JMethodID,
+    get_nano: JMethodID,
+}
+
+static DURATION_API: OnceLock<DurationApi> = OnceLock::new();
+
+impl DurationApi {
+    fn get(env: &mut JNIEnv) -> Result<&'static DurationApi> {
+        lookup_cached(&DURATION_API, env, "java/time/Duration", |env, class| {
+            Ok(DurationApi {
+                of_seconds: env.get_static_method_id(
+                    class,
+                    "ofSeconds",
+                    "(JJ)Ljava/time/Duration;",
+                )?,
+                get_seconds: env.get_method_id(class, "getSeconds", "()J")?,
+                get_nano: env.get_method_id(class, "getNano", "()I")?,
+                class: env.new_global_ref(class)?,
+            })
+        })
+    }
+}
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.time.Duration` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JDuration<'local>(JObject<'local>);
+
+impl<'local> AsRef<JDuration<'local>> for JDuration<'local> {
+    fn as_ref(&self) -> &JDuration<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JDuration<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JDuration<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JDuration<'local>> for JObject<'local> {
+    fn from(other: JDuration) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a duration.
+impl<'local> From<JObject<'local>> for JDuration<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a duration.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JDuration<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JDuration` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JDuration<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JDuration<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JDuration<'local> {
+    /// Creates a [`JDuration`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.time.Duration`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a new `java.time.Duration` for the given [`Duration`]
+    /// (`Duration.ofSeconds()`).
+    ///
+    /// Returns [`Error::TimeOutOfRange`] if `duration` is longer than a
+    /// `Duration` can represent.
+    pub fn from_duration(
+        env: &mut JNIEnv<'local>,
+        duration: Duration,
+    ) -> Result<JDuration<'local>> {
+        let seconds = jlong::try_from(duration.as_secs())
+            .map_err(|_| Error::TimeOutOfRange("Rust Duration to Java Duration"))?;
+        let nanos = duration.subsec_nanos() as jlong;
+
+        let api = DurationApi::get(env)?;
+        // Safety: the method ID is for `Duration.ofSeconds(long, long)` and the arguments match
+        // that signature.
+        let duration = unsafe {
+            env.call_static_method_unchecked(
+                &api.class,
+                api.of_seconds,
+                ReturnType::Object,
+                &[jvalue { j: seconds }, jvalue { j: nanos }],
+            )
+        }?
+        .l()?;
+        Ok(JDuration::from(duration))
+    }
+
+    /// Converts this `Duration` into a Rust [`Duration`].
+    ///
+    /// Returns [`Error::TimeOutOfRange`] if the `Duration` is negative, since
+    /// a Rust `Duration` can't be negative.
+    pub fn to_duration(&self, env: &mut JNIEnv) -> Result<Duration> {
+        let api = DurationApi::get(env)?;
+        // Safety: the method IDs are for `Duration.getSeconds()` and `Duration.getNano()`, which
+        // take no arguments.
+        let seconds = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.get_seconds,
+                ReturnType::Primitive(Primitive::Long),
+                &[],
+            )
+        }?
+        .j()?;
+        let nanos = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.get_nano,
+                ReturnType::Primitive(Primitive::Int),
+                &[],
+            )
+        }?
+        .i()?;
+
+        let seconds = u64::try_from(seconds)
+            .map_err(|_| Error::TimeOutOfRange("Java Duration to Rust Duration"))?;
+        Ok(Duration::new(seconds, nanos as u32))
+    }
+}
+
+// Safety: `JDuration` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JDuration<'any> {
+    type Kind<'local> = JDuration<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JDuration::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/time/Duration".into())
+    }
+}
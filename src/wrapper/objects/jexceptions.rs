@@ -0,0 +1,214 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, JThrowable, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// A [`Reference`] type for a subclass of `java.lang.Throwable`.
+///
+/// This is used by [`JNIEnv::throw_typed`] to throw a new exception, and by
+/// [`JThrowable::is_instance`] and [`JThrowable::downcast`] to match a caught
+/// exception, without naming the exception class as a string.
+///
+/// It's implemented for [`JThrowable`] and for the wrappers of common
+/// exception classes in this module, such as [`JIllegalArgumentException`].
+pub trait ThrowableType: Reference {
+    /// The binary name of the Java class, such as
+    /// `"java/lang/IllegalArgumentException"`.
+    const CLASS_NAME: &'static str;
+}
+
+impl<'any> ThrowableType for JThrowable<'any> {
+    const CLASS_NAME: &'static str = "java/lang/Throwable";
+}
+
+impl<'local> JThrowable<'local> {
+    /// Returns `true` if this throwable is an instance of the exception type
+    /// `E` (or one of its subclasses).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::{JIllegalArgumentException, JThrowable}};
+    /// #
+    /// # fn example(env: &mut JNIEnv, exception: &JThrowable) -> Result<()> {
+    /// if exception.is_instance::<JIllegalArgumentException>(env)? {
+    ///     // ...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_instance<E: ThrowableType>(&self, env: &mut JNIEnv) -> Result<bool> {
+        env.is_instance_of(self, E::CLASS_NAME)
+    }
+
+    /// Converts this throwable into the exception type `E`, if it's an
+    /// instance of `E` (or one of its subclasses).
+    ///
+    /// If it isn't, the original throwable is returned as the `Err` value, so
+    /// that it can be matched against another type.
+    pub fn downcast<E: ThrowableType>(
+        self,
+        env: &mut JNIEnv,
+    ) -> Result<std::result::Result<E::Kind<'local>, JThrowable<'local>>> {
+        if self.is_instance::<E>(env)? {
+            // Safety: the reference is owned by `self`, which is consumed, and it has been checked
+            // to be an instance of `E`.
+            Ok(Ok(unsafe { E::kind_from_raw(self.into_raw() as jobject) }))
+        } else {
+            Ok(Err(self))
+        }
+    }
+}
+
+/// Defines a wrapper for a `java.lang.Throwable` subclass that derefs to
+/// [`JThrowable`], along with its [`Reference`] and [`ThrowableType`]
+/// implementations.
+macro_rules! throwable_type {
+    ($(#[$attr:meta])* $name:ident, $class:literal, $java_name:literal) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Debug, Default)]
+        pub struct $name<'local>(JObject<'local>);
+
+        impl<'local> AsRef<$name<'local>> for $name<'local> {
+            fn as_ref(&self) -> &$name<'local> {
+                self
+            }
+        }
+
+        impl<'local> AsRef<JThrowable<'local>> for $name<'local> {
+            fn as_ref(&self) -> &JThrowable<'local> {
+                self
+            }
+        }
+
+        impl<'local> AsRef<JObject<'local>> for $name<'local> {
+            fn as_ref(&self) -> &JObject<'local> {
+                &self.0
+            }
+        }
+
+        impl<'local> ::std::ops::Deref for $name<'local> {
+            type Target = JThrowable<'local>;
+
+            fn deref(&self) -> &Self::Target {
+                (&self.0).into()
+            }
+        }
+
+        impl<'local> From<$name<'local>> for JObject<'local> {
+            fn from(other: $name<'local>) -> JObject<'local> {
+                other.0
+            }
+        }
+
+        impl<'local> From<$name<'local>> for JThrowable<'local> {
+            fn from(other: $name<'local>) -> JThrowable<'local> {
+                other.0.into()
+            }
+        }
+
+        #[doc = concat!("This conversion assumes that the `JObject` is a pointer to a `", $java_name, "`.")]
+        impl<'local> From<JObject<'local>> for $name<'local> {
+            fn from(other: JObject<'local>) -> Self {
+                unsafe { Self::from_raw(other.into_raw()) }
+            }
+        }
+
+        #[doc = concat!("This conversion assumes that the `JObject` is a pointer to a `", $java_name, "`.")]
+        impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref $name<'local> {
+            fn from(other: &'obj_ref JObject<'local>) -> Self {
+                // Safety: the wrapper is `repr(transparent)` around `JObject`.
+                unsafe { &*(other as *const JObject<'local> as *const $name<'local>) }
+            }
+        }
+
+        impl<'local> $name<'local> {
+            #[doc = concat!("Creates a [`", stringify!($name), "`] that wraps the given `raw` [`jobject`]")]
+            ///
+            /// # Safety
+            ///
+            /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+            ///
+            #[doc = concat!("* `raw` must be a valid raw JNI local reference to a `", $java_name, "`.")]
+            /// * There must not be any other `JObject` representing the same local reference.
+            /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+            ///   was created in.
+            pub const unsafe fn from_raw(raw: jobject) -> Self {
+                Self(JObject::from_raw(raw))
+            }
+
+            /// Unwrap to the raw jni type.
+            pub const fn into_raw(self) -> jobject {
+                self.0.into_raw()
+            }
+        }
+
+        // Safety: the wrapper is `repr(transparent)` around `JObject`.
+        unsafe impl<'any> Reference for $name<'any> {
+            type Kind<'local> = $name<'local>;
+
+            unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+                $name::from_raw(raw)
+            }
+
+            fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+                class_cache::find_class(env, $class.into())
+            }
+        }
+
+        impl<'any> ThrowableType for $name<'any> {
+            const CLASS_NAME: &'static str = $class;
+        }
+    };
+}
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.lang.RuntimeException` Java class.
+    JRuntimeException,
+    "java/lang/RuntimeException",
+    "java.lang.RuntimeException"
+);
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.lang.NullPointerException` Java class.
+    JNullPointerException,
+    "java/lang/NullPointerException",
+    "java.lang.NullPointerException"
+);
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.lang.IllegalArgumentException` Java class.
+    JIllegalArgumentException,
+    "java/lang/IllegalArgumentException",
+    "java.lang.IllegalArgumentException"
+);
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.lang.IllegalStateException` Java class.
+    JIllegalStateException,
+    "java/lang/IllegalStateException",
+    "java.lang.IllegalStateException"
+);
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.io.IOException` Java class.
+    JIoException,
+    "java/io/IOException",
+    "java.io.IOException"
+);
+
+throwable_type!(
+    /// Lifetime'd representation of a `jobject` that is an instance of the
+    /// `java.lang.InterruptedException` Java class.
+    JInterruptedException,
+    "java/lang/InterruptedException",
+    "java.lang.InterruptedException"
+);
@@ -0,0 +1,149 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, JRunnable, JValue, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.util.concurrent.Executor` Java interface. Just a `JObject` wrapped in a new
+/// class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JExecutor<'local>(JObject<'local>);
+
+impl<'local> AsRef<JExecutor<'local>> for JExecutor<'local> {
+    fn as_ref(&self) -> &JExecutor<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JExecutor<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JExecutor<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JExecutor<'local>> for JObject<'local> {
+    fn from(other: JExecutor) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an executor.
+impl<'local> From<JObject<'local>> for JExecutor<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an executor.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JExecutor<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JExecutor` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JExecutor<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JExecutor<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JExecutor<'local> {
+    /// Creates a [`JExecutor`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.util.concurrent.Executor`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Submits a runnable to the executor (`Executor.execute(Runnable)`).
+    pub fn execute(&self, env: &mut JNIEnv, runnable: &JRunnable) -> Result<()> {
+        env.call_method(
+            self,
+            "execute",
+            "(Ljava/lang/Runnable;)V",
+            &[JValue::from(runnable)],
+        )?;
+        Ok(())
+    }
+
+    /// Submits a Rust closure to the executor, which is run with an attached
+    /// [`JNIEnv`] on whichever thread the executor runs it on.
+    ///
+    /// This makes it possible to schedule work on a Java thread pool, or any
+    /// other `Executor`. See [`JRunnable::from_fn`] for how the closure is
+    /// run, and how errors and panics are reported.
+    ///
+    /// If the executor throws an exception, such as a
+    /// `RejectedExecutionException`, the closure is dropped without being run
+    /// and the exception is left pending.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JExecutor};
+    /// #
+    /// # fn example(env: &mut JNIEnv, executor: &JExecutor) -> Result<()> {
+    /// executor.execute_rust(env, |env| {
+    ///     // This runs on one of the executor's threads
+    ///     let thread = env
+    ///         .call_static_method("java/lang/Thread", "currentThread", "()Ljava/lang/Thread;", &[])?
+    ///         .l()?;
+    ///     let name = env
+    ///         .call_method(&thread, "getName", "()Ljava/lang/String;", &[])?
+    ///         .l()?;
+    ///     let name: String = env.get_string(&name.into())?.into();
+    ///     println!("running on {}", name);
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    pub fn execute_rust<F>(&self, env: &mut JNIEnv, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut JNIEnv) -> Result<()> + Send + 'static,
+    {
+        let runnable = JRunnable::from_fn(env, f)?;
+        let res = self.execute(env, &runnable);
+        if res.is_err() {
+            runnable.cancel(env)?;
+        }
+        env.delete_local_ref(runnable);
+        res
+    }
+}
+
+// Safety: `JExecutor` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JExecutor<'any> {
+    type Kind<'local> = JExecutor<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JExecutor::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/util/concurrent/Executor".into())
+    }
+}
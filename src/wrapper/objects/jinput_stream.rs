@@ -0,0 +1,225 @@
+use std::io;
+
+use crate::{
+    descriptors::class_cache,
+    errors::{Error, Result},
+    objects::{AutoLocal, JByteArray, JClass, JMethodID, JObject, JValue, Reference},
+    signature::{Primitive, ReturnType},
+    sys::{jbyte, jobject, jsize},
+    JNIEnv,
+};
+
+/// The size of the Java `byte[]` that's used to copy data between Rust and a
+/// Java stream.
+pub(super) const STREAM_BUFFER_SIZE: jsize = 8 * 1024;
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.io.InputStream` Java class. Just a `JObject` wrapped in a new class.
+///
+/// Use [`JInputStream::reader`] to read from the stream with
+/// [`std::io::Read`].
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JInputStream<'local>(JObject<'local>);
+
+impl<'local> AsRef<JInputStream<'local>> for JInputStream<'local> {
+    fn as_ref(&self) -> &JInputStream<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JInputStream<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JInputStream<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JInputStream<'local>> for JObject<'local> {
+    fn from(other: JInputStream) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an input stream.
+impl<'local> From<JObject<'local>> for JInputStream<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an input stream.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JInputStream<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JInputStream` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JInputStream<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JInputStream<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JInputStream<'local> {
+    /// Creates a [`JInputStream`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.io.InputStream`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Returns a [`std::io::Read`] implementation that reads from this stream.
+    ///
+    /// Data is copied from Java through a single `byte[]` buffer that's
+    /// allocated once by this method and reused for every read.
+    ///
+    /// If the Java stream throws an exception, the exception is cleared and
+    /// returned as an [`io::Error`] whose message is the exception's
+    /// `toString()`.
+    ///
+    /// The stream is not closed when the reader is dropped. Use
+    /// [`JInputStream::close`] to close it.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JInputStream};
+    /// # use std::io::Read;
+    /// #
+    /// # fn example(env: &mut JNIEnv, stream: &JInputStream) -> Result<()> {
+    /// let mut contents = String::new();
+    /// stream
+    ///     .reader(env)?
+    ///     .read_to_string(&mut contents)
+    ///     .expect("failed to read stream");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reader<'reader, 'other_local>(
+        &'reader self,
+        env: &'reader mut JNIEnv<'other_local>,
+    ) -> Result<JInputStreamReader<'reader, 'local, 'other_local>> {
+        let class = AutoLocal::new(env.find_class("java/io/InputStream")?, env);
+        let read = env.get_method_id(&class, "read", "([BII)I")?;
+        drop(class);
+
+        let buffer = env.new_byte_array(STREAM_BUFFER_SIZE)?;
+        let buffer = env.auto_local(buffer);
+
+        Ok(JInputStreamReader {
+            stream: self,
+            env,
+            buffer,
+            read,
+        })
+    }
+
+    /// Closes the stream (`InputStream.close()`).
+    pub fn close(&self, env: &mut JNIEnv) -> Result<()> {
+        env.call_method(self, "close", "()V", &[])?;
+        Ok(())
+    }
+}
+
+/// A [`std::io::Read`] implementation for a [`JInputStream`]. This gets
+/// returned from [`JInputStream::reader`].
+pub struct JInputStreamReader<'reader, 'local, 'other_local> {
+    stream: &'reader JInputStream<'local>,
+    env: &'reader mut JNIEnv<'other_local>,
+    buffer: AutoLocal<'other_local, JByteArray<'other_local>>,
+    read: JMethodID,
+}
+
+impl<'reader, 'local, 'other_local> io::Read for JInputStreamReader<'reader, 'local, 'other_local> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(STREAM_BUFFER_SIZE as usize);
+        if len == 0 {
+            return Ok(0);
+        }
+
+        // Safety: the method ID is for `InputStream.read(byte[], int, int)`, and the arguments
+        // match that signature.
+        let read = unsafe {
+            self.env.call_method_unchecked(
+                self.stream,
+                self.read,
+                ReturnType::Primitive(Primitive::Int),
+                &[
+                    JValue::from(&*self.buffer).as_jni(),
+                    JValue::from(0).as_jni(),
+                    JValue::from(len as jsize).as_jni(),
+                ],
+            )
+        }
+        .and_then(|read| read.i())
+        .map_err(|err| to_io_error(self.env, err))?;
+
+        // A negative count means the end of the stream was reached
+        if read <= 0 {
+            return Ok(0);
+        }
+
+        // A broken stream may claim to have read more than it was asked for,
+        // which must not be copied past the end of `buf`
+        let read = read as usize;
+        if read > len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "InputStream.read returned {} bytes but only {} were requested",
+                    read, len
+                ),
+            ));
+        }
+
+        // Safety: `u8` and `jbyte` (`i8`) have the same size and alignment, and `read` is no
+        // larger than `buf`
+        let dest = unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut jbyte, read) };
+        self.env
+            .get_byte_array_region(&*self.buffer, 0, dest)
+            .map_err(|err| to_io_error(self.env, err))?;
+        Ok(read)
+    }
+}
+
+/// Converts an error from calling a Java stream into an [`io::Error`],
+/// clearing and describing any pending Java exception.
+pub(super) fn to_io_error(env: &mut JNIEnv, err: Error) -> io::Error {
+    match env.take_exception_description() {
+        Some(exception) => io::Error::other(exception),
+        None => io::Error::other(err),
+    }
+}
+
+// Safety: `JInputStream` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JInputStream<'any> {
+    type Kind<'local> = JInputStream<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JInputStream::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/io/InputStream".into())
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    convert::TryFrom,
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    convert::lookup_cached,
+    descriptors::class_cache,
+    errors::{Error, Result},
+    objects::{GlobalRef, JClass, JMethodID, JObject, JStaticMethodID, Reference},
+    signature::{Primitive, ReturnType},
+    sys::{jlong, jobject, jvalue},
+    JNIEnv,
+};
+
+/// The class and method IDs used by [`JInstant`].
+struct InstantApi {
+    class: GlobalRef,
+    of_epoch_second: JStaticMethodID,
+    get_epoch_second: JMethodID,
+    get_nano: JMethodID,
+}
+
+static INSTANT_API: OnceLock<InstantApi> = OnceLock::new();
+
+impl InstantApi {
+    fn get(env: &mut JNIEnv) -> Result<&'static InstantApi> {
+        lookup_cached(&INSTANT_API, env, "java/time/Instant", |env, class| {
+            Ok(InstantApi {
+                of_epoch_second: env.get_static_method_id(
+                    class,
+                    "ofEpochSecond",
+                    "(JJ)Ljava/time/Instant;",
+                )?,
+                get_epoch_second: env.get_method_id(class, "getEpochSecond", "()J")?,
+                get_nano: env.get_method_id(class, "getNano", "()I")?,
+                class: env.new_global_ref(class)?,
+            })
+        })
+    }
+}
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.time.Instant` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JInstant<'local>(JObject<'local>);
+
+impl<'local> AsRef<JInstant<'local>> for JInstant<'local> {
+    fn as_ref(&self) -> &JInstant<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JInstant<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JInstant<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JInstant<'local>> for JObject<'local> {
+    fn from(other: JInstant) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an instant.
+impl<'local> From<JObject<'local>> for JInstant<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an instant.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JInstant<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JInstant` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JInstant<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JInstant<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JInstant<'local> {
+    /// Creates a [`JInstant`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.time.Instant`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a new `java.time.Instant` for the given [`SystemTime`]
+    /// (`Instant.ofEpochSecond()`).
+    ///
+    /// Returns [`Error::TimeOutOfRange`] if `time` can't be represented by an
+    /// `Instant`.
+    pub fn from_system_time(
+        env: &mut JNIEnv<'local>,
+        time: SystemTime,
+    ) -> Result<JInstant<'local>> {
+        let (seconds, nanos) = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since) => (since.as_secs() as i128, since.subsec_nanos() as jlong),
+            Err(before) => {
+                let before = before.duration();
+                (
+                    -(before.as_secs() as i128),
+                    -(before.subsec_nanos() as jlong),
+                )
+            }
+        };
+        let seconds =
+            jlong::try_from(seconds).map_err(|_| Error::TimeOutOfRange("SystemTime to Instant"))?;
+
+        let api = InstantApi::get(env)?;
+        // Safety: the method ID is for `Instant.ofEpochSecond(long, long)` and the arguments match
+        // that signature.
+        let instant = unsafe {
+            env.call_static_method_unchecked(
+                &api.class,
+                api.of_epoch_second,
+                ReturnType::Object,
+                &[jvalue { j: seconds }, jvalue { j: nanos }],
+            )
+        }?
+        .l()?;
+        Ok(JInstant::from(instant))
+    }
+
+    /// Converts this `Instant` into a [`SystemTime`].
+    ///
+    /// Returns [`Error::TimeOutOfRange`] if the `Instant` can't be represented
+    /// by a `SystemTime` on this platform.
+    pub fn to_system_time(&self, env: &mut JNIEnv) -> Result<SystemTime> {
+        let api = InstantApi::get(env)?;
+        // Safety: the method IDs are for `Instant.getEpochSecond()` and `Instant.getNano()`, which
+        // take no arguments.
+        let seconds = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.get_epoch_second,
+                ReturnType::Primitive(Primitive::Long),
+                &[],
+            )
+        }?
+        .j()?;
+        let nanos = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.get_nano,
+                ReturnType::Primitive(Primitive::Int),
+                &[],
+            )
+        }?
+        .i()?;
+
+        // `nanos` is always in the range `0..1_000_000_000`, even for times before the epoch
+        let time = if seconds >= 0 {
+            SystemTime::UNIX_EPOCH.checked_add(Duration::new(seconds as u64, nanos as u32))
+        } else {
+            SystemTime::UNIX_EPOCH
+                .checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+                .and_then(|time| time.checked_add(Duration::from_nanos(nanos as u64)))
+        };
+        time.ok_or(Error::TimeOutOfRange("Instant to SystemTime"))
+    }
+}
+
+// Safety: `JInstant` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JInstant<'any> {
+    type Kind<'local> = JInstant<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JInstant::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/time/Instant".into())
+    }
+}
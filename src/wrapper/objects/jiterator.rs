@@ -0,0 +1,192 @@
+use crate::{
+    errors::*,
+    objects::{AutoLocal, GlobalRef, JClass, JMethodID, JObject, Reference},
+    signature::{Primitive, ReturnType},
+    JNIEnv,
+};
+
+use std::marker::PhantomData;
+
+/// Wrapper for JObjects that implement `java/util/Iterator`.
+///
+/// Looks up the class and method ids on creation rather than for every method
+/// call.
+///
+/// `JIterator` does not implement [`std::iter::Iterator`] and cannot be used
+/// with a `for` loop. This is because its `next` method uses a `&mut JNIEnv`
+/// to call the Java iterator. Use a `while let` loop instead:
+///
+/// ```rust,no_run
+/// # use jni::{errors::Result, JNIEnv, objects::{AutoLocal, JObject}};
+/// #
+/// # fn example(env: &mut JNIEnv, collection: &JObject) -> Result<()> {
+/// let iterator = env
+///     .call_method(collection, "iterator", "()Ljava/util/Iterator;", &[])?
+///     .l()?;
+/// let mut iterator = env.get_iterator(&iterator)?;
+///
+/// while let Some(obj) = iterator.next(env)? {
+///     let obj: AutoLocal<JObject> = env.auto_local(obj);
+///
+///     // Do something with `obj` here.
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Alternatively, [`JIterator::try_for_each`] and [`JIterator::collect_vec`]
+/// take care of deleting the local reference that's created for each element.
+///
+/// The element type `E` defaults to [`JObject`], and an iterator over some
+/// other [`Reference`] type can be created with [`JIterator::typed`]. Since
+/// Java generics are erased, no runtime check is made that the elements are
+/// actually of type `E`.
+pub struct JIterator<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference = JObject<'static>> {
+    internal: &'obj_ref JObject<'other_local_1>,
+    _phantom_class: PhantomData<AutoLocal<'local, JClass<'local>>>,
+    _phantom_element: PhantomData<E>,
+    has_next: JMethodID,
+    next: JMethodID,
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference>
+    AsRef<JIterator<'local, 'other_local_1, 'obj_ref, E>>
+    for JIterator<'local, 'other_local_1, 'obj_ref, E>
+{
+    fn as_ref(&self) -> &JIterator<'local, 'other_local_1, 'obj_ref, E> {
+        self
+    }
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference> AsRef<JObject<'other_local_1>>
+    for JIterator<'local, 'other_local_1, 'obj_ref, E>
+{
+    fn as_ref(&self) -> &JObject<'other_local_1> {
+        self.internal
+    }
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JIterator<'local, 'other_local_1, 'obj_ref> {
+    /// Create an iterator from the environment and an object. This looks up
+    /// the necessary class and method ids to call all of the methods on it so
+    /// that extra work doesn't need to be done on every method call.
+    pub fn from_env(
+        env: &mut JNIEnv<'local>,
+        obj: &'obj_ref JObject<'other_local_1>,
+    ) -> Result<JIterator<'local, 'other_local_1, 'obj_ref>> {
+        let class = AutoLocal::new(env.find_class("java/util/Iterator")?, env);
+
+        let has_next = env.get_method_id(&class, "hasNext", "()Z")?;
+        let next = env.get_method_id(&class, "next", "()Ljava/lang/Object;")?;
+
+        Ok(JIterator {
+            internal: obj,
+            _phantom_class: PhantomData,
+            _phantom_element: PhantomData,
+            has_next,
+            next,
+        })
+    }
+
+    /// Converts this iterator into an iterator with elements of type `F`.
+    ///
+    /// This is an unchecked cast (in the same way as converting a [`JObject`]
+    /// into a [`JString`](crate::objects::JString)), since Java generics are
+    /// erased and the elements can't be checked ahead of time.
+    pub fn typed<F: Reference>(self) -> JIterator<'local, 'other_local_1, 'obj_ref, F> {
+        JIterator {
+            internal: self.internal,
+            _phantom_class: PhantomData,
+            _phantom_element: PhantomData,
+            has_next: self.has_next,
+            next: self.next,
+        }
+    }
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference>
+    JIterator<'local, 'other_local_1, 'obj_ref, E>
+{
+    /// Advances the iterator and returns the next element, or `None` if there
+    /// are no more elements.
+    ///
+    /// This method creates a new local reference. To prevent excessive memory
+    /// usage or overflow error, the local reference should be deleted using
+    /// [`JNIEnv::delete_local_ref`] or [`JNIEnv::auto_local`] before the next
+    /// loop iteration.
+    ///
+    /// This method returns:
+    ///
+    /// * `Ok(Some(_))`: if there was another element.
+    /// * `Ok(None)`: if there are no more elements.
+    /// * `Err(_)`: if there was an error calling the Java method to
+    ///   get the next element.
+    ///
+    /// This is like [`std::iter::Iterator::next`], but requires a parameter of
+    /// type `&mut JNIEnv` in order to call into Java.
+    pub fn next<'other_local_2>(
+        &mut self,
+        env: &mut JNIEnv<'other_local_2>,
+    ) -> Result<Option<E::Kind<'other_local_2>>> {
+        // SAFETY: We keep the class loaded, and fetched the method ID for these functions. We know none expect args.
+        let has_next = unsafe {
+            env.call_method_unchecked(
+                self.internal,
+                self.has_next,
+                ReturnType::Primitive(Primitive::Boolean),
+                &[],
+            )
+        }?
+        .z()?;
+
+        if !has_next {
+            return Ok(None);
+        }
+
+        let next = unsafe {
+            env.call_method_unchecked(self.internal, self.next, ReturnType::Object, &[])
+        }?
+        .l()?;
+
+        // Safety: the caller asserted the iterator only returns `E` elements
+        Ok(Some(unsafe { E::kind_from_raw(next.into_raw()) }))
+    }
+
+    /// Calls `f` for each remaining element, stopping at the first error.
+    ///
+    /// The local reference for each element is deleted after `f` returns, so
+    /// `f` should use [`JNIEnv::new_local_ref`] or [`JNIEnv::new_global_ref`]
+    /// if it needs to keep a reference to the element.
+    pub fn try_for_each<'other_local_2, F, T>(
+        &mut self,
+        env: &mut JNIEnv<'other_local_2>,
+        mut f: F,
+    ) -> std::result::Result<(), T>
+    where
+        F: FnMut(
+            &mut JNIEnv<'other_local_2>,
+            &E::Kind<'other_local_2>,
+        ) -> std::result::Result<(), T>,
+        T: From<Error>,
+    {
+        while let Some(element) = self.next(env)? {
+            let res = f(env, &element);
+            env.delete_local_ref(element);
+            res?;
+        }
+        Ok(())
+    }
+
+    /// Collects all remaining elements into a `Vec` of [`GlobalRef`]s.
+    ///
+    /// No local reference is leaked for the elements, so this can be used for
+    /// large collections without needing a local reference frame.
+    pub fn collect_vec(&mut self, env: &mut JNIEnv) -> Result<Vec<GlobalRef>> {
+        let mut elements = Vec::new();
+        self.try_for_each(env, |env, element| {
+            elements.push(env.new_global_ref(element)?);
+            Ok::<_, Error>(())
+        })?;
+        Ok(elements)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     errors::*,
-    objects::{AutoLocal, JClass, JMethodID, JObject, JValue},
+    objects::{AutoLocal, JClass, JMethodID, JObject, JValue, Reference},
     signature::{Primitive, ReturnType},
     sys::jint,
     JNIEnv,
@@ -13,9 +13,16 @@ use std::marker::PhantomData;
 ///
 /// Looks up the class and method ids on creation rather than for every method
 /// call.
-pub struct JList<'local, 'other_local_1: 'obj_ref, 'obj_ref> {
+///
+/// The element type `E` defaults to [`JObject`], and a list of some other
+/// [`Reference`] type can be created with [`JList::typed`], e.g. so that
+/// a `JList<JString>` returns [`JString`](crate::objects::JString) elements.
+/// Since Java generics are erased, no runtime check is made that the elements
+/// are actually of type `E`.
+pub struct JList<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference = JObject<'static>> {
     internal: &'obj_ref JObject<'other_local_1>,
     _phantom_class: PhantomData<AutoLocal<'local, JClass<'local>>>,
+    _phantom_element: PhantomData<E>,
     get: JMethodID,
     add: JMethodID,
     add_idx: JMethodID,
@@ -23,16 +30,17 @@ pub struct JList<'local, 'other_local_1: 'obj_ref, 'obj_ref> {
     size: JMethodID,
 }
 
-impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> AsRef<JList<'local, 'other_local_1, 'obj_ref>>
-    for JList<'local, 'other_local_1, 'obj_ref>
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference>
+    AsRef<JList<'local, 'other_local_1, 'obj_ref, E>>
+    for JList<'local, 'other_local_1, 'obj_ref, E>
 {
-    fn as_ref(&self) -> &JList<'local, 'other_local_1, 'obj_ref> {
+    fn as_ref(&self) -> &JList<'local, 'other_local_1, 'obj_ref, E> {
         self
     }
 }
 
-impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> AsRef<JObject<'other_local_1>>
-    for JList<'local, 'other_local_1, 'obj_ref>
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference> AsRef<JObject<'other_local_1>>
+    for JList<'local, 'other_local_1, 'obj_ref, E>
 {
     fn as_ref(&self) -> &JObject<'other_local_1> {
         self.internal
@@ -40,7 +48,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> AsRef<JObject<'other_local_1>>
 }
 
 impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, 'obj_ref> {
-    /// Create a map from the environment and an object. This looks up the
+    /// Create a list from the environment and an object. This looks up the
     /// necessary class and method ids to call all of the methods on it so that
     /// exra work doesn't need to be done on every method call.
     pub fn from_env(
@@ -58,6 +66,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         Ok(JList {
             internal: obj,
             _phantom_class: PhantomData,
+            _phantom_element: PhantomData,
             get,
             add,
             add_idx,
@@ -66,13 +75,47 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         })
     }
 
+    /// Converts this list into a list with elements of type `F`.
+    ///
+    /// This is an unchecked cast (in the same way as converting a [`JObject`]
+    /// into a [`JString`](crate::objects::JString)), since Java generics are
+    /// erased and the elements of the list can't be checked ahead of time.
+    ///
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::{JList, JObject, JString}};
+    /// #
+    /// # fn example(env: &mut JNIEnv, obj: &JObject) -> Result<()> {
+    /// let list: JList<JString> = env.get_list(obj)?.typed();
+    /// if let Some(first) = list.get(env, 0)? {
+    ///     let first: String = env.get_string(&first)?.into();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn typed<F: Reference>(self) -> JList<'local, 'other_local_1, 'obj_ref, F> {
+        JList {
+            internal: self.internal,
+            _phantom_class: PhantomData,
+            _phantom_element: PhantomData,
+            get: self.get,
+            add: self.add,
+            add_idx: self.add_idx,
+            remove: self.remove,
+            size: self.size,
+        }
+    }
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference>
+    JList<'local, 'other_local_1, 'obj_ref, E>
+{
     /// Look up the value for a key. Returns `Some` if it's found and `None` if
     /// a null pointer would be returned.
     pub fn get<'other_local_2>(
         &self,
         env: &mut JNIEnv<'other_local_2>,
         idx: jint,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+    ) -> Result<Option<E::Kind<'other_local_2>>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -85,7 +128,8 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the list only contains `E` elements
+            Ok(val) => Ok(Some(unsafe { E::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -94,7 +138,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
     }
 
     /// Append an element to the list
-    pub fn add(&self, env: &mut JNIEnv, value: &JObject) -> Result<()> {
+    pub fn add(&self, env: &mut JNIEnv, value: &E::Kind<'_>) -> Result<()> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -102,7 +146,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
                 self.internal,
                 self.add,
                 ReturnType::Primitive(Primitive::Boolean),
-                &[JValue::from(value).as_jni()],
+                &[JValue::from(value.as_ref()).as_jni()],
             )
         };
 
@@ -111,7 +155,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
     }
 
     /// Insert an element at a specific index
-    pub fn insert(&self, env: &mut JNIEnv, idx: jint, value: &JObject) -> Result<()> {
+    pub fn insert(&self, env: &mut JNIEnv, idx: jint, value: &E::Kind<'_>) -> Result<()> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -119,7 +163,10 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
                 self.internal,
                 self.add_idx,
                 ReturnType::Primitive(Primitive::Void),
-                &[JValue::from(idx).as_jni(), JValue::from(value).as_jni()],
+                &[
+                    JValue::from(idx).as_jni(),
+                    JValue::from(value.as_ref()).as_jni(),
+                ],
             )
         };
 
@@ -132,7 +179,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         &self,
         env: &mut JNIEnv<'other_local_2>,
         idx: jint,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+    ) -> Result<Option<E::Kind<'other_local_2>>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a int, rather than any other java type.
         let result = unsafe {
@@ -145,7 +192,8 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the list only contains `E` elements
+            Ok(val) => Ok(Some(unsafe { E::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -174,7 +222,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
     pub fn pop<'other_local_2>(
         &self,
         env: &mut JNIEnv<'other_local_2>,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+    ) -> Result<Option<E::Kind<'other_local_2>>> {
         let size = self.size(env)?;
         if size == 0 {
             return Ok(None);
@@ -192,7 +240,8 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the list only contains `E` elements
+            Ok(val) => Ok(Some(unsafe { E::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -233,7 +282,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
     pub fn iter<'list>(
         &'list self,
         env: &mut JNIEnv,
-    ) -> Result<JListIter<'list, 'local, 'obj_ref, 'other_local_1>> {
+    ) -> Result<JListIter<'list, 'local, 'obj_ref, 'other_local_1, E>> {
         Ok(JListIter {
             list: self,
             current: 0,
@@ -247,14 +296,20 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JList<'local, 'other_local_1, '
 ///
 /// TODO: make the iterator implementation for java iterators its own thing
 /// and generic enough to use elsewhere.
-pub struct JListIter<'list, 'local, 'other_local_1: 'obj_ref, 'obj_ref> {
-    list: &'list JList<'local, 'other_local_1, 'obj_ref>,
+pub struct JListIter<
+    'list,
+    'local,
+    'other_local_1: 'obj_ref,
+    'obj_ref,
+    E: Reference = JObject<'static>,
+> {
+    list: &'list JList<'local, 'other_local_1, 'obj_ref, E>,
     current: jint,
     size: jint,
 }
 
-impl<'list, 'local, 'other_local_1: 'obj_ref, 'obj_ref>
-    JListIter<'list, 'local, 'other_local_1, 'obj_ref>
+impl<'list, 'local, 'other_local_1: 'obj_ref, 'obj_ref, E: Reference>
+    JListIter<'list, 'local, 'other_local_1, 'obj_ref, E>
 {
     /// Advances the iterator and returns the next object in the
     /// `java.util.List`, or `None` if there are no more objects.
@@ -281,7 +336,7 @@ impl<'list, 'local, 'other_local_1: 'obj_ref, 'obj_ref>
     pub fn next<'other_local_2>(
         &mut self,
         env: &mut JNIEnv<'other_local_2>,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+    ) -> Result<Option<E::Kind<'other_local_2>>> {
         if self.current == self.size {
             return Ok(None);
         }
@@ -1,35 +1,49 @@
 use crate::{
     errors::*,
-    objects::{AutoLocal, JClass, JMethodID, JObject, JValue},
+    objects::{AutoLocal, JClass, JMethodID, JObject, JString, JValue, Reference},
     signature::{Primitive, ReturnType},
     JNIEnv,
 };
 
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
 /// Wrapper for JObjects that implement `java/util/Map`. Provides methods to get
 /// and set entries and a way to iterate over key/value pairs.
 ///
 /// Looks up the class and method ids on creation rather than for every method
 /// call.
-pub struct JMap<'local, 'other_local_1: 'obj_ref, 'obj_ref> {
+///
+/// The key and value types `K` and `V` default to [`JObject`], and a map with
+/// other [`Reference`] types can be created with [`JMap::typed`], e.g. so that
+/// a `JMap<JString, JString>` returns [`JString`](crate::objects::JString)
+/// keys and values. Since Java generics are erased, no runtime check is made
+/// that the keys and values are actually of type `K` and `V`.
+pub struct JMap<
+    'local,
+    'other_local_1: 'obj_ref,
+    'obj_ref,
+    K: Reference = JObject<'static>,
+    V: Reference = JObject<'static>,
+> {
     internal: &'obj_ref JObject<'other_local_1>,
     class: AutoLocal<'local, JClass<'local>>,
+    _phantom_entry: PhantomData<(K, V)>,
     get: JMethodID,
     put: JMethodID,
     remove: JMethodID,
 }
 
-impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> AsRef<JMap<'local, 'other_local_1, 'obj_ref>>
-    for JMap<'local, 'other_local_1, 'obj_ref>
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, K: Reference, V: Reference>
+    AsRef<JMap<'local, 'other_local_1, 'obj_ref, K, V>>
+    for JMap<'local, 'other_local_1, 'obj_ref, K, V>
 {
-    fn as_ref(&self) -> &JMap<'local, 'other_local_1, 'obj_ref> {
+    fn as_ref(&self) -> &JMap<'local, 'other_local_1, 'obj_ref, K, V> {
         self
     }
 }
 
-impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> AsRef<JObject<'other_local_1>>
-    for JMap<'local, 'other_local_1, 'obj_ref>
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, K: Reference, V: Reference>
+    AsRef<JObject<'other_local_1>> for JMap<'local, 'other_local_1, 'obj_ref, K, V>
 {
     fn as_ref(&self) -> &JObject<'other_local_1> {
         self.internal
@@ -60,19 +74,89 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
         Ok(JMap {
             internal: obj,
             class,
+            _phantom_entry: PhantomData,
             get,
             put,
             remove,
         })
     }
 
+    /// Creates a new `java.util.HashMap` that contains a copy of each of the
+    /// given string keys and values, and returns it.
+    ///
+    /// The returned object can be wrapped with [`JNIEnv::get_map`], and only
+    /// one local reference is returned, since the local references for the
+    /// keys and values are deleted once they've been stored.
+    ///
+    /// ```rust,no_run
+    /// # use std::collections::HashMap;
+    /// # use jni::{errors::Result, JNIEnv, objects::JMap};
+    /// #
+    /// # fn example(env: &mut JNIEnv, properties: &HashMap<String, String>) -> Result<()> {
+    /// let map = JMap::from_iter(env, properties)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_iter<I, KS, VS>(env: &mut JNIEnv<'local>, iter: I) -> Result<JObject<'local>>
+    where
+        I: IntoIterator<Item = (KS, VS)>,
+        KS: AsRef<str>,
+        VS: AsRef<str>,
+    {
+        let obj = env.new_object("java/util/HashMap", "()V", &[])?;
+        let map: JMap<JString, JString> = env.get_map(&obj)?.typed();
+        for (key, value) in iter {
+            let key = env.auto_local(env.new_string(key.as_ref())?);
+            let value = env.auto_local(env.new_string(value.as_ref())?);
+            if let Some(old) = map.put(env, &key, &value)? {
+                env.delete_local_ref(old);
+            }
+        }
+        drop(map);
+        Ok(obj)
+    }
+
+    /// Converts this map into a map with keys of type `L` and values of type
+    /// `W`.
+    ///
+    /// This is an unchecked cast (in the same way as converting a [`JObject`]
+    /// into a [`JString`](crate::objects::JString)), since Java generics are
+    /// erased and the entries of the map can't be checked ahead of time.
+    ///
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::{JMap, JObject, JString}};
+    /// #
+    /// # fn example(env: &mut JNIEnv, obj: &JObject) -> Result<()> {
+    /// let map: JMap<JString, JString> = env.get_map(obj)?.typed();
+    /// let key = env.new_string("key")?;
+    /// if let Some(value) = map.get(env, &key)? {
+    ///     let value: String = env.get_string(&value)?.into();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn typed<L: Reference, W: Reference>(self) -> JMap<'local, 'other_local_1, 'obj_ref, L, W> {
+        JMap {
+            internal: self.internal,
+            class: self.class,
+            _phantom_entry: PhantomData,
+            get: self.get,
+            put: self.put,
+            remove: self.remove,
+        }
+    }
+}
+
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, K: Reference, V: Reference>
+    JMap<'local, 'other_local_1, 'obj_ref, K, V>
+{
     /// Look up the value for a key. Returns `Some` if it's found and `None` if
     /// a null pointer would be returned.
     pub fn get<'other_local_2>(
         &self,
         env: &mut JNIEnv<'other_local_2>,
-        key: &JObject,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+        key: &K::Kind<'_>,
+    ) -> Result<Option<V::Kind<'other_local_2>>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -80,12 +164,13 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
                 self.internal,
                 self.get,
                 ReturnType::Object,
-                &[JValue::from(key).as_jni()],
+                &[JValue::from(key.as_ref()).as_jni()],
             )
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the map only contains `V` values
+            Ok(val) => Ok(Some(unsafe { V::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -98,9 +183,9 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
     pub fn put<'other_local_2>(
         &self,
         env: &mut JNIEnv<'other_local_2>,
-        key: &JObject,
-        value: &JObject,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+        key: &K::Kind<'_>,
+        value: &V::Kind<'_>,
+    ) -> Result<Option<V::Kind<'other_local_2>>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -108,12 +193,16 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
                 self.internal,
                 self.put,
                 ReturnType::Object,
-                &[JValue::from(key).as_jni(), JValue::from(value).as_jni()],
+                &[
+                    JValue::from(key.as_ref()).as_jni(),
+                    JValue::from(value.as_ref()).as_jni(),
+                ],
             )
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the map only contains `V` values
+            Ok(val) => Ok(Some(unsafe { V::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -126,8 +215,8 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
     pub fn remove<'other_local_2>(
         &self,
         env: &mut JNIEnv<'other_local_2>,
-        key: &JObject,
-    ) -> Result<Option<JObject<'other_local_2>>> {
+        key: &K::Kind<'_>,
+    ) -> Result<Option<V::Kind<'other_local_2>>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for this function.
         // Provided argument is statically known as a JObject/null, rather than another primitive type.
         let result = unsafe {
@@ -135,12 +224,13 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
                 self.internal,
                 self.remove,
                 ReturnType::Object,
-                &[JValue::from(key).as_jni()],
+                &[JValue::from(key.as_ref()).as_jni()],
             )
         };
 
         match result {
-            Ok(val) => Ok(Some(val.l()?)),
+            // Safety: the caller asserted the map only contains `V` values
+            Ok(val) => Ok(Some(unsafe { V::kind_from_raw(val.l()?.into_raw()) })),
             Err(e) => match e {
                 Error::NullPtr(_) => Ok(None),
                 _ => Err(e),
@@ -182,7 +272,7 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
     pub fn iter<'map, 'iter_local>(
         &'map self,
         env: &mut JNIEnv<'iter_local>,
-    ) -> Result<JMapIter<'map, 'local, 'other_local_1, 'obj_ref, 'iter_local>> {
+    ) -> Result<JMapIter<'map, 'local, 'other_local_1, 'obj_ref, 'iter_local, K, V>> {
         let iter_class = AutoLocal::new(env.find_class("java/util/Iterator")?, env);
 
         let has_next = env.get_method_id(&iter_class, "hasNext", "()Z")?;
@@ -236,13 +326,45 @@ impl<'local, 'other_local_1: 'obj_ref, 'obj_ref> JMap<'local, 'other_local_1, 'o
     }
 }
 
+impl<'local, 'other_local_1: 'obj_ref, 'obj_ref, 'key, 'value>
+    JMap<'local, 'other_local_1, 'obj_ref, JString<'key>, JString<'value>>
+{
+    /// Copies the entries of this map into a Rust [`HashMap`], converting
+    /// each key and value with [`JString::to_rust_string`].
+    ///
+    /// The local references for the keys and values are deleted as they're
+    /// converted, so this can be used for large maps without needing a local
+    /// reference frame.
+    ///
+    /// # Errors
+    /// Returns [`Error::NullPtr`] if any key or value is `null`.
+    pub fn to_hashmap(&self, env: &mut JNIEnv) -> Result<HashMap<String, String>> {
+        let mut entries = HashMap::new();
+        let mut iter = self.iter(env)?;
+        while let Some((key, value)) = iter.next(env)? {
+            let key = env.auto_local(key);
+            let value = env.auto_local(value);
+            entries.insert(key.to_rust_string(env)?, value.to_rust_string(env)?);
+        }
+        Ok(entries)
+    }
+}
+
 /// An iterator over the keys and values in a map. See [`JMap::iter`] for more
 /// information.
 ///
 /// TODO: make the iterator implementation for java iterators its own thing
 /// and generic enough to use elsewhere.
-pub struct JMapIter<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_local> {
-    _phantom_map: PhantomData<&'map JMap<'local, 'other_local_1, 'obj_ref>>,
+pub struct JMapIter<
+    'map,
+    'local,
+    'other_local_1: 'obj_ref,
+    'obj_ref,
+    'iter_local,
+    K: Reference = JObject<'static>,
+    V: Reference = JObject<'static>,
+> {
+    _phantom_map: PhantomData<&'map JMap<'local, 'other_local_1, 'obj_ref, K, V>>,
     has_next: JMethodID,
     next: JMethodID,
     get_key: JMethodID,
@@ -250,8 +372,8 @@ pub struct JMapIter<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_loca
     iter: AutoLocal<'iter_local, JObject<'iter_local>>,
 }
 
-impl<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_local>
-    JMapIter<'map, 'local, 'other_local_1, 'obj_ref, 'iter_local>
+impl<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_local, K: Reference, V: Reference>
+    JMapIter<'map, 'local, 'other_local_1, 'obj_ref, 'iter_local, K, V>
 {
     /// Advances the iterator and returns the next key-value pair in the
     /// `java.util.Map`, or `None` if there are no more objects.
@@ -278,7 +400,7 @@ impl<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_local>
     pub fn next<'other_local_2>(
         &mut self,
         env: &mut JNIEnv<'other_local_2>,
-    ) -> Result<Option<(JObject<'other_local_2>, JObject<'other_local_2>)>> {
+    ) -> Result<Option<(K::Kind<'other_local_2>, V::Kind<'other_local_2>)>> {
         // SAFETY: We keep the class loaded, and fetched the method ID for these functions. We know none expect args.
 
         let has_next = unsafe {
@@ -307,6 +429,13 @@ impl<'map, 'local, 'other_local_1: 'obj_ref, 'obj_ref, 'iter_local>
             unsafe { env.call_method_unchecked(&next, self.get_value, ReturnType::Object, &[]) }?
                 .l()?;
 
-        Ok(Some((key, value)))
+        // Safety: the caller asserted the map only contains `K` keys and `V`
+        // values
+        unsafe {
+            Ok(Some((
+                K::kind_from_raw(key.into_raw()),
+                V::kind_from_raw(value.into_raw()),
+            )))
+        }
     }
 }
@@ -0,0 +1,154 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JClassLoader, JObject, JString, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.lang.Module` Java class. Just a `JObject` wrapped in a new class.
+///
+/// Modules were added in Java 9. A `JModule` can be obtained for a class
+/// with [`JNIEnv::get_module`].
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JModule<'local>(JObject<'local>);
+
+impl<'local> AsRef<JModule<'local>> for JModule<'local> {
+    fn as_ref(&self) -> &JModule<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JModule<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JModule<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JModule<'local>> for JObject<'local> {
+    fn from(other: JModule) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a module.
+impl<'local> From<JObject<'local>> for JModule<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a module.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JModule<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JModule` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JModule<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JModule<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JModule<'local> {
+    /// Creates a [`JModule`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.lang.Module`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Returns whether this is a named module (`Module.isNamed()`).
+    ///
+    /// Classes that are loaded from the class path, rather than the module
+    /// path, belong to the unnamed module of their class loader.
+    pub fn is_named(&self, env: &mut JNIEnv) -> Result<bool> {
+        env.call_method(self, "isNamed", "()Z", &[])?.z()
+    }
+
+    /// Returns the name of this module (`Module.getName()`), such as
+    /// `"java.base"`, or `None` for an unnamed module.
+    pub fn name(&self, env: &mut JNIEnv) -> Result<Option<String>> {
+        let name = env
+            .call_method(self, "getName", "()Ljava/lang/String;", &[])?
+            .l()?;
+        if name.is_null() {
+            return Ok(None);
+        }
+        let name = JString::from(name);
+        let res = name.to_rust_string(env);
+        env.delete_local_ref(name);
+        res.map(Some)
+    }
+
+    /// Returns the `java.lang.module.ModuleDescriptor` of this module
+    /// (`Module.getDescriptor()`), or `None` for an unnamed module.
+    ///
+    /// The descriptor describes the module's name, version, dependencies and
+    /// which packages it exports.
+    pub fn descriptor<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<Option<JObject<'other_local>>> {
+        let descriptor = env
+            .call_method(
+                self,
+                "getDescriptor",
+                "()Ljava/lang/module/ModuleDescriptor;",
+                &[],
+            )?
+            .l()?;
+        Ok((!descriptor.is_null()).then_some(descriptor))
+    }
+
+    /// Returns the class loader of this module (`Module.getClassLoader()`).
+    ///
+    /// The returned loader is null for modules that are defined to the
+    /// bootstrap class loader, such as `java.base`.
+    pub fn class_loader<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<JClassLoader<'other_local>> {
+        let loader = env
+            .call_method(self, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+            .l()?;
+        Ok(JClassLoader::from(loader))
+    }
+}
+
+// Safety: `JModule` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JModule<'any> {
+    type Kind<'local> = JModule<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JModule::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Module".into())
+    }
+}
@@ -1,9 +1,15 @@
 use std::marker::PhantomData;
 
-use crate::sys::jobject;
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, Reference},
+    sys::jobject,
+    JNIEnv,
+};
 
 #[cfg(doc)]
-use crate::{objects::GlobalRef, JNIEnv};
+use crate::objects::GlobalRef;
 
 /// Wrapper around [`sys::jobject`] that adds a lifetime to ensure that
 /// the underlying JNI pointer won't be accessible to safe Rust code if the
@@ -101,3 +107,17 @@ impl<'local> std::default::Default for JObject<'local> {
         Self::null()
     }
 }
+
+// Safety: `Kind<'local>` is `JObject<'local>` itself, and `kind_from_raw` is
+// `JObject::from_raw`.
+unsafe impl<'any> Reference for JObject<'any> {
+    type Kind<'local> = JObject<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JObject::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Object".into())
+    }
+}
@@ -1,65 +1,90 @@
+use std::{convert::TryFrom, marker::PhantomData};
+
 use crate::{
-    objects::JObject,
-    sys::{jobject, jobjectArray},
+    errors::{Error, Error::JniCall, JniError, Result},
+    objects::{AutoLocal, JClass, JObject, JString, Reference},
+    sys::{jobject, jobjectArray, jsize},
+    JNIEnv,
 };
 
 use super::AsJArrayRaw;
 
 /// Lifetime'd representation of a [`jobjectArray`] which wraps a [`JObject`] reference
+///
+/// The element type `T` defaults to [`JObject`]. An array of some other
+/// [`Reference`] type, such as `JObjectArray<JString>`, can be created with
+/// [`JObjectArray::new`] or [`JObjectArray::from_iter`], and its elements are
+/// returned as that type by [`JObjectArray::get_element`].
+///
+/// Unlike Java generics, Java arrays do know their element type at runtime,
+/// and the JVM checks that the elements that are stored in an array are
+/// instances of its element type. However, no check is made that an array
+/// that is converted from a [`JObject`] with `From` (or [`JObjectArray::cast`])
+/// actually has the element type `T`.
 #[repr(transparent)]
 #[derive(Debug)]
-pub struct JObjectArray<'local>(JObject<'local>);
+pub struct JObjectArray<'local, T: Reference = JObject<'static>> {
+    internal: JObject<'local>,
+    _phantom_element: PhantomData<T>,
+}
 
-impl<'local> AsRef<JObjectArray<'local>> for JObjectArray<'local> {
-    fn as_ref(&self) -> &JObjectArray<'local> {
+impl<'local, T: Reference> AsRef<JObjectArray<'local, T>> for JObjectArray<'local, T> {
+    fn as_ref(&self) -> &JObjectArray<'local, T> {
         self
     }
 }
 
-impl<'local> AsRef<JObject<'local>> for JObjectArray<'local> {
+impl<'local, T: Reference> AsRef<JObject<'local>> for JObjectArray<'local, T> {
     fn as_ref(&self) -> &JObject<'local> {
         self
     }
 }
 
-impl<'local> ::std::ops::Deref for JObjectArray<'local> {
+impl<'local, T: Reference> ::std::ops::Deref for JObjectArray<'local, T> {
     type Target = JObject<'local>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.internal
     }
 }
 
-impl<'local> From<JObjectArray<'local>> for JObject<'local> {
-    fn from(other: JObjectArray) -> JObject {
-        other.0
+impl<'local, T: Reference> From<JObjectArray<'local, T>> for JObject<'local> {
+    fn from(other: JObjectArray<'local, T>) -> JObject<'local> {
+        other.internal
     }
 }
 
-/// This conversion assumes that the `JObject` is a pointer to a class object.
-impl<'local> From<JObject<'local>> for JObjectArray<'local> {
-    fn from(other: JObject) -> Self {
+/// This conversion assumes that the `JObject` is a pointer to an array with
+/// elements of type `T`.
+impl<'local, T: Reference> From<JObject<'local>> for JObjectArray<'local, T> {
+    fn from(other: JObject<'local>) -> Self {
         unsafe { Self::from_raw(other.into_raw()) }
     }
 }
 
-/// This conversion assumes that the `JObject` is a pointer to a class object.
-impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JObjectArray<'local> {
+/// This conversion assumes that the `JObject` is a pointer to an array with
+/// elements of type `T`.
+impl<'local, 'obj_ref, T: Reference> From<&'obj_ref JObject<'local>>
+    for &'obj_ref JObjectArray<'local, T>
+{
     fn from(other: &'obj_ref JObject<'local>) -> Self {
         // Safety: `JObjectArray` is `repr(transparent)` around `JObject`.
-        unsafe { &*(other as *const JObject<'local> as *const JObjectArray<'local>) }
+        unsafe { &*(other as *const JObject<'local> as *const JObjectArray<'local, T>) }
     }
 }
 
-impl<'local> std::default::Default for JObjectArray<'local> {
+impl<'local, T: Reference> std::default::Default for JObjectArray<'local, T> {
     fn default() -> Self {
-        Self(JObject::null())
+        Self {
+            internal: JObject::null(),
+            _phantom_element: PhantomData,
+        }
     }
 }
 
-unsafe impl<'local> AsJArrayRaw<'local> for JObjectArray<'local> {}
+unsafe impl<'local, T: Reference> AsJArrayRaw<'local> for JObjectArray<'local, T> {}
 
-impl<'local> JObjectArray<'local> {
+impl<'local, T: Reference> JObjectArray<'local, T> {
     /// Creates a [`JObjectArray`] that wraps the given `raw` [`jobjectArray`]
     ///
     /// # Safety
@@ -71,11 +96,237 @@ impl<'local> JObjectArray<'local> {
     /// * The lifetime `'local` must not outlive the local reference frame that the local reference
     ///   was created in.
     pub const unsafe fn from_raw(raw: jobjectArray) -> Self {
-        Self(JObject::from_raw(raw as jobject))
+        Self {
+            internal: JObject::from_raw(raw as jobject),
+            _phantom_element: PhantomData,
+        }
     }
 
     /// Unwrap to the raw jni type.
     pub const fn into_raw(self) -> jobjectArray {
-        self.0.into_raw() as jobjectArray
+        self.internal.into_raw() as jobjectArray
+    }
+
+    /// Changes the element type of this array, without any runtime check
+    /// that the elements are actually of type `U`.
+    pub fn cast<U: Reference>(self) -> JObjectArray<'local, U> {
+        JObjectArray::from(self.internal)
+    }
+
+    /// Creates a new array of `length` elements, which are all initially set
+    /// to `initial_element`.
+    ///
+    /// The element class is looked up with [`Reference::lookup_class`].
+    ///
+    /// See [`JNIEnv::new_object_array`].
+    pub fn new<'other_local>(
+        env: &mut JNIEnv<'local>,
+        length: jsize,
+        initial_element: impl AsRef<T::Kind<'other_local>>,
+    ) -> Result<Self> {
+        let initial_element: &JObject = initial_element.as_ref().as_ref();
+        let class = T::lookup_class(env)?;
+        let array = env.new_object_array(length, &class, initial_element)?;
+        env.delete_local_ref(class);
+        Ok(array.cast())
+    }
+
+    /// Creates a new array that contains the elements of `iter`.
+    ///
+    /// The elements are collected into a [`Vec`] first, since the length of
+    /// the array must be known before it's created.
+    ///
+    /// # Errors
+    /// Returns [`Error::JniCall`] with [`JniError::InvalidArguments`] if there
+    /// are more than [`jsize::MAX`] elements.
+    pub fn from_iter<'other_local, E>(
+        env: &mut JNIEnv<'local>,
+        iter: impl IntoIterator<Item = E>,
+    ) -> Result<Self>
+    where
+        E: AsRef<T::Kind<'other_local>>,
+    {
+        let elements: Vec<E> = iter.into_iter().collect();
+        let length =
+            jsize::try_from(elements.len()).map_err(|_| JniCall(JniError::InvalidArguments))?;
+        let class = T::lookup_class(env)?;
+        let array: Self = env
+            .new_object_array(length, &class, JObject::null())?
+            .cast();
+        env.delete_local_ref(class);
+        for (index, element) in (0..length).zip(&elements) {
+            array.set_element(env, index, element)?;
+        }
+        Ok(array)
+    }
+
+    /// Returns a local reference to the element at `index`.
+    ///
+    /// See [`JNIEnv::get_object_array_element`].
+    pub fn get_element<'env_local>(
+        &self,
+        env: &mut JNIEnv<'env_local>,
+        index: jsize,
+    ) -> Result<T::Kind<'env_local>> {
+        let element = env.get_object_array_element(self, index)?;
+        // Safety: `element` is a new local reference, which is either null or an element of this
+        // array, so it's an instance of `T`.
+        Ok(unsafe { T::kind_from_raw(element.into_raw()) })
+    }
+
+    /// Sets the element at `index`.
+    ///
+    /// See [`JNIEnv::set_object_array_element`].
+    pub fn set_element<'other_local>(
+        &self,
+        env: &JNIEnv,
+        index: jsize,
+        value: impl AsRef<T::Kind<'other_local>>,
+    ) -> Result<()> {
+        let value: &JObject = value.as_ref().as_ref();
+        env.set_object_array_element(self, index, value)
+    }
+
+    /// Returns an iterator over the elements of this array.
+    ///
+    /// The length of the array is read once, when the iterator is created.
+    /// See [`JObjectArrayIter`] for how to use it.
+    pub fn iter<'array>(&'array self, env: &JNIEnv) -> Result<JObjectArrayIter<'array, 'local, T>> {
+        Ok(JObjectArrayIter {
+            array: self,
+            index: 0,
+            length: env.get_array_length(self)?,
+        })
+    }
+
+    /// Calls `f` for each element, and collects the results into a [`Vec`],
+    /// stopping at the first error.
+    ///
+    /// The local reference for each element is deleted after `f` returns, so
+    /// this doesn't leak a local reference per element. `f` should use
+    /// [`JNIEnv::new_local_ref`] or [`JNIEnv::new_global_ref`] if it needs to
+    /// keep a reference to the element.
+    pub fn try_map_collect<'env_local, F, U, E>(
+        &self,
+        env: &mut JNIEnv<'env_local>,
+        mut f: F,
+    ) -> std::result::Result<Vec<U>, E>
+    where
+        F: FnMut(&mut JNIEnv<'env_local>, &T::Kind<'env_local>) -> std::result::Result<U, E>,
+        E: From<Error>,
+    {
+        let mut iter = self.iter(env)?;
+        let mut results = Vec::with_capacity(iter.len());
+        while let Some(element) = iter.next(env)? {
+            results.push(f(env, &element)?);
+        }
+        Ok(results)
+    }
+
+    /// Returns local references to all the elements of this array, in a
+    /// [`Vec`].
+    ///
+    /// Each element is a new local reference, so this should only be used
+    /// for arrays that are small enough to not exhaust the local reference
+    /// capacity. Use [`JObjectArray::iter`] or [`JObjectArray::try_map_collect`]
+    /// to visit the elements of larger arrays.
+    pub fn to_vec<'env_local>(
+        &self,
+        env: &mut JNIEnv<'env_local>,
+    ) -> Result<Vec<T::Kind<'env_local>>> {
+        let length = env.get_array_length(self)?;
+        (0..length)
+            .map(|index| self.get_element(env, index))
+            .collect()
+    }
+}
+
+impl<'local, 'any> JObjectArray<'local, JString<'any>> {
+    /// Converts each element of this `String[]` array into a Rust [`String`],
+    /// with [`JString::to_rust_string`].
+    ///
+    /// # Errors
+    /// Returns [`Error::NullPtr`] if any element is `null`.
+    pub fn to_string_vec(&self, env: &mut JNIEnv) -> Result<Vec<String>> {
+        self.try_map_collect(env, |env, string| string.to_rust_string(env))
+    }
+}
+
+/// An iterator over the elements of a [`JObjectArray`], created with
+/// [`JObjectArray::iter`].
+///
+/// Like [`JIterator`](super::JIterator), this doesn't implement
+/// [`std::iter::Iterator`], since its `next` method needs a `&mut JNIEnv` to
+/// get each element. Use a `while let` loop instead:
+///
+/// ```rust,no_run
+/// # use jni::{errors::Result, JNIEnv, objects::{JObjectArray, JString}};
+/// #
+/// # fn example(env: &mut JNIEnv, array: &JObjectArray<JString>) -> Result<()> {
+/// let mut iter = array.iter(env)?;
+///
+/// while let Some(string) = iter.next(env)? {
+///     // `string` is an `AutoLocal<JString>`, which deletes its local
+///     // reference at the end of each iteration.
+///     let string = string.to_rust_string(env)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JObjectArrayIter<'array, 'local, T: Reference> {
+    array: &'array JObjectArray<'local, T>,
+    index: jsize,
+    length: jsize,
+}
+
+impl<'array, 'local, T: Reference> JObjectArrayIter<'array, 'local, T> {
+    /// Returns the next element, or `None` if there are no more elements.
+    ///
+    /// The element is wrapped in an [`AutoLocal`], so its local reference is
+    /// deleted when it's dropped.
+    ///
+    /// This is like [`std::iter::Iterator::next`], but requires a parameter of
+    /// type `&mut JNIEnv` in order to get the element.
+    pub fn next<'env_local>(
+        &mut self,
+        env: &mut JNIEnv<'env_local>,
+    ) -> Result<Option<AutoLocal<'env_local, T::Kind<'env_local>>>> {
+        if self.index >= self.length {
+            return Ok(None);
+        }
+        let element = self.array.get_element(env, self.index)?;
+        self.index += 1;
+        Ok(Some(AutoLocal::new(element, env)))
+    }
+
+    /// Returns the number of remaining elements.
+    pub fn len(&self) -> usize {
+        (self.length - self.index) as usize
+    }
+
+    /// Returns `true` if there are no remaining elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Safety: `JObjectArray` is `repr(transparent)` around `JObject`.
+unsafe impl<'any, T: Reference + 'static> Reference for JObjectArray<'any, T> {
+    type Kind<'local> = JObjectArray<'local, T>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JObjectArray::from_raw(raw as _)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        // There's no JNI function that looks up an array class from its element class, but it's
+        // the class of any array with that element type.
+        let element_class = T::lookup_class(env)?;
+        let array = env.new_object_array(0, &element_class, JObject::null())?;
+        let class = env.get_object_class(&array)?;
+        env.delete_local_ref(array);
+        env.delete_local_ref(element_class);
+        Ok(class)
     }
 }
@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, JValue, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.util.Optional` Java class. Just a `JObject` wrapped in a new class.
+///
+/// The value type `T` defaults to [`JObject`], and an optional with some other
+/// [`Reference`] type can be created with [`JOptional::typed`]. Since Java
+/// generics are erased, no runtime check is made that the value is actually
+/// of type `T`.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JOptional<'local, T: Reference = JObject<'static>> {
+    internal: JObject<'local>,
+    _phantom_value: PhantomData<T>,
+}
+
+impl<'local, T: Reference> AsRef<JOptional<'local, T>> for JOptional<'local, T> {
+    fn as_ref(&self) -> &JOptional<'local, T> {
+        self
+    }
+}
+
+impl<'local, T: Reference> AsRef<JObject<'local>> for JOptional<'local, T> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local, T: Reference> ::std::ops::Deref for JOptional<'local, T> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.internal
+    }
+}
+
+impl<'local, T: Reference> From<JOptional<'local, T>> for JObject<'local> {
+    fn from(other: JOptional<'local, T>) -> JObject<'local> {
+        other.internal
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an optional.
+impl<'local, T: Reference> From<JObject<'local>> for JOptional<'local, T> {
+    fn from(other: JObject<'local>) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an optional.
+impl<'local, 'obj_ref, T: Reference> From<&'obj_ref JObject<'local>>
+    for &'obj_ref JOptional<'local, T>
+{
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JOptional` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JOptional<'local, T>) }
+    }
+}
+
+impl<'local, T: Reference> std::default::Default for JOptional<'local, T> {
+    fn default() -> Self {
+        Self {
+            internal: JObject::null(),
+            _phantom_value: PhantomData,
+        }
+    }
+}
+
+impl<'local, T: Reference> JOptional<'local, T> {
+    /// Creates a [`JOptional`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.util.Optional`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self {
+            internal: JObject::from_raw(raw),
+            _phantom_value: PhantomData,
+        }
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.internal.into_raw()
+    }
+
+    /// Converts this optional into an optional with a value of type `U`.
+    ///
+    /// This is an unchecked cast (in the same way as converting a [`JObject`]
+    /// into a [`JString`](crate::objects::JString)), since Java generics are
+    /// erased and the value can't be checked ahead of time.
+    pub fn typed<U: Reference>(self) -> JOptional<'local, U> {
+        JOptional {
+            internal: self.internal,
+            _phantom_value: PhantomData,
+        }
+    }
+
+    /// Creates a new `Optional` from a Rust [`Option`], via
+    /// `Optional.ofNullable()`.
+    ///
+    /// A `Some` value that is a `null` reference also results in an empty
+    /// `Optional`.
+    pub fn from_option(
+        env: &mut JNIEnv<'local>,
+        value: Option<&T::Kind<'_>>,
+    ) -> Result<JOptional<'local, T>> {
+        let null = JObject::null();
+        let value = value.map_or(&null, |value| value.as_ref());
+        let optional = env
+            .call_static_method(
+                "java/util/Optional",
+                "ofNullable",
+                "(Ljava/lang/Object;)Ljava/util/Optional;",
+                &[JValue::from(value)],
+            )?
+            .l()?;
+        Ok(JOptional::from(optional))
+    }
+
+    /// Returns `true` if this optional contains a value (`Optional.isPresent()`).
+    pub fn is_present(&self, env: &mut JNIEnv) -> Result<bool> {
+        env.call_method(self, "isPresent", "()Z", &[])?.z()
+    }
+
+    /// Returns the value of this optional (`Optional.get()`).
+    ///
+    /// If the optional is empty, Java throws a `NoSuchElementException` and
+    /// this returns [`Error::JavaException`](crate::errors::Error::JavaException).
+    ///
+    /// This method creates a new local reference for the value.
+    pub fn get<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<T::Kind<'other_local>> {
+        let value = env
+            .call_method(self, "get", "()Ljava/lang/Object;", &[])?
+            .l()?;
+        // Safety: the caller asserted the optional only holds a `T` value
+        Ok(unsafe { T::kind_from_raw(value.into_raw()) })
+    }
+
+    /// Returns the value of this optional, or a `null` reference if the
+    /// optional is empty (`Optional.orElse(null)`).
+    ///
+    /// This method creates a new local reference for the value.
+    pub fn or_null<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<T::Kind<'other_local>> {
+        let null = JObject::null();
+        let value = env
+            .call_method(
+                self,
+                "orElse",
+                "(Ljava/lang/Object;)Ljava/lang/Object;",
+                &[JValue::from(&null)],
+            )?
+            .l()?;
+        // Safety: the caller asserted the optional only holds a `T` value
+        Ok(unsafe { T::kind_from_raw(value.into_raw()) })
+    }
+
+    /// Converts this optional into a Rust [`Option`], which is `None` if the
+    /// optional is empty.
+    ///
+    /// This method creates a new local reference for the value.
+    pub fn to_option<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<Option<T::Kind<'other_local>>> {
+        let value = self.or_null(env)?;
+        if value.as_ref().is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(value))
+        }
+    }
+}
+
+// Safety: `JOptional` is `repr(transparent)` around `JObject`.
+unsafe impl<'any, T: Reference + 'static> Reference for JOptional<'any, T> {
+    type Kind<'local> = JOptional<'local, T>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JOptional::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/util/Optional".into())
+    }
+}
@@ -0,0 +1,216 @@
+use std::io;
+
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{AutoLocal, JByteArray, JClass, JMethodID, JObject, JValue, Reference},
+    signature::{Primitive, ReturnType},
+    sys::{jbyte, jobject, jsize},
+    JNIEnv,
+};
+
+use super::jinput_stream::{to_io_error, STREAM_BUFFER_SIZE};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.io.OutputStream` Java class. Just a `JObject` wrapped in a new class.
+///
+/// Use [`JOutputStream::writer`] to write to the stream with
+/// [`std::io::Write`].
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JOutputStream<'local>(JObject<'local>);
+
+impl<'local> AsRef<JOutputStream<'local>> for JOutputStream<'local> {
+    fn as_ref(&self) -> &JOutputStream<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JOutputStream<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JOutputStream<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JOutputStream<'local>> for JObject<'local> {
+    fn from(other: JOutputStream) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an output stream.
+impl<'local> From<JObject<'local>> for JOutputStream<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to an output stream.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JOutputStream<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JOutputStream` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JOutputStream<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JOutputStream<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JOutputStream<'local> {
+    /// Creates a [`JOutputStream`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.io.OutputStream`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Returns a [`std::io::Write`] implementation that writes to this stream.
+    ///
+    /// Data is copied to Java through a single `byte[]` buffer that's
+    /// allocated once by this method and reused for every write.
+    ///
+    /// If the Java stream throws an exception, the exception is cleared and
+    /// returned as an [`io::Error`] whose message is the exception's
+    /// `toString()`.
+    ///
+    /// The stream is neither flushed nor closed when the writer is dropped.
+    /// Use [`std::io::Write::flush`] and [`JOutputStream::close`] to do so.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use jni::{errors::Result, JNIEnv, objects::JOutputStream};
+    /// # use std::io::Write;
+    /// #
+    /// # fn example(env: &mut JNIEnv, stream: &JOutputStream) -> Result<()> {
+    /// let mut writer = stream.writer(env)?;
+    /// writer
+    ///     .write_all(b"Hello from Rust")
+    ///     .and_then(|_| writer.flush())
+    ///     .expect("failed to write stream");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn writer<'writer, 'other_local>(
+        &'writer self,
+        env: &'writer mut JNIEnv<'other_local>,
+    ) -> Result<JOutputStreamWriter<'writer, 'local, 'other_local>> {
+        let class = AutoLocal::new(env.find_class("java/io/OutputStream")?, env);
+        let write = env.get_method_id(&class, "write", "([BII)V")?;
+        let flush = env.get_method_id(&class, "flush", "()V")?;
+        drop(class);
+
+        let buffer = env.new_byte_array(STREAM_BUFFER_SIZE)?;
+        let buffer = env.auto_local(buffer);
+
+        Ok(JOutputStreamWriter {
+            stream: self,
+            env,
+            buffer,
+            write,
+            flush,
+        })
+    }
+
+    /// Closes the stream (`OutputStream.close()`).
+    pub fn close(&self, env: &mut JNIEnv) -> Result<()> {
+        env.call_method(self, "close", "()V", &[])?;
+        Ok(())
+    }
+}
+
+/// A [`std::io::Write`] implementation for a [`JOutputStream`]. This gets
+/// returned from [`JOutputStream::writer`].
+pub struct JOutputStreamWriter<'writer, 'local, 'other_local> {
+    stream: &'writer JOutputStream<'local>,
+    env: &'writer mut JNIEnv<'other_local>,
+    buffer: AutoLocal<'other_local, JByteArray<'other_local>>,
+    write: JMethodID,
+    flush: JMethodID,
+}
+
+impl<'writer, 'local, 'other_local> io::Write
+    for JOutputStreamWriter<'writer, 'local, 'other_local>
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(STREAM_BUFFER_SIZE as usize);
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let written = (|| -> Result<usize> {
+            // Safety: `u8` and `jbyte` (`i8`) have the same size and alignment
+            let src = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const jbyte, len) };
+            self.env.set_byte_array_region(&*self.buffer, 0, src)?;
+
+            // Safety: the method ID is for `OutputStream.write(byte[], int, int)`, and the
+            // arguments match that signature.
+            unsafe {
+                self.env.call_method_unchecked(
+                    self.stream,
+                    self.write,
+                    ReturnType::Primitive(Primitive::Void),
+                    &[
+                        JValue::from(&*self.buffer).as_jni(),
+                        JValue::from(0).as_jni(),
+                        JValue::from(len as jsize).as_jni(),
+                    ],
+                )
+            }?;
+            Ok(len)
+        })();
+
+        written.map_err(|err| to_io_error(self.env, err))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Safety: the method ID is for `OutputStream.flush()`, which takes no arguments.
+        let flushed = unsafe {
+            self.env.call_method_unchecked(
+                self.stream,
+                self.flush,
+                ReturnType::Primitive(Primitive::Void),
+                &[],
+            )
+        };
+
+        flushed
+            .map(|_| ())
+            .map_err(|err| to_io_error(self.env, err))
+    }
+}
+
+// Safety: `JOutputStream` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JOutputStream<'any> {
+    type Kind<'local> = JOutputStream<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JOutputStream::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/io/OutputStream".into())
+    }
+}
@@ -1,14 +1,16 @@
-use std::marker::PhantomData;
+use std::{convert::TryFrom, marker::PhantomData};
 
 use crate::{
+    errors::{Error::JniCall, JniError, Result},
     objects::JObject,
-    sys::{jarray, jobject},
+    sys::{jarray, jobject, jsize},
+    JNIEnv,
 };
 
 use super::TypeArray;
 
 #[cfg(doc)]
-use crate::JNIEnv;
+use crate::{errors::Error, objects::AutoElements};
 
 /// Lifetime'd representation of a [`jarray`] which wraps a [`JObject`] reference
 ///
@@ -101,6 +103,72 @@ impl<'local, T: TypeArray> JPrimitiveArray<'local, T> {
     pub const fn into_raw(self) -> jarray {
         self.obj.into_raw() as jarray
     }
+
+    /// Creates a new Java array with a copy of the elements of `buf`.
+    ///
+    /// This is a generic version of [`JNIEnv::byte_array_from_slice`], for
+    /// every primitive element type.
+    ///
+    /// # Errors
+    /// Returns [`Error::JniCall`] with [`JniError::InvalidArguments`] if `buf`
+    /// has more than [`jsize::MAX`] elements.
+    pub fn from_slice(env: &JNIEnv<'local>, buf: &[T]) -> Result<Self> {
+        let length = jsize::try_from(buf.len()).map_err(|_| JniCall(JniError::InvalidArguments))?;
+        // Safety: `new_array` returns a new local reference to an array of `T`
+        let array = unsafe { Self::from_raw(T::new_array(env, length)?) };
+        array.set_region(env, 0, buf)?;
+        Ok(array)
+    }
+
+    /// Copies all the elements of this array into a new [`Vec`].
+    ///
+    /// This is a generic version of [`JNIEnv::convert_byte_array`], for
+    /// every primitive element type.
+    pub fn to_vec(&self, env: &JNIEnv) -> Result<Vec<T>> {
+        let length = env.get_array_length(self)?;
+        let mut vec = vec![T::default(); length as usize];
+        self.get_region(env, 0, &mut vec)?;
+        Ok(vec)
+    }
+
+    /// Copies elements of this array, from the `start` index, into the `buf`
+    /// slice. The number of copied elements is equal to the `buf` length.
+    ///
+    /// Unlike [`AutoElements`], this only copies the requested window of the
+    /// array, instead of pinning or copying the whole array.
+    ///
+    /// This is a generic version of methods like
+    /// [`JNIEnv::get_byte_array_region`].
+    ///
+    /// # Errors
+    /// If `start` is negative _or_ `start + buf.len()` is greater than
+    /// [`array.length`] then no elements are copied, an
+    /// `ArrayIndexOutOfBoundsException` is thrown, and `Err` is returned.
+    ///
+    /// [`array.length`]: JNIEnv::get_array_length
+    pub fn get_region(&self, env: &JNIEnv, start: jsize, buf: &mut [T]) -> Result<()> {
+        let array = null_check!(self, "get_region array argument")?;
+        // Safety: the array is non-null, and its element type is `T`
+        unsafe { T::get_region(env, array.as_raw() as jarray, start, buf) }
+    }
+
+    /// Copies the contents of the `buf` slice into this array, at the `start`
+    /// index.
+    ///
+    /// This is a generic version of methods like
+    /// [`JNIEnv::set_byte_array_region`].
+    ///
+    /// # Errors
+    /// If `start` is negative _or_ `start + buf.len()` is greater than
+    /// [`array.length`] then no elements are copied, an
+    /// `ArrayIndexOutOfBoundsException` is thrown, and `Err` is returned.
+    ///
+    /// [`array.length`]: JNIEnv::get_array_length
+    pub fn set_region(&self, env: &JNIEnv, start: jsize, buf: &[T]) -> Result<()> {
+        let array = null_check!(self, "set_region array argument")?;
+        // Safety: the array is non-null, and its element type is `T`
+        unsafe { T::set_region(env, array.as_raw() as jarray, start, buf) }
+    }
 }
 
 /// Lifetime'd representation of a [`crate::sys::jbooleanArray`] which wraps a [`JObject`] reference
@@ -0,0 +1,215 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use super::bundled_class::BundledClass;
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, JValue, Reference},
+    sys::{jlong, jobject},
+    JNIEnv, NativeMethod, DEFAULT_LOCAL_FRAME_CAPACITY,
+};
+
+/// The bundled class that runs Rust closures, compiled from `RustRunnable.java`.
+pub(super) static RUST_RUNNABLE: BundledClass = BundledClass::new(
+    "io/github/jni_rs/RustRunnable",
+    include_bytes!("../../java/io/github/jni_rs/RustRunnable.class"),
+    |env, class| {
+        env.register_native_methods(
+            class,
+            &[NativeMethod {
+                name: "run".into(),
+                sig: "(J)V".into(),
+                fn_ptr: run_closure as *mut _,
+            }],
+        )
+    },
+);
+
+type Closure = Box<dyn FnOnce(&mut JNIEnv) -> Result<()> + Send>;
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.lang.Runnable` Java interface. Just a `JObject` wrapped in a new
+/// class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JRunnable<'local>(JObject<'local>);
+
+impl<'local> AsRef<JRunnable<'local>> for JRunnable<'local> {
+    fn as_ref(&self) -> &JRunnable<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JRunnable<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JRunnable<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JRunnable<'local>> for JObject<'local> {
+    fn from(other: JRunnable) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a runnable.
+impl<'local> From<JObject<'local>> for JRunnable<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a runnable.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JRunnable<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JRunnable` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JRunnable<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JRunnable<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JRunnable<'local> {
+    /// Creates a [`JRunnable`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.lang.Runnable`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a `Runnable` that runs a Rust closure.
+    ///
+    /// When the `Runnable` is run, on whichever thread that is, the closure
+    /// is called in a local frame with [`DEFAULT_LOCAL_FRAME_CAPACITY`]. If
+    /// the closure returns an error, and hasn't thrown an exception, a
+    /// `java.lang.RuntimeException` is thrown with the error's description.
+    /// If the closure panics, a `java.lang.RuntimeException` is thrown, since
+    /// a panic can't unwind into Java.
+    ///
+    /// The closure is only run the first time that the `Runnable` is run.
+    /// If the `Runnable` is never run, the closure is leaked.
+    ///
+    /// The `Runnable` is an instance of a small helper class,
+    /// `io.github.jni_rs.RustRunnable`. If [`JNIEnv::find_class`] can't find
+    /// it, the copy that's bundled with jni-rs is defined in the system class
+    /// loader the first time that this is called.
+    ///
+    /// On Android, where classes can't be defined from bytecode, the
+    /// application has to include `RustRunnable.java` (from the `src/java`
+    /// directory of the jni-rs crate), and keep it from being removed or
+    /// renamed by R8 or ProGuard. This also has to be called first on a
+    /// thread where `find_class` can find the application's classes, such as
+    /// in `JNI_OnLoad` or a native method, unless a
+    /// [default class loader](crate::JavaVM::set_default_class_loader) is set.
+    pub fn from_fn<F>(env: &mut JNIEnv<'local>, f: F) -> Result<JRunnable<'local>>
+    where
+        F: FnOnce(&mut JNIEnv) -> Result<()> + Send + 'static,
+    {
+        let class = RUST_RUNNABLE.lookup(env)?;
+        let closure: Box<Closure> = Box::new(Box::new(f));
+        let closure = Box::into_raw(closure);
+        let runnable = env.new_object(&class, "(J)V", &[JValue::Long(closure as jlong)]);
+        env.delete_local_ref(class);
+        match runnable {
+            Ok(runnable) => Ok(JRunnable::from(runnable)),
+            Err(err) => {
+                // Safety: the closure wasn't given to a `RustRunnable`
+                drop(unsafe { Box::from_raw(closure) });
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs the runnable on the current thread (`Runnable.run()`).
+    pub fn run(&self, env: &mut JNIEnv) -> Result<()> {
+        env.call_method(self, "run", "()V", &[])?;
+        Ok(())
+    }
+
+    /// Drops the Rust closure of a runnable that was created by
+    /// [`JRunnable::from_fn`], if it hasn't been run yet, so that it isn't
+    /// run or leaked.
+    ///
+    /// This is for a runnable that won't be run, such as one that an executor
+    /// rejected. Any pending exception is preserved. This does nothing for
+    /// other runnables.
+    pub fn cancel(&self, env: &mut JNIEnv) -> Result<()> {
+        let exception = env.exception_occurred();
+        if exception.is_some() {
+            env.exception_clear();
+        }
+        let class = RUST_RUNNABLE.lookup(env)?;
+        let cancelled = (|| -> Result<()> {
+            if env.is_instance_of(self, &class)? {
+                let closure = env.call_method(self, "take", "()J", &[])?.j()?;
+                if closure != 0 {
+                    // Safety: `take` gives up the runnable's ownership of the
+                    // closure
+                    drop(unsafe { Box::from_raw(closure as *mut Closure) });
+                }
+            }
+            Ok(())
+        })();
+        env.delete_local_ref(class);
+        if let Some(exception) = exception {
+            env.throw(exception)?;
+        }
+        cancelled
+    }
+}
+
+/// The implementation of `RustRunnable.run(long)`.
+extern "system" fn run_closure(mut env: JNIEnv, _class: JClass, closure: jlong) {
+    // Safety: `RustRunnable.take` gives up the runnable's ownership of the
+    // closure, and it's only passed here once
+    let closure = unsafe { Box::from_raw(closure as *mut Closure) };
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        env.with_local_frame(DEFAULT_LOCAL_FRAME_CAPACITY, |env| closure(env))
+    }));
+    if env.exception_check() {
+        return;
+    }
+    let message = match res {
+        Ok(Ok(())) => return,
+        Ok(Err(err)) => err.to_string(),
+        Err(_) => String::from("Rust closure panicked"),
+    };
+    let _ = env.throw_new("java/lang/RuntimeException", message);
+}
+
+// Safety: `JRunnable` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JRunnable<'any> {
+    type Kind<'local> = JRunnable<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JRunnable::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Runnable".into())
+    }
+}
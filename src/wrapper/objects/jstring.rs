@@ -1,6 +1,15 @@
+use std::{borrow::Cow, os::raw::c_char};
+
+use cesu8::from_java_cesu8;
+use log::debug;
+
 use crate::{
-    objects::JObject,
-    sys::{jobject, jstring},
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, Reference},
+    strings::JavaStr16,
+    sys::{jchar, jobject, jsize, jstring},
+    JNIEnv,
 };
 
 /// Lifetime'd representation of a `jstring`. Just a `JObject` wrapped in a new
@@ -72,4 +81,127 @@ impl<'local> JString<'local> {
     pub const fn into_raw(self) -> jstring {
         self.0.into_raw() as jstring
     }
+
+    /// Creates a new Java string from UTF-16 code units.
+    ///
+    /// See [`JNIEnv::new_string_utf16`].
+    pub fn from_utf16(env: &JNIEnv<'local>, chars: impl AsRef<[u16]>) -> Result<Self> {
+        env.new_string_utf16(chars)
+    }
+
+    /// Gets the UTF-16 code units of this string, without converting them to
+    /// UTF-8.
+    ///
+    /// See [`JNIEnv::get_string_utf16`].
+    pub fn chars_utf16<'env_local, 'obj_ref>(
+        &'obj_ref self,
+        env: &mut JNIEnv<'env_local>,
+    ) -> Result<JavaStr16<'env_local, 'local, 'obj_ref>> {
+        env.get_string_utf16(self)
+    }
+
+    /// Returns the length of this string, in UTF-16 code units.
+    ///
+    /// See [`JNIEnv::get_string_length`].
+    pub fn length(&self, env: &JNIEnv) -> Result<jsize> {
+        env.get_string_length(self)
+    }
+
+    /// Copies the UTF-16 code units of this string from the `start` index to
+    /// the `buf` slice, without copying or converting the rest of the string.
+    ///
+    /// See [`JNIEnv::get_string_region`].
+    pub fn get_region(&self, env: &JNIEnv, start: jsize, buf: &mut [jchar]) -> Result<()> {
+        env.get_string_region(self, start, buf)
+    }
+
+    /// Converts this string into a Rust [`String`].
+    ///
+    /// This gives the same result as converting the [`JavaStr`] from
+    /// [`JNIEnv::get_string`] into a `String`, but it's faster, especially for
+    /// strings that only contain ASCII characters:
+    ///
+    /// * The string's [modified UTF-8] bytes are copied directly into the
+    ///   returned `String`'s buffer (with `GetStringUTFRegion`), instead of
+    ///   into a temporary buffer that's allocated by the JVM.
+    /// * If the bytes are all ASCII, which is checked a word at a time, they
+    ///   are already valid UTF-8 and are used without any decoding.
+    /// * Like [`JNIEnv::get_string_unchecked`], no check is made that this
+    ///   object is actually a `java.lang.String`.
+    ///
+    /// Strings that contain code points that are encoded differently in
+    /// modified UTF-8 and UTF-8 are decoded in the same way as by
+    /// [`JNIStr::to_str`].
+    ///
+    /// [`JavaStr`]: crate::strings::JavaStr
+    /// [`JNIStr::to_str`]: crate::strings::JNIStr::to_str
+    /// [modified UTF-8]: https://en.wikipedia.org/wiki/UTF-8#Modified_UTF-8
+    pub fn to_rust_string(&self, env: &JNIEnv) -> Result<String> {
+        let len = env.get_string_length(self)?;
+        let utf_len = env.get_string_utf_length(self)? as usize;
+        // Leave room for the null terminator that JVMs write, even though the JNI spec doesn't
+        // mention it
+        let mut bytes = Vec::<u8>::with_capacity(utf_len + 1);
+        // Safety: `self` is non-null (it was checked by `get_string_length`), the buffer has room
+        // for the `utf_len` bytes of the whole string and a null terminator, and only the bytes
+        // that were written are included in the length.
+        unsafe {
+            jni_call_check_ex!(
+                env,
+                v1_2,
+                GetStringUTFRegion,
+                self.as_raw(),
+                0,
+                len,
+                bytes.as_mut_ptr() as *mut c_char
+            )?;
+            bytes.set_len(utf_len);
+        }
+
+        if bytes.is_ascii() {
+            // Safety: ASCII is valid UTF-8
+            return Ok(unsafe { String::from_utf8_unchecked(bytes) });
+        }
+
+        match from_java_cesu8(&bytes) {
+            // The bytes are already valid UTF-8, so they can be used as they are
+            Ok(Cow::Borrowed(_)) => {
+                // Safety: `from_java_cesu8` only borrows its input if it's valid UTF-8
+                Ok(unsafe { String::from_utf8_unchecked(bytes) })
+            }
+            Ok(Cow::Owned(string)) => Ok(string),
+            Err(e) => {
+                debug!("error decoding java cesu8: {:#?}", e);
+                Ok(String::from_utf8_lossy(&bytes).into_owned())
+            }
+        }
+    }
+
+    /// Copies `len` UTF-16 code units of this string, from the `start` index,
+    /// into the `buf` slice in modified UTF-8 encoding, and returns the number
+    /// of bytes that were written.
+    ///
+    /// See [`JNIEnv::get_string_utf_region`].
+    pub fn get_utf_region(
+        &self,
+        env: &JNIEnv,
+        start: jsize,
+        len: jsize,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        env.get_string_utf_region(self, start, len, buf)
+    }
+}
+
+// Safety: `JString` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JString<'any> {
+    type Kind<'local> = JString<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JString::from_raw(raw as _)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/String".into())
+    }
 }
@@ -0,0 +1,235 @@
+use std::sync::OnceLock;
+
+use crate::{
+    convert::lookup_cached,
+    descriptors::class_cache,
+    errors::Result,
+    objects::{GlobalRef, JClass, JMethodID, JObject, JString, JValue, Reference},
+    signature::ReturnType,
+    sys::{jint, jobject},
+    JNIEnv,
+};
+
+/// The class and method IDs used by [`JStringBuilder`].
+struct StringBuilderApi {
+    class: GlobalRef,
+    new: JMethodID,
+    new_with_capacity: JMethodID,
+    append_string: JMethodID,
+    append_int: JMethodID,
+    to_string: JMethodID,
+}
+
+static STRING_BUILDER_API: OnceLock<StringBuilderApi> = OnceLock::new();
+
+impl StringBuilderApi {
+    fn get(env: &mut JNIEnv) -> Result<&'static StringBuilderApi> {
+        lookup_cached(
+            &STRING_BUILDER_API,
+            env,
+            "java/lang/StringBuilder",
+            |env, class| {
+                Ok(StringBuilderApi {
+                    new: env.get_method_id(class, "<init>", "()V")?,
+                    new_with_capacity: env.get_method_id(class, "<init>", "(I)V")?,
+                    append_string: env.get_method_id(
+                        class,
+                        "append",
+                        "(Ljava/lang/String;)Ljava/lang/StringBuilder;",
+                    )?,
+                    append_int: env.get_method_id(
+                        class,
+                        "append",
+                        "(I)Ljava/lang/StringBuilder;",
+                    )?,
+                    to_string: env.get_method_id(class, "toString", "()Ljava/lang/String;")?,
+                    class: env.new_global_ref(class)?,
+                })
+            },
+        )
+    }
+}
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.lang.StringBuilder` Java class. Just a `JObject` wrapped in a new class.
+///
+/// This can be used to assemble a large Java string incrementally, without
+/// first building it as a Rust [`String`] and then converting all of it to
+/// modified UTF-8 in one go.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JStringBuilder<'local>(JObject<'local>);
+
+impl<'local> AsRef<JStringBuilder<'local>> for JStringBuilder<'local> {
+    fn as_ref(&self) -> &JStringBuilder<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JStringBuilder<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JStringBuilder<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JStringBuilder<'local>> for JObject<'local> {
+    fn from(other: JStringBuilder) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a string builder.
+impl<'local> From<JObject<'local>> for JStringBuilder<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a string builder.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JStringBuilder<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JStringBuilder` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JStringBuilder<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JStringBuilder<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JStringBuilder<'local> {
+    /// Creates a [`JStringBuilder`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.lang.StringBuilder`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Creates a new, empty `StringBuilder` (`new StringBuilder()`).
+    pub fn new(env: &mut JNIEnv<'local>) -> Result<JStringBuilder<'local>> {
+        let api = StringBuilderApi::get(env)?;
+        // Safety: the method ID is for `StringBuilder()`, which takes no arguments.
+        let builder = unsafe { env.new_object_unchecked(&api.class, api.new, &[]) }?;
+        Ok(JStringBuilder::from(builder))
+    }
+
+    /// Creates a new, empty `StringBuilder` with the given initial capacity, in
+    /// UTF-16 code units (`new StringBuilder(int)`).
+    pub fn with_capacity(
+        env: &mut JNIEnv<'local>,
+        capacity: jint,
+    ) -> Result<JStringBuilder<'local>> {
+        let api = StringBuilderApi::get(env)?;
+        // Safety: the method ID is for `StringBuilder(int)`, and the argument matches that
+        // signature.
+        let builder = unsafe {
+            env.new_object_unchecked(
+                &api.class,
+                api.new_with_capacity,
+                &[JValue::from(capacity).as_jni()],
+            )
+        }?;
+        Ok(JStringBuilder::from(builder))
+    }
+
+    /// Appends a Rust string (`StringBuilder.append(String)`).
+    ///
+    /// Only `value` is converted to a temporary Java string, so the contents
+    /// of the builder aren't copied.
+    pub fn append_str(&self, env: &mut JNIEnv, value: &str) -> Result<()> {
+        let value = env.new_string(value)?;
+        let result = self.append_jstring(env, &value);
+        env.delete_local_ref(value);
+        result
+    }
+
+    /// Appends a Java string (`StringBuilder.append(String)`).
+    ///
+    /// A null `value` appends `"null"`, as in Java.
+    pub fn append_jstring(&self, env: &mut JNIEnv, value: &JString) -> Result<()> {
+        let api = StringBuilderApi::get(env)?;
+        // Safety: the method ID is for `StringBuilder.append(String)`, and the argument matches
+        // that signature.
+        let this = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.append_string,
+                ReturnType::Object,
+                &[JValue::from(value).as_jni()],
+            )
+        }?
+        .l()?;
+        // `append` returns `this`, which we don't need another reference to
+        env.delete_local_ref(this);
+        Ok(())
+    }
+
+    /// Appends the decimal representation of an `int`
+    /// (`StringBuilder.append(int)`).
+    pub fn append_int(&self, env: &mut JNIEnv, value: jint) -> Result<()> {
+        let api = StringBuilderApi::get(env)?;
+        // Safety: the method ID is for `StringBuilder.append(int)`, and the argument matches that
+        // signature.
+        let this = unsafe {
+            env.call_method_unchecked(
+                self,
+                api.append_int,
+                ReturnType::Object,
+                &[JValue::from(value).as_jni()],
+            )
+        }?
+        .l()?;
+        // `append` returns `this`, which we don't need another reference to
+        env.delete_local_ref(this);
+        Ok(())
+    }
+
+    /// Returns the contents of the builder as a new Java string
+    /// (`StringBuilder.toString()`).
+    pub fn to_jstring<'other_local>(
+        &self,
+        env: &mut JNIEnv<'other_local>,
+    ) -> Result<JString<'other_local>> {
+        let api = StringBuilderApi::get(env)?;
+        // Safety: the method ID is for `StringBuilder.toString()`, which takes no arguments.
+        let string =
+            unsafe { env.call_method_unchecked(self, api.to_string, ReturnType::Object, &[]) }?
+                .l()?;
+        Ok(JString::from(string))
+    }
+}
+
+// Safety: `JStringBuilder` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JStringBuilder<'any> {
+    type Kind<'local> = JStringBuilder<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JStringBuilder::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/StringBuilder".into())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, Reference},
+    sys::jobject,
+    JNIEnv,
+};
+
+/// Lifetime'd representation of a `jobject` that is an instance of the
+/// `java.lang.Thread` Java class. Just a `JObject` wrapped in a new class.
+#[repr(transparent)]
+#[derive(Debug)]
+pub struct JThread<'local>(JObject<'local>);
+
+impl<'local> AsRef<JThread<'local>> for JThread<'local> {
+    fn as_ref(&self) -> &JThread<'local> {
+        self
+    }
+}
+
+impl<'local> AsRef<JObject<'local>> for JThread<'local> {
+    fn as_ref(&self) -> &JObject<'local> {
+        self
+    }
+}
+
+impl<'local> ::std::ops::Deref for JThread<'local> {
+    type Target = JObject<'local>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'local> From<JThread<'local>> for JObject<'local> {
+    fn from(other: JThread) -> JObject {
+        other.0
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a thread.
+impl<'local> From<JObject<'local>> for JThread<'local> {
+    fn from(other: JObject) -> Self {
+        unsafe { Self::from_raw(other.into_raw()) }
+    }
+}
+
+/// This conversion assumes that the `JObject` is a pointer to a thread.
+impl<'local, 'obj_ref> From<&'obj_ref JObject<'local>> for &'obj_ref JThread<'local> {
+    fn from(other: &'obj_ref JObject<'local>) -> Self {
+        // Safety: `JThread` is `repr(transparent)` around `JObject`.
+        unsafe { &*(other as *const JObject<'local> as *const JThread<'local>) }
+    }
+}
+
+impl<'local> std::default::Default for JThread<'local> {
+    fn default() -> Self {
+        Self(JObject::null())
+    }
+}
+
+impl<'local> JThread<'local> {
+    /// Creates a [`JThread`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// `raw` may be a null pointer. If `raw` is not a null pointer, then:
+    ///
+    /// * `raw` must be a valid raw JNI local reference to a `java.lang.Thread`.
+    /// * There must not be any other `JObject` representing the same local reference.
+    /// * The lifetime `'local` must not outlive the local reference frame that the local reference
+    ///   was created in.
+    pub const unsafe fn from_raw(raw: jobject) -> Self {
+        Self(JObject::from_raw(raw))
+    }
+
+    /// Unwrap to the raw jni type.
+    pub const fn into_raw(self) -> jobject {
+        self.0.into_raw()
+    }
+
+    /// Returns the Java thread that is running the calling code
+    /// (`Thread.currentThread()`).
+    ///
+    /// When a native method is called from a virtual thread, this is the
+    /// virtual thread, not the platform thread that it's mounted on.
+    pub fn current(env: &mut JNIEnv<'local>) -> Result<JThread<'local>> {
+        let thread = env
+            .call_static_method(
+                "java/lang/Thread",
+                "currentThread",
+                "()Ljava/lang/Thread;",
+                &[],
+            )?
+            .l()?;
+        Ok(JThread::from(thread))
+    }
+
+    /// Returns whether this is a virtual thread.
+    ///
+    /// See [`JNIEnv::is_virtual_thread`].
+    pub fn is_virtual(&self, env: &JNIEnv) -> Result<bool> {
+        env.is_virtual_thread(self)
+    }
+}
+
+// Safety: `JThread` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JThread<'any> {
+    type Kind<'local> = JThread<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JThread::from_raw(raw)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Thread".into())
+    }
+}
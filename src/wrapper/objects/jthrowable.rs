@@ -1,6 +1,9 @@
 use crate::{
-    objects::JObject,
+    descriptors::class_cache,
+    errors::Result,
+    objects::{JClass, JObject, Reference},
     sys::{jobject, jthrowable},
+    JNIEnv,
 };
 
 /// Lifetime'd representation of a `jthrowable`. Just a `JObject` wrapped in a
@@ -73,3 +76,16 @@ impl<'local> JThrowable<'local> {
         self.0.into_raw() as jthrowable
     }
 }
+
+// Safety: `JThrowable` is `repr(transparent)` around `JObject`.
+unsafe impl<'any> Reference for JThrowable<'any> {
+    type Kind<'local> = JThrowable<'local>;
+
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local> {
+        JThrowable::from_raw(raw as _)
+    }
+
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>> {
+        class_cache::find_class(env, "java/lang/Throwable".into())
+    }
+}
@@ -17,12 +17,27 @@ pub use self::jstaticfieldid::*;
 mod jobject;
 pub use self::jobject::*;
 
+mod reference;
+pub use self::reference::*;
+
 mod jthrowable;
 pub use self::jthrowable::*;
 
+mod jexceptions;
+pub use self::jexceptions::*;
+
 mod jclass;
 pub use self::jclass::*;
 
+mod jclass_loader;
+pub use self::jclass_loader::*;
+
+mod jmodule;
+pub use self::jmodule::*;
+
+mod jthread;
+pub use self::jthread::*;
+
 mod jstring;
 pub use self::jstring::*;
 
@@ -32,6 +47,46 @@ pub use self::jmap::*;
 mod jlist;
 pub use self::jlist::*;
 
+mod jiterator;
+pub use self::jiterator::*;
+
+mod joptional;
+pub use self::joptional::*;
+
+mod jcompletable_future;
+pub use self::jcompletable_future::*;
+
+mod jrunnable;
+pub use self::jrunnable::*;
+
+// Helper classes that are bundled with jni-rs
+mod bundled_class;
+pub(crate) use self::bundled_class::clear_bundled_classes;
+
+mod jexecutor;
+pub use self::jexecutor::*;
+
+mod jinput_stream;
+pub use self::jinput_stream::*;
+
+mod joutput_stream;
+pub use self::joutput_stream::*;
+
+mod jinstant;
+pub use self::jinstant::*;
+
+mod jduration;
+pub use self::jduration::*;
+
+mod jbig_integer;
+pub use self::jbig_integer::*;
+
+mod jbig_decimal;
+pub use self::jbig_decimal::*;
+
+mod jstring_builder;
+pub use self::jstring_builder::*;
+
 mod jbytebuffer;
 pub use self::jbytebuffer::*;
 
@@ -49,6 +104,9 @@ pub use self::auto_local::*;
 mod release_mode;
 pub use self::release_mode::*;
 
+mod reference_type;
+pub use self::reference_type::*;
+
 /// Primitive Array types
 mod jobject_array;
 pub use self::jobject_array::*;
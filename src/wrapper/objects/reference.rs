@@ -0,0 +1,49 @@
+use crate::{
+    errors::Result,
+    objects::{JClass, JObject},
+    sys::jobject,
+    JNIEnv,
+};
+
+#[cfg(feature = "macros")]
+pub use jni_macros::Reference;
+
+/// A Java reference type, such as [`JObject`] or [`JString`](super::JString),
+/// that can be used as the element type of typed wrappers like
+/// [`JList`](super::JList) and [`JMap`](super::JMap).
+///
+/// Since Java generics are erased at runtime, a typed wrapper like
+/// `JList<JString>` is only an assertion made by the Rust code about the
+/// objects that are stored in the Java collection. No runtime check is made
+/// that the elements are actually instances of the given type, in the same
+/// way that no check is made when converting a [`JObject`] into a
+/// [`JString`](super::JString) with `From`.
+///
+/// With the `macros` feature, this can be derived for a newtype wrapper
+/// around a [`JObject`], with `#[derive(Reference)]`. See the documentation
+/// of the `Reference` derive macro for details.
+///
+/// # Safety
+///
+/// `Kind<'local>` must either be [`JObject<'local>`] itself or a
+/// `#[repr(transparent)]` wrapper around it, and [`Self::kind_from_raw`] must have the same
+/// semantics as [`JObject::from_raw`].
+pub unsafe trait Reference {
+    /// The same reference type, with the given local reference frame lifetime.
+    type Kind<'local>: AsRef<JObject<'local>> + Into<JObject<'local>> + 'local;
+
+    /// Creates a [`Self::Kind`] that wraps the given `raw` [`jobject`]
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`JObject::from_raw`] apply, and `raw`
+    /// must either be `null` or refer to an instance of this reference type.
+    unsafe fn kind_from_raw<'local>(raw: jobject) -> Self::Kind<'local>;
+
+    /// Looks up the Java class of this reference type, such as
+    /// `java.lang.String` for [`JString`](super::JString).
+    ///
+    /// This is used to create arrays of this type, with
+    /// [`JObjectArray::new`](super::JObjectArray::new).
+    fn lookup_class<'local>(env: &mut JNIEnv<'local>) -> Result<JClass<'local>>;
+}
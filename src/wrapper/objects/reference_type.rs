@@ -0,0 +1,60 @@
+use crate::{objects::JObject, sys, JNIVersion, JavaVM};
+
+#[cfg(doc)]
+use crate::JNIEnv;
+
+/// The kind of a JNI object reference, as returned by
+/// [`JNIEnv::get_object_ref_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReferenceType {
+    /// The reference is null, or is not a valid reference.
+    Invalid,
+    /// A local reference, belonging to a JNI stack frame.
+    Local,
+    /// A global reference.
+    Global,
+    /// A weak global reference.
+    WeakGlobal,
+}
+
+impl From<sys::jobjectRefType> for ReferenceType {
+    fn from(ty: sys::jobjectRefType) -> Self {
+        match ty {
+            sys::jobjectRefType::JNILocalRefType => ReferenceType::Local,
+            sys::jobjectRefType::JNIGlobalRefType => ReferenceType::Global,
+            sys::jobjectRefType::JNIWeakGlobalRefType => ReferenceType::WeakGlobal,
+            _ => ReferenceType::Invalid,
+        }
+    }
+}
+
+/// Checks (in debug builds) that `raw` is null or a reference of the
+/// `expected` kind, to catch raw references being wrapped by the wrong type.
+///
+/// The check is skipped if the current thread isn't attached, or if the JVM
+/// doesn't support JNI 1.6.
+///
+/// # Safety
+///
+/// `raw` must be null or a valid reference.
+pub(crate) unsafe fn debug_assert_ref_type(
+    vm: &JavaVM,
+    raw: sys::jobject,
+    expected: ReferenceType,
+) {
+    if !cfg!(debug_assertions) || raw.is_null() {
+        return;
+    }
+    let env = match unsafe { vm.get_env(JNIVersion::V1_6) } {
+        Ok(env) => env,
+        Err(_) => return,
+    };
+    let obj = unsafe { JObject::from_raw(raw) };
+    if let Ok(actual) = env.get_object_ref_type(&obj) {
+        assert_eq!(
+            actual, expected,
+            "expected a {:?} reference, but {:p} is a {:?} reference",
+            expected, raw, actual
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
-use log::{debug, warn};
+use log::debug;
 
 use crate::{
     errors::Result,
-    objects::{GlobalRef, JObject},
-    sys, JNIEnv, JNIVersion, JavaVM,
+    objects::{debug_assert_ref_type, GlobalRef, JObject, ReferenceType},
+    sys,
+    wrapper::java_vm::deferred_refs::{self, delete_ref, RefKind},
+    JNIEnv, JNIVersion, JavaVM,
 };
 
 // Note: `WeakRef` must not implement `Into<JObject>`! If it did, then it would be possible to
@@ -44,7 +46,7 @@ use crate::{
 ///
 /// To create a weak global reference, use the [`JNIEnv::new_weak_ref`] method.
 /// To delete it, simply drop the `WeakRef` (but be sure to do so on an
-/// attached thread if possible; see the warning below).
+/// attached thread if possible; see below).
 ///
 ///
 /// # Clone and Drop Behavior
@@ -57,18 +59,16 @@ use crate::{
 /// existing one. To do that, use the [`WeakRef::clone_in_jvm`] method.
 ///
 ///
-/// # Warning: Drop On an Attached Thread If Possible
+/// # Dropping On an Unattached Thread
 ///
-/// When a `WeakRef` is dropped, a JNI call is made to delete the global
-/// reference. If this frequently happens on a thread that is not already
-/// attached to the JVM, the thread will be temporarily attached using
-/// [`JavaVM::attach_current_thread`], causing a severe performance penalty.
+/// As with [`GlobalRef`], a `WeakRef` that is dropped on a thread that is not
+/// attached to the JVM can't be deleted straight away. Instead, it's added to
+/// a queue, and deleted the next time that any thread attaches to the JVM,
+/// creates or deletes a global or weak reference, or calls
+/// [`JNIEnv::delete_deferred_refs`].
 ///
-/// To avoid this performance penalty, ensure that `WeakRef`s are only
-/// dropped on a thread that is already attached (or never dropped at all).
-///
-/// In the event that a global reference is dropped on an unattached thread, a
-/// message is [logged][log] at [`log::Level::Warn`].
+/// In the event that a weak reference is dropped on an unattached thread, a
+/// message is [logged][log] at [`log::Level::Debug`].
 
 #[derive(Clone)]
 pub struct WeakRef {
@@ -91,6 +91,9 @@ impl WeakRef {
     /// Expects a valid raw weak global reference that should be created with `NewWeakGlobalRef`
     /// JNI function.
     pub(crate) unsafe fn from_raw(vm: JavaVM, raw: sys::jweak) -> Self {
+        debug_assert_ref_type(&vm, raw, ReferenceType::WeakGlobal);
+        #[cfg(feature = "stats")]
+        crate::stats::weak_ref_created(&vm, raw);
         WeakRef {
             inner: Arc::new(WeakRefGuard { raw, vm }),
         }
@@ -113,7 +116,12 @@ impl WeakRef {
         // XXX: Don't use env.new_local_ref here because that will treat `null`
         // return values (for non-null objects) as out-of-memory errors
         let r = unsafe {
-            JObject::from_raw(jni_call_unchecked!(env, v1_2, NewLocalRef, self.as_raw()))
+            JObject::from_raw(track_local!(jni_call_unchecked!(
+                env,
+                v1_2,
+                NewLocalRef,
+                self.as_raw()
+            )))
         };
 
         // Per JNI spec, `NewLocalRef` will return a null pointer if the object was GC'd.
@@ -202,29 +210,17 @@ impl WeakRef {
 
 impl Drop for WeakRefGuard {
     fn drop(&mut self) {
-        fn drop_impl(env: &JNIEnv, raw: sys::jweak) -> Result<()> {
-            // Safety: This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
-            // jni-rs requires JNI_VERSION > 1.2
-            unsafe {
-                jni_call_unchecked!(env, v1_2, DeleteWeakGlobalRef, raw);
-            }
-            Ok(())
-        }
-
         // Safety: we can assume we couldn't have created the weak reference in the first place without
         // having already required the JavaVM to support JNI >= 1.4
-        let res = match unsafe { self.vm.get_env(JNIVersion::V1_4) } {
-            Ok(env) => drop_impl(&env, self.raw),
+        match unsafe { self.vm.get_env(JNIVersion::V1_4) } {
+            Ok(env) => unsafe {
+                delete_ref(&env, self.raw, RefKind::Weak);
+                deferred_refs::flush(&env);
+            },
             Err(_) => {
-                warn!("Dropping a WeakRef in a detached thread. Fix your code if this message appears frequently (see the WeakRef docs).");
-                self.vm
-                    .attach_current_thread()
-                    .and_then(|env| drop_impl(&env, self.raw))
+                debug!("A JNI weak reference was dropped on a thread that is not attached, so its deletion has been deferred. For more information, see the documentation for `jni::objects::WeakRef`.");
+                unsafe { deferred_refs::defer(self.raw, RefKind::Weak) };
             }
-        };
-
-        if let Err(err) = res {
-            debug!("error dropping weak ref: {:#?}", err);
         }
     }
 }
@@ -0,0 +1,647 @@
+use std::{
+    collections::BTreeMap,
+    ffi::c_void,
+    panic::{catch_unwind, AssertUnwindSafe, Location},
+};
+
+use log::error;
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass, JClassLoader},
+    sys, JNIEnv, JNIVersion, JavaVM, NativeMethod, NativeMethodEx,
+};
+
+/// The native methods of a single Java class, to be registered by
+/// [`register_all`].
+pub struct ClassRegistration<'a> {
+    /// The name of the class, in the same `/`-separated form that's used by
+    /// [`JNIEnv::find_class`], such as `"com/example/Foo"`.
+    pub class_name: &'a str,
+    /// The native methods to register for the class.
+    pub methods: &'a [NativeMethod],
+    /// An optional hook that's called with the class after the native methods
+    /// of _all_ the classes have been registered.
+    pub init: Option<fn(&mut JNIEnv, &JClass) -> Result<()>>,
+}
+
+/// Registers the native methods of multiple classes, loading each class
+/// through the system (application) class loader.
+///
+/// This is typically called from `JNI_OnLoad`. See [`register_all_with_loader`]
+/// for details.
+pub fn register_all(
+    env: &mut JNIEnv,
+    registrations: &[ClassRegistration],
+) -> std::result::Result<(), RegistrationError> {
+    let loader = JClassLoader::system(env).map_err(|err| RegistrationError {
+        failures: vec![failure(
+            env,
+            "java/lang/ClassLoader",
+            RegistrationStage::Load,
+            err,
+        )],
+    })?;
+    let result = register_all_with_loader(env, &loader, registrations);
+    env.delete_local_ref(loader);
+    result
+}
+
+/// Registers the native methods of multiple classes, loading each class
+/// through the given class loader.
+///
+/// This is done in two passes:
+///
+/// 1. Each class is loaded (without being initialized) and its native
+///    methods are registered.
+/// 2. The `init` hook of each class that was successfully registered is
+///    called, in the order that the classes are given.
+///
+/// Since all the native methods are registered before any `init` hook runs,
+/// the hooks can call into any of the registered classes. Classes should be
+/// given in dependency order if their `init` hooks depend on each other.
+///
+/// A failure for one class doesn't stop the others from being registered.
+/// Instead, all the failures are returned together as a [`RegistrationError`].
+/// Any Java exception that's thrown is cleared and recorded in the failure.
+///
+/// Loading classes through an explicit class loader (instead of with
+/// [`JNIEnv::find_class`]) makes it possible to register application
+/// classes from threads that were attached from native code, which would
+/// otherwise only be able to find system classes.
+pub fn register_all_with_loader(
+    env: &mut JNIEnv,
+    loader: &JClassLoader,
+    registrations: &[ClassRegistration],
+) -> std::result::Result<(), RegistrationError> {
+    register_all_impl(env, Some(loader), registrations)
+}
+
+/// Registers the native methods of multiple classes like
+/// [`register_all_with_loader`], loading each class through `loader`, or with
+/// [`JNIEnv::find_class`] if it's `None`.
+fn register_all_impl(
+    env: &mut JNIEnv,
+    loader: Option<&JClassLoader>,
+    registrations: &[ClassRegistration],
+) -> std::result::Result<(), RegistrationError> {
+    let mut failures = Vec::new();
+
+    let mut classes: Vec<Option<GlobalRef>> = Vec::with_capacity(registrations.len());
+    for registration in registrations {
+        let registered = load_and_register(env, loader, registration.class_name, |env, class| {
+            env.register_native_methods(class, registration.methods)
+        });
+        match registered {
+            Ok(class) => classes.push(Some(class)),
+            Err((stage, err)) => {
+                failures.push(failure(env, registration.class_name, stage, err));
+                classes.push(None);
+            }
+        }
+    }
+
+    for (registration, class) in registrations.iter().zip(&classes) {
+        if let (Some(init), Some(class)) = (registration.init, class) {
+            if let Err(err) = init(env, class.as_obj().into()) {
+                failures.push(failure(
+                    env,
+                    registration.class_name,
+                    RegistrationStage::Init,
+                    err,
+                ));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(RegistrationError { failures })
+    }
+}
+
+/// Loads the class with the given name through `loader` (or with
+/// [`JNIEnv::find_class`] if it's `None`), and registers its native methods
+/// with `register`.
+fn load_and_register(
+    env: &mut JNIEnv,
+    loader: Option<&JClassLoader>,
+    class_name: &str,
+    register: impl FnOnce(&mut JNIEnv, &JClass) -> Result<()>,
+) -> std::result::Result<GlobalRef, (RegistrationStage, Error)> {
+    let class = match loader {
+        Some(loader) => loader.load_class(env, class_name),
+        None => env.find_class(class_name),
+    }
+    .map_err(|err| (RegistrationStage::Load, err))?;
+    let class = env.auto_local(class);
+
+    register(env, &class).map_err(|err| (RegistrationStage::RegisterNatives, err))?;
+
+    env.new_global_ref(&class)
+        .map_err(|err| (RegistrationStage::RegisterNatives, err))
+}
+
+/// Creates a [`ClassRegistrationFailure`], clearing and describing any
+/// pending exception.
+fn failure(
+    env: &mut JNIEnv,
+    class_name: &str,
+    stage: RegistrationStage,
+    source: Error,
+) -> ClassRegistrationFailure {
+    let exception = env.take_exception_description();
+
+    ClassRegistrationFailure {
+        class_name: class_name.to_owned(),
+        stage,
+        source,
+        exception,
+    }
+}
+
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+pub use inventory;
+
+#[cfg(feature = "auto-register")]
+pub use jni_macros::jni_native;
+
+#[cfg(feature = "macros")]
+pub use jni_macros::jni_class;
+
+/// A native method that can be declared in a `static` or a `const`, unlike a
+/// [`NativeMethod`].
+///
+/// These are generated by the `jni_class` attribute (with the `macros`
+/// feature) and the `jni_native` attribute (with the `auto-register`
+/// feature).
+#[derive(Debug)]
+pub struct NativeEntry {
+    /// The name of the class, in the `/`-separated form that's used by
+    /// [`JNIEnv::find_class`].
+    pub class_name: &'static str,
+    /// The name of the Java method.
+    pub name: &'static str,
+    /// The type signature of the Java method.
+    pub sig: &'static str,
+    /// Returns a pointer to the function that implements the method.
+    pub fn_ptr: fn() -> *mut c_void,
+    /// The path of the Rust function that implements the method.
+    pub rust_fn: &'static str,
+    /// A description of the method as it's declared in Java, such as
+    /// `"native int com.example.Calculator.add(int, int)"`.
+    pub java_type: &'static str,
+    /// Where the method was declared in the Rust source code.
+    pub location: &'static Location<'static>,
+}
+
+impl NativeEntry {
+    /// Returns the [`NativeMethod`] to register.
+    pub fn to_native_method(&self) -> NativeMethod {
+        NativeMethod {
+            name: self.name.into(),
+            sig: self.sig.into(),
+            fn_ptr: (self.fn_ptr)(),
+        }
+    }
+
+    /// Returns the [`NativeMethodEx`] to register with
+    /// [`JNIEnv::register_native_methods_checked`], which describes the Rust
+    /// function, Java method and source location if the method is rejected.
+    pub fn to_native_method_ex(&self) -> NativeMethodEx {
+        NativeMethodEx {
+            method: self.to_native_method(),
+            rust_fn: Some(self.rust_fn),
+            java_type: Some(self.java_type),
+            location: Some(self.location),
+        }
+    }
+}
+
+/// Registers the native methods of the given entries with
+/// [`JNIEnv::register_native_methods_checked`], grouped by class, looking up
+/// each class with [`JNIEnv::find_class`].
+///
+/// This stops at the first class that fails to be registered. See
+/// [`register_all`] for registering multiple classes through a class loader.
+pub fn register_entries(env: &mut JNIEnv, entries: &[NativeEntry]) -> Result<()> {
+    for (class_name, methods) in group_by_class(entries.iter()) {
+        env.register_native_methods_checked(class_name, &methods)?;
+    }
+    Ok(())
+}
+
+/// Groups native methods by their class name, in order of class name.
+fn group_by_class<'a>(
+    entries: impl Iterator<Item = &'a NativeEntry>,
+) -> BTreeMap<&'a str, Vec<NativeMethodEx>> {
+    let mut classes: BTreeMap<&str, Vec<NativeMethodEx>> = BTreeMap::new();
+    for entry in entries {
+        classes
+            .entry(entry.class_name)
+            .or_default()
+            .push(entry.to_native_method_ex());
+    }
+    classes
+}
+
+#[cfg(feature = "auto-register")]
+inventory::collect!(NativeEntry);
+
+/// Returns every native method that's declared with the [`jni_native`]
+/// attribute, in any crate that's linked into the program.
+#[cfg(feature = "auto-register")]
+pub fn native_entries() -> impl Iterator<Item = &'static NativeEntry> {
+    inventory::iter::<NativeEntry>.into_iter()
+}
+
+/// Registers every native method that's declared with the [`jni_native`]
+/// attribute, loading each class through the system (application) class
+/// loader.
+///
+/// The methods are grouped by class, and registered with
+/// [`register_collected_with_loader`].
+#[cfg(feature = "auto-register")]
+pub fn register_collected(env: &mut JNIEnv) -> std::result::Result<(), RegistrationError> {
+    let loader = JClassLoader::system(env).map_err(|err| RegistrationError {
+        failures: vec![failure(
+            env,
+            "java/lang/ClassLoader",
+            RegistrationStage::Load,
+            err,
+        )],
+    })?;
+    let result = register_collected_with_loader(env, &loader);
+    env.delete_local_ref(loader);
+    result
+}
+
+/// Registers every native method that's declared with the [`jni_native`]
+/// attribute, loading each class through the given class loader.
+///
+/// This replaces a hand-written table of [`ClassRegistration`]s: the methods
+/// are grouped by class (in order of class name), and registered like
+/// [`register_all_with_loader`] does, so a failure for one class doesn't stop
+/// the others from being registered. The methods are registered with
+/// [`JNIEnv::register_native_methods_checked`], so a failure names the Rust
+/// function of each method that was rejected.
+#[cfg(feature = "auto-register")]
+pub fn register_collected_with_loader(
+    env: &mut JNIEnv,
+    loader: &JClassLoader,
+) -> std::result::Result<(), RegistrationError> {
+    let mut failures = Vec::new();
+    for (class_name, methods) in group_by_class(native_entries()) {
+        let registered = load_and_register(env, Some(loader), class_name, |env, class| {
+            env.register_native_methods_checked(class, &methods)
+        });
+        if let Err((stage, err)) = registered {
+            failures.push(failure(env, class_name, stage, err));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(RegistrationError { failures })
+    }
+}
+
+/// Runs the future that's returned by `prepare` on the given runtime, and
+/// returns a new local reference to a `CompletableFuture` that's completed
+/// with its output, as the result of a native method.
+///
+/// This is used by the [`jni_native`] attribute for an `async fn`. If
+/// `prepare` fails, or panics, an exception is thrown (see
+/// [`ExceptionMap`](crate::errors::ExceptionMap)) and null is returned. If the
+/// future itself panics, the `CompletableFuture` is completed exceptionally
+/// (see [`JNIEnv::new_completable_future_from`]).
+#[cfg(all(feature = "auto-register", feature = "tokio"))]
+#[doc(hidden)]
+pub fn spawn_native_future<F, P>(
+    env: &mut JNIEnv,
+    runtime: &tokio::runtime::Handle,
+    prepare: P,
+) -> sys::jobject
+where
+    F: std::future::Future<Output = Result<GlobalRef>> + Send + 'static,
+    P: FnOnce(&mut JNIEnv) -> Result<F>,
+{
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let future = prepare(env)?;
+        env.new_completable_future_from(runtime, future)
+    }));
+    if env.exception_check() {
+        return std::ptr::null_mut();
+    }
+    let _ = match res {
+        Ok(Ok(completable)) => return completable.into_raw(),
+        Ok(Err(err)) => ExceptionMap::throw(env, &err),
+        Err(_) => env.throw_new("java/lang/RuntimeException", "Rust native method panicked"),
+    };
+    std::ptr::null_mut()
+}
+
+/// Throws an exception for a native method argument that's null, and returns
+/// a zeroed value for the method to return.
+///
+/// This is used by the [`jni_native`] attribute with `non_null = [...]`. The
+/// exception is the one that [`ExceptionMap`] maps [`Error::NullPtr`] to, or
+/// otherwise a `java.lang.NullPointerException`.
+///
+/// # Safety
+///
+/// `env` must be a valid `JNIEnv` pointer for the current thread, and `T` must
+/// be a type that can be zeroed, such as a JNI primitive or reference type.
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+pub unsafe fn null_argument<T>(env: *mut sys::JNIEnv, name: &'static str) -> T {
+    let mut env = JNIEnv::from_raw_unchecked(env);
+    if !env.exception_check() {
+        let exception = ExceptionMap::lookup(&Error::NullPtr(name)).unwrap_or_else(|| Exception {
+            class: "java/lang/NullPointerException".into(),
+            msg: format!("argument `{}` is null", name),
+        });
+        let _ = env.throw_new(exception.class, exception.msg);
+    }
+    std::mem::zeroed()
+}
+
+/// Calls `f`, the body of a native method, in a `tracing` span with the
+/// method's class, name and signature.
+///
+/// This is used by the [`jni_native`] attribute with `instrument = true`. An
+/// error event is recorded if the method returns with a pending exception, or
+/// panics.
+#[cfg(all(feature = "auto-register", feature = "tracing"))]
+#[doc(hidden)]
+pub fn instrument<T>(
+    env: *mut sys::JNIEnv,
+    class_name: &str,
+    name: &str,
+    sig: &str,
+    f: impl FnOnce() -> T,
+) -> T {
+    let span = tracing::info_span!("native_method", class = class_name, method = name, sig);
+    let _entered = span.enter();
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(output) => {
+            // Safety: `env` is the `JNIEnv` pointer that the method was called with
+            if unsafe { JNIEnv::from_raw_unchecked(env) }.exception_check() {
+                tracing::error!("native method threw an exception");
+            }
+            output
+        }
+        Err(payload) => {
+            tracing::error!("native method panicked");
+            std::panic::resume_unwind(payload)
+        }
+    }
+}
+
+/// Wraps the future of an `async` native method so that it runs in the
+/// current `tracing` span, and records an error event if it fails.
+#[cfg(all(feature = "auto-register", feature = "tokio", feature = "tracing"))]
+#[doc(hidden)]
+pub fn instrument_future<F>(future: F) -> impl std::future::Future<Output = Result<GlobalRef>>
+where
+    F: std::future::Future<Output = Result<GlobalRef>>,
+{
+    use tracing::Instrument;
+
+    async move {
+        let result = future.await;
+        if let Err(err) = &result {
+            tracing::error!(error = %err, "native method failed");
+        }
+        result
+    }
+    .in_current_span()
+}
+
+/// Generates the `JNI_OnLoad` and `JNI_OnUnload` functions of a library.
+///
+/// The generated `JNI_OnLoad`:
+///
+/// 1. Stores the [`JavaVM`], so that it can be retrieved from anywhere with
+///    [`JavaVM::singleton`].
+/// 2. With `class_loader`, looks up the given class and sets its class loader
+///    as the [default class loader](JavaVM::set_default_class_loader), so that
+///    the library's classes can be found from native threads.
+/// 3. With `register`, registers the native methods of the given
+///    [`ClassRegistration`]s like [`register_all_with_loader`] does, using the
+///    class loader from `class_loader`. Otherwise, each class is looked up
+///    with [`JNIEnv::find_class`], which uses the class loader of the class
+///    that loaded the library during `JNI_OnLoad`.
+/// 4. With `on_load`, calls the given `fn(&mut JNIEnv) -> Result<()>`.
+/// 5. Returns the JNI `version` that the library needs, which is
+///    [`JNIVersion::V1_6`] by default.
+///
+/// If any of these steps fails (or panics), the error is logged and
+/// `JNI_OnLoad` returns `JNI_ERR`, which makes `System.loadLibrary` throw an
+/// `UnsatisfiedLinkError`.
+///
+/// The generated `JNI_OnUnload` calls the `on_unload` function, if it's
+/// given, with the `JavaVM`, and then forgets the stored `JavaVM`.
+///
+/// Each option is optional, but they have to be given in this order, each
+/// followed by a comma.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use jni::{
+///     errors::Result,
+///     objects::JClass,
+///     registry::ClassRegistration,
+///     sys::jint,
+///     JNIEnv, JNIVersion, JavaVM, NativeMethod,
+/// };
+///
+/// extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+///     a + b
+/// }
+///
+/// fn on_load(env: &mut JNIEnv) -> Result<()> {
+///     env.cache_class("com/example/Point")
+/// }
+///
+/// fn on_unload(_vm: &JavaVM) {
+///     log::info!("unloaded");
+/// }
+///
+/// jni::jni_on_load! {
+///     version: JNIVersion::V1_8,
+///     class_loader: "com/example/Main",
+///     register: [ClassRegistration {
+///         class_name: "com/example/Main",
+///         methods: &[NativeMethod {
+///             name: "add".into(),
+///             sig: "(II)I".into(),
+///             fn_ptr: add as *mut _,
+///         }],
+///         init: None,
+///     }],
+///     on_load: on_load,
+///     on_unload: on_unload,
+/// }
+/// ```
+#[macro_export]
+macro_rules! jni_on_load {
+    (
+        $(version: $version:expr,)?
+        $(class_loader: $class_loader:expr,)?
+        $(register: $registrations:expr,)?
+        $(on_load: $on_load:expr,)?
+        $(on_unload: $on_unload:expr,)?
+    ) => {
+        #[no_mangle]
+        pub unsafe extern "system" fn JNI_OnLoad(
+            vm: *mut $crate::sys::JavaVM,
+            _reserved: *mut ::std::os::raw::c_void,
+        ) -> $crate::sys::jint {
+            #[allow(unused_mut)]
+            let mut version = $crate::JNIVersion::V1_6;
+            $(version = $version;)?
+            #[allow(unused_mut)]
+            let mut class_loader: ::std::option::Option<&str> = None;
+            $(class_loader = Some($class_loader);)?
+            let registrations: &[$crate::registry::ClassRegistration] = &[];
+            $(
+                let registrations = $registrations;
+                let registrations: &[$crate::registry::ClassRegistration] = &registrations[..];
+            )?
+            #[allow(unused_mut)]
+            let mut init: ::std::option::Option<
+                fn(&mut $crate::JNIEnv) -> $crate::errors::Result<()>,
+            > = None;
+            $(init = Some($on_load);)?
+            $crate::registry::on_load(
+                vm,
+                $crate::registry::OnLoad {
+                    version,
+                    class_loader,
+                    registrations,
+                    init,
+                },
+            )
+        }
+
+        #[no_mangle]
+        pub unsafe extern "system" fn JNI_OnUnload(
+            vm: *mut $crate::sys::JavaVM,
+            _reserved: *mut ::std::os::raw::c_void,
+        ) {
+            #[allow(unused_mut)]
+            let mut on_unload: ::std::option::Option<fn(&$crate::JavaVM)> = None;
+            $(on_unload = Some($on_unload);)?
+            $crate::registry::on_unload(vm, on_unload)
+        }
+    };
+}
+
+/// The options of [`jni_on_load!`], which are passed to [`on_load`].
+#[doc(hidden)]
+pub struct OnLoad<'a> {
+    pub version: JNIVersion,
+    pub class_loader: Option<&'a str>,
+    pub registrations: &'a [ClassRegistration<'a>],
+    pub init: Option<fn(&mut JNIEnv) -> Result<()>>,
+}
+
+/// The implementation of the `JNI_OnLoad` that's generated by
+/// [`jni_on_load!`].
+///
+/// # Safety
+///
+/// `vm` must be the `JavaVM` that's passed to `JNI_OnLoad`.
+#[doc(hidden)]
+pub unsafe fn on_load(vm: *mut sys::JavaVM, options: OnLoad) -> sys::jint {
+    let res = catch_unwind(AssertUnwindSafe(|| -> std::result::Result<(), String> {
+        let vm = JavaVM::from_raw(vm).map_err(|err| err.to_string())?;
+        JavaVM::set_singleton(Some(vm.clone()));
+        let mut env = vm.get_env(options.version).map_err(|err| err.to_string())?;
+        env.with_local_frame(8, |env| Ok::<_, Error>(load(&vm, env, &options)))
+            .map_err(|err| err.to_string())?
+    }));
+    match res {
+        Ok(Ok(())) => options.version.into(),
+        Ok(Err(err)) => {
+            error!("JNI_OnLoad failed: {}", err);
+            sys::JNI_ERR
+        }
+        Err(_) => {
+            error!("JNI_OnLoad panicked");
+            sys::JNI_ERR
+        }
+    }
+}
+
+/// Sets the default class loader, registers the native methods and calls the
+/// `init` function for [`on_load`].
+fn load(vm: &JavaVM, env: &mut JNIEnv, options: &OnLoad) -> std::result::Result<(), String> {
+    let loader = match options.class_loader {
+        Some(class_name) => {
+            let loader = class_loader(env, class_name).map_err(|err| {
+                let exception = env.take_exception_description();
+                format!(
+                    "failed to get the class loader of {}: {}",
+                    class_name,
+                    exception.unwrap_or_else(|| err.to_string())
+                )
+            })?;
+            vm.set_default_class_loader(
+                env.new_global_ref(&loader).map_err(|err| err.to_string())?,
+            );
+            Some(loader)
+        }
+        None => None,
+    };
+    register_all_impl(env, loader.as_ref(), options.registrations)
+        .map_err(|err| err.to_string())?;
+    if let Some(init) = options.init {
+        init(env).map_err(|err| {
+            let exception = env.take_exception_description();
+            exception.unwrap_or_else(|| err.to_string())
+        })?;
+    }
+    Ok(())
+}
+
+/// The implementation of the `JNI_OnUnload` that's generated by
+/// [`jni_on_load!`].
+///
+/// # Safety
+///
+/// `vm` must be the `JavaVM` that's passed to `JNI_OnUnload`.
+#[doc(hidden)]
+pub unsafe fn on_unload(vm: *mut sys::JavaVM, on_unload: Option<fn(&JavaVM)>) {
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        if let (Some(on_unload), Ok(vm)) = (on_unload, JavaVM::from_raw(vm)) {
+            on_unload(&vm);
+        }
+    }));
+    if res.is_err() {
+        error!("JNI_OnUnload panicked");
+    }
+    JavaVM::set_singleton(None);
+}
+
+/// Returns the class loader of the class with the given name.
+fn class_loader<'local>(
+    env: &mut JNIEnv<'local>,
+    class_name: &str,
+) -> Result<JClassLoader<'local>> {
+    let class = env.find_class(class_name)?;
+    let loader = env
+        .call_method(&class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    env.delete_local_ref(class);
+    if loader.is_null() {
+        // The class was loaded by the bootstrap class loader
+        return Err(Error::NullPtr("class loader"));
+    }
+    Ok(JClassLoader::from(loader))
+}
@@ -0,0 +1,883 @@
+//! Conversion of Rust values to and from Java objects with [`serde`](::serde).
+//!
+//! [`to_jobject`] serializes any [`Serialize`] type into a tree of standard
+//! Java objects, and [`from_jobject`] deserializes such a tree back into any
+//! [`DeserializeOwned`] type. This gives a quick way to pass structured data
+//! across the JNI boundary without writing (or generating) bindings for a
+//! Java class.
+//!
+//! Rust values are mapped to Java objects as follows:
+//!
+//! | Rust                                    | Java                                          |
+//! |-----------------------------------------|-----------------------------------------------|
+//! | `bool`                                  | `java.lang.Boolean`                           |
+//! | `i8`, `i16`, `i32`, `i64`               | `Byte`, `Short`, `Integer`, `Long`            |
+//! | `u8`, `u16`, `u32`, `u64`               | `Short`, `Integer`, `Long`, `Long`            |
+//! | `f32`, `f64`                            | `Float`, `Double`                             |
+//! | `char`                                  | `Character` (or `String` if it's outside the BMP) |
+//! | `String`, `&str`                        | `java.lang.String`                            |
+//! | bytes (e.g. with `serde_bytes`)         | `byte[]`                                      |
+//! | `None`, `()`, unit structs              | `null`                                        |
+//! | `Some(x)`, newtype structs              | the mapping of `x`                            |
+//! | sequences, tuples and tuple structs     | `java.util.ArrayList`                         |
+//! | maps and structs                        | `java.util.HashMap`                           |
+//! | unit enum variants                      | the name of the variant, as a `String`        |
+//! | other enum variants                     | a `HashMap` from the variant name to its data |
+//!
+//! Unsigned integers are widened, so that they are never mistaken for
+//! negative numbers by Java code. A `u64` that's too big for a `Long` can't be
+//! serialized.
+//!
+//! When deserializing, any `java.util.Map`, `java.util.Collection` or object
+//! array is accepted where a map or sequence is expected, and a `byte[]` is
+//! also accepted where a sequence of integers is expected. Numbers are
+//! converted to the requested type as long as they're in range, so a Java
+//! `Integer` can be deserialized into a `u8`, for example.
+//!
+//! With the `serde_json` feature, [`to_json_string`] and [`from_json_string`]
+//! instead pass values across as JSON strings, which can be cheaper for large
+//! values when the Java side already has a JSON library.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # use jni::{JNIEnv, objects::JObject};
+//! # use serde::{Deserialize, Serialize};
+//! #
+//! #[derive(Serialize, Deserialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! # fn example(env: &mut JNIEnv) -> Result<(), jni::serde::SerdeError> {
+//! // `obj` is a `java.util.HashMap` with the keys "x" and "y".
+//! let obj: JObject = jni::serde::to_jobject(env, &Point { x: 1, y: 2 })?;
+//! let point: Point = jni::serde::from_jobject(env, &obj)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{convert::TryFrom, fmt::Display};
+
+use ::serde::{
+    de::{self, DeserializeOwned, IntoDeserializer},
+    forward_to_deserialize_any,
+    ser::{self, Serialize},
+};
+use thiserror::Error;
+
+use crate::{
+    errors::Error,
+    objects::{
+        JByteArray, JIterator, JMapIter, JObject, JObjectArray, JString, JValue, JValueOwned,
+    },
+    JNIEnv,
+};
+
+/// An error from [`to_jobject`] or [`from_jobject`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SerdeError {
+    /// A JNI call failed.
+    ///
+    /// If this is an [`Error::JavaException`], the exception is still pending.
+    #[error(transparent)]
+    Jni(#[from] Error),
+
+    /// A Java object of this class can't be deserialized.
+    #[error("Can't deserialize an instance of {0}")]
+    UnsupportedClass(String),
+
+    /// A value couldn't be serialized or deserialized, such as a `u64` that's
+    /// too big for a Java `Long`, or a Java object that doesn't match the
+    /// type it's being deserialized into.
+    #[error("{0}")]
+    Message(String),
+
+    /// A value couldn't be converted to or from JSON.
+    #[cfg(feature = "serde_json")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, SerdeError>;
+
+/// The capacity of the local frame that [`to_jobject`] serializes a value in.
+/// Each level of nesting only holds a few references at a time.
+const SERIALIZE_FRAME_CAPACITY: i32 = 16;
+
+/// Serializes `value` into a new tree of Java objects.
+///
+/// See the [module documentation](self) for how Rust values are mapped to
+/// Java objects. `None` and other unit values are serialized as `null`.
+///
+/// The value is serialized in a new local frame, so the local references for
+/// all the intermediate objects are deleted (even if serialization fails), and
+/// only the returned local reference is left.
+pub fn to_jobject<'local, T>(env: &mut JNIEnv<'local>, value: &T) -> Result<JObject<'local>>
+where
+    T: Serialize + ?Sized,
+{
+    env.with_local_frame_returning_local(SERIALIZE_FRAME_CAPACITY, |env| {
+        value.serialize(Serializer { env })
+    })
+}
+
+/// Deserializes a value from a tree of Java objects, such as one created by
+/// [`to_jobject`].
+///
+/// See the [module documentation](self) for which Java objects are accepted.
+pub fn from_jobject<'other_local, T>(env: &mut JNIEnv, obj: &JObject<'other_local>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer { env, obj })
+}
+
+/// Serializes `value` as JSON, into a new Java `String`.
+#[cfg(feature = "serde_json")]
+pub fn to_json_string<'local, T>(env: &mut JNIEnv<'local>, value: &T) -> Result<JString<'local>>
+where
+    T: Serialize + ?Sized,
+{
+    let json = serde_json::to_string(value)?;
+    Ok(env.new_string(json)?)
+}
+
+/// Deserializes a value from a Java `String` that contains JSON.
+#[cfg(feature = "serde_json")]
+pub fn from_json_string<T>(env: &mut JNIEnv, string: &JString) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let json = string.to_rust_string(env)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Boxes a primitive value by calling the static `valueOf` method of `class`.
+fn box_value<'local>(
+    env: &mut JNIEnv<'local>,
+    class: &str,
+    sig: &str,
+    value: JValue,
+) -> Result<JObject<'local>> {
+    Ok(env
+        .call_static_method(class, "valueOf", sig, &[value])?
+        .l()?)
+}
+
+/// Wraps `value` in a new single-entry `HashMap`, with the variant name as
+/// its key. This is the same "externally tagged" representation of enums
+/// that's used by `serde_json`.
+fn wrap_variant<'local>(
+    env: &mut JNIEnv<'local>,
+    variant: &str,
+    value: JObject<'local>,
+) -> Result<JObject<'local>> {
+    let map = env.new_object("java/util/HashMap", "()V", &[])?;
+    let key = env.new_string(variant)?;
+    put(env, &map, &key, &value)?;
+    env.delete_local_ref(key);
+    env.delete_local_ref(value);
+    Ok(map)
+}
+
+fn put(env: &mut JNIEnv, map: &JObject, key: &JObject, value: &JObject) -> Result<()> {
+    let previous = env
+        .call_method(
+            map,
+            "put",
+            "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+            &[key.into(), value.into()],
+        )?
+        .l()?;
+    env.delete_local_ref(previous);
+    Ok(())
+}
+
+struct Serializer<'a, 'local> {
+    env: &'a mut JNIEnv<'local>,
+}
+
+impl<'a, 'local> ser::Serializer for Serializer<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    type SerializeSeq = Compound<'a, 'local>;
+    type SerializeTuple = Compound<'a, 'local>;
+    type SerializeTupleStruct = Compound<'a, 'local>;
+    type SerializeTupleVariant = Compound<'a, 'local>;
+    type SerializeMap = Compound<'a, 'local>;
+    type SerializeStruct = Compound<'a, 'local>;
+    type SerializeStructVariant = Compound<'a, 'local>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        box_value(
+            self.env,
+            "java/lang/Boolean",
+            "(Z)Ljava/lang/Boolean;",
+            v.into(),
+        )
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        box_value(self.env, "java/lang/Byte", "(B)Ljava/lang/Byte;", v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        box_value(
+            self.env,
+            "java/lang/Short",
+            "(S)Ljava/lang/Short;",
+            v.into(),
+        )
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        box_value(
+            self.env,
+            "java/lang/Integer",
+            "(I)Ljava/lang/Integer;",
+            v.into(),
+        )
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        box_value(self.env, "java/lang/Long", "(J)Ljava/lang/Long;", v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        self.serialize_i16(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        let v = i64::try_from(v)
+            .map_err(|_| SerdeError::Message(format!("{} is too big for a java.lang.Long", v)))?;
+        self.serialize_i64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        box_value(
+            self.env,
+            "java/lang/Float",
+            "(F)Ljava/lang/Float;",
+            v.into(),
+        )
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        box_value(
+            self.env,
+            "java/lang/Double",
+            "(D)Ljava/lang/Double;",
+            v.into(),
+        )
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        match JValue::try_from(v) {
+            Ok(value) => box_value(
+                self.env,
+                "java/lang/Character",
+                "(C)Ljava/lang/Character;",
+                value,
+            ),
+            // A `char` outside the Basic Multilingual Plane needs a surrogate pair.
+            Err(_) => self.serialize_str(v.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(self.env.new_string(v)?.into())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        Ok(self.env.byte_array_from_slice(v)?.into())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(JObject::null())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(JObject::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        Ok(JObject::null())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        let value = value.serialize(Serializer {
+            env: &mut *self.env,
+        })?;
+        wrap_variant(self.env, variant, value)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Compound::new_list(self.env, None)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Compound::new_list(self.env, None)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Compound::new_list(self.env, None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Compound::new_list(self.env, Some(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Compound::new_map(self.env, None)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Compound::new_map(self.env, None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Compound::new_map(self.env, Some(variant))
+    }
+}
+
+/// Builds an `ArrayList` (for sequences) or a `HashMap` (for maps), which is
+/// wrapped with [`wrap_variant`] at the end if it's the data of an enum
+/// variant.
+struct Compound<'a, 'local> {
+    env: &'a mut JNIEnv<'local>,
+    collection: JObject<'local>,
+    variant: Option<&'static str>,
+    key: Option<JObject<'local>>,
+}
+
+impl<'a, 'local> Compound<'a, 'local> {
+    fn new_list(env: &'a mut JNIEnv<'local>, variant: Option<&'static str>) -> Result<Self> {
+        let collection = env.new_object("java/util/ArrayList", "()V", &[])?;
+        Ok(Compound {
+            env,
+            collection,
+            variant,
+            key: None,
+        })
+    }
+
+    fn new_map(env: &'a mut JNIEnv<'local>, variant: Option<&'static str>) -> Result<Self> {
+        let collection = env.new_object("java/util/HashMap", "()V", &[])?;
+        Ok(Compound {
+            env,
+            collection,
+            variant,
+            key: None,
+        })
+    }
+
+    fn add<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let value = value.serialize(Serializer {
+            env: &mut *self.env,
+        })?;
+        self.env.call_method(
+            &self.collection,
+            "add",
+            "(Ljava/lang/Object;)Z",
+            &[(&value).into()],
+        )?;
+        self.env.delete_local_ref(value);
+        Ok(())
+    }
+
+    fn put_value<T: Serialize + ?Sized>(&mut self, key: &JObject, value: &T) -> Result<()> {
+        let value = value.serialize(Serializer {
+            env: &mut *self.env,
+        })?;
+        put(self.env, &self.collection, key, &value)?;
+        self.env.delete_local_ref(value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<JObject<'local>> {
+        match self.variant {
+            Some(variant) => wrap_variant(self.env, variant, self.collection),
+            None => Ok(self.collection),
+        }
+    }
+}
+
+impl<'a, 'local> ser::SerializeSeq for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeTuple for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeTupleStruct for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeTupleVariant for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeMap for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        let key = key.serialize(Serializer {
+            env: &mut *self.env,
+        })?;
+        if let Some(previous) = self.key.replace(key) {
+            self.env.delete_local_ref(previous);
+        }
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self.key.take().ok_or_else(|| {
+            SerdeError::Message("serialize_value called before serialize_key".to_owned())
+        })?;
+        let res = self.put_value(&key, value);
+        self.env.delete_local_ref(key);
+        res
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeStruct for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let key = self.env.new_string(key)?;
+        let res = self.put_value(&key, value);
+        self.env.delete_local_ref(key);
+        res
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+impl<'a, 'local> ser::SerializeStructVariant for Compound<'a, 'local> {
+    type Ok = JObject<'local>;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        self.finish()
+    }
+}
+
+struct Deserializer<'a, 'local, 'other_local> {
+    env: &'a mut JNIEnv<'local>,
+    obj: &'a JObject<'other_local>,
+}
+
+impl<'a, 'local, 'other_local> Deserializer<'a, 'local, 'other_local> {
+    fn class_name(&mut self) -> Result<String> {
+        let class = self.env.get_object_class(self.obj)?;
+        let name = self
+            .env
+            .call_method(&class, "getName", "()Ljava/lang/String;", &[])?
+            .l()?;
+        self.env.delete_local_ref(class);
+        let name = JString::from(name);
+        let res = name.to_rust_string(self.env);
+        self.env.delete_local_ref(name);
+        Ok(res?)
+    }
+
+    fn unbox(&mut self, method: &str, sig: &str) -> Result<JValueOwned<'local>> {
+        Ok(self.env.call_method(self.obj, method, sig, &[])?)
+    }
+
+    /// Calls `visitor.visit_seq` with the elements of a `Collection`.
+    fn visit_collection<'de, V: de::Visitor<'de>>(
+        &mut self,
+        obj: &JObject,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let iterator = self
+            .env
+            .call_method(obj, "iterator", "()Ljava/util/Iterator;", &[])?
+            .l()?;
+        let res = self.env.get_iterator(&iterator).map_err(SerdeError::from);
+        let res = res.and_then(|mut iter| {
+            visitor.visit_seq(SeqAccess {
+                env: &mut *self.env,
+                iter: &mut iter,
+            })
+        });
+        self.env.delete_local_ref(iterator);
+        res
+    }
+
+    fn visit_map<'de, V: de::Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value> {
+        let map = self.env.get_map(self.obj)?;
+        let mut iter = map.iter(self.env)?;
+        visitor.visit_map(MapAccess {
+            env: &mut *self.env,
+            iter: &mut iter,
+            value: None,
+        })
+    }
+}
+
+impl<'de, 'a, 'local, 'other_local> de::Deserializer<'de>
+    for Deserializer<'a, 'local, 'other_local>
+{
+    type Error = SerdeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(mut self, visitor: V) -> Result<V::Value> {
+        if self.obj.is_null() {
+            return visitor.visit_unit();
+        }
+
+        let class_name = self.class_name()?;
+        match class_name.as_str() {
+            "java.lang.String" => {
+                let string = <&JString>::from(self.obj);
+                visitor.visit_string(string.to_rust_string(self.env)?)
+            }
+            "java.lang.Boolean" => visitor.visit_bool(self.unbox("booleanValue", "()Z")?.z()?),
+            "java.lang.Byte" => visitor.visit_i8(self.unbox("byteValue", "()B")?.b()?),
+            "java.lang.Short" => visitor.visit_i16(self.unbox("shortValue", "()S")?.s()?),
+            "java.lang.Integer" => visitor.visit_i32(self.unbox("intValue", "()I")?.i()?),
+            "java.lang.Long" => visitor.visit_i64(self.unbox("longValue", "()J")?.j()?),
+            "java.lang.Float" => visitor.visit_f32(self.unbox("floatValue", "()F")?.f()?),
+            "java.lang.Double" => visitor.visit_f64(self.unbox("doubleValue", "()D")?.d()?),
+            "java.lang.Character" => visitor.visit_char(self.unbox("charValue", "()C")?.c_char()?),
+            "[B" => {
+                let bytes = self.env.convert_byte_array(<&JByteArray>::from(self.obj))?;
+                visitor.visit_byte_buf(bytes)
+            }
+            _ if class_name.starts_with("[L") || class_name.starts_with("[[") => {
+                let array: &JObjectArray = self.obj.into();
+                let list = self
+                    .env
+                    .call_static_method(
+                        "java/util/Arrays",
+                        "asList",
+                        "([Ljava/lang/Object;)Ljava/util/List;",
+                        &[array.into()],
+                    )?
+                    .l()?;
+                let res = self.visit_collection(&list, visitor);
+                self.env.delete_local_ref(list);
+                res
+            }
+            _ if self.env.is_instance_of(self.obj, "java/util/Map")? => self.visit_map(visitor),
+            _ if self.env.is_instance_of(self.obj, "java/util/Collection")? => {
+                let obj = self.obj;
+                self.visit_collection(obj, visitor)
+            }
+            _ => Err(SerdeError::UnsupportedClass(class_name)),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.obj.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if !self.obj.is_null() && self.env.is_instance_of(self.obj, "[B")? {
+            let bytes = self.env.convert_byte_array(<&JByteArray>::from(self.obj))?;
+            visitor.visit_seq(de::value::SeqDeserializer::new(bytes.into_iter()))
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if !self.obj.is_null() && self.env.is_instance_of(self.obj, "java/lang/String")? {
+            let string = <&JString>::from(self.obj);
+            let variant = string.to_rust_string(self.env)?;
+            return visitor.visit_enum(variant.into_deserializer());
+        }
+        if self.obj.is_null() || !self.env.is_instance_of(self.obj, "java/util/Map")? {
+            return Err(SerdeError::Message(
+                "expected a String or a single-entry Map for an enum".to_owned(),
+            ));
+        }
+
+        let map = self.env.get_map(self.obj)?;
+        let mut iter = map.iter(self.env)?;
+        let (variant, value) = iter.next(self.env)?.ok_or_else(|| {
+            SerdeError::Message(
+                "expected a single-entry Map for an enum, but it was empty".to_owned(),
+            )
+        })?;
+        let res = if iter.next(self.env)?.is_some() {
+            Err(SerdeError::Message(
+                "expected a single-entry Map for an enum, but it has more entries".to_owned(),
+            ))
+        } else {
+            visitor.visit_enum(EnumAccess {
+                env: &mut *self.env,
+                variant: &variant,
+                value: &value,
+            })
+        };
+        self.env.delete_local_ref(variant);
+        self.env.delete_local_ref(value);
+        res
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, 'local, 'iter> {
+    env: &'a mut JNIEnv<'local>,
+    iter: &'a mut JIterator<'local, 'iter, 'iter>,
+}
+
+impl<'de, 'a, 'local, 'iter> de::SeqAccess<'de> for SeqAccess<'a, 'local, 'iter> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>> {
+        let element = match self.iter.next(self.env)? {
+            Some(element) => element,
+            None => return Ok(None),
+        };
+        let res = seed.deserialize(Deserializer {
+            env: &mut *self.env,
+            obj: &element,
+        });
+        self.env.delete_local_ref(element);
+        res.map(Some)
+    }
+}
+
+struct MapAccess<'a, 'local, 'iter> {
+    env: &'a mut JNIEnv<'local>,
+    iter: &'a mut JMapIter<'iter, 'local, 'iter, 'iter, 'local>,
+    value: Option<JObject<'local>>,
+}
+
+impl<'de, 'a, 'local, 'iter> de::MapAccess<'de> for MapAccess<'a, 'local, 'iter> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let (key, value) = match self.iter.next(self.env)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        if let Some(previous) = self.value.replace(value) {
+            self.env.delete_local_ref(previous);
+        }
+        let res = seed.deserialize(Deserializer {
+            env: &mut *self.env,
+            obj: &key,
+        });
+        self.env.delete_local_ref(key);
+        res.map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.value.take().ok_or_else(|| {
+            SerdeError::Message("next_value_seed called before next_key_seed".to_owned())
+        })?;
+        let res = seed.deserialize(Deserializer {
+            env: &mut *self.env,
+            obj: &value,
+        });
+        self.env.delete_local_ref(value);
+        res
+    }
+}
+
+struct EnumAccess<'a, 'local, 'other_local> {
+    env: &'a mut JNIEnv<'local>,
+    variant: &'a JObject<'other_local>,
+    value: &'a JObject<'other_local>,
+}
+
+impl<'de, 'a, 'local, 'other_local> de::EnumAccess<'de> for EnumAccess<'a, 'local, 'other_local> {
+    type Error = SerdeError;
+    type Variant = Deserializer<'a, 'local, 'other_local>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(Deserializer {
+            env: &mut *self.env,
+            obj: self.variant,
+        })?;
+        Ok((
+            variant,
+            Deserializer {
+                env: self.env,
+                obj: self.value,
+            },
+        ))
+    }
+}
+
+impl<'de, 'a, 'local, 'other_local> de::VariantAccess<'de>
+    for Deserializer<'a, 'local, 'other_local>
+{
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,187 @@
+//! Counters of the live [`GlobalRef`]s and [`WeakRef`]s.
+//!
+//! With the `stats` feature, the number of JVM global and weak global
+//! references that are owned by [`GlobalRef`]s and [`WeakRef`]s is counted,
+//! so that it can be exported as a metric. JVMs have a limit on the number of
+//! global references (which is small on Android), so a count that keeps
+//! growing indicates a leak, such as `GlobalRef`s that are stored in a
+//! collection and never removed.
+//!
+//! A reference is counted from when it's created until its last `GlobalRef`
+//! or `WeakRef` is dropped and the JVM reference is deleted. Clones of a
+//! `GlobalRef` or `WeakRef` share the same JVM reference, and aren't counted
+//! separately. References that are created through the raw JNI interface
+//! aren't counted.
+//!
+//! # Per-class breakdown
+//!
+//! With [`set_per_class`], the class of each new reference is also recorded,
+//! which can be used to find out what is leaking with
+//! [`global_refs_by_class`] and [`weak_refs_by_class`]. Looking up the class
+//! name calls into the JVM each time that a reference is created, so this is
+//! disabled by default.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use crate::{
+    errors::Result,
+    objects::{JObject, JString},
+    sys, JNIVersion, JavaVM,
+};
+
+#[cfg(doc)]
+use crate::objects::{GlobalRef, WeakRef};
+
+/// Counts of one kind of reference.
+struct Counter {
+    count: AtomicUsize,
+    /// The class name of each reference that was created while the
+    /// per-class breakdown was enabled, by raw reference.
+    classes: Mutex<Option<HashMap<usize, String>>>,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Counter {
+            count: AtomicUsize::new(0),
+            classes: Mutex::new(None),
+        }
+    }
+
+    fn created(&self, vm: &JavaVM, raw: sys::jobject) {
+        if raw.is_null() {
+            return;
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        if PER_CLASS.load(Ordering::Relaxed) {
+            // The name is looked up before locking, since it may create more
+            // references
+            let name = class_name(vm, raw).unwrap_or_else(|| String::from("<unknown>"));
+            let mut classes = self.classes.lock().unwrap_or_else(PoisonError::into_inner);
+            classes
+                .get_or_insert_with(HashMap::new)
+                .insert(raw as usize, name);
+        }
+    }
+
+    fn deleted(&self, raw: sys::jobject) {
+        if raw.is_null() {
+            return;
+        }
+        self.count.fetch_sub(1, Ordering::Relaxed);
+        // This is called while dropping a reference, so it mustn't panic
+        let mut classes = self.classes.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(classes) = classes.as_mut() {
+            classes.remove(&(raw as usize));
+        }
+    }
+
+    fn by_class(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        let classes = self.classes.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(classes) = classes.as_ref() {
+            for name in classes.values() {
+                *counts.entry(name.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
+
+static GLOBAL_REFS: Counter = Counter::new();
+static WEAK_REFS: Counter = Counter::new();
+static PER_CLASS: AtomicBool = AtomicBool::new(false);
+
+/// Returns the number of live JVM global references that are owned by
+/// [`GlobalRef`]s.
+pub fn global_ref_count() -> usize {
+    GLOBAL_REFS.count.load(Ordering::Relaxed)
+}
+
+/// Returns the number of live JVM weak global references that are owned by
+/// [`WeakRef`]s.
+pub fn weak_ref_count() -> usize {
+    WEAK_REFS.count.load(Ordering::Relaxed)
+}
+
+/// Enables or disables recording the class of each new [`GlobalRef`] and
+/// [`WeakRef`], for [`global_refs_by_class`] and [`weak_refs_by_class`].
+///
+/// References that were created while this was disabled aren't included in
+/// the breakdown.
+pub fn set_per_class(enabled: bool) {
+    PER_CLASS.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns the number of live [`GlobalRef`]s by the binary name of their
+/// object's class, such as `"java.lang.String"`.
+///
+/// Only references that were created while [`set_per_class`] was enabled are
+/// included. The class is `"<unknown>"` if it couldn't be looked up.
+pub fn global_refs_by_class() -> HashMap<String, usize> {
+    GLOBAL_REFS.by_class()
+}
+
+/// Returns the number of live [`WeakRef`]s by the binary name of their
+/// object's class.
+///
+/// See [`global_refs_by_class`].
+pub fn weak_refs_by_class() -> HashMap<String, usize> {
+    WEAK_REFS.by_class()
+}
+
+pub(crate) fn global_ref_created(vm: &JavaVM, raw: sys::jobject) {
+    GLOBAL_REFS.created(vm, raw);
+}
+
+pub(crate) fn global_ref_deleted(raw: sys::jobject) {
+    GLOBAL_REFS.deleted(raw);
+}
+
+pub(crate) fn weak_ref_created(vm: &JavaVM, raw: sys::jweak) {
+    WEAK_REFS.created(vm, raw);
+}
+
+pub(crate) fn weak_ref_deleted(raw: sys::jweak) {
+    WEAK_REFS.deleted(raw);
+}
+
+/// Looks up the name of the class of `raw`, a global or weak global
+/// reference, without disturbing a pending exception.
+///
+/// Returns `None` if the name can't be looked up, or if `raw` is a weak
+/// reference whose object has already been collected.
+fn class_name(vm: &JavaVM, raw: sys::jobject) -> Option<String> {
+    // Safety: the reference has just been created on this thread, so it's
+    // attached
+    let mut env = unsafe { vm.get_env(JNIVersion::V1_4) }.ok()?;
+    if env.exception_check() {
+        return None;
+    }
+    let name = env.with_local_frame(4, |env| -> Result<Option<String>> {
+        // `GetObjectClass` can't be given a weak reference that has been
+        // cleared, so the object is looked up through a local reference,
+        // which is null if it has been.
+        // Safety: `raw` is a valid global or weak global reference
+        let obj = unsafe { jni_call_unchecked!(env, v1_2, NewLocalRef, raw) };
+        if obj.is_null() {
+            return Ok(None);
+        }
+        let obj = unsafe { JObject::from_raw(obj) };
+        let class = env.get_object_class(&obj)?;
+        let name = env
+            .call_method(&class, "getName", "()Ljava/lang/String;", &[])?
+            .l()?;
+        JString::from(name).to_rust_string(env).map(Some)
+    });
+    if name.is_err() {
+        env.exception_clear();
+    }
+    name.ok().flatten()
+}
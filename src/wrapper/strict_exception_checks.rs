@@ -0,0 +1,55 @@
+//! Checks for JNI calls that are made while a Java exception is pending.
+//!
+//! With the `strict-exception-checks` feature, every [`JNIEnv`] method that
+//! checks for an exception after its JNI call also checks that no exception
+//! is pending before it, and reports the call if there is one. Calling most
+//! JNI functions with a pending exception is undefined behavior, and with
+//! this crate it makes the call fail with [`Error::JavaException`] for an
+//! exception that was thrown earlier, so a missed check turns into errors
+//! that are hard to trace back to it.
+//!
+//! By default, this panics in debug builds and logs an error with a
+//! backtrace otherwise (see [`set_action`]). The backtrace of the panic or
+//! the log message shows the call site, if backtraces are enabled with
+//! `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+//!
+//! This adds an `ExceptionCheck` call to every checked call, and shouldn't
+//! be enabled in production.
+
+use std::backtrace::Backtrace;
+
+use log::error;
+
+use crate::diagnostics::{Action, ActionSetting};
+#[cfg(doc)]
+use crate::{errors::Error, JNIEnv};
+
+static ACTION: ActionSetting = ActionSetting::new();
+
+/// Sets what to do when a JNI call is made while an exception is pending.
+///
+/// With [`Action::Log`], the error is logged with a backtrace and the call is
+/// made anyway. This applies to all threads. By default, this is
+/// [`Action::Panic`] in debug builds and [`Action::Log`] otherwise.
+pub fn set_action(action: Action) {
+    ACTION.set(action);
+}
+
+/// Returns the action that's set by [`set_action`].
+pub fn action() -> Action {
+    ACTION.get()
+}
+
+/// Reports that the JNI function `name` is about to be called while an
+/// exception is pending.
+pub(crate) fn pending_exception(name: &'static str) {
+    let message = format!(
+        "JNI function {} was called while a Java exception was pending; \
+         an earlier error wasn't handled",
+        name
+    );
+    match action() {
+        Action::Panic => panic!("{}", message),
+        Action::Log => error!("{}\n{}", message, Backtrace::capture()),
+    }
+}
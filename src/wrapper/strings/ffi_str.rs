@@ -1,6 +1,7 @@
 use std::{
     borrow::{Borrow, Cow, ToOwned},
     ffi::{CStr, CString},
+    fmt,
     os::raw::c_char,
 };
 
@@ -116,6 +117,32 @@ impl JNIString {
         string.into()
     }
 
+    /// Formats `args` directly into a Java-compatible string, like
+    /// [`format!`] followed by [`JNIString::new`], but without building an
+    /// intermediate [`String`].
+    ///
+    /// This is usually called through the [`jni_format!`][crate::jni_format]
+    /// macro.
+    pub fn from_format(args: fmt::Arguments) -> Self {
+        struct Writer(Vec<u8>);
+
+        impl fmt::Write for Writer {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.extend_from_slice(&to_java_cesu8(s));
+                Ok(())
+            }
+        }
+
+        let mut writer = Writer(Vec::new());
+        fmt::Write::write_fmt(&mut writer, args)
+            .expect("a Display implementation returned an error unexpectedly");
+        // Safety: modified UTF-8 encodes U+0000 as two non-zero bytes, so the
+        // encoded pieces don't contain any nul bytes.
+        JNIString {
+            internal: unsafe { CString::from_vec_unchecked(writer.0) },
+        }
+    }
+
     /// Converts a `CString` into a `JNIString`.
     ///
     /// This method is zero-cost.
@@ -210,6 +237,42 @@ impl JNIStr {
         &*(cstr as *const CStr as *const JNIStr)
     }
 
+    /// Converts a `&'static CStr` to a `&'static JNIStr`, checking that it is
+    /// encoded in Java's [Modified UTF-8].
+    ///
+    /// This is a `const fn`, so it can be used to build `&'static JNIStr`
+    /// constants, in which case an invalid string is a compile-time error:
+    ///
+    /// ```rust
+    /// # use std::ffi::CStr;
+    /// # use jni::strings::JNIStr;
+    /// const CLASS: &JNIStr = JNIStr::from_cstr_checked(
+    ///     match CStr::from_bytes_with_nul(b"java/lang/String\0") {
+    ///         Ok(cstr) => cstr,
+    ///         Err(_) => panic!("not nul-terminated"),
+    ///     },
+    /// );
+    /// assert_eq!(CLASS.to_str(), "java/lang/String");
+    /// ```
+    ///
+    /// Standard UTF-8 strings are valid Modified UTF-8 as long as they don't
+    /// contain code points greater than U+FFFF, which Modified UTF-8 encodes
+    /// as surrogate pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is not valid Modified UTF-8. In a const context,
+    /// this fails compilation instead.
+    ///
+    /// [Modified UTF-8]: https://docs.oracle.com/en/java/javase/11/docs/specs/jni/types.html#modified-utf-8-strings
+    pub const fn from_cstr_checked(cstr: &'static CStr) -> &'static JNIStr {
+        if !is_modified_utf8(cstr.to_bytes()) {
+            panic!("string is not valid modified UTF-8");
+        }
+        // Safety: the string was just checked to be modified UTF-8.
+        unsafe { Self::from_cstr_unchecked(cstr) }
+    }
+
     /// Returns a `CStr` view of the string.
     ///
     /// To get a view of the raw bytes of the string, call this method, then
@@ -256,6 +319,78 @@ impl JNIStr {
     }
 }
 
+/// Creates a [`JNIString`] using interpolation of runtime expressions, like
+/// [`format!`], but encoding the result directly into Java's [modified UTF-8].
+///
+/// This is useful for building class and member names at runtime:
+///
+/// ```rust
+/// # use jni::jni_format;
+/// let name = "Inner";
+/// let class = jni_format!("com/example/Outer${}", name);
+/// assert_eq!(class.to_str(), "com/example/Outer$Inner");
+/// ```
+///
+/// See [`JNIString::from_format`].
+///
+/// [modified UTF-8]: https://en.wikipedia.org/wiki/UTF-8#Modified_UTF-8
+#[macro_export]
+macro_rules! jni_format {
+    ($($arg:tt)*) => {
+        $crate::strings::JNIString::from_format(::std::format_args!($($arg)*))
+    };
+}
+
+/// Returns whether `bytes` (without the nul terminator) are valid [modified
+/// UTF-8].
+///
+/// That is, the bytes are a sequence of one to three byte UTF-8 encodings of
+/// code points U+0001 through U+FFFF (including surrogates), or of the two byte
+/// encoding `0xC0 0x80` of U+0000.
+///
+/// [modified UTF-8]: https://en.wikipedia.org/wiki/UTF-8#Modified_UTF-8
+const fn is_modified_utf8(bytes: &[u8]) -> bool {
+    const fn is_continuation(byte: u8) -> bool {
+        byte & 0xC0 == 0x80
+    }
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let lead = bytes[i];
+        match lead {
+            0x01..=0x7F => i += 1,
+            0xC0..=0xDF => {
+                if i + 1 >= bytes.len() || !is_continuation(bytes[i + 1]) {
+                    return false;
+                }
+                let code_point = ((lead as u32 & 0x1F) << 6) | (bytes[i + 1] as u32 & 0x3F);
+                // Overlong encodings are only allowed for U+0000.
+                if code_point != 0 && code_point < 0x80 {
+                    return false;
+                }
+                i += 2;
+            }
+            0xE0..=0xEF => {
+                if i + 2 >= bytes.len()
+                    || !is_continuation(bytes[i + 1])
+                    || !is_continuation(bytes[i + 2])
+                {
+                    return false;
+                }
+                let code_point = ((lead as u32 & 0x0F) << 12)
+                    | ((bytes[i + 1] as u32 & 0x3F) << 6)
+                    | (bytes[i + 2] as u32 & 0x3F);
+                if code_point < 0x800 {
+                    return false;
+                }
+                i += 3;
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
 // impls for CoW
 impl Borrow<JNIStr> for JNIString {
     fn borrow(&self) -> &JNIStr {
@@ -284,3 +419,39 @@ impl AsRef<JNIStr> for JNIString {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{is_modified_utf8, JNIString};
+
+    #[test]
+    fn modified_utf8_validation() {
+        assert!(is_modified_utf8(b""));
+        assert!(is_modified_utf8(b"java/lang/String"));
+        assert!(is_modified_utf8("caf\u{e9} \u{2603}".as_bytes()));
+        // U+0000 and a surrogate pair for U+1F600
+        assert!(is_modified_utf8(b"a\xC0\x80b"));
+        assert!(is_modified_utf8(b"\xED\xA0\xBD\xED\xB8\x80"));
+
+        // Standard UTF-8 four byte sequence for U+1F600
+        assert!(!is_modified_utf8("\u{1F600}".as_bytes()));
+        // Overlong encodings, other than of U+0000
+        assert!(!is_modified_utf8(b"\xC1\xBF"));
+        assert!(!is_modified_utf8(b"\xE0\x80\xAF"));
+        // Truncated and stray continuation bytes
+        assert!(!is_modified_utf8(b"\xC3"));
+        assert!(!is_modified_utf8(b"\xE2\x98"));
+        assert!(!is_modified_utf8(b"\x80"));
+    }
+
+    #[test]
+    fn jni_format() {
+        let name = "caf\u{e9}\u{0}\u{1F600}";
+        let formatted = crate::jni_format!("com/example/{}_{:03}", name, 7);
+        assert_eq!(
+            formatted.as_cstr(),
+            JNIString::new(format!("com/example/{}_{:03}", name, 7)).as_cstr()
+        );
+        assert_eq!(formatted.to_str(), format!("com/example/{}_007", name));
+    }
+}
@@ -0,0 +1,217 @@
+use jni_sys::jchar;
+use std::{
+    borrow::{Borrow, Cow},
+    convert::TryFrom,
+    ops::Deref,
+};
+
+use log::warn;
+
+use crate::{errors::*, objects::JString, JNIEnv};
+
+/// Represents the UTF-16 code units of a string in the JVM.
+///
+/// This type is returned by [`JNIEnv::get_string_utf16`]. It derefs to a
+/// `[u16]` slice of the string's code units, without any of the re-encoding
+/// that's needed to get a [`JavaStr`][crate::strings::JavaStr]. That makes
+/// it a better choice when the string is going to be passed on to something
+/// that expects UTF-16 anyway, such as a Windows API.
+///
+/// Unlike a `JavaStr`, the code units are _not_ null terminated.
+///
+/// The owned, Rust-side counterpart of this type is [`JavaString16`].
+pub struct JavaStr16<'local, 'other_local: 'obj_ref, 'obj_ref> {
+    internal: *const jchar,
+    len: usize,
+    obj: &'obj_ref JString<'other_local>,
+    env: JNIEnv<'local>,
+}
+
+impl<'local, 'other_local: 'obj_ref, 'obj_ref> JavaStr16<'local, 'other_local, 'obj_ref> {
+    /// Gets the UTF-16 code units of a [`JString`] with `GetStringChars`.
+    ///
+    /// The implementation may either create a copy of the characters or pin
+    /// them to avoid them being moved by the garbage collector. Either way,
+    /// they must be released with `ReleaseStringChars`, which is done when the
+    /// returned `JavaStr16` is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that the Object passed in is an instance of `java.lang.String`,
+    /// passing in anything else will lead to undefined behaviour (The JNI implementation
+    /// is likely to crash or abort the process).
+    pub(crate) unsafe fn from_env_totally_unchecked(
+        env: &JNIEnv<'local>,
+        obj: &'obj_ref JString<'other_local>,
+    ) -> Result<Self> {
+        let raw = null_check!(obj, "get_string_chars obj argument")?.as_raw();
+        let len = env.get_string_length(obj)? as usize;
+        let ptr: *const jchar =
+            jni_call_only_check_null_ret!(env, v1_1, GetStringChars, raw, std::ptr::null_mut())?;
+
+        Ok(Self {
+            internal: ptr,
+            len,
+            obj,
+
+            // Safety: The cloned `JNIEnv` will not be used to create any local references, only to
+            // release `ptr`.
+            env: env.unsafe_clone(),
+        })
+    }
+
+    /// Release the backing characters
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that [Self::internal] was obtained from `GetStringChars` for
+    /// [Self::obj], and that it's not used again afterwards.
+    unsafe fn release_string_chars(&mut self) -> Result<()> {
+        let obj = null_check!(self.obj, "release_string_chars obj argument")?;
+        // This method is safe to call in case of pending exceptions (see the chapter 2 of the spec)
+        jni_call_unchecked!(
+            self.env,
+            v1_1,
+            ReleaseStringChars,
+            obj.as_raw(),
+            self.internal
+        );
+
+        Ok(())
+    }
+
+    /// Decodes the code units into a Rust string, replacing any unpaired
+    /// surrogates with [`U+FFFD REPLACEMENT CHARACTER`][std::char::REPLACEMENT_CHARACTER].
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(self)
+    }
+}
+
+impl<'local, 'other_local: 'obj_ref, 'obj_ref> Deref for JavaStr16<'local, 'other_local, 'obj_ref> {
+    type Target = [u16];
+
+    fn deref(&self) -> &Self::Target {
+        if self.len == 0 {
+            return &[];
+        }
+        // Safety: `GetStringChars` returned a pointer to `len` code units, which stay valid until
+        // they are released on drop.
+        unsafe { std::slice::from_raw_parts(self.internal, self.len) }
+    }
+}
+
+impl<'local, 'other_local: 'obj_ref, 'obj_ref> AsRef<[u16]>
+    for JavaStr16<'local, 'other_local, 'obj_ref>
+{
+    fn as_ref(&self) -> &[u16] {
+        self
+    }
+}
+
+impl<'local, 'other_local: 'obj_ref, 'obj_ref> From<JavaStr16<'local, 'other_local, 'obj_ref>>
+    for JavaString16
+{
+    fn from(other: JavaStr16) -> JavaString16 {
+        JavaString16(other.to_vec())
+    }
+}
+
+impl<'local, 'other_local: 'obj_ref, 'obj_ref> Drop for JavaStr16<'local, 'other_local, 'obj_ref> {
+    fn drop(&mut self) {
+        match unsafe { self.release_string_chars() } {
+            Ok(()) => {}
+            Err(e) => warn!("error dropping java str16: {}", e),
+        }
+    }
+}
+
+/// An owned string of UTF-16 code units, which can be passed to
+/// [`JNIEnv::new_string_utf16`] to create a Java string without converting
+/// from UTF-8.
+///
+/// Like Java strings, this isn't required to be valid UTF-16, so it can hold
+/// unpaired surrogates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JavaString16(Vec<u16>);
+
+impl JavaString16 {
+    /// Creates an empty string.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unwraps the underlying code units.
+    pub fn into_vec(self) -> Vec<u16> {
+        self.0
+    }
+
+    /// Decodes the code units into a Rust string, replacing any unpaired
+    /// surrogates with [`U+FFFD REPLACEMENT CHARACTER`][std::char::REPLACEMENT_CHARACTER].
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(&self.0)
+    }
+}
+
+impl Deref for JavaString16 {
+    type Target = [u16];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u16]> for JavaString16 {
+    fn as_ref(&self) -> &[u16] {
+        self
+    }
+}
+
+impl Borrow<[u16]> for JavaString16 {
+    fn borrow(&self) -> &[u16] {
+        self
+    }
+}
+
+impl From<Vec<u16>> for JavaString16 {
+    fn from(other: Vec<u16>) -> Self {
+        Self(other)
+    }
+}
+
+impl From<&[u16]> for JavaString16 {
+    fn from(other: &[u16]) -> Self {
+        Self(other.to_vec())
+    }
+}
+
+impl<'a> From<Cow<'a, [u16]>> for JavaString16 {
+    fn from(other: Cow<'a, [u16]>) -> Self {
+        Self(other.into_owned())
+    }
+}
+
+impl From<&str> for JavaString16 {
+    fn from(other: &str) -> Self {
+        Self(other.encode_utf16().collect())
+    }
+}
+
+impl From<&String> for JavaString16 {
+    fn from(other: &String) -> Self {
+        Self::from(other.as_str())
+    }
+}
+
+impl From<String> for JavaString16 {
+    fn from(other: String) -> Self {
+        Self::from(other.as_str())
+    }
+}
+
+impl TryFrom<JavaString16> for String {
+    type Error = std::string::FromUtf16Error;
+
+    fn try_from(other: JavaString16) -> std::result::Result<Self, Self::Error> {
+        String::from_utf16(&other.0)
+    }
+}
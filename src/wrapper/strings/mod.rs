@@ -4,3 +4,6 @@ pub use self::ffi_str::*;
 
 mod java_str;
 pub use self::java_str::*;
+
+mod java_str16;
+pub use self::java_str16::*;
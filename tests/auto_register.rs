@@ -0,0 +1,51 @@
+#![cfg(all(feature = "invocation", feature = "auto-register"))]
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use jni::{
+    objects::{JClass, JClassLoader, JValue},
+    registry::{self, jni_native},
+    sys::jlong,
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+// A class with a `static native void run(long)` method, which isn't on the
+// class path
+const CLASS_NAME: &str = "io/github/jni_rs/RustRunnable";
+const CLASS_BYTECODE: &[u8] = include_bytes!("../src/java/io/github/jni_rs/RustRunnable.class");
+
+static RAN_WITH: AtomicI64 = AtomicI64::new(0);
+
+#[jni_native(class = "io.github.jni_rs.RustRunnable", sig = "(J)V")]
+extern "system" fn run(_env: JNIEnv, _class: JClass, value: jlong) {
+    RAN_WITH.store(value, Ordering::SeqCst);
+}
+
+#[test]
+fn register_collected_native_methods() {
+    let mut env = attach_current_thread();
+
+    let entries: Vec<_> = registry::native_entries().collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].class_name, CLASS_NAME);
+    assert_eq!(entries[0].name, "run");
+    assert_eq!(entries[0].rust_fn, "auto_register::run");
+    assert_eq!(
+        entries[0].java_type,
+        "native void io.github.jni_rs.RustRunnable.run(long)"
+    );
+    assert!(entries[0].location.file().ends_with("auto_register.rs"));
+
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    unwrap(env.define_class(CLASS_NAME, &loader, CLASS_BYTECODE), &env);
+
+    registry::register_collected(&mut env).unwrap();
+    unwrap(
+        env.call_static_method(CLASS_NAME, "run", "(J)V", &[JValue::Long(42)]),
+        &env,
+    );
+    assert_eq!(RAN_WITH.load(Ordering::SeqCst), 42);
+}
@@ -0,0 +1,296 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use assert_matches::assert_matches;
+
+use jni::{
+    convert::{jni_call, FromJava, IntoJava},
+    errors::{Error, JniError},
+    objects::{JObject, JObjectArray, JString, JValue, Reference},
+    sys::jint,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+/// Converted with the constructor `Locale(String, String)`.
+#[derive(Debug, PartialEq, IntoJava, FromJava)]
+#[java(class = "java.util.Locale", constructor)]
+struct Locale {
+    language: String,
+    country: String,
+}
+
+/// Converted with the no-argument constructor and setters.
+#[derive(Debug, PartialEq, IntoJava, FromJava)]
+#[java(class = "java/lang/Thread")]
+struct Thread {
+    name: String,
+    priority: i32,
+    #[java(getter = "isDaemon")]
+    daemon: bool,
+    #[java(skip)]
+    local_only: u8,
+}
+
+#[derive(Debug, PartialEq, FromJava)]
+#[java(class = "java.lang.ThreadGroup")]
+struct ThreadGroup {
+    name: String,
+    max_priority: i32,
+}
+
+#[derive(Debug, PartialEq, FromJava)]
+#[java(class = "java.lang.Thread")]
+struct ThreadWithGroup {
+    #[java(getter = "getThreadGroup")]
+    group: ThreadGroup,
+}
+
+#[derive(Debug, PartialEq, FromJava)]
+#[java(class = "java.net.HttpCookie")]
+struct Cookie {
+    name: String,
+    comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq, IntoJava, FromJava)]
+#[java(class = "java.util.concurrent.TimeUnit")]
+enum TimeUnit {
+    Seconds,
+    #[java(name = "MILLISECONDS")]
+    Millis,
+    Days,
+}
+
+#[derive(Debug, PartialEq, FromJava)]
+#[java(class = "java/lang/Thread$State")]
+enum ThreadState {
+    New,
+    Runnable,
+    TimedWaiting,
+}
+
+#[derive(Debug, Reference)]
+#[java(class = "java.lang.StringBuilder")]
+#[repr(transparent)]
+struct JStringBuilder<'local>(JObject<'local>);
+
+#[test]
+fn derive_constructor_round_trip() {
+    let mut env = attach_current_thread();
+
+    let locale = Locale {
+        language: "en".to_owned(),
+        country: "US".to_owned(),
+    };
+    let obj = unwrap(unwrap(locale.to_java(&mut env), &env).l(), &env);
+    let tag = unwrap(
+        env.call_method(&obj, "toLanguageTag", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let tag = unwrap(tag.l(), &env);
+    assert_eq!(
+        String::from(unwrap(env.get_string((&tag).into()), &env)),
+        "en-US"
+    );
+
+    let round_trip = unwrap(Locale::from_java(&mut env, JValue::from(&obj)), &env);
+    assert_eq!(round_trip, locale);
+}
+
+#[test]
+fn derive_setters_round_trip() {
+    let mut env = attach_current_thread();
+
+    let thread = Thread {
+        name: "worker".to_owned(),
+        priority: 3,
+        daemon: true,
+        local_only: 42,
+    };
+    let obj = unwrap(unwrap(thread.to_java(&mut env), &env).l(), &env);
+    let round_trip = unwrap(Thread::from_java(&mut env, JValue::from(&obj)), &env);
+    assert_eq!(
+        round_trip,
+        Thread {
+            local_only: 0,
+            ..thread
+        }
+    );
+
+    // Nested objects are converted with their own `FromJava` implementation.
+    let with_group = unwrap(
+        ThreadWithGroup::from_java(&mut env, JValue::from(&obj)),
+        &env,
+    );
+    assert_eq!(with_group.group.name, "main");
+    assert_eq!(with_group.group.max_priority, 10);
+}
+
+#[test]
+fn derive_option_and_errors() {
+    let mut env = attach_current_thread();
+
+    let name = unwrap(env.new_string("session"), &env);
+    let value = unwrap(env.new_string("1234"), &env);
+    let obj = unwrap(
+        env.new_object(
+            "java/net/HttpCookie",
+            "(Ljava/lang/String;Ljava/lang/String;)V",
+            &[(&name).into(), (&value).into()],
+        ),
+        &env,
+    );
+    let cookie = unwrap(Cookie::from_java(&mut env, JValue::from(&obj)), &env);
+    assert_eq!(
+        cookie,
+        Cookie {
+            name: "session".to_owned(),
+            comment: None,
+        }
+    );
+
+    assert_matches!(
+        Cookie::from_java(&mut env, JValue::from(&name)),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+    assert_matches!(
+        Cookie::from_java(&mut env, JValue::from(&JObject::null())),
+        Err(Error::NullPtr(_))
+    );
+    assert_matches!(
+        Cookie::from_java(&mut env, JValue::Int(1)),
+        Err(Error::WrongJValueType(_, _))
+    );
+}
+
+#[test]
+fn derive_enum_round_trip() {
+    let mut env = attach_current_thread();
+
+    for unit in [TimeUnit::Seconds, TimeUnit::Millis, TimeUnit::Days] {
+        let value = unwrap(unit.to_java(&mut env), &env);
+        let obj = unwrap(value.borrow().l(), &env);
+        let name = unwrap(
+            env.call_method(obj, "name", "()Ljava/lang/String;", &[]),
+            &env,
+        );
+        let name = unwrap(String::from_java(&mut env, name.borrow()), &env);
+        assert_eq!(
+            name,
+            match unit {
+                TimeUnit::Seconds => "SECONDS",
+                TimeUnit::Millis => "MILLISECONDS",
+                TimeUnit::Days => "DAYS",
+            }
+        );
+        assert_eq!(
+            unwrap(TimeUnit::from_java(&mut env, value.borrow()), &env),
+            unit
+        );
+    }
+
+    let state = unwrap(
+        env.get_static_field(
+            "java/lang/Thread$State",
+            "TIMED_WAITING",
+            "Ljava/lang/Thread$State;",
+        ),
+        &env,
+    );
+    assert_eq!(
+        unwrap(ThreadState::from_java(&mut env, state.borrow()), &env),
+        ThreadState::TimedWaiting
+    );
+
+    let hours = unwrap(
+        env.get_static_field(
+            "java/util/concurrent/TimeUnit",
+            "HOURS",
+            "Ljava/util/concurrent/TimeUnit;",
+        ),
+        &env,
+    );
+    assert_matches!(
+        TimeUnit::from_java(&mut env, hours.borrow()),
+        Err(Error::UnknownEnumConstant(name)) if name == "HOURS"
+    );
+    assert_matches!(
+        TimeUnit::from_java(&mut env, state.borrow()),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+}
+
+#[test]
+fn derive_reference() {
+    let mut env = attach_current_thread();
+
+    let class = unwrap(JStringBuilder::lookup_class(&mut env), &env);
+    let expected = unwrap(env.find_class("java/lang/StringBuilder"), &env);
+    assert!(env.is_same_object(&class, &expected));
+    // The second lookup uses the cached class
+    let cached = unwrap(JStringBuilder::lookup_class(&mut env), &env);
+    assert!(env.is_same_object(&cached, &expected));
+
+    let obj = unwrap(env.new_object(&class, "()V", &[]), &env);
+    let builder = JStringBuilder::from(obj);
+    let array = unwrap(
+        JObjectArray::<JStringBuilder>::new(&mut env, 2, &builder),
+        &env,
+    );
+    let element = unwrap(array.get_element(&mut env, 1), &env);
+    assert!(env.is_same_object(&element, &builder));
+    assert!(JStringBuilder::default().is_null());
+
+    let raw = builder.into_raw();
+    let builder = unsafe { JStringBuilder::from_raw(raw) };
+    assert!(unwrap(env.is_instance_of(&builder, &class), &env));
+}
+
+#[test]
+fn jni_call_methods() {
+    let mut env = attach_current_thread();
+
+    let string = unwrap(env.new_string("hello"), &env);
+    assert_eq!(
+        unwrap(jni_call!(&mut env, string.length() -> jint), &env),
+        5
+    );
+    assert_eq!(
+        unwrap(jni_call!(&mut env, string.charAt(1: jint) -> jchar), &env),
+        'e' as u16
+    );
+
+    let builder = unwrap(env.new_object("java/lang/StringBuilder", "()V", &[]), &env);
+    for _ in 0..2 {
+        let returned = unwrap(
+            jni_call!(
+                &mut env,
+                builder.append(&string: &JString) -> JObject as "Ljava/lang/StringBuilder;"
+            ),
+            &env,
+        );
+        assert!(env.is_same_object(&returned, &builder));
+    }
+    let built = unwrap(jni_call!(&mut env, builder.toString() -> JString), &env);
+    assert_eq!(
+        String::from(unwrap(env.get_string(&built), &env)),
+        "hellohello"
+    );
+
+    // The same call site is used with different classes
+    for class in ["java/util/ArrayList", "java/util/LinkedList"] {
+        let list = unwrap(env.new_object(class, "()V", &[]), &env);
+        let added = jni_call!(&mut env, list.add(&string: &JObject) -> bool);
+        assert!(unwrap(added, &env));
+        assert_eq!(unwrap(jni_call!(&mut env, list.size() -> jint), &env), 1);
+        unwrap(jni_call!(&mut env, list.clear()), &env);
+        assert_eq!(unwrap(jni_call!(&mut env, list.size() -> jint), &env), 0);
+    }
+
+    let null = JObject::null();
+    assert_matches!(
+        jni_call!(&mut env, null.hashCode() -> jint),
+        Err(Error::NullPtr(_))
+    );
+}
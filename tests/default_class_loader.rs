@@ -0,0 +1,49 @@
+#![cfg(feature = "invocation")]
+
+use assert_matches::assert_matches;
+use jni::{
+    errors::Error,
+    objects::{JClassLoader, JValue},
+};
+
+mod util;
+use util::{attach_current_thread, jvm, unwrap};
+
+const CLASS_NAME: &str = "io/github/jni_rs/RustRunnable";
+const CLASS_BYTECODE: &[u8] = include_bytes!("../src/java/io/github/jni_rs/RustRunnable.class");
+
+// The default class loader is global, so this is the only test in this file
+#[test]
+fn find_class_falls_back_to_default_class_loader() {
+    let mut env = attach_current_thread();
+    assert!(jvm().default_class_loader().is_none());
+
+    // Define a class in a class loader that `FindClass` doesn't use
+    let system = unwrap(JClassLoader::system(&mut env), &env);
+    let loader = unwrap(
+        env.new_object(
+            "java/security/SecureClassLoader",
+            "(Ljava/lang/ClassLoader;)V",
+            &[JValue::from(&system)],
+        ),
+        &env,
+    );
+    let class = unwrap(env.define_class(CLASS_NAME, &loader, CLASS_BYTECODE), &env);
+
+    assert_matches!(env.find_class(CLASS_NAME), Err(Error::JavaException));
+    env.exception_clear();
+
+    jvm().set_default_class_loader(unwrap(env.new_global_ref(&loader), &env));
+    let found = unwrap(env.find_class(CLASS_NAME), &env);
+    assert!(env.is_same_object(&class, &found));
+
+    // A class that the default class loader can't find either throws the
+    // original exception
+    assert_matches!(env.find_class("does/not/Exist"), Err(Error::JavaException));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear();
+    assert!(unwrap(
+        env.is_instance_of(&exception, "java/lang/NoClassDefFoundError"),
+        &env
+    ));
+}
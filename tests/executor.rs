@@ -1,15 +1,21 @@
 #![cfg(feature = "invocation")]
 
 use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc, Barrier,
+        mpsc, Arc, Barrier,
     },
-    thread::spawn,
+    thread::{self, spawn},
     time::Duration,
 };
 
-use jni::{objects::AutoLocal, sys::jint, Executor};
+use jni::{
+    errors::Error,
+    objects::{AutoLocal, JValue},
+    sys::jint,
+    Executor,
+};
 
 use rusty_fork::rusty_fork_test;
 
@@ -35,6 +41,108 @@ fn concurrent_threads() {
     test_concurrent_threads(executor, THREAD_NUM)
 }
 
+#[test]
+fn local_frame_capacity() {
+    const STRING_NUM: usize = 256;
+    let executor = Executor::new(jvm().clone()).local_frame_capacity(STRING_NUM as jint);
+    let strings = executor
+        .with_attached(|env| {
+            let mut strings = Vec::with_capacity(STRING_NUM);
+            for i in 0..STRING_NUM {
+                strings.push(env.new_string(i.to_string())?);
+            }
+            Ok::<_, jni::errors::Error>(strings.len())
+        })
+        .unwrap();
+    assert_eq!(strings, STRING_NUM);
+}
+
+#[test]
+#[should_panic(expected = "capacity should be a positive integer")]
+fn local_frame_capacity_zero() {
+    let _ = Executor::new(jvm().clone()).local_frame_capacity(0);
+}
+
+#[test]
+fn thread_pool_execute_blocking() {
+    let executor = Executor::new(jvm().clone()).thread_pool(2);
+    let (thread, abs) = executor
+        .execute_blocking(|env| {
+            let abs = env
+                .call_static_method("java/lang/Math", "abs", "(I)I", &[JValue::from(-3)])?
+                .i()?;
+            Ok::<_, Error>((thread::current().name().map(String::from), abs))
+        })
+        .unwrap();
+    assert!(thread.unwrap().starts_with("jni-executor-"));
+    assert_eq!(abs, 3);
+
+    let err = executor
+        .execute_blocking(|env| env.find_class("does/not/Exist").map(|_| ()))
+        .unwrap_err();
+    let exception = match err {
+        Error::CaughtJavaException(exception) => exception,
+        err => panic!("expected a caught exception, got {:?}", err),
+    };
+    let is_no_class_def = executor
+        .with_attached(|env| env.is_instance_of(&exception, "java/lang/NoClassDefFoundError"))
+        .unwrap();
+    assert!(is_no_class_def);
+
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        executor.execute_blocking(|_| -> Result<(), Error> { panic!("job panicked") })
+    }));
+    assert!(panicked.is_err());
+
+    // The pool still works after a job panicked
+    assert_eq!(executor.execute_blocking(|_| Ok::<_, Error>(1)).unwrap(), 1);
+}
+
+#[test]
+fn thread_pool_execute() {
+    let executor = Executor::new(jvm().clone()).thread_pool(2);
+    let (sender, receiver) = mpsc::channel();
+    for i in 0..4 {
+        let sender = sender.clone();
+        executor
+            .execute(move |env| {
+                let string = env.new_string(i.to_string())?;
+                let len = env.get_string_length(&string)?;
+                sender.send(len).unwrap();
+                Ok(())
+            })
+            .unwrap();
+    }
+    let lens: Vec<_> = receiver.iter().take(4).collect();
+    assert_eq!(lens, vec![1; 4]);
+}
+
+#[test]
+fn thread_pool_execute_clears_exceptions() {
+    let executor = Executor::new(jvm().clone()).thread_pool(1);
+    executor
+        .execute(|env| env.find_class("does/not/Exist").map(|_| ()))
+        .unwrap();
+    // The job runs on the same thread, after the one that threw
+    let pending = executor
+        .execute_blocking(|env| Ok::<_, Error>(env.exception_check()))
+        .unwrap();
+    assert!(!pending);
+}
+
+#[test]
+fn execute_without_thread_pool() {
+    let executor = Executor::new(jvm().clone());
+    let (sender, receiver) = mpsc::channel();
+    executor
+        .execute(move |_| {
+            sender.send(thread::current().id()).unwrap();
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(receiver.recv().unwrap(), thread::current().id());
+}
+
 fn test_single_thread(executor: Executor) {
     let mut atomic = AtomicIntegerProxy::new(executor, 0).unwrap();
     assert_eq!(0, atomic.get().unwrap());
@@ -190,6 +298,10 @@ fn test_destroy() {
         drop(jvm);
         println!("MAIN: jvm destroyed");
     }
+    assert!(matches!(
+        util::jvm().attach_current_thread(),
+        Err(Error::JavaVMDestroyed)
+    ));
 
     println!("MAIN: joining (waiting for) all threads");
     let mut joined = 0;
@@ -0,0 +1,31 @@
+#![cfg(all(feature = "invocation", unix))]
+
+use std::sync::Mutex;
+
+use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+
+static OUTPUT: Mutex<String> = Mutex::new(String::new());
+
+fn capture(message: &str) {
+    OUTPUT.lock().unwrap().push_str(message);
+}
+
+// This is in its own test binary, since a JVM can't be created again in the
+// same process after it failed to be created
+#[test]
+fn vfprintf_hook_captures_jvm_output() {
+    let jvm_args = InitArgsBuilder::new()
+        .version(JNIVersion::V1_8)
+        .option("-Xno-such-option")
+        .vfprintf_hook(capture)
+        .build()
+        .unwrap_or_else(|e| panic!("{:#?}", e));
+
+    assert!(JavaVM::new(jvm_args).is_err());
+    let output = OUTPUT.lock().unwrap();
+    assert!(
+        output.contains("Unrecognized option: -Xno-such-option"),
+        "{:?}",
+        output
+    );
+}
@@ -0,0 +1,22 @@
+package io.github.jni_rs;
+
+/** Compiled by the `include_java_class!` test. */
+public class Greeter extends GreeterBase {
+    public static String greet(String name) {
+        return new Greeter().greeting(new Name(name));
+    }
+
+    static class Name {
+        final String value;
+
+        Name(String value) {
+            this.value = value;
+        }
+    }
+}
+
+abstract class GreeterBase {
+    String greeting(Greeter.Name name) {
+        return "Hello, " + name.value + "!";
+    }
+}
@@ -0,0 +1,12 @@
+package io.github.jni_rs;
+
+/**
+ * A class with a native method, which is used to test registering native
+ * methods.
+ *
+ * <p>The class is compiled with {@code javac --release 8 -g:none} and checked
+ * in as {@code NativeAdder.class}.
+ */
+public class NativeAdder {
+    public static native int add(int a, int b);
+}
@@ -0,0 +1,22 @@
+package io.github.jni_rs;
+
+import java.io.InputStream;
+
+/**
+ * An {@link InputStream} that claims to have read one more byte than it was
+ * asked for, which is used to test that jni-rs doesn't trust the count.
+ *
+ * <p>The class is compiled with {@code javac --release 8 -g:none} and checked
+ * in as {@code OverReportingInputStream.class}.
+ */
+public class OverReportingInputStream extends InputStream {
+    @Override
+    public int read() {
+        return 0;
+    }
+
+    @Override
+    public int read(byte[] b, int off, int len) {
+        return len + 1;
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "invocation")]
+
+use jni::JavaVM;
+
+mod util;
+use util::{call_java_abs, jvm};
+
+#[test]
+fn existing_adopts_created_vm() {
+    let created = jvm();
+    assert!(JavaVM::singleton().is_none());
+
+    let existing = JavaVM::existing()
+        .unwrap_or_else(|e| panic!("{:#?}", e))
+        .expect("the JavaVM was created");
+    assert_eq!(existing.get_raw(), created.get_raw());
+    assert_eq!(
+        JavaVM::singleton().map(|vm| vm.get_raw()),
+        Some(created.get_raw())
+    );
+
+    let mut env = existing.attach_current_thread().unwrap();
+    assert_eq!(call_java_abs(&mut env, -3), 3);
+}
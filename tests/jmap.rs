@@ -41,3 +41,24 @@ pub fn jmap_push_and_iterate() {
     let orig = data.to_vec();
     assert_eq!(orig, collected);
 }
+
+#[test]
+pub fn jmap_typed_put_and_get() {
+    let mut env = attach_current_thread();
+
+    let map_object = unwrap(env.new_object("java/util/HashMap", "()V", &[]), &env);
+    let map: JMap<JString, JString> = unwrap(JMap::from_env(&mut env, &map_object), &env).typed();
+
+    let key = unwrap(env.new_string("key"), &env);
+    let value = unwrap(env.new_string("value"), &env);
+    unwrap(map.put(&mut env, &key, &value), &env);
+
+    let found: JString = unwrap(map.get(&mut env, &key), &env).expect("value should be found");
+    let found: String = unwrap(env.get_string(&found), &env).into();
+    assert_eq!(found, "value");
+
+    let mut iter = unwrap(map.iter(&mut env), &env);
+    let (key, _): (JString, JString) = unwrap(iter.next(&mut env), &env).unwrap();
+    let key: String = unwrap(env.get_string(&key), &env).into();
+    assert_eq!(key, "key");
+}
@@ -5,20 +5,29 @@ use assert_matches::assert_matches;
 
 use jni::{
     descriptors::Desc,
-    errors::{CharToJavaError, Error},
+    errors::{CharToJavaError, Error, Exception, ExceptionMap, JniError, RegistrationStage},
     objects::{
-        AutoElements, AutoLocal, JByteBuffer, JList, JObject, JString, JThrowable, JValue,
-        ReleaseMode,
+        AutoElements, AutoLocal, JBigDecimal, JBigInteger, JByteArray, JByteBuffer, JClass,
+        JClassLoader, JCompletableFuture, JDoubleArray, JDuration, JExecutor, JFloatArray,
+        JIllegalArgumentException, JIllegalStateException, JInputStream, JInstant, JIntArray,
+        JInterruptedException, JIoException, JList, JLongArray, JMap, JModule,
+        JNullPointerException, JObject, JObjectArray, JOptional, JOutputStream, JPrimitiveArray,
+        JRunnable, JRuntimeException, JString, JStringBuilder, JThread, JThrowable, JValue,
+        ReferenceType, ReleaseMode, TypeArray,
     },
+    registry::{register_all, register_entries, ClassRegistration, NativeEntry},
     signature::{JavaType, Primitive, ReturnType},
-    strings::JNIString,
+    strings::{JNIString, JavaString16},
     sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort, jsize},
-    JNIEnv,
+    JNIEnv, JNIVersion, NativeMethod, NativeMethodEx,
 };
 
 mod util;
 use util::{attach_current_thread, unwrap};
 
+jni::jni_field!(java_type = "java.awt.Point", field x: jint);
+jni::jni_field!(java_type = "java.awt.Point", field y: jlong);
+
 static ARRAYLIST_CLASS: &str = "java/util/ArrayList";
 static EXCEPTION_CLASS: &str = "java/lang/Exception";
 static ARITHMETIC_EXCEPTION_CLASS: &str = "java/lang/ArithmeticException";
@@ -136,6 +145,40 @@ pub fn is_not_same_object_null() {
     assert!(env.is_same_object(JObject::null(), JObject::null()));
 }
 
+#[test]
+pub fn get_object_ref_type() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+    assert_eq!(
+        unwrap(env.get_object_ref_type(&string), &env),
+        ReferenceType::Local
+    );
+
+    let global = unwrap(env.new_global_ref(&string), &env);
+    assert_eq!(
+        unwrap(env.get_object_ref_type(&global), &env),
+        ReferenceType::Global
+    );
+    assert_eq!(unwrap(global.ref_type(&env), &env), ReferenceType::Global);
+
+    let weak = unwrap(env.new_weak_ref(&string), &env).unwrap();
+    let weak_obj = unsafe { JObject::from_raw(weak.as_raw()) };
+    assert_eq!(
+        unwrap(env.get_object_ref_type(&weak_obj), &env),
+        ReferenceType::WeakGlobal
+    );
+
+    assert_eq!(
+        unwrap(env.get_object_ref_type(JObject::null()), &env),
+        ReferenceType::Invalid
+    );
+    let null_global = unwrap(env.new_global_ref(JObject::null()), &env);
+    assert_eq!(
+        unwrap(null_global.ref_type(&env), &env),
+        ReferenceType::Invalid
+    );
+}
+
 #[test]
 pub fn get_static_public_field() {
     let mut env = attach_current_thread();
@@ -312,6 +355,44 @@ pub fn call_static_method_ok() {
     assert_eq!(val, 10);
 }
 
+#[test]
+pub fn call_static_method_cached_class() {
+    let mut env = attach_current_thread();
+
+    // `java/lang` classes are cached on first use, so the second call with the
+    // same class name doesn't need `FindClass`
+    for _ in 0..2 {
+        let x = JValue::from(-10);
+        let val: jint = unwrap(
+            env.call_static_method(MATH_CLASS, MATH_ABS_METHOD_NAME, MATH_ABS_SIGNATURE, &[x]),
+            &env,
+        )
+        .i()
+        .unwrap();
+        assert_eq!(val, 10);
+    }
+
+    unwrap(env.cache_class("java/util/Collections"), &env);
+    let list = unwrap(
+        env.call_static_method(
+            "java/util/Collections",
+            "emptyList",
+            "()Ljava/util/List;",
+            &[],
+        ),
+        &env,
+    )
+    .l()
+    .unwrap();
+    assert!(unwrap(env.is_instance_of(&list, "java/util/List"), &env));
+
+    assert_matches!(
+        env.cache_class("com/example/DoesNotExist"),
+        Err(Error::JavaException)
+    );
+    env.exception_clear();
+}
+
 #[test]
 pub fn call_static_method_unchecked_ok() {
     let mut env = attach_current_thread();
@@ -414,6 +495,56 @@ pub fn call_new_object_with_array_class() {
     assert!(result.is_err())
 }
 
+#[test]
+pub fn alloc_object_skips_constructor() {
+    let mut env = attach_current_thread();
+
+    // `Thread()` would assign a name, so an allocated thread has a null name field.
+    let thread = unwrap(env.alloc_object("java/lang/Thread"), &env);
+    assert!(unwrap(
+        env.is_instance_of(&thread, "java/lang/Thread"),
+        &env
+    ));
+    let name = unwrap(env.get_field(&thread, "name", "Ljava/lang/String;"), &env);
+    assert!(unwrap(name.l(), &env).is_null());
+
+    let integer = unwrap(
+        env.alloc_object("java/util/concurrent/atomic/AtomicInteger"),
+        &env,
+    );
+    unwrap(env.set_field(&integer, "value", "I", JValue::Int(5)), &env);
+    let value = unwrap(env.call_method(&integer, "get", "()I", &[]), &env);
+    assert_eq!(unwrap(value.i(), &env), 5);
+}
+
+#[test]
+pub fn alloc_object_errors() {
+    let mut env = attach_current_thread();
+
+    assert_matches!(
+        env.alloc_object("java/lang/Number"),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/InstantiationException"),
+        None,
+    );
+    assert_matches!(
+        env.alloc_object("java/lang/Runnable"),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/InstantiationException"),
+        None,
+    );
+    assert_matches!(
+        env.alloc_object("[I"),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+}
+
 #[test]
 pub fn call_static_method_throws() {
     let mut env = attach_current_thread();
@@ -660,6 +791,85 @@ test_auto_array_read_write!(
     1.0
 );
 
+fn check_primitive_array_region<T>(env: &mut JNIEnv, array: JPrimitiveArray<T>, values: [T; 3])
+where
+    T: TypeArray + Default + PartialEq + std::fmt::Debug,
+{
+    unwrap(array.set_region(env, 1, &values), env);
+
+    let mut window = [T::default(); 2];
+    unwrap(array.get_region(env, 2, &mut window), env);
+    assert_eq!(window, [values[1], values[2]]);
+
+    let mut all = [T::default(); 4];
+    unwrap(array.get_region(env, 0, &mut all), env);
+    assert_eq!(all, [T::default(), values[0], values[1], values[2]]);
+
+    assert_matches!(
+        array.get_region(env, 3, &mut window),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(
+        env,
+        Some("java/lang/ArrayIndexOutOfBoundsException"),
+        None,
+    );
+    assert_matches!(
+        array.set_region(env, -1, &values),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(
+        env,
+        Some("java/lang/ArrayIndexOutOfBoundsException"),
+        None,
+    );
+}
+
+#[test]
+fn primitive_array_regions() {
+    let mut env = attach_current_thread();
+
+    let array = unwrap(env.new_boolean_array(4), &env);
+    check_primitive_array_region(&mut env, array, [true, false, true]);
+    let array = unwrap(env.new_byte_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1, -2, 3]);
+    let array = unwrap(env.new_char_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1, 2, 3]);
+    let array = unwrap(env.new_short_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1, -2, 3]);
+    let array = unwrap(env.new_int_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1, -2, 3]);
+    let array = unwrap(env.new_long_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1, -2, 3]);
+    let array = unwrap(env.new_float_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1.0, -2.5, 3.0]);
+    let array = unwrap(env.new_double_array(4), &env);
+    check_primitive_array_region(&mut env, array, [1.0, -2.5, 3.0]);
+}
+
+#[test]
+fn primitive_array_from_slice_to_vec() {
+    let env = attach_current_thread();
+
+    let ints = [i32::MIN, -1, 0, 1, i32::MAX];
+    let array = unwrap(JIntArray::from_slice(&env, &ints), &env);
+    assert_eq!(unwrap(env.get_array_length(&array), &env), 5);
+    assert_eq!(unwrap(array.to_vec(&env), &env), ints);
+
+    let longs = [i64::MIN, 42, i64::MAX];
+    let array = unwrap(JLongArray::from_slice(&env, &longs), &env);
+    assert_eq!(unwrap(array.to_vec(&env), &env), longs);
+
+    let doubles = [f64::MIN, 0.5, f64::INFINITY];
+    let array = unwrap(JDoubleArray::from_slice(&env, &doubles), &env);
+    assert_eq!(unwrap(array.to_vec(&env), &env), doubles);
+
+    let empty = unwrap(JFloatArray::from_slice(&env, &[]), &env);
+    assert!(unwrap(empty.to_vec(&env), &env).is_empty());
+
+    assert_matches!(JByteArray::default().to_vec(&env), Err(Error::NullPtr(_)));
+}
+
 #[test]
 #[ignore] // Disabled until issue #283 is resolved
 pub fn get_long_array_elements_commit() {
@@ -868,6 +1078,34 @@ pub fn get_direct_buffer_address_null_arg() {
     assert!(result.is_err());
 }
 
+#[test]
+pub fn with_borrowed_byte_buffer() {
+    let mut env = attach_current_thread();
+    let data = vec![1u8, 2, 3, 4];
+
+    let (retained, read_only, second) = unsafe {
+        env.with_borrowed_byte_buffer(&data, |env, buf| -> Result<_, Error> {
+            assert_eq!(env.get_direct_buffer_capacity(buf)?, data.len());
+            let read_only = env.call_method(buf, "isReadOnly", "()Z", &[])?.z()?;
+            let second = env
+                .call_method(buf, "get", "(I)B", &[JValue::Int(1)])?
+                .b()?;
+            Ok((env.new_global_ref(buf)?, read_only, second))
+        })
+    }
+    .unwrap();
+
+    assert!(read_only);
+    assert_eq!(second, 2);
+
+    // The buffer must not be usable once the closure has returned
+    let capacity = unwrap(env.call_method(&retained, "capacity", "()I", &[]), &env);
+    assert_eq!(capacity.i().unwrap(), 0);
+    let result = env.call_method(&retained, "get", "(I)B", &[JValue::Int(0)]);
+    assert_matches!(result, Err(Error::JavaException));
+    env.exception_clear();
+}
+
 // Group test for testing the family of new_PRIMITIVE_array functions with correct arguments
 #[test]
 pub fn new_primitive_array_ok() {
@@ -962,6 +1200,68 @@ fn get_super_class_null() {
     assert!(result.unwrap().is_none());
 }
 
+#[test]
+fn ensure_local_capacity() {
+    let mut env = attach_current_thread();
+    unwrap(env.ensure_local_capacity(256), &env);
+    unwrap(
+        env.with_local_frame(16, |env| {
+            env.ensure_local_capacity(256)?;
+            for i in 0..256 {
+                env.new_string(i.to_string())?;
+            }
+            Ok(())
+        }),
+        &env,
+    );
+    assert_matches!(
+        env.ensure_local_capacity(usize::MAX),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+}
+
+#[test]
+fn get_module() {
+    let mut env = attach_current_thread();
+    // Modules were added in Java 9
+    if env.version() < JNIVersion::V9 {
+        assert_matches!(env.get_module(STRING_CLASS), Err(Error::UnsupportedVersion));
+        return;
+    }
+    let module = unwrap(env.get_module(STRING_CLASS), &env);
+    assert!(unwrap(module.is_named(&mut env), &env));
+    assert_eq!(
+        unwrap(module.name(&mut env), &env).as_deref(),
+        Some("java.base")
+    );
+    assert!(unwrap(module.descriptor(&mut env), &env).is_some());
+    // `java.base` is defined to the bootstrap class loader
+    assert!(unwrap(module.class_loader(&mut env), &env).is_null());
+
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    let unnamed = unwrap(
+        env.call_method(&loader, "getUnnamedModule", "()Ljava/lang/Module;", &[]),
+        &env,
+    );
+    let unnamed = JModule::from(unwrap(unnamed.l(), &env));
+    assert!(!unwrap(unnamed.is_named(&mut env), &env));
+    assert_eq!(unwrap(unnamed.name(&mut env), &env), None);
+    assert!(unwrap(unnamed.descriptor(&mut env), &env).is_none());
+}
+
+#[test]
+fn is_virtual_thread() {
+    let mut env = attach_current_thread();
+    let thread = unwrap(JThread::current(&mut env), &env);
+    assert!(!thread.is_null());
+    // Threads that are attached from native code are platform threads
+    assert!(!unwrap(thread.is_virtual(&env), &env));
+    assert_matches!(
+        env.is_virtual_thread(JObject::null()),
+        Err(Error::NullPtr(_))
+    );
+}
+
 #[test]
 fn convert_byte_array() {
     let env = attach_current_thread();
@@ -1092,123 +1392,1261 @@ fn short_lifetime_list_sub_fn_get_first_element<'local>(
 }
 
 #[test]
-fn get_object_array_element() {
+fn typed_list() {
     let mut env = attach_current_thread();
-    let array = env
-        .new_object_array(1, STRING_CLASS, JObject::null())
-        .unwrap();
-    assert!(!array.is_null());
-    assert!(env.get_object_array_element(&array, 0).unwrap().is_null());
-    let test_str = env.new_string("test").unwrap();
-    env.set_object_array_element(&array, 0, test_str).unwrap();
-    assert!(!env.get_object_array_element(&array, 0).unwrap().is_null());
+    let list_object = unwrap(env.new_object(ARRAYLIST_CLASS, "()V", &[]), &env);
+    let list: JList<JString> = unwrap(env.get_list(&list_object), &env).typed();
+
+    let first = unwrap(env.new_string("first"), &env);
+    let second = unwrap(env.new_string("second"), &env);
+    unwrap(list.add(&mut env, &second), &env);
+    unwrap(list.insert(&mut env, 0, &first), &env);
+    assert_eq!(unwrap(list.size(&mut env), &env), 2);
+
+    let element: JString = unwrap(list.get(&mut env, 1), &env).unwrap();
+    assert_eq!(unwrap(env.get_string(&element), &env).to_str(), "second");
+
+    let element: JString = unwrap(list.pop(&mut env), &env).unwrap();
+    assert_eq!(unwrap(env.get_string(&element), &env).to_str(), "second");
 }
 
 #[test]
-pub fn throw_new() {
+fn iterator_collect_and_for_each() {
     let mut env = attach_current_thread();
+    let list_object = unwrap(env.new_object(ARRAYLIST_CLASS, "()V", &[]), &env);
+    let list: JList<JString> = unwrap(env.get_list(&list_object), &env).typed();
+    for s in ["a", "b", "c"] {
+        let s = unwrap(env.new_string(s), &env);
+        unwrap(list.add(&mut env, &s), &env);
+    }
 
-    let result = env.throw_new(RUNTIME_EXCEPTION_CLASS, "Test Exception");
-    assert!(result.is_ok());
+    let iterator = unwrap(
+        env.call_method(&list_object, "iterator", "()Ljava/util/Iterator;", &[]),
+        &env,
+    );
+    let iterator = unwrap(iterator.l(), &env);
+    let mut iterator = unwrap(env.get_iterator(&iterator), &env).typed::<JString>();
+    let mut collected = Vec::new();
+    unwrap(
+        iterator.try_for_each(&mut env, |env, s| {
+            collected.push(String::from(env.get_string(s)?));
+            Ok(())
+        }),
+        &env,
+    );
+    assert_eq!(collected, ["a", "b", "c"]);
+    assert!(unwrap(iterator.next(&mut env), &env).is_none());
+
+    let iterator = unwrap(
+        env.call_method(&list_object, "iterator", "()Ljava/util/Iterator;", &[]),
+        &env,
+    );
+    let iterator = unwrap(iterator.l(), &env);
+    let mut iterator = unwrap(env.get_iterator(&iterator), &env);
+    let globals = unwrap(iterator.collect_vec(&mut env), &env);
+    assert_eq!(globals.len(), 3);
+}
+
+#[test]
+fn optional_conversions() {
+    let mut env = attach_current_thread();
+
+    let empty = unwrap(JOptional::<JString>::from_option(&mut env, None), &env);
+    assert!(!unwrap(empty.is_present(&mut env), &env));
+    assert!(unwrap(empty.or_null(&mut env), &env).is_null());
+    assert!(unwrap(empty.to_option(&mut env), &env).is_none());
+    let result = empty.get(&mut env).map(|_| ());
+    assert_matches!(result, Err(Error::JavaException));
     assert_pending_java_exception_detailed(
         &mut env,
-        Some(RUNTIME_EXCEPTION_CLASS),
-        Some("Test Exception"),
+        Some("java/util/NoSuchElementException"),
+        None,
+    );
+
+    let value = unwrap(env.new_string("value"), &env);
+    let present = unwrap(
+        JOptional::<JString>::from_option(&mut env, Some(&value)),
+        &env,
     );
+    assert!(unwrap(present.is_present(&mut env), &env));
+    let got = unwrap(present.get(&mut env), &env);
+    assert_eq!(String::from(unwrap(env.get_string(&got), &env)), "value");
+    let got = unwrap(present.to_option(&mut env), &env).expect("value should be present");
+    assert_eq!(String::from(unwrap(env.get_string(&got), &env)), "value");
 }
 
 #[test]
-pub fn throw_new_fail() {
+fn register_all_reports_failures() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn init(env: &mut JNIEnv, class: &JClass) -> jni::errors::Result<()> {
+        assert!(env.is_assignable_from(class, INTEGER_CLASS)?);
+        INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    extern "system" fn not_called(_env: JNIEnv, _class: JClass) {}
+
     let mut env = attach_current_thread();
+    let missing_method = [NativeMethod {
+        name: "noSuchNativeMethod".into(),
+        sig: "()V".into(),
+        fn_ptr: not_called as *mut _,
+    }];
+    let registrations = [
+        ClassRegistration {
+            class_name: "java/lang/Integer",
+            methods: &[],
+            init: Some(init),
+        },
+        ClassRegistration {
+            class_name: "com/example/DoesNotExist",
+            methods: &[],
+            init: Some(init),
+        },
+        ClassRegistration {
+            class_name: "java/lang/Integer",
+            methods: &missing_method,
+            init: Some(init),
+        },
+    ];
+
+    let err = register_all(&mut env, &registrations).unwrap_err();
+    assert!(!env.exception_check());
+    assert_eq!(INIT_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(err.failures.len(), 2);
+
+    assert_eq!(err.failures[0].class_name, "com/example/DoesNotExist");
+    assert_eq!(err.failures[0].stage, RegistrationStage::Load);
+    assert_matches!(err.failures[0].source, Error::JavaException);
+    assert!(err.failures[0]
+        .exception
+        .as_deref()
+        .unwrap()
+        .contains("ClassNotFoundException"));
 
-    let result = env.throw_new("java/lang/NonexistentException", "Test Exception");
-    assert!(result.is_err());
-    // Just to clear the java.lang.NoClassDefFoundError
-    assert_pending_java_exception(&mut env);
+    assert_eq!(err.failures[1].stage, RegistrationStage::RegisterNatives);
+    assert!(err.failures[1]
+        .exception
+        .as_deref()
+        .unwrap()
+        .contains("noSuchNativeMethod"));
 }
 
 #[test]
-pub fn throw_defaults() {
+fn completable_future_complete() {
     let mut env = attach_current_thread();
 
-    test_throwable_descriptor_with_default_type(&mut env, TEST_EXCEPTION_MESSAGE);
-    test_throwable_descriptor_with_default_type(&mut env, TEST_EXCEPTION_MESSAGE.to_owned());
-    test_throwable_descriptor_with_default_type(&mut env, JNIString::from(TEST_EXCEPTION_MESSAGE));
+    let future = unwrap(JCompletableFuture::<JString>::new(&mut env), &env);
+    assert!(!unwrap(future.is_done(&mut env), &env));
+
+    let value = unwrap(env.new_string("value"), &env);
+    assert!(unwrap(future.complete(&mut env, &value), &env));
+    assert!(unwrap(future.is_done(&mut env), &env));
+    assert!(!unwrap(future.complete(&mut env, &value), &env));
+
+    let failed = unwrap(JCompletableFuture::<JObject>::new(&mut env), &env);
+    let exception: JThrowable =
+        unwrap(env.new_object(RUNTIME_EXCEPTION_CLASS, "()V", &[]), &env).into();
+    assert!(unwrap(
+        failed.complete_exceptionally(&mut env, &exception),
+        &env
+    ));
+    assert!(unwrap(failed.is_done(&mut env), &env));
 }
 
+#[cfg(feature = "async")]
 #[test]
-pub fn test_conversion() {
-    let env = attach_current_thread();
-    let orig_obj: JObject = env.new_string("Hello, world!").unwrap().into();
+fn completable_future_into_future() {
+    use std::{
+        future::Future,
+        sync::Arc,
+        task::{Context, Poll, Wake},
+        thread::{self, Thread},
+    };
 
-    let obj: JObject = unwrap(env.new_local_ref(&orig_obj), &env);
-    let string = JString::from(obj);
-    let actual = JObject::from(string);
-    assert!(env.is_same_object(&orig_obj, actual));
+    struct ThreadWaker(Thread);
 
-    let global_ref = env.new_global_ref(&orig_obj).unwrap();
-    assert!(env.is_same_object(&orig_obj, global_ref));
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
 
-    let weak_ref = unwrap(env.new_weak_ref(&orig_obj), &env).expect("weak ref should not be null");
-    let actual =
-        unwrap(weak_ref.upgrade_local(&env), &env).expect("weak ref should not have been GC'd");
-    assert!(env.is_same_object(&orig_obj, actual));
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
 
-    let obj: JObject = unwrap(env.new_local_ref(&orig_obj), &env);
-    let auto_local = env.auto_local(obj);
-    assert!(env.is_same_object(&orig_obj, auto_local));
+    let mut env = attach_current_thread();
+
+    let future = unwrap(JCompletableFuture::<JString>::new(&mut env), &env);
+    let future_ref = unwrap(env.new_global_ref(&future), &env);
+    let output = unwrap(future.into_future(&mut env), &env);
+
+    let future: &JCompletableFuture<JString> = future_ref.as_obj().into();
+    let value = unwrap(env.new_string("value"), &env);
+    unwrap(future.complete(&mut env, &value), &env);
+
+    let value = block_on(output)
+        .unwrap()
+        .expect("future should complete normally");
+    let value: &JString = value.as_obj().into();
+    assert_eq!(String::from(unwrap(env.get_string(value), &env)), "value");
+
+    let failed = unwrap(JCompletableFuture::<JObject>::new(&mut env), &env);
+    let exception: JThrowable =
+        unwrap(env.new_object(RUNTIME_EXCEPTION_CLASS, "()V", &[]), &env).into();
+    unwrap(failed.complete_exceptionally(&mut env, &exception), &env);
+    let output = unwrap(failed.into_future(&mut env), &env);
+    let thrown = block_on(output)
+        .unwrap()
+        .expect_err("future should complete exceptionally");
+    assert!(env.is_same_object(&exception, &thrown));
 }
 
+#[cfg(feature = "async")]
 #[test]
-pub fn test_null_get_string() {
+fn completable_future_into_future_when_complete_fails() {
     let mut env = attach_current_thread();
-    let s = unsafe { JString::from_raw(std::ptr::null_mut() as _) };
-    let ret = env.get_string(&s);
-    assert!(ret.is_err());
+
+    // An `Object` has no `whenComplete` method, so registering the handler
+    // fails, and the exception is still pending once its state is released
+    let object = unwrap(env.new_object("java/lang/Object", "()V", &[]), &env);
+    let future = JCompletableFuture::<JObject>::from(object);
+    let result = future.into_future(&mut env);
+    // `CompletableFutureOutput` isn't `Debug`, so `assert_matches!` can't be used
+    assert!(matches!(result, Err(Error::JavaException)));
+    assert_pending_java_exception_detailed(&mut env, Some("java/lang/NoSuchMethodError"), None);
 }
 
+#[cfg(feature = "tokio")]
 #[test]
-pub fn test_invalid_list_get_string() {
+fn new_completable_future_from() {
     let mut env = attach_current_thread();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
 
-    let class = env.find_class("java/util/List").unwrap();
-    let class = JString::from(JObject::from(class));
-    let class = env.auto_local(class);
+    let value = unwrap(env.new_string("value"), &env);
+    let value = unwrap(env.new_global_ref(value), &env);
+    let future = unwrap(
+        env.new_completable_future_from(runtime.handle(), async move { Ok(value) }),
+        &env,
+    );
+    let failed = unwrap(
+        env.new_completable_future_from(runtime.handle(), async { Err(Error::TryLock) }),
+        &env,
+    );
+    let panicked = unwrap(
+        env.new_completable_future_from(runtime.handle(), async { panic!("the future panicked") }),
+        &env,
+    );
 
-    let ret = env.get_string(&class);
-    assert!(ret.is_err());
-}
+    // Run the spawned tasks, which complete the Java futures
+    runtime.block_on(async {
+        for _ in 0..4 {
+            tokio::task::yield_now().await;
+        }
+    });
 
-fn test_throwable_descriptor_with_default_type<'local, D>(env: &mut JNIEnv<'local>, descriptor: D)
-where
-    D: Desc<'local, JThrowable<'local>>,
-{
-    let result = descriptor.lookup(env);
-    assert!(result.is_ok());
-    let exception = result.unwrap();
-    let exception = exception.as_ref();
+    assert!(unwrap(future.is_done(&mut env), &env));
+    let value = unwrap(
+        env.call_method(&future, "join", "()Ljava/lang/Object;", &[]),
+        &env,
+    );
+    let value = JString::from(unwrap(value.l(), &env));
+    assert_eq!(String::from(unwrap(env.get_string(&value), &env)), "value");
 
-    assert_exception_type(env, exception, RUNTIME_EXCEPTION_CLASS);
-    assert_exception_message(env, exception, TEST_EXCEPTION_MESSAGE);
-}
+    assert!(unwrap(failed.is_done(&mut env), &env));
+    let result = env.call_method(&failed, "join", "()Ljava/lang/Object;", &[]);
+    assert_matches!(result, Err(Error::JavaException));
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/util/concurrent/CompletionException"),
+        Some("java.lang.RuntimeException: Mutex already locked"),
+    );
 
-// Helper method that asserts that result is Error and the cause is JavaException.
-fn assert_exception(res: &Result<jobject, Error>, expect_message: &str) {
-    assert!(res.is_err());
-    assert!(res
-        .as_ref()
-        .map_err(|error| matches!(error, Error::JavaException))
-        .expect_err(expect_message));
+    assert!(unwrap(panicked.is_done(&mut env), &env));
+    let result = env.call_method(&panicked, "join", "()Ljava/lang/Object;", &[]);
+    assert_matches!(result, Err(Error::JavaException));
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/util/concurrent/CompletionException"),
+        Some("java.lang.RuntimeException: Rust future panicked"),
+    );
 }
 
-// Shortcut to `assert_pending_java_exception_detailed()` without checking for expected  type and
-// message of exception.
-fn assert_pending_java_exception(env: &mut JNIEnv) {
-    assert_pending_java_exception_detailed(env, None, None)
+#[cfg(feature = "tokio")]
+#[test]
+fn spawn_attached() {
+    let env = attach_current_thread();
+    let vm = unwrap(env.get_java_vm(), &env);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let task = vm.spawn_attached(runtime.handle(), |vm| async move {
+        tokio::task::yield_now().await;
+        let env = vm.attach_current_thread()?;
+        let string = env.new_string("spawned")?;
+        env.get_string_length(&string)
+    });
+    let blocking = vm.spawn_blocking_attached(runtime.handle(), |env| {
+        let string = env.new_string("blocking")?;
+        Ok::<_, Error>((std::thread::current().id(), env.get_string_length(&string)?))
+    });
+
+    let (length, blocking) =
+        runtime.block_on(async { (task.await.unwrap(), blocking.await.unwrap()) });
+    assert_eq!(unwrap(length, &env), 7);
+    let (thread, blocking_length) = unwrap(blocking, &env);
+    assert_eq!(blocking_length, 8);
+    assert_ne!(thread, std::thread::current().id());
 }
 
-// Helper method that asserts there is a pending Java exception of `expected_type` with
-// `expected_message` and clears it if any.
+#[test]
+fn runnable_from_fn() {
+    let mut env = attach_current_thread();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let runnable = unwrap(
+        JRunnable::from_fn(&mut env, move |env| {
+            let string = env.new_string("run")?;
+            sender.send(env.get_string_length(&string)?).unwrap();
+            Ok(())
+        }),
+        &env,
+    );
+    unwrap(runnable.run(&mut env), &env);
+    assert_eq!(receiver.try_recv(), Ok(3));
+    // The closure is only run once
+    unwrap(runnable.run(&mut env), &env);
+    assert!(receiver.try_recv().is_err());
+
+    let failing = unwrap(JRunnable::from_fn(&mut env, |_| Err(Error::TryLock)), &env);
+    assert_matches!(failing.run(&mut env), Err(Error::JavaException));
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("Mutex already locked"),
+    );
+
+    let panicking = unwrap(
+        JRunnable::from_fn(&mut env, |_| panic!("closure panicked")),
+        &env,
+    );
+    assert_matches!(panicking.run(&mut env), Err(Error::JavaException));
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("Rust closure panicked"),
+    );
+}
+
+#[test]
+fn executor_execute_rust() {
+    let mut env = attach_current_thread();
+    let executor: JExecutor = unwrap(
+        env.call_static_method(
+            "java/util/concurrent/Executors",
+            "newSingleThreadExecutor",
+            "()Ljava/util/concurrent/ExecutorService;",
+            &[],
+        )
+        .and_then(|executor| executor.l()),
+        &env,
+    )
+    .into();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    unwrap(
+        executor.execute_rust(&mut env, move |env| {
+            let thread = JThread::current(env)?;
+            let name = env
+                .call_method(&thread, "getName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let name = env.get_string(&JString::from(name))?.into();
+            sender.send(name).unwrap();
+            Ok(())
+        }),
+        &env,
+    );
+    let name: String = receiver
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .unwrap();
+    assert!(name.starts_with("pool-"));
+
+    // A rejected closure is dropped without being run
+    unwrap(env.call_method(&executor, "shutdown", "()V", &[]), &env);
+    let dropped = std::sync::Arc::new(());
+    let closure_ref = dropped.clone();
+    let result = executor.execute_rust(&mut env, move |_| {
+        drop(closure_ref);
+        Ok(())
+    });
+    assert_matches!(result, Err(Error::JavaException));
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/util/concurrent/RejectedExecutionException"),
+        None,
+    );
+    assert_eq!(std::sync::Arc::strong_count(&dropped), 1);
+}
+
+#[test]
+fn register_native_methods_checked_reports_rejected_methods() {
+    extern "system" fn not_called(_env: JNIEnv, _class: JClass) {}
+
+    let mut env = attach_current_thread();
+    let methods = [NativeMethodEx::new(NativeMethod {
+        name: "noSuchNativeMethod".into(),
+        sig: "()V".into(),
+        fn_ptr: not_called as *mut _,
+    })
+    .rust_fn("jni_api::not_called")
+    .java_type("static native void noSuchNativeMethod()")];
+
+    let err = env
+        .register_native_methods_checked(INTEGER_CLASS, &methods)
+        .unwrap_err();
+    assert!(!env.exception_check());
+
+    let message = match err {
+        Error::RegisterNativesFailed(message) => message,
+        err => panic!("unexpected error: {:?}", err),
+    };
+    assert!(message.contains("noSuchNativeMethod()V"));
+    assert!(message.contains("static native void noSuchNativeMethod()"));
+    assert!(message.contains("jni_api::not_called"));
+    assert!(message.contains("jni_api.rs"));
+    assert!(message.contains("NoSuchMethodError"));
+}
+
+#[test]
+fn register_native_methods_checked_unregisters_on_failure() {
+    const CLASS_NAME: &str = "io/github/jni_rs/NativeAdder";
+    const CLASS_BYTECODE: &[u8] = include_bytes!("java/io/github/jni_rs/NativeAdder.class");
+
+    extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+        a + b
+    }
+
+    let mut env = attach_current_thread();
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    let class = unwrap(env.define_class(CLASS_NAME, &loader, CLASS_BYTECODE), &env);
+    let methods = [
+        NativeMethodEx::new(NativeMethod {
+            name: "add".into(),
+            sig: "(II)I".into(),
+            fn_ptr: add as *mut _,
+        }),
+        NativeMethodEx::new(NativeMethod {
+            name: "noSuchNativeMethod".into(),
+            sig: "()V".into(),
+            fn_ptr: add as *mut _,
+        }),
+    ];
+
+    let err = env
+        .register_native_methods_checked(&class, &methods)
+        .unwrap_err();
+    assert_matches!(err, Error::RegisterNativesFailed(_));
+    assert!(!env.exception_check());
+
+    // `add` is valid, but it mustn't stay registered when the other method is rejected
+    let result = env.call_static_method(&class, "add", "(II)I", &[1.into(), 2.into()]);
+    assert_matches!(result, Err(Error::JavaException));
+    assert_pending_java_exception_detailed(&mut env, Some("java/lang/UnsatisfiedLinkError"), None);
+
+    unwrap(
+        env.register_native_methods_checked(&class, &methods[..1]),
+        &env,
+    );
+    let sum = unwrap(
+        env.call_static_method(&class, "add", "(II)I", &[1.into(), 2.into()]),
+        &env,
+    );
+    assert_eq!(sum.i().unwrap(), 3);
+}
+
+#[test]
+fn register_entries_reports_rust_fn() {
+    extern "system" fn not_called(_env: JNIEnv, _class: JClass) {}
+
+    let mut env = attach_current_thread();
+    let entries = [NativeEntry {
+        class_name: INTEGER_CLASS,
+        name: "noSuchNativeMethod",
+        sig: "()V",
+        fn_ptr: || not_called as *mut _,
+        rust_fn: "jni_api::not_called",
+        java_type: "native void java.lang.Integer.noSuchNativeMethod()",
+        location: std::panic::Location::caller(),
+    }];
+
+    let err = register_entries(&mut env, &entries).unwrap_err();
+    assert!(!env.exception_check());
+    let message = match err {
+        Error::RegisterNativesFailed(message) => message,
+        err => panic!("unexpected error: {:?}", err),
+    };
+    assert!(message.contains(
+        "noSuchNativeMethod()V (Java: native void java.lang.Integer.noSuchNativeMethod()) \
+         implemented by jni_api::not_called declared at"
+    ));
+}
+
+#[test]
+fn input_and_output_stream_io() {
+    use std::io::{Read, Write};
+
+    let mut env = attach_current_thread();
+    // Larger than the buffer that's used to copy to and from Java
+    let data: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+
+    let output = unwrap(
+        env.new_object("java/io/ByteArrayOutputStream", "()V", &[]),
+        &env,
+    );
+    let output = JOutputStream::from(output);
+    {
+        let mut writer = output.writer(&mut env).unwrap();
+        writer.write_all(&data).unwrap();
+        writer.flush().unwrap();
+    }
+    unwrap(output.close(&mut env), &env);
+
+    let bytes = unwrap(env.call_method(&output, "toByteArray", "()[B", &[]), &env);
+    let bytes = unwrap(bytes.l(), &env);
+    let input = unwrap(
+        env.new_object(
+            "java/io/ByteArrayInputStream",
+            "([B)V",
+            &[JValue::from(&bytes)],
+        ),
+        &env,
+    );
+    let input = JInputStream::from(input);
+    let mut read = Vec::new();
+    input
+        .reader(&mut env)
+        .unwrap()
+        .read_to_end(&mut read)
+        .unwrap();
+    assert_eq!(read, data);
+}
+
+#[test]
+fn input_stream_exception_is_io_error() {
+    use std::io::Read;
+
+    let mut env = attach_current_thread();
+    // Reading from an unconnected pipe throws an `IOException`
+    let input = unwrap(env.new_object("java/io/PipedInputStream", "()V", &[]), &env);
+    let input = JInputStream::from(input);
+
+    let mut buf = [0u8; 16];
+    let err = input.reader(&mut env).unwrap().read(&mut buf).unwrap_err();
+    assert!(err.to_string().contains("IOException"));
+    assert!(!env.exception_check());
+}
+
+#[test]
+fn input_stream_over_reported_count_is_io_error() {
+    use std::io::{ErrorKind, Read};
+
+    const CLASS_NAME: &str = "io/github/jni_rs/OverReportingInputStream";
+    const CLASS_BYTECODE: &[u8] =
+        include_bytes!("java/io/github/jni_rs/OverReportingInputStream.class");
+
+    let mut env = attach_current_thread();
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    let class = unwrap(env.define_class(CLASS_NAME, &loader, CLASS_BYTECODE), &env);
+    let input = unwrap(env.new_object(&class, "()V", &[]), &env);
+    let input = JInputStream::from(input);
+
+    // The stream claims to have read 17 bytes into a 16 byte buffer
+    let mut buf = [0u8; 16];
+    let err = input.reader(&mut env).unwrap().read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(!env.exception_check());
+}
+
+#[test]
+fn instant_system_time_round_trip() {
+    use std::time::{Duration, SystemTime};
+
+    let mut env = attach_current_thread();
+    let times = [
+        SystemTime::now(),
+        SystemTime::UNIX_EPOCH,
+        SystemTime::UNIX_EPOCH - Duration::new(86_400, 500),
+    ];
+    for time in times {
+        let instant = unwrap(JInstant::from_system_time(&mut env, time), &env);
+        assert_eq!(unwrap(instant.to_system_time(&mut env), &env), time);
+    }
+
+    let instant = unwrap(
+        env.call_static_method(
+            "java/time/Instant",
+            "ofEpochMilli",
+            "(J)Ljava/time/Instant;",
+            &[JValue::from(-1500i64)],
+        ),
+        &env,
+    );
+    let instant = JInstant::from(unwrap(instant.l(), &env));
+    assert_eq!(
+        unwrap(instant.to_system_time(&mut env), &env),
+        SystemTime::UNIX_EPOCH - Duration::from_millis(1500)
+    );
+}
+
+#[test]
+fn duration_round_trip() {
+    use std::time::Duration;
+
+    let mut env = attach_current_thread();
+    let duration = Duration::new(90, 123_456_789);
+    let java_duration = unwrap(JDuration::from_duration(&mut env, duration), &env);
+    assert_eq!(unwrap(java_duration.to_duration(&mut env), &env), duration);
+
+    let millis = unwrap(
+        env.call_method(&java_duration, "toMillis", "()J", &[]),
+        &env,
+    );
+    assert_eq!(unwrap(millis.j(), &env), 90_123);
+
+    let negative = unwrap(
+        env.call_method(&java_duration, "negated", "()Ljava/time/Duration;", &[]),
+        &env,
+    );
+    let negative = JDuration::from(unwrap(negative.l(), &env));
+    assert_matches!(
+        negative.to_duration(&mut env),
+        Err(Error::TimeOutOfRange(_))
+    );
+
+    assert_matches!(
+        JDuration::from_duration(&mut env, Duration::from_secs(u64::MAX)),
+        Err(Error::TimeOutOfRange(_))
+    );
+}
+
+#[test]
+fn big_integer_round_trip() {
+    let mut env = attach_current_thread();
+
+    // -129 in two's-complement, big-endian bytes
+    let bytes = [0xff, 0x7f];
+    let big = unwrap(JBigInteger::from_bytes(&mut env, &bytes), &env);
+    assert_eq!(unwrap(big.to_string(&mut env), &env), "-129");
+    assert_eq!(unwrap(big.to_bytes(&mut env), &env), bytes);
+
+    let big = unwrap(
+        JBigInteger::from_str(&mut env, "123456789012345678901234567890"),
+        &env,
+    );
+    let bytes = unwrap(big.to_bytes(&mut env), &env);
+    let round_trip = unwrap(JBigInteger::from_bytes(&mut env, &bytes), &env);
+    assert_eq!(
+        unwrap(round_trip.to_string(&mut env), &env),
+        "123456789012345678901234567890"
+    );
+
+    assert_matches!(
+        JBigInteger::from_str(&mut env, "12x"),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception(&mut env);
+}
+
+#[test]
+fn big_decimal_round_trip() {
+    let mut env = attach_current_thread();
+
+    let big = unwrap(JBigDecimal::from_str(&mut env, "-12.345"), &env);
+    assert_eq!(unwrap(big.scale(&mut env), &env), 3);
+    let unscaled = unwrap(big.unscaled_bytes(&mut env), &env);
+    // -12345 in two's-complement, big-endian bytes
+    assert_eq!(unscaled, [0xcf, 0xc7]);
+
+    let round_trip = unwrap(
+        JBigDecimal::from_unscaled_bytes(&mut env, &unscaled, 3),
+        &env,
+    );
+    assert_eq!(unwrap(round_trip.to_string(&mut env), &env), "-12.345");
+
+    assert_matches!(
+        JBigDecimal::from_str(&mut env, "not a number"),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception(&mut env);
+}
+
+#[test]
+fn string_builder_append() {
+    let mut env = attach_current_thread();
+
+    let builder = unwrap(JStringBuilder::with_capacity(&mut env, 64), &env);
+    unwrap(builder.append_str(&mut env, "answer: "), &env);
+    unwrap(builder.append_int(&mut env, 42), &env);
+    let suffix = unwrap(env.new_string(", ünïcödé"), &env);
+    unwrap(builder.append_jstring(&mut env, &suffix), &env);
+    unwrap(builder.append_jstring(&mut env, &JString::default()), &env);
+
+    let string = unwrap(builder.to_jstring(&mut env), &env);
+    let string: String = unwrap(env.get_string(&string), &env).into();
+    assert_eq!(string, "answer: 42, ünïcödénull");
+
+    let empty = unwrap(JStringBuilder::new(&mut env), &env);
+    let empty = unwrap(empty.to_jstring(&mut env), &env);
+    assert_eq!(String::from(unwrap(env.get_string(&empty), &env)), "");
+}
+
+#[test]
+fn throw_typed_and_downcast() {
+    let mut env = attach_current_thread();
+
+    unwrap(
+        env.throw_typed::<JIllegalArgumentException, _>("bad argument"),
+        &env,
+    );
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear();
+
+    assert!(unwrap(
+        exception.is_instance::<JIllegalArgumentException>(&mut env),
+        &env
+    ));
+    assert!(unwrap(
+        exception.is_instance::<JRuntimeException>(&mut env),
+        &env
+    ));
+    assert!(!unwrap(
+        exception.is_instance::<JIoException>(&mut env),
+        &env
+    ));
+
+    let exception = match unwrap(exception.downcast::<JIllegalStateException>(&mut env), &env) {
+        Ok(_) => panic!("IllegalArgumentException is not an IllegalStateException"),
+        Err(exception) => exception,
+    };
+    let exception = match unwrap(
+        exception.downcast::<JIllegalArgumentException>(&mut env),
+        &env,
+    ) {
+        Ok(exception) => exception,
+        Err(_) => panic!("expected an IllegalArgumentException"),
+    };
+
+    let message = unwrap(
+        env.call_method(&exception, "getMessage", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let message = JString::from(unwrap(message.l(), &env));
+    assert_eq!(
+        String::from(unwrap(env.get_string(&message), &env)),
+        "bad argument"
+    );
+
+    unwrap(env.throw(JThrowable::from(exception)), &env);
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/IllegalArgumentException"),
+        Some("bad argument"),
+    );
+
+    unwrap(env.throw_typed::<JNullPointerException, _>("null"), &env);
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/NullPointerException"),
+        Some("null"),
+    );
+}
+
+#[test]
+fn exception_catch_as() {
+    let mut env = attach_current_thread();
+
+    assert!(unwrap(env.exception_catch_as::<JThrowable>(), &env).is_none());
+
+    unwrap(
+        env.throw_typed::<JIllegalStateException, _>("wrong state"),
+        &env,
+    );
+    let caught = unwrap(env.exception_catch_as::<JRuntimeException>(), &env);
+    assert!(!env.exception_check());
+    let caught = caught.expect("IllegalStateException should be caught");
+    assert!(unwrap(
+        env.is_instance_of(&caught, "java/lang/IllegalStateException"),
+        &env
+    ));
+
+    unwrap(env.throw_typed::<JIoException, _>("io failure"), &env);
+    let caught = unwrap(env.exception_catch_as::<JInterruptedException>(), &env);
+    assert!(caught.is_none());
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/io/IOException"),
+        Some("io failure"),
+    );
+}
+
+#[test]
+fn throw_new_with_backtrace() {
+    let mut env = attach_current_thread();
+
+    unwrap(
+        env.throw_new_with_backtrace("java/lang/IllegalStateException", "rust error"),
+        &env,
+    );
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear();
+    assert!(unwrap(
+        exception.is_instance::<JIllegalStateException>(&mut env),
+        &env
+    ));
+
+    let message = unwrap(
+        env.call_method(&exception, "getMessage", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let message = JString::from(unwrap(message.l(), &env));
+    let message: String = unwrap(env.get_string(&message), &env).into();
+    // Whether a backtrace is included depends on `RUST_BACKTRACE`
+    assert!(message == "rust error" || message.starts_with("rust error\n\nRust backtrace:\n"));
+}
+
+#[test]
+fn exception_map_throw() {
+    #[derive(Debug)]
+    struct NotFound(&'static str);
+
+    impl std::fmt::Display for NotFound {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{} not found", self.0)
+        }
+    }
+
+    let mut env = attach_current_thread();
+
+    unwrap(ExceptionMap::throw(&mut env, &NotFound("key")), &env);
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/RuntimeException"),
+        Some("key not found"),
+    );
+
+    ExceptionMap::register(|err: &NotFound| Exception {
+        class: "java/util/NoSuchElementException".into(),
+        msg: err.0.into(),
+    });
+    unwrap(ExceptionMap::throw(&mut env, &NotFound("key")), &env);
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/util/NoSuchElementException"),
+        Some("key"),
+    );
+
+    ExceptionMap::unregister::<NotFound>();
+    assert!(ExceptionMap::lookup(&NotFound("key")).is_none());
+}
+
+#[test]
+fn get_string_region() {
+    let mut env = attach_current_thread();
+    let string = unwrap(env.new_string("héllo, wörld"), &env);
+
+    assert_eq!(unwrap(string.length(&env), &env), 12);
+    assert_eq!(unwrap(env.get_string_utf_length(&string), &env), 14);
+
+    let mut utf16 = [0u16; 5];
+    unwrap(string.get_region(&env, 7, &mut utf16), &env);
+    assert_eq!(String::from_utf16(&utf16).unwrap(), "wörld");
+
+    let mut utf8 = [0u8; 16];
+    let len = unwrap(string.get_utf_region(&env, 0, 5, &mut utf8), &env);
+    assert_eq!(std::str::from_utf8(&utf8[..len]).unwrap(), "héllo");
+
+    // The buffer must have room for the worst case
+    assert_matches!(
+        string.get_utf_region(&env, 0, 6, &mut utf8),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+
+    assert_matches!(
+        string.get_region(&env, 10, &mut utf16),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some("java/lang/StringIndexOutOfBoundsException"),
+        None,
+    );
+}
+
+#[test]
+fn string_to_rust_string() {
+    let env = attach_current_thread();
+
+    for text in [
+        "",
+        "plain ascii",
+        "héllo, wörld",
+        "nul \0 and 🦀 supplementary",
+    ] {
+        let string = unwrap(env.new_string(text), &env);
+        assert_eq!(unwrap(string.to_rust_string(&env), &env), text);
+    }
+}
+
+#[test]
+fn string_utf16_round_trip() {
+    let mut env = attach_current_thread();
+
+    // Includes an unpaired surrogate, which can't be represented in UTF-8
+    let chars: Vec<u16> = "a 🦀 b".encode_utf16().chain([0xD800]).collect();
+    let string = unwrap(JString::from_utf16(&env, &chars), &env);
+    assert_eq!(unwrap(string.length(&env), &env), chars.len() as jsize);
+
+    let java_str = unwrap(string.chars_utf16(&mut env), &env);
+    assert_eq!(&*java_str, &chars[..]);
+    assert_eq!(java_str.to_string_lossy(), "a 🦀 b\u{FFFD}");
+
+    let owned = JavaString16::from(java_str);
+    assert_eq!(owned.into_vec(), chars);
+
+    let empty = unwrap(env.new_string_utf16(JavaString16::from("")), &env);
+    assert!(unwrap(empty.chars_utf16(&mut env), &env).is_empty());
+}
+
+#[test]
+fn get_string_utf16_rejects_non_string() {
+    let mut env = attach_current_thread();
+    let obj = unwrap(env.new_object("java/lang/Object", "()V", &[]), &env);
+
+    assert_matches!(
+        env.get_string_utf16(&obj.into()).err(),
+        Some(Error::JniCall(JniError::InvalidArguments))
+    );
+}
+
+#[test]
+fn typed_object_array() {
+    let mut env = attach_current_thread();
+
+    let empty = unwrap(env.new_string(""), &env);
+    let array = unwrap(JObjectArray::<JString>::new(&mut env, 2, &empty), &env);
+    let class = unwrap(env.get_object_class(&array), &env);
+    let class_name = unwrap(
+        env.call_method(&class, "getName", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let class_name = JString::from(unwrap(class_name.l(), &env));
+    assert_eq!(
+        unwrap(class_name.to_rust_string(&env), &env),
+        "[Ljava.lang.String;"
+    );
+
+    let hello = unwrap(env.new_string("hello"), &env);
+    unwrap(array.set_element(&env, 1, &hello), &env);
+    let element: JString = unwrap(array.get_element(&mut env, 1), &env);
+    assert_eq!(unwrap(element.to_rust_string(&env), &env), "hello");
+
+    // The JVM checks the element type, even if the Rust type is wrong
+    let object = unwrap(env.new_object("java/lang/Object", "()V", &[]), &env);
+    let untyped = array.cast::<JObject>();
+    assert_matches!(
+        untyped.set_element(&env, 0, &object),
+        Err(Error::JavaException)
+    );
+    assert_pending_java_exception_detailed(&mut env, Some("java/lang/ArrayStoreException"), None);
+}
+
+#[test]
+fn object_array_from_iter() {
+    let mut env = attach_current_thread();
+
+    let strings: Vec<JString> = ["a", "b", "c"]
+        .iter()
+        .map(|s| unwrap(env.new_string(s), &env))
+        .collect();
+    let array = unwrap(JObjectArray::<JString>::from_iter(&mut env, &strings), &env);
+    assert_eq!(unwrap(env.get_array_length(&array), &env), 3);
+
+    let elements = unwrap(array.to_vec(&mut env), &env);
+    let elements: Vec<String> = elements
+        .iter()
+        .map(|s| unwrap(s.to_rust_string(&env), &env))
+        .collect();
+    assert_eq!(elements, ["a", "b", "c"]);
+
+    // Arrays of arrays look up their class from the element class
+    let nested = unwrap(
+        JObjectArray::<JObjectArray<JString>>::from_iter(&mut env, [&array]),
+        &env,
+    );
+    let class = unwrap(env.get_object_class(&nested), &env);
+    let class_name = unwrap(
+        env.call_method(&class, "getName", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let class_name = JString::from(unwrap(class_name.l(), &env));
+    assert_eq!(
+        unwrap(class_name.to_rust_string(&env), &env),
+        "[[Ljava.lang.String;"
+    );
+}
+
+#[test]
+fn object_array_iter() {
+    let mut env = attach_current_thread();
+
+    let strings: Vec<JString> = ["x", "y"]
+        .iter()
+        .map(|s| unwrap(env.new_string(s), &env))
+        .collect();
+    let array = unwrap(JObjectArray::<JString>::from_iter(&mut env, &strings), &env);
+
+    let mut iter = unwrap(array.iter(&env), &env);
+    assert_eq!(iter.len(), 2);
+    let mut elements = Vec::new();
+    while let Some(element) = unwrap(iter.next(&mut env), &env) {
+        elements.push(unwrap(element.to_rust_string(&env), &env));
+    }
+    assert_eq!(elements, ["x", "y"]);
+    assert!(iter.is_empty());
+
+    let lengths = unwrap(
+        array.try_map_collect(&mut env, |env, element| env.get_string_length(element)),
+        &env,
+    );
+    assert_eq!(lengths, [1, 1]);
+
+    let result: Result<Vec<()>, Error> =
+        array.try_map_collect(&mut env, |_, _| Err(Error::NullPtr("stop")));
+    assert_matches!(result, Err(Error::NullPtr("stop")));
+}
+
+#[test]
+fn string_array_round_trip() {
+    let mut env = attach_current_thread();
+
+    let array = unwrap(env.new_string_array(&["one", "two", "🦀"]), &env);
+    assert_eq!(
+        unwrap(array.to_string_vec(&mut env), &env),
+        ["one", "two", "🦀"]
+    );
+
+    let empty = unwrap(env.new_string_array(&[] as &[&str]), &env);
+    assert!(unwrap(empty.to_string_vec(&mut env), &env).is_empty());
+
+    let with_null = unwrap(
+        JObjectArray::<JString>::new(&mut env, 1, JString::default()),
+        &env,
+    );
+    assert_matches!(with_null.to_string_vec(&mut env), Err(Error::NullPtr(_)));
+}
+
+#[test]
+fn string_hash_map_round_trip() {
+    let mut env = attach_current_thread();
+
+    let mut entries = std::collections::HashMap::new();
+    entries.insert("a".to_string(), "1".to_string());
+    entries.insert("b".to_string(), "2".to_string());
+
+    let obj = unwrap(JMap::from_iter(&mut env, &entries), &env);
+    let size = unwrap(env.call_method(&obj, "size", "()I", &[]), &env);
+    assert_eq!(unwrap(size.i(), &env), 2);
+
+    let map: JMap<JString, JString> = unwrap(env.get_map(&obj), &env).typed();
+    assert_eq!(unwrap(map.to_hashmap(&mut env), &env), entries);
+}
+
+#[test]
+fn get_object_array_element() {
+    let mut env = attach_current_thread();
+    let array = env
+        .new_object_array(1, STRING_CLASS, JObject::null())
+        .unwrap();
+    assert!(!array.is_null());
+    assert!(env.get_object_array_element(&array, 0).unwrap().is_null());
+    let test_str = env.new_string("test").unwrap();
+    env.set_object_array_element(&array, 0, test_str).unwrap();
+    assert!(!env.get_object_array_element(&array, 0).unwrap().is_null());
+}
+
+#[test]
+pub fn throw_new() {
+    let mut env = attach_current_thread();
+
+    let result = env.throw_new(RUNTIME_EXCEPTION_CLASS, "Test Exception");
+    assert!(result.is_ok());
+    assert_pending_java_exception_detailed(
+        &mut env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("Test Exception"),
+    );
+}
+
+#[test]
+pub fn throw_new_fail() {
+    let mut env = attach_current_thread();
+
+    let result = env.throw_new("java/lang/NonexistentException", "Test Exception");
+    assert!(result.is_err());
+    // Just to clear the java.lang.NoClassDefFoundError
+    assert_pending_java_exception(&mut env);
+}
+
+#[test]
+pub fn throw_defaults() {
+    let mut env = attach_current_thread();
+
+    test_throwable_descriptor_with_default_type(&mut env, TEST_EXCEPTION_MESSAGE);
+    test_throwable_descriptor_with_default_type(&mut env, TEST_EXCEPTION_MESSAGE.to_owned());
+    test_throwable_descriptor_with_default_type(&mut env, JNIString::from(TEST_EXCEPTION_MESSAGE));
+}
+
+#[test]
+pub fn test_conversion() {
+    let env = attach_current_thread();
+    let orig_obj: JObject = env.new_string("Hello, world!").unwrap().into();
+
+    let obj: JObject = unwrap(env.new_local_ref(&orig_obj), &env);
+    let string = JString::from(obj);
+    let actual = JObject::from(string);
+    assert!(env.is_same_object(&orig_obj, actual));
+
+    let global_ref = env.new_global_ref(&orig_obj).unwrap();
+    assert!(env.is_same_object(&orig_obj, global_ref));
+
+    let weak_ref = unwrap(env.new_weak_ref(&orig_obj), &env).expect("weak ref should not be null");
+    let actual =
+        unwrap(weak_ref.upgrade_local(&env), &env).expect("weak ref should not have been GC'd");
+    assert!(env.is_same_object(&orig_obj, actual));
+
+    let obj: JObject = unwrap(env.new_local_ref(&orig_obj), &env);
+    let auto_local = env.auto_local(obj);
+    assert!(env.is_same_object(&orig_obj, auto_local));
+}
+
+#[test]
+pub fn class_loader_read_resource() {
+    let mut env = attach_current_thread();
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+
+    let class_file = unwrap(
+        loader.read_resource(&mut env, "java/lang/Object.class"),
+        &env,
+    )
+    .expect("Object.class should be readable as a resource");
+    assert_eq!(&class_file[..4], &[0xCA, 0xFE, 0xBA, 0xBE]);
+
+    let missing = unwrap(loader.read_resource(&mut env, "does/not/Exist.class"), &env);
+    assert!(missing.is_none());
+}
+
+#[test]
+pub fn lock_object() {
+    let mut env = attach_current_thread();
+    let obj = unwrap(env.new_object(EXCEPTION_CLASS, "()V", &[]), &env);
+    let obj = env.auto_local(obj);
+
+    fn holds_lock(env: &mut JNIEnv, obj: &JObject) -> bool {
+        let held = env.call_static_method(
+            "java/lang/Thread",
+            "holdsLock",
+            "(Ljava/lang/Object;)Z",
+            &[JValue::Object(obj)],
+        );
+        unwrap(held, env).z().unwrap()
+    }
+
+    assert!(!holds_lock(&mut env, &obj));
+    {
+        let _outer = unwrap(env.lock_object(&obj), &env);
+        assert!(holds_lock(&mut env, &obj));
+        {
+            // Monitors are re-entrant
+            let _inner = unwrap(env.lock_object(&obj), &env);
+            assert!(holds_lock(&mut env, &obj));
+        }
+        assert!(holds_lock(&mut env, &obj));
+    }
+    assert!(!holds_lock(&mut env, &obj));
+}
+
+#[test]
+pub fn lock_object_null_arg() {
+    let env = attach_current_thread();
+    let result = env.lock_object(JObject::null()).map(|_| ());
+    assert_matches!(result, Err(Error::NullPtr(_)));
+}
+
+#[test]
+pub fn test_null_get_string() {
+    let mut env = attach_current_thread();
+    let s = unsafe { JString::from_raw(std::ptr::null_mut() as _) };
+    let ret = env.get_string(&s);
+    assert!(ret.is_err());
+}
+
+#[test]
+pub fn test_invalid_list_get_string() {
+    let mut env = attach_current_thread();
+
+    let class = env.find_class("java/util/List").unwrap();
+    let class = JString::from(JObject::from(class));
+    let class = env.auto_local(class);
+
+    let ret = env.get_string(&class);
+    assert!(ret.is_err());
+}
+
+fn test_throwable_descriptor_with_default_type<'local, D>(env: &mut JNIEnv<'local>, descriptor: D)
+where
+    D: Desc<'local, JThrowable<'local>>,
+{
+    let result = descriptor.lookup(env);
+    assert!(result.is_ok());
+    let exception = result.unwrap();
+    let exception = exception.as_ref();
+
+    assert_exception_type(env, exception, RUNTIME_EXCEPTION_CLASS);
+    assert_exception_message(env, exception, TEST_EXCEPTION_MESSAGE);
+}
+
+// Helper method that asserts that result is Error and the cause is JavaException.
+fn assert_exception(res: &Result<jobject, Error>, expect_message: &str) {
+    assert!(res.is_err());
+    assert!(res
+        .as_ref()
+        .map_err(|error| matches!(error, Error::JavaException))
+        .expect_err(expect_message));
+}
+
+// Shortcut to `assert_pending_java_exception_detailed()` without checking for expected  type and
+// message of exception.
+fn assert_pending_java_exception(env: &mut JNIEnv) {
+    assert_pending_java_exception_detailed(env, None, None)
+}
+
+// Helper method that asserts there is a pending Java exception of `expected_type` with
+// `expected_message` and clears it if any.
 fn assert_pending_java_exception_detailed(
     env: &mut JNIEnv,
     expected_type: Option<&str>,
@@ -1342,3 +2780,31 @@ fn test_java_char_conversion() {
         assert_eq!(c, '☃');
     }
 }
+
+#[test]
+fn jni_field_get_and_set() {
+    let mut env = attach_current_thread();
+
+    let point = unwrap(
+        env.new_object("java/awt/Point", "(II)V", &[JValue::Int(1), JValue::Int(2)]),
+        &env,
+    );
+    assert_eq!(unwrap(x::get(&mut env, &point), &env), 1);
+    unwrap(x::set(&mut env, &point, &42), &env);
+    assert_eq!(unwrap(x::get(&mut env, &point), &env), 42);
+    assert_eq!(
+        unwrap(env.get_field(&point, "x", "I"), &env).i().unwrap(),
+        42
+    );
+
+    let string = unwrap(env.new_string("not a point"), &env);
+    assert_matches!(
+        x::get(&mut env, &string),
+        Err(Error::JniCall(JniError::InvalidArguments))
+    );
+    assert_matches!(x::get(&mut env, &JObject::null()), Err(Error::NullPtr(_)));
+
+    // The Java field is an `int`, so it isn't found with the signature of a `long`
+    assert_matches!(y::get(&mut env, &point), Err(Error::JavaException));
+    assert_pending_java_exception_detailed(&mut env, Some("java/lang/NoSuchFieldError"), None);
+}
@@ -0,0 +1,192 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use jni::{
+    objects::{
+        include_java_class, EmbeddedClasses, JClass, JClassLoader, JIntArray, JObject, JString,
+        JValue,
+    },
+    registry::jni_class,
+    sys::{jboolean, jint, jlong, jobject},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+// A class with a `static native void run(long)` method, which isn't on the
+// class path
+const CLASS_NAME: &str = "io/github/jni_rs/RustRunnable";
+const CLASS_BYTECODE: &[u8] = include_bytes!("../src/java/io/github/jni_rs/RustRunnable.class");
+
+// `Greeter` extends `GreeterBase` and has a nested `Greeter$Name` class, from
+// the same source file
+const GREETER: EmbeddedClasses = include_java_class!("tests/java/io/github/jni_rs/Greeter.java");
+const GREETER_8: EmbeddedClasses =
+    include_java_class!("tests/java/io/github/jni_rs/Greeter.java", release = 8);
+
+static RAN_WITH: AtomicI64 = AtomicI64::new(0);
+
+struct Runnable;
+
+#[jni_class(class = "io.github.jni_rs.RustRunnable", register, verify = "src/java")]
+impl Runnable {
+    extern "system" fn run(_env: JNIEnv, _class: JClass, value: jlong) {
+        Self::record(value);
+    }
+
+    fn record(value: jlong) {
+        RAN_WITH.store(value, Ordering::SeqCst);
+    }
+}
+
+#[repr(transparent)]
+struct Handle(jobject);
+
+struct Inferred;
+
+#[jni_class(
+    class = "com/example/Inferred",
+    type_map(Handle = "Lcom/example/Handle;")
+)]
+impl Inferred {
+    extern "system" fn is_empty<'local>(
+        _env: JNIEnv<'local>,
+        _this: JObject<'local>,
+        _name: JString<'local>,
+        _values: JIntArray<'local>,
+    ) -> jboolean {
+        false
+    }
+
+    #[jni(name = "nativeHandle")]
+    extern "system" fn handle(_env: JNIEnv, _class: JClass, handle: jobject) -> Handle {
+        Handle(handle)
+    }
+
+    #[jni(sig = "(Ljava/util/List;)I")]
+    extern "system" fn size(_env: JNIEnv, _this: JObject, _list: JObject) -> jint {
+        0
+    }
+}
+
+#[repr(transparent)]
+struct Handles(jobject);
+
+#[repr(transparent)]
+struct Bytes(jobject);
+
+struct FromFile;
+
+#[jni_class(
+    class = "com/example/FromFile",
+    type_map(Bytes = "Ljava/nio/ByteBuffer;"),
+    type_map_file = "tests/jni_class_type_map.txt"
+)]
+impl FromFile {
+    extern "system" fn first(_env: JNIEnv, _class: JClass, _handles: Handles) -> Handle {
+        Handle(std::ptr::null_mut())
+    }
+
+    extern "system" fn wrap(_env: JNIEnv, _class: JClass, bytes: jobject) -> Bytes {
+        Bytes(bytes)
+    }
+}
+
+#[test]
+fn jni_class_type_map_file() {
+    let sigs: Vec<_> = FromFile::NATIVE_METHODS
+        .iter()
+        .map(|entry| entry.sig)
+        .collect();
+    assert_eq!(
+        sigs,
+        vec![
+            "([Lcom/example/Handle;)Lcom/example/Handle;",
+            "(Ljava/lang/Object;)Ljava/nio/ByteBuffer;",
+        ]
+    );
+}
+
+#[test]
+fn jni_class_native_methods() {
+    let methods: Vec<_> = Inferred::NATIVE_METHODS
+        .iter()
+        .map(|entry| (entry.class_name, entry.name, entry.sig))
+        .collect();
+    assert_eq!(
+        methods,
+        vec![
+            ("com/example/Inferred", "isEmpty", "(Ljava/lang/String;[I)Z"),
+            (
+                "com/example/Inferred",
+                "nativeHandle",
+                "(Ljava/lang/Object;)Lcom/example/Handle;"
+            ),
+            ("com/example/Inferred", "size", "(Ljava/util/List;)I"),
+        ]
+    );
+    assert_eq!(
+        Inferred::NATIVE_METHODS[0].rust_fn,
+        "jni_class::Inferred::is_empty"
+    );
+    assert_eq!(
+        Inferred::NATIVE_METHODS[0].java_type,
+        "native boolean com.example.Inferred.isEmpty(java.lang.String, int[])"
+    );
+    assert!(Inferred::NATIVE_METHODS[0]
+        .location
+        .file()
+        .ends_with("jni_class.rs"));
+
+    assert_eq!(Runnable::NATIVE_METHODS.len(), 1);
+    assert_eq!(Runnable::NATIVE_METHODS[0].sig, "(J)V");
+
+    let mut env = attach_current_thread();
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    unwrap(env.define_class(CLASS_NAME, &loader, CLASS_BYTECODE), &env);
+
+    unwrap(Runnable::register(&mut env), &env);
+    unwrap(
+        env.call_static_method(CLASS_NAME, "run", "(J)V", &[JValue::Long(7)]),
+        &env,
+    );
+    assert_eq!(RAN_WITH.load(Ordering::SeqCst), 7);
+}
+
+#[test]
+fn include_java_class_defines_classes() {
+    assert_eq!(GREETER.name, "io/github/jni_rs/Greeter");
+    let names: Vec<_> = GREETER.classes.iter().map(|(name, _)| *name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "io/github/jni_rs/Greeter$Name",
+            "io/github/jni_rs/GreeterBase",
+            "io/github/jni_rs/Greeter",
+        ]
+    );
+    // Compiled for Java 8 (class file version 52) by default
+    for (_, bytes) in GREETER.classes {
+        assert_eq!(u16::from_be_bytes([bytes[6], bytes[7]]), 52);
+    }
+    assert_eq!(GREETER_8.classes, GREETER.classes);
+
+    let mut env = attach_current_thread();
+    let loader = unwrap(JClassLoader::system(&mut env), &env);
+    let class = unwrap(GREETER.define(&mut env, &loader), &env);
+    let name = unwrap(env.new_string("Rust"), &env);
+    let greeting = unwrap(
+        env.call_static_method(
+            &class,
+            "greet",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[JValue::from(&name)],
+        )
+        .and_then(|value| value.l()),
+        &env,
+    );
+    let greeting: String = unwrap(env.get_string(&JString::from(greeting)), &env).into();
+    assert_eq!(greeting, "Hello, Rust!");
+}
@@ -8,10 +8,11 @@ use std::{
 use jni::{
     objects::{AutoLocal, JValue},
     sys::jint,
+    JNIVersion,
 };
 
 mod util;
-use util::{attach_current_thread, unwrap};
+use util::{attach_current_thread, jvm, unwrap};
 
 #[test]
 pub fn global_ref_works_in_other_threads() {
@@ -78,3 +79,22 @@ pub fn global_ref_works_in_other_threads() {
         );
     }
 }
+
+#[test]
+pub fn global_ref_dropped_on_unattached_thread() {
+    let env = attach_current_thread();
+    let string = unwrap(env.new_string("deferred"), &env);
+    let global = unwrap(env.new_global_ref(&string), &env);
+
+    // The reference is queued, instead of attaching the thread to delete it
+    spawn(move || {
+        drop(global);
+        assert!(unsafe { jvm().get_env(JNIVersion::V1_4) }.is_err());
+    })
+    .join()
+    .unwrap();
+
+    env.delete_deferred_refs();
+    // The local reference to the same object isn't affected
+    assert_eq!(unwrap(env.get_string_length(&string), &env), 8);
+}
@@ -0,0 +1,151 @@
+#![cfg(all(feature = "invocation", feature = "auto-register", feature = "tokio"))]
+
+use jni::{
+    errors::{Error, Result},
+    objects::{GlobalRef, JObject, JString},
+    registry::{self, jni_native},
+    JavaVM,
+};
+use lazy_static::lazy_static;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+lazy_static! {
+    static ref RUNTIME: Runtime = Builder::new_current_thread().build().unwrap();
+}
+
+fn runtime() -> Handle {
+    RUNTIME.handle().clone()
+}
+
+#[jni_native(
+    class = "com/example/Async",
+    sig = "(Ljava/lang/String;)Ljava/util/concurrent/CompletableFuture;",
+    runtime = runtime
+)]
+async fn echo(vm: JavaVM, value: GlobalRef) -> Result<GlobalRef> {
+    tokio::task::yield_now().await;
+    let _env = vm.attach_current_thread()?;
+    if value.is_null() {
+        return Err(Error::NullPtr("value"));
+    }
+    Ok(value)
+}
+
+#[jni_native(
+    class = "com/example/Async",
+    sig = "(Ljava/lang/String;)Ljava/util/concurrent/CompletableFuture;",
+    runtime = runtime,
+    non_null = [value]
+)]
+async fn echo_non_null(value: GlobalRef) -> Result<GlobalRef> {
+    Ok(value)
+}
+
+#[jni_native(
+    class = "com/example/Async",
+    sig = "()Ljava/util/concurrent/CompletableFuture;",
+    runtime = runtime
+)]
+async fn panics() -> Result<GlobalRef> {
+    tokio::task::yield_now().await;
+    panic!("the async native method panicked")
+}
+
+/// Runs the spawned tasks, which complete the Java futures.
+fn run_spawned_tasks() {
+    RUNTIME.block_on(async {
+        for _ in 0..4 {
+            tokio::task::yield_now().await;
+        }
+    });
+}
+
+#[test]
+fn async_native_method_returns_completable_future() {
+    let mut env = attach_current_thread();
+
+    let entry = registry::native_entries()
+        .find(|entry| entry.name == "echo")
+        .unwrap();
+    assert_eq!(entry.rust_fn, "jni_native_async::echo");
+
+    let value = unwrap(env.new_string("value"), &env);
+    let future = echo(unsafe { env.unsafe_clone() }, JObject::null(), value.into());
+    assert!(!future.is_null());
+    let future = unsafe { JObject::from_raw(future) };
+    run_spawned_tasks();
+
+    let value = unwrap(
+        env.call_method(&future, "join", "()Ljava/lang/Object;", &[]),
+        &env,
+    );
+    let value = JString::from(unwrap(value.l(), &env));
+    assert_eq!(String::from(unwrap(env.get_string(&value), &env)), "value");
+
+    let failed = echo(
+        unsafe { env.unsafe_clone() },
+        JObject::null(),
+        JObject::null(),
+    );
+    let failed = unsafe { JObject::from_raw(failed) };
+    run_spawned_tasks();
+    let result = env.call_method(&failed, "join", "()Ljava/lang/Object;", &[]);
+    assert!(matches!(result, Err(Error::JavaException)));
+    env.exception_clear();
+}
+
+#[test]
+fn async_native_method_with_null_argument() {
+    let mut env = attach_current_thread();
+
+    let future = echo_non_null(
+        unsafe { env.unsafe_clone() },
+        JObject::null(),
+        JObject::null(),
+    );
+    assert!(future.is_null());
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear();
+    assert!(unwrap(
+        env.is_instance_of(&exception, "java/lang/NullPointerException"),
+        &env
+    ));
+}
+
+#[test]
+fn async_native_method_that_panics() {
+    let mut env = attach_current_thread();
+
+    let future = panics(unsafe { env.unsafe_clone() }, JObject::null());
+    assert!(!future.is_null());
+    let future = unsafe { JObject::from_raw(future) };
+    run_spawned_tasks();
+
+    let done = unwrap(env.call_method(&future, "isDone", "()Z", &[]), &env);
+    assert!(unwrap(done.z(), &env));
+    let result = env.call_method(&future, "join", "()Ljava/lang/Object;", &[]);
+    assert!(matches!(result, Err(Error::JavaException)));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear();
+    let cause = unwrap(
+        env.call_method(&exception, "getCause", "()Ljava/lang/Throwable;", &[]),
+        &env,
+    );
+    let cause = unwrap(cause.l(), &env);
+    assert!(unwrap(
+        env.is_instance_of(&cause, "java/lang/RuntimeException"),
+        &env
+    ));
+    let message = unwrap(
+        env.call_method(&cause, "getMessage", "()Ljava/lang/String;", &[]),
+        &env,
+    );
+    let message = JString::from(unwrap(message.l(), &env));
+    assert_eq!(
+        String::from(unwrap(env.get_string(&message), &env)),
+        "Rust future panicked"
+    );
+}
@@ -0,0 +1,131 @@
+#![cfg(all(feature = "invocation", feature = "auto-register", feature = "tracing"))]
+
+use std::sync::{Arc, Mutex};
+
+use jni::{
+    objects::JClass,
+    registry::jni_native,
+    sys::{jint, jlong},
+    JNIEnv,
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+mod util;
+use util::attach_current_thread;
+
+#[jni_native(class = "com/example/Traced", sig = "(II)I", instrument = true)]
+extern "system" fn add(_env: JNIEnv, _class: JClass, a: jint, b: jint) -> jint {
+    a + b
+}
+
+#[jni_native(class = "com/example/Traced", sig = "(J)V", instrument = true)]
+extern "system" fn fail(mut env: JNIEnv, _class: JClass, _value: jlong) {
+    let _ = env.throw_new("java/lang/IllegalStateException", "failed");
+}
+
+/// Collects the fields of a span or an event as `name=value` strings.
+#[derive(Default)]
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+}
+
+/// The index of the span that an event was recorded in, if any, and the
+/// event's fields.
+type RecordedEvent = (Option<usize>, Vec<String>);
+
+/// A subscriber that records the fields of each `native_method` span that's
+/// entered, and of each event.
+///
+/// Other spans, such as those of the `trace-jni` feature, are ignored.
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<Vec<String>>>>,
+    entered: Arc<Mutex<Vec<usize>>>,
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        !metadata.is_span() || metadata.name() == "native_method"
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(fields.0);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let current = self.entered.lock().unwrap().last().copied();
+        self.events.lock().unwrap().push((current, fields.0));
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered
+            .lock()
+            .unwrap()
+            .push(span.into_u64() as usize - 1);
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn instrumented_native_methods() {
+    let env = attach_current_thread();
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let env = unsafe { env.unsafe_clone() };
+        assert_eq!(
+            add(unsafe { env.unsafe_clone() }, JClass::default(), 1, 2),
+            3
+        );
+        fail(env, JClass::default(), 0);
+    });
+    assert!(env.exception_check());
+    env.exception_clear();
+
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(
+        *spans,
+        [
+            [
+                "class=\"com/example/Traced\"",
+                "method=\"add\"",
+                "sig=\"(II)I\""
+            ],
+            [
+                "class=\"com/example/Traced\"",
+                "method=\"fail\"",
+                "sig=\"(J)V\""
+            ],
+        ]
+    );
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(
+        *events,
+        [(
+            Some(1),
+            vec!["message=native method threw an exception".to_string()]
+        )]
+    );
+}
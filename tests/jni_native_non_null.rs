@@ -0,0 +1,86 @@
+#![cfg(all(feature = "invocation", feature = "auto-register"))]
+
+use jni::{
+    errors::{Error, Exception, ExceptionMap},
+    objects::{JClass, JObject, JString},
+    registry::jni_native,
+    sys::jint,
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[jni_native(
+    class = "com/example/Strings",
+    sig = "(Ljava/lang/String;Ljava/lang/String;)I",
+    non_null = [first]
+)]
+extern "system" fn length(env: JNIEnv, _class: JClass, first: JString, second: JString) -> jint {
+    let first = env.get_string_length(&first).unwrap();
+    let second = if second.is_null() {
+        0
+    } else {
+        env.get_string_length(&second).unwrap()
+    };
+    first + second
+}
+
+/// Clears the pending exception, and returns its class (of the ones that are
+/// expected) and message.
+fn take_exception(env: &mut JNIEnv) -> (&'static str, String) {
+    let exception = env.exception_occurred().expect("an exception is pending");
+    env.exception_clear();
+    let class = [
+        "java/lang/NullPointerException",
+        "java/lang/IllegalArgumentException",
+    ]
+    .iter()
+    .find(|class| unwrap(env.is_instance_of(&exception, **class), env))
+    .expect("an expected exception class");
+    let message = unwrap(
+        env.call_method(&exception, "getMessage", "()Ljava/lang/String;", &[]),
+        env,
+    );
+    let message = JString::from(unwrap(message.l(), env));
+    let message = String::from(unwrap(env.get_string(&message), env));
+    (class, message)
+}
+
+#[test]
+fn non_null_arguments() {
+    let mut env = attach_current_thread();
+    let string = unwrap(env.new_string("abc"), &env);
+
+    let call = |env: &mut JNIEnv, first: &JObject| {
+        length(
+            unsafe { env.unsafe_clone() },
+            JClass::default(),
+            JString::from(unwrap(env.new_local_ref(first), env)),
+            JString::default(),
+        )
+    };
+
+    assert_eq!(call(&mut env, &string), 3);
+    assert!(!env.exception_check());
+
+    assert_eq!(call(&mut env, &JObject::null()), 0);
+    assert_eq!(
+        take_exception(&mut env),
+        (
+            "java/lang/NullPointerException",
+            String::from("argument `first` is null")
+        )
+    );
+
+    ExceptionMap::register(|err: &Error| Exception {
+        class: "java/lang/IllegalArgumentException".into(),
+        msg: err.to_string(),
+    });
+    assert_eq!(call(&mut env, &JObject::null()), 0);
+    ExceptionMap::unregister::<Error>();
+    assert_eq!(
+        take_exception(&mut env).0,
+        "java/lang/IllegalArgumentException"
+    );
+}
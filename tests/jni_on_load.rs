@@ -0,0 +1,63 @@
+#![cfg(feature = "invocation")]
+
+use std::{
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use jni::{
+    errors::Result, objects::JClass, registry::ClassRegistration, sys, JNIEnv, JNIVersion, JavaVM,
+};
+
+mod util;
+use util::{attach_current_thread, jvm};
+
+static LOADED: AtomicUsize = AtomicUsize::new(0);
+static UNLOADED: AtomicUsize = AtomicUsize::new(0);
+static REGISTERED: AtomicUsize = AtomicUsize::new(0);
+
+fn registered(env: &mut JNIEnv, class: &JClass) -> Result<()> {
+    assert!(env.is_assignable_from(class, "java/lang/Integer")?);
+    REGISTERED.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+fn on_load(env: &mut JNIEnv) -> Result<()> {
+    env.find_class("java/lang/String")?;
+    LOADED.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+fn on_unload(_vm: &JavaVM) {
+    UNLOADED.fetch_add(1, Ordering::SeqCst);
+}
+
+jni::jni_on_load! {
+    version: JNIVersion::V1_8,
+    // Without a `class_loader`, the class is looked up with `find_class`
+    register: [ClassRegistration {
+        class_name: "java/lang/Integer",
+        methods: &[],
+        init: Some(registered),
+    }],
+    on_load: on_load,
+    on_unload: on_unload,
+}
+
+// The `JavaVM` singleton is global, so this is the only test in this file
+#[test]
+fn generated_on_load_and_on_unload() {
+    let _env = attach_current_thread();
+    assert!(JavaVM::singleton().is_none());
+
+    let version = unsafe { JNI_OnLoad(jvm().get_raw(), ptr::null_mut()) };
+    assert_eq!(version, sys::JNI_VERSION_1_8);
+    assert_eq!(LOADED.load(Ordering::SeqCst), 1);
+    assert_eq!(REGISTERED.load(Ordering::SeqCst), 1);
+    let singleton = JavaVM::singleton().unwrap();
+    assert_eq!(singleton.get_raw(), jvm().get_raw());
+
+    unsafe { JNI_OnUnload(jvm().get_raw(), ptr::null_mut()) };
+    assert_eq!(UNLOADED.load(Ordering::SeqCst), 1);
+    assert!(JavaVM::singleton().is_none());
+}
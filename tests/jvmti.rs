@@ -0,0 +1,95 @@
+#![cfg(feature = "invocation")]
+
+use std::sync::Mutex;
+
+use jni::{
+    jvmti::{Capabilities, Capability, HeapFilter},
+    objects::JClass,
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, jvm};
+
+#[test]
+fn jvmti_tags_objects() {
+    let mut env = attach_current_thread();
+    let jvmti = jvm().get_jvmti_env().unwrap();
+    assert!(jvmti.version().unwrap() >= jni::jvmti::sys::JVMTI_VERSION_1_2);
+
+    let tag_objects = Capabilities::new().with(Capability::TagObjects);
+    assert!(jvmti
+        .potential_capabilities()
+        .unwrap()
+        .contains(Capability::TagObjects));
+    jvmti.add_capabilities(&tag_objects).unwrap();
+    assert!(jvmti
+        .capabilities()
+        .unwrap()
+        .contains(Capability::TagObjects));
+
+    let object = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+    assert_eq!(jvmti.get_tag(&object).unwrap(), 0);
+    jvmti.set_tag(&object, 42).unwrap();
+    assert_eq!(jvmti.get_tag(&object).unwrap(), 42);
+
+    // Only the tagged object is reported
+    let mut tags = Vec::new();
+    jvmti
+        .iterate_through_heap(HeapFilter::UNTAGGED, None, |object| {
+            tags.push(*object.tag);
+            *object.tag = 0;
+            true
+        })
+        .unwrap();
+    assert_eq!(tags, [42]);
+    assert_eq!(jvmti.get_tag(&object).unwrap(), 0);
+
+    jvmti.dispose().unwrap();
+}
+
+#[test]
+fn jvmti_lists_loaded_classes() {
+    let mut env = attach_current_thread();
+    let jvmti = jvm().get_jvmti_env().unwrap();
+    let signatures: Vec<_> = jvmti
+        .get_loaded_classes(&mut env)
+        .unwrap()
+        .iter()
+        .map(|class| jvmti.get_class_signature(class).unwrap())
+        .collect();
+    assert!(signatures.iter().any(|sig| sig == "Ljava/lang/String;"));
+    jvmti.dispose().unwrap();
+}
+
+static PREPARED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn on_class_prepare(env: &mut JNIEnv, class: &JClass) {
+    let name = env
+        .call_method(class, "getName", "()Ljava/lang/String;", &[])
+        .and_then(|name| name.l())
+        .and_then(|name| Ok(env.get_string(&name.into())?.into()));
+    if let Ok(name) = name {
+        PREPARED.lock().unwrap().push(name);
+    }
+}
+
+#[test]
+fn jvmti_class_prepare_hook() {
+    let mut env = attach_current_thread();
+    let jvmti = jvm().get_jvmti_env().unwrap();
+    jvmti
+        .set_class_prepare_hook(Some(on_class_prepare))
+        .unwrap();
+
+    env.find_class("javax/sql/rowset/serial/SerialArray")
+        .unwrap();
+    jvmti.set_class_prepare_hook(None).unwrap();
+
+    assert!(PREPARED
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|name| name == "javax.sql.rowset.serial.SerialArray"));
+    jvmti.dispose().unwrap();
+}
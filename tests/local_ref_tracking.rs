@@ -0,0 +1,103 @@
+#![cfg(all(feature = "invocation", feature = "local-ref-tracking"))]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use jni::{diagnostics::Action, errors::Error, local_ref_tracking};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+fn counts_live_references_per_frame() {
+    let mut env = attach_current_thread();
+    assert_eq!(local_ref_tracking::live_local_refs(), None);
+
+    unwrap(
+        env.with_local_frame(16, |env| {
+            let first = env.new_string("first")?;
+            let _second = env.new_string("second")?;
+            assert_eq!(local_ref_tracking::live_local_refs(), Some(2));
+
+            env.with_local_frame(16, |env| {
+                assert_eq!(local_ref_tracking::live_local_refs(), Some(0));
+                let _class = env.get_object_class(&first)?;
+                assert_eq!(local_ref_tracking::live_local_refs(), Some(1));
+                Ok::<_, Error>(())
+            })?;
+
+            assert_eq!(local_ref_tracking::live_local_refs(), Some(2));
+            env.delete_local_ref(first);
+            assert_eq!(local_ref_tracking::live_local_refs(), Some(1));
+            Ok(())
+        }),
+        &env,
+    );
+
+    assert_eq!(local_ref_tracking::live_local_refs(), None);
+}
+
+#[test]
+fn reports_frames_over_the_limit() {
+    let mut env = attach_current_thread();
+    local_ref_tracking::set_threshold(4);
+    local_ref_tracking::set_action(Action::Panic);
+
+    // The frame's capacity raises the limit above the threshold
+    unwrap(
+        env.with_local_frame(8, |env| {
+            for i in 0..8 {
+                env.new_string(i.to_string())?;
+            }
+            Ok(())
+        }),
+        &env,
+    );
+
+    // ...as does `ensure_local_capacity`
+    unwrap(
+        env.with_local_frame(4, |env| {
+            env.ensure_local_capacity(8)?;
+            for i in 0..8 {
+                env.new_string(i.to_string())?;
+            }
+            Ok(())
+        }),
+        &env,
+    );
+
+    // Deleted references don't count
+    unwrap(
+        env.with_local_frame(4, |env| {
+            for i in 0..8 {
+                let string = env.new_string(i.to_string())?;
+                env.delete_local_ref(string);
+            }
+            Ok(())
+        }),
+        &env,
+    );
+
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        env.with_local_frame(4, |env| {
+            for i in 0..8 {
+                env.new_string(i.to_string())?;
+            }
+            Ok::<_, Error>(())
+        })
+    }));
+    let payload = res.unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert!(message.contains("5 live local references"), "{}", message);
+    assert_eq!(local_ref_tracking::live_local_refs(), None);
+
+    local_ref_tracking::set_action(Action::Log);
+    unwrap(
+        env.with_local_frame(4, |env| {
+            for i in 0..8 {
+                env.new_string(i.to_string())?;
+            }
+            Ok(())
+        }),
+        &env,
+    );
+}
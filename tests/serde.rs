@@ -0,0 +1,187 @@
+#![cfg(all(feature = "invocation", feature = "serde"))]
+
+use std::collections::BTreeMap;
+
+use assert_matches::assert_matches;
+use serde::{Deserialize, Serialize};
+
+use jni::{
+    objects::{JMap, JObject, JString},
+    serde::{from_jobject, to_jobject, SerdeError},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, print_exception, unwrap};
+
+fn unwrap_serde<T>(res: Result<T, SerdeError>, env: &JNIEnv) -> T {
+    res.unwrap_or_else(|e| {
+        print_exception(env);
+        panic!("{:#?}", e);
+    })
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    name: String,
+    id: u32,
+    score: f64,
+    tags: Vec<String>,
+    parent: Option<Box<Record>>,
+    shape: Shape,
+    kind: Kind,
+    attributes: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle(f32),
+    Rectangle { width: u16, height: u16 },
+    Line(i8, i8),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Kind {
+    Plain,
+    Fancy,
+}
+
+#[test]
+fn serde_round_trip() {
+    let mut env = attach_current_thread();
+
+    let record = Record {
+        name: "ü ⌘ 😀".to_owned(),
+        id: u32::MAX,
+        score: 0.5,
+        tags: vec!["a".to_owned(), "b".to_owned()],
+        parent: Some(Box::new(Record {
+            name: "parent".to_owned(),
+            id: 1,
+            score: -1.0,
+            tags: Vec::new(),
+            parent: None,
+            shape: Shape::Circle(2.0),
+            kind: Kind::Plain,
+            attributes: BTreeMap::new(),
+        })),
+        shape: Shape::Rectangle {
+            width: 3,
+            height: 4,
+        },
+        kind: Kind::Fancy,
+        attributes: vec![("x".to_owned(), i64::MIN), ("y".to_owned(), 7)]
+            .into_iter()
+            .collect(),
+    };
+
+    let obj = unwrap_serde(to_jobject(&mut env, &record), &env);
+    assert!(unwrap(env.is_instance_of(&obj, "java/util/HashMap"), &env));
+
+    let round_trip: Record = unwrap_serde(from_jobject(&mut env, &obj), &env);
+    assert_eq!(round_trip, record);
+
+    let line = unwrap_serde(to_jobject(&mut env, &Shape::Line(-1, 1)), &env);
+    let round_trip: Shape = unwrap_serde(from_jobject(&mut env, &line), &env);
+    assert_eq!(round_trip, Shape::Line(-1, 1));
+}
+
+#[test]
+fn serde_to_jobject_mapping() {
+    let mut env = attach_current_thread();
+
+    let obj = unwrap_serde(to_jobject(&mut env, &(true, 'x', 255u8, ())), &env);
+    let list = unwrap(env.get_list(&obj), &env);
+    assert_eq!(unwrap(list.size(&mut env), &env), 4);
+
+    let first = unwrap(list.get(&mut env, 0), &env).unwrap();
+    assert!(unwrap(
+        env.is_instance_of(&first, "java/lang/Boolean"),
+        &env
+    ));
+    let second = unwrap(list.get(&mut env, 1), &env).unwrap();
+    assert!(unwrap(
+        env.is_instance_of(&second, "java/lang/Character"),
+        &env
+    ));
+    let third = unwrap(list.get(&mut env, 2), &env).unwrap();
+    assert!(unwrap(env.is_instance_of(&third, "java/lang/Short"), &env));
+    let value = unwrap(env.call_method(&third, "intValue", "()I", &[]), &env);
+    assert_eq!(unwrap(value.i(), &env), 255);
+    let fourth = unwrap(list.get(&mut env, 3), &env).unwrap();
+    assert!(fourth.is_null());
+
+    let obj = unwrap_serde(to_jobject(&mut env, &Kind::Plain), &env);
+    let string = JString::from(obj);
+    assert_eq!(unwrap(string.to_rust_string(&env), &env), "Plain");
+
+    assert_matches!(to_jobject(&mut env, &u64::MAX), Err(SerdeError::Message(_)));
+    // The partially built collections are deleted along with the frame
+    assert_matches!(
+        to_jobject(&mut env, &vec![vec![1u64], vec![u64::MAX]]),
+        Err(SerdeError::Message(_))
+    );
+    assert!(!env.exception_check());
+}
+
+#[test]
+fn serde_from_java_collections() {
+    let mut env = attach_current_thread();
+
+    // Any `Map` and `Collection` can be deserialized, not just the ones that `to_jobject` creates.
+    let map_obj = unwrap(env.new_object("java/util/TreeMap", "()V", &[]), &env);
+    let map = unwrap(JMap::from_env(&mut env, &map_obj), &env);
+    let key = JObject::from(unwrap(env.new_string("numbers"), &env));
+    let set = unwrap(env.new_object("java/util/TreeSet", "()V", &[]), &env);
+    for i in &[3, 1, 2] {
+        let value = unwrap_serde(to_jobject(&mut env, &i), &env);
+        unwrap(
+            env.call_method(&set, "add", "(Ljava/lang/Object;)Z", &[(&value).into()]),
+            &env,
+        );
+    }
+    unwrap(map.put(&mut env, &key, &set), &env);
+
+    let value: BTreeMap<String, Vec<u8>> = unwrap_serde(from_jobject(&mut env, &map_obj), &env);
+    assert_eq!(value["numbers"], vec![1, 2, 3]);
+
+    // Object arrays and `byte[]` are deserialized as sequences.
+    let array = unwrap(env.new_string_array(&["a", "b"]), &env);
+    let value: Vec<String> = unwrap_serde(from_jobject(&mut env, &array), &env);
+    assert_eq!(value, vec!["a", "b"]);
+    let bytes = unwrap(env.byte_array_from_slice(&[1, 2, 3]), &env);
+    let value: Vec<u8> = unwrap_serde(from_jobject(&mut env, &bytes), &env);
+    assert_eq!(value, vec![1, 2, 3]);
+
+    // `null` is `None`
+    let value: Option<String> = unwrap_serde(from_jobject(&mut env, &JObject::null()), &env);
+    assert_eq!(value, None);
+
+    // Out of range numbers and unsupported classes are rejected.
+    let obj = unwrap_serde(to_jobject(&mut env, &-1), &env);
+    assert_matches!(
+        from_jobject::<u32>(&mut env, &obj),
+        Err(SerdeError::Message(_))
+    );
+    let obj = unwrap(env.new_object("java/lang/Object", "()V", &[]), &env);
+    assert_matches!(
+        from_jobject::<u32>(&mut env, &obj),
+        Err(SerdeError::UnsupportedClass(name)) if name == "java.lang.Object"
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn serde_json_round_trip() {
+    let mut env = attach_current_thread();
+
+    let value = vec![Shape::Circle(1.0), Shape::Line(1, 2)];
+    let string = unwrap_serde(jni::serde::to_json_string(&mut env, &value), &env);
+    assert_eq!(
+        unwrap(string.to_rust_string(&env), &env),
+        r#"[{"Circle":1.0},{"Line":[1,2]}]"#
+    );
+    let round_trip: Vec<Shape> =
+        unwrap_serde(jni::serde::from_json_string(&mut env, &string), &env);
+    assert_eq!(round_trip, value);
+}
@@ -0,0 +1,76 @@
+#![cfg(all(feature = "invocation", feature = "stats"))]
+
+use std::thread::spawn;
+
+use jni::stats;
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+// The counters are global, so this is the only test in this file
+#[test]
+fn counts_live_references() {
+    let env = attach_current_thread();
+    let string = unwrap(env.new_string("stats"), &env);
+
+    let globals = stats::global_ref_count();
+    let weaks = stats::weak_ref_count();
+
+    let global = unwrap(env.new_global_ref(&string), &env);
+    let clone = global.clone();
+    let weak = unwrap(env.new_weak_ref(&string), &env).unwrap();
+    assert_eq!(stats::global_ref_count(), globals + 1);
+    assert_eq!(stats::weak_ref_count(), weaks + 1);
+
+    // A null `GlobalRef` doesn't own a JVM reference
+    let null = unwrap(env.new_global_ref(jni::objects::JObject::null()), &env);
+    assert_eq!(stats::global_ref_count(), globals + 1);
+
+    drop(global);
+    assert_eq!(stats::global_ref_count(), globals + 1);
+    drop(clone);
+    drop(null);
+    drop(weak);
+    assert_eq!(stats::global_ref_count(), globals);
+    assert_eq!(stats::weak_ref_count(), weaks);
+
+    assert!(stats::global_refs_by_class().is_empty());
+    stats::set_per_class(true);
+    let first = unwrap(env.new_global_ref(&string), &env);
+    let second = unwrap(env.new_global_ref(&string), &env);
+    let weak = unwrap(env.new_weak_ref(&string), &env).unwrap();
+    stats::set_per_class(false);
+
+    assert_eq!(
+        stats::global_refs_by_class().get("java.lang.String"),
+        Some(&2)
+    );
+    assert_eq!(
+        stats::weak_refs_by_class().get("java.lang.String"),
+        Some(&1)
+    );
+    drop(first);
+    assert_eq!(
+        stats::global_refs_by_class().get("java.lang.String"),
+        Some(&1)
+    );
+    drop(second);
+    drop(weak);
+    assert_eq!(stats::global_refs_by_class().get("java.lang.String"), None);
+    assert_eq!(stats::weak_refs_by_class().get("java.lang.String"), None);
+
+    // References that are dropped on an unattached thread are deleted later
+    let global = unwrap(env.new_global_ref(&string), &env);
+    let weak = unwrap(env.new_weak_ref(&string), &env).unwrap();
+    spawn(move || {
+        drop(global);
+        drop(weak);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(stats::global_ref_count(), globals + 1);
+    assert_eq!(stats::weak_ref_count(), weaks + 1);
+    env.delete_deferred_refs();
+    assert_eq!(stats::global_ref_count(), globals);
+    assert_eq!(stats::weak_ref_count(), weaks);
+}
@@ -0,0 +1,32 @@
+#![cfg(all(feature = "invocation", feature = "strict-exception-checks"))]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use jni::{diagnostics::Action, errors::Error, strict_exception_checks};
+
+mod util;
+use util::attach_current_thread;
+
+// The action is global, so this is the only test in this file
+#[test]
+fn reports_calls_with_pending_exception() {
+    let mut env = attach_current_thread();
+    env.find_class("java/lang/String").unwrap();
+
+    env.throw_new("java/lang/RuntimeException", "unchecked")
+        .unwrap();
+    strict_exception_checks::set_action(Action::Panic);
+    let panic = catch_unwind(AssertUnwindSafe(|| env.find_class("java/lang/String")))
+        .expect_err("the call should panic");
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains("FindClass"), "{}", message);
+
+    strict_exception_checks::set_action(Action::Log);
+    assert!(matches!(
+        env.find_class("java/lang/String"),
+        Err(Error::JavaException)
+    ));
+
+    env.exception_clear();
+    env.find_class("java/lang/String").unwrap();
+}
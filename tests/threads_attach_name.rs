@@ -0,0 +1,71 @@
+#![cfg(feature = "invocation")]
+
+use std::thread;
+
+use jni::JNIEnv;
+
+mod util;
+use util::jvm;
+
+fn java_thread_name(env: &mut JNIEnv) -> String {
+    let thread = env
+        .call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )
+        .and_then(|thread| thread.l())
+        .unwrap();
+    let name = env
+        .call_method(thread, "getName", "()Ljava/lang/String;", &[])
+        .and_then(|name| name.l())
+        .unwrap();
+    env.get_string(&name.into()).unwrap().into()
+}
+
+#[test]
+fn attached_thread_is_named_after_rust_thread() {
+    let name = thread::Builder::new()
+        .name("rust-worker".to_owned())
+        .spawn(|| java_thread_name(&mut jvm().attach_current_thread().unwrap()))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(name, "rust-worker");
+}
+
+#[test]
+fn attached_thread_name_can_be_overridden() {
+    let name = thread::Builder::new()
+        .name("rust-worker".to_owned())
+        .spawn(|| {
+            let mut env = jvm()
+                .attach_current_thread_permanently_with_name("custom-name")
+                .unwrap();
+            java_thread_name(&mut env)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(name, "custom-name");
+}
+
+#[test]
+fn daemon_thread_name_can_be_overridden() {
+    let name = thread::Builder::new()
+        .name("rust-worker".to_owned())
+        .spawn(|| {
+            // Safety: the JavaVM isn't destroyed while the thread is attached
+            let mut env = unsafe {
+                jvm()
+                    .attach_current_thread_as_daemon_with_name("custom-daemon")
+                    .unwrap()
+            };
+            java_thread_name(&mut env)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(name, "custom-daemon");
+}
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "invocation", feature = "trace-jni"))]
+
+use std::sync::{Arc, Mutex};
+
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+/// Collects the fields of a span as `name=value` strings.
+#[derive(Default)]
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+}
+
+/// A span's name, its fields and the index of its parent span, if any.
+type RecordedSpan = (&'static str, Vec<String>, Option<usize>);
+
+/// A subscriber that records the `jni` spans, with the spans that they were
+/// created in.
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    entered: Arc<Mutex<Vec<usize>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "jni"
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        let parent = self.entered.lock().unwrap().last().copied();
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata().name(), fields.0, parent));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.entered
+            .lock()
+            .unwrap()
+            .push(span.into_u64() as usize - 1);
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn traces_jni_calls() {
+    let mut env = attach_current_thread();
+    let string = unwrap(env.new_string("traced"), &env);
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        unwrap(env.find_class("java/lang/String"), &env);
+        let len = unwrap(env.call_method(&string, "length", "()I", &[]), &env);
+        assert_eq!(unwrap(len.i(), &env), 6);
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    let span = |name: &str| {
+        spans
+            .iter()
+            .position(|(span, _, _)| *span == name)
+            .unwrap_or_else(|| panic!("no {} span in {:?}", name, spans))
+    };
+
+    let find_class = span("find_class");
+    assert_eq!(spans[find_class].1, ["class=java/lang/String"]);
+    assert_eq!(spans[span("FindClass")].2, Some(find_class));
+
+    let call_method = span("call_method");
+    assert_eq!(spans[call_method].1, ["name=length", "sig=()I"]);
+    assert_eq!(spans[span("CallIntMethodA")].2, Some(call_method));
+    assert_eq!(
+        spans[span("GetMethodID")].2.map(|parent| spans[parent].0),
+        Some("get_method_id")
+    );
+}